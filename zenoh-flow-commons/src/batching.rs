@@ -0,0 +1,92 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::fmt::Display;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::deserialize::{
+    deserialize_size, deserialize_time, duration_schema, serialize_size, serialize_time,
+    size_schema,
+};
+
+/// Structure to configure how the messages of a link are batched when they are sent over Zenoh.
+///
+/// When batching is enabled, the messages are aggregated into a single Zenoh publication until either (i) the size of
+/// the batch exceeds `max-size` or (ii) the oldest message of the batch has waited for `max-latency`. The receiving
+/// runtime unpacks the batch and forwards its messages in the order they were sent.
+///
+/// A batch is always sent through a regular Zenoh publication: the messages of a batched link never go through Zenoh's
+/// shared memory, even if it is enabled.
+///
+/// # Example
+///
+/// ```
+/// # use zenoh_flow_commons::BatchingConfiguration;
+/// # let batching_yaml = r#"
+/// max-size: 64KiB
+/// max-latency: 500us
+/// # "#;
+/// # let batching = serde_yaml::from_str::<BatchingConfiguration>(batching_yaml).unwrap();
+/// # assert_eq!(batching.max_size, 65_536);
+/// # assert_eq!(batching.max_latency, 500);
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BatchingConfiguration {
+    /// Size, converted in bytes, above which a batch is published.
+    #[serde(
+        deserialize_with = "deserialize_size",
+        serialize_with = "serialize_size"
+    )]
+    #[schemars(schema_with = "size_schema")]
+    pub max_size: usize,
+    /// Duration, converted in microseconds, after which a batch is published.
+    #[serde(
+        deserialize_with = "deserialize_time",
+        serialize_with = "serialize_time"
+    )]
+    #[schemars(schema_with = "duration_schema")]
+    pub max_latency: u64,
+}
+
+impl Display for BatchingConfiguration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "max size: {} bytes, max latency: {}µs",
+            self.max_size, self.max_latency
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchingConfiguration;
+
+    #[test]
+    fn test_serialize_deserialize() {
+        let batching = BatchingConfiguration {
+            max_size: 1_500,
+            max_latency: 2_000,
+        };
+
+        let json = serde_json::to_string(&batching).unwrap();
+        assert_eq!(
+            batching,
+            serde_json::from_str::<BatchingConfiguration>(&json).unwrap()
+        );
+    }
+}
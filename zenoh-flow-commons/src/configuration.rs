@@ -14,6 +14,7 @@
 
 use std::ops::{Deref, DerefMut};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::merge::IMergeOverwrite;
@@ -76,7 +77,7 @@ use crate::merge::IMergeOverwrite;
 // - JSON is the most supported representation when going online,
 // - a `serde_json::Value` can be converted to a `serde_yaml::Value` whereas the opposite is not true (YAML introduces
 //   "tags" which are not supported by JSON).
-#[derive(Default, Deserialize, Debug, Serialize, Clone, PartialEq, Eq)]
+#[derive(Default, Deserialize, Debug, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 pub struct Configuration(serde_json::Value);
 
 impl Deref for Configuration {
@@ -13,13 +13,23 @@
 //
 
 //! This module exposes the functions [deserialize_size] and [deserialize_time] that are used
-//! throughout Zenoh-Flow to "parse" values used to express time or size.
+//! throughout Zenoh-Flow to "parse" values used to express time or size, as well as their serialising
+//! counterparts [serialize_size] and [serialize_time].
 //!
 //! The external crates [bytesize] and [humantime] are leveraged for these purposes.
+//!
+//! It also exposes the [JSON Schemas](schemars) of these values, such that editors can validate them.
 
 use std::{str::FromStr, sync::Arc};
 
-use serde::Deserializer;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{
+        InstanceType, Metadata, NumberValidation, Schema, SchemaObject, StringValidation,
+        SubschemaValidation,
+    },
+};
+use serde::{Deserialize, Deserializer, Serializer};
 use zenoh_keyexpr::OwnedKeyExpr;
 
 /// Deserialise, from a String, an `Arc<str>` that is guaranteed to be a valid Zenoh-Flow [NodeId](crate::NodeId) or
@@ -70,10 +80,20 @@ Caused by:
     Ok(id.into())
 }
 
+/// The textual representations of a size: either a number of bytes or a string that [bytesize] can parse.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SizeEntry {
+    Bytes(u64),
+    Text(String),
+}
+
 /// Deserialise a bytes size leveraging the [bytesize] crate.
 ///
 /// This allows parsing, for instance, "1Ko" into "1024" bytes. For more example, see the [bytesize] crate.
 ///
+/// A plain number of bytes is also accepted, such that a size serialised with [serialize_size] can be deserialised.
+///
 /// # Errors
 ///
 /// See the [bytesize] documentation.
@@ -81,15 +101,17 @@ pub fn deserialize_size<'de, D>(deserializer: D) -> std::result::Result<usize, D
 where
     D: Deserializer<'de>,
 {
-    let size_str: String = serde::de::Deserialize::deserialize(deserializer)?;
-    let size_u64 = bytesize::ByteSize::from_str(&size_str)
-        .map_err(|e| {
-            serde::de::Error::custom(format!(
-                "Unable to parse value as bytes {size_str}:\n{:?}",
-                e
-            ))
-        })?
-        .as_u64();
+    let size_u64 = match SizeEntry::deserialize(deserializer)? {
+        SizeEntry::Bytes(bytes) => bytes,
+        SizeEntry::Text(size_str) => bytesize::ByteSize::from_str(&size_str)
+            .map_err(|e| {
+                serde::de::Error::custom(format!(
+                    "Unable to parse value as bytes {size_str}:\n{:?}",
+                    e
+                ))
+            })?
+            .as_u64(),
+    };
 
     usize::try_from(size_u64).map_err(|e| serde::de::Error::custom(format!(
         "Unable to convert < {} > into a `usize`. Maybe check the architecture of the target device?\n{:?}",
@@ -97,31 +119,124 @@ where
     )))
 }
 
-/// Deserialise a duration in *microseconds* leveraging the [humantime] crate.
+/// Serialise a bytes size in a format that [deserialize_size] accepts.
+pub fn serialize_size<S: Serializer>(
+    size: &usize,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&size.to_string())
+}
+
+/// Parse a duration in *microseconds* leveraging the [humantime] crate.
 ///
 /// This allows parsing, for instance, "1ms" as 1000 microseconds.
 ///
 /// # Errors
 ///
-/// See the [humantime] documentation.
-pub fn deserialize_time<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let buf: &str = serde::de::Deserialize::deserialize(deserializer)?;
-    let time_u128 = buf
+/// This function will return an error if the duration cannot be parsed by [humantime] or if it does not fit in a
+/// `u64`.
+pub fn parse_time(duration: &str) -> std::result::Result<u64, String> {
+    let time_u128 = duration
         .parse::<humantime::Duration>()
-        .map_err(serde::de::Error::custom)?
+        .map_err(|e| format!("Unable to parse < {} > as a duration:\n{:?}", duration, e))?
         .as_micros();
 
     u64::try_from(time_u128).map_err(|e| {
-        serde::de::Error::custom(format!(
+        format!(
             "Unable to convert < {} > into a `u64`. Maybe lower the value?\n{:?}",
             time_u128, e
-        ))
+        )
     })
 }
 
+/// Deserialise a duration in *microseconds* leveraging the [humantime] crate.
+///
+/// This allows parsing, for instance, "1ms" as 1000 microseconds.
+///
+/// # Errors
+///
+/// See [parse_time].
+pub fn deserialize_time<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let duration: String = serde::de::Deserialize::deserialize(deserializer)?;
+    parse_time(&duration).map_err(serde::de::Error::custom)
+}
+
+/// Serialise a duration, in *microseconds*, in a format that [deserialize_time] accepts.
+pub fn serialize_time<S: Serializer>(
+    micros: &u64,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{micros}us"))
+}
+
+/// Returns a JSON Schema of a string, with the provided `description` and `examples`.
+fn string_schema(description: &str, examples: &[&str]) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.into()),
+            examples: examples.iter().map(|example| (*example).into()).collect(),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+/// Returns the JSON Schema of an identifier accepted by [deserialize_id].
+pub fn id_schema(_: &mut SchemaGenerator) -> Schema {
+    let mut schema = string_schema(
+        "An identifier: a non-empty canonical Zenoh key expression without any of the symbols: * # $ ? >",
+        &[],
+    );
+    schema.string = Some(Box::new(StringValidation {
+        min_length: Some(1),
+        pattern: Some("^[^*#$?>]+$".into()),
+        ..Default::default()
+    }));
+
+    schema.into()
+}
+
+/// Returns the JSON Schema of a size accepted by [deserialize_size]: a number of bytes or a string parsed by the
+/// `bytesize` crate.
+pub fn size_schema(_: &mut SchemaGenerator) -> Schema {
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(vec![
+                SchemaObject {
+                    instance_type: Some(InstanceType::Integer.into()),
+                    number: Some(Box::new(NumberValidation {
+                        minimum: Some(0.0),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }
+                .into(),
+                string_schema(
+                    "A size, parsed by `bytesize`, e.g. `64KiB` or `10MB`.",
+                    &["64KiB", "10MB"],
+                )
+                .into(),
+            ]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Returns the JSON Schema of a duration, as accepted by the `humantime` crate.
+pub fn duration_schema(_: &mut SchemaGenerator) -> Schema {
+    string_schema(
+        "A duration, parsed by `humantime`, e.g. `500us`, `10ms` or `1s`.",
+        &["500us", "10ms", "1s"],
+    )
+    .into()
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
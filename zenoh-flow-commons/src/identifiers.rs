@@ -15,11 +15,12 @@
 use std::{fmt::Display, ops::Deref, str::FromStr, sync::Arc};
 
 use anyhow::anyhow;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use zenoh_protocol::core::ZenohId;
 
-use crate::deserialize::deserialize_id;
+use crate::deserialize::{deserialize_id, id_schema};
 
 /// A `NodeId` uniquely identifies a Node within a data flow.
 ///
@@ -30,8 +31,14 @@ use crate::deserialize::deserialize_id;
 /// # Performance
 ///
 /// A `NodeId` is encapsulated in an [Arc] rendering clone operations inexpensive.
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Hash)]
-pub struct NodeId(#[serde(deserialize_with = "deserialize_id")] Arc<str>);
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Clone, Hash,
+)]
+pub struct NodeId(
+    #[serde(deserialize_with = "deserialize_id")]
+    #[schemars(schema_with = "id_schema")]
+    Arc<str>,
+);
 
 impl Deref for NodeId {
     type Target = Arc<str>;
@@ -74,8 +81,14 @@ impl From<&str> for NodeId {
 /// # Performance
 ///
 /// A `PortId` is encapsulated in an [Arc] rendering clone operations inexpensive.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
-pub struct PortId(#[serde(deserialize_with = "deserialize_id")] Arc<str>);
+#[derive(
+    Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema,
+)]
+pub struct PortId(
+    #[serde(deserialize_with = "deserialize_id")]
+    #[schemars(schema_with = "id_schema")]
+    Arc<str>,
+);
 
 impl Deref for PortId {
     type Target = Arc<str>;
@@ -110,9 +123,9 @@ impl From<&str> for PortId {
 ///
 /// A Zenoh-Flow runtime will, by default, reuse the [ZenohId] of the Zenoh
 /// [session](https://docs.rs/zenoh/0.10.1-rc/zenoh/struct.Session.html) it will create to connect to the Zenoh network.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Default)]
 #[repr(transparent)]
-pub struct RuntimeId(ZenohId);
+pub struct RuntimeId(#[schemars(with = "String")] ZenohId);
 
 impl RuntimeId {
     /// Generate a new random identifier, guaranteed (with a high probability) to be unique.
@@ -160,8 +173,8 @@ impl FromStr for RuntimeId {
 ///
 /// Internally, it uses a [Uuid v4](uuid::Uuid::new_v4) that it wraps inside an [Arc]. This allows for inexpensive
 /// `clone` operations.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
-pub struct InstanceId(Arc<Uuid>);
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceId(#[schemars(with = "String")] Arc<Uuid>);
 
 impl Display for InstanceId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -17,11 +17,20 @@
 //! ⚠️ This crate is intended for internal usage within Zenoh-Flow. All structures that are exposed in public
 //! facing API are re-exposed in the relevant crates.
 
+mod batching;
+pub use batching::BatchingConfiguration;
+
+mod compression;
+pub use compression::Compression;
+
 mod configuration;
 pub use configuration::Configuration;
 
 mod deserialize;
-pub use deserialize::deserialize_id;
+pub use deserialize::{
+    deserialize_id, deserialize_size, deserialize_time, duration_schema, id_schema, parse_time,
+    serialize_size, serialize_time, size_schema,
+};
 
 mod identifiers;
 pub use identifiers::{InstanceId, NodeId, PortId, RuntimeId};
@@ -29,11 +38,16 @@ pub use identifiers::{InstanceId, NodeId, PortId, RuntimeId};
 mod merge;
 pub use merge::IMergeOverwrite;
 
+mod qos;
+pub use qos::{CongestionControl, Priority, QoS, Reliability};
+
 mod shared_memory;
 pub use shared_memory::SharedMemoryConfiguration;
 
 mod utils;
-pub use utils::try_parse_from_file;
+pub use utils::{
+    try_deserialize_from_str, try_parse_from_file, try_parse_from_str, try_render_from_str,
+};
 
 mod vars;
 pub use vars::{parse_vars, Vars};
@@ -0,0 +1,100 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::fmt::Display;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The quality of service with which Zenoh delivers the messages of a link or of a publisher.
+///
+/// All fields are optional, their default values are the ones of Zenoh.
+///
+/// The `express` flag is not supported by the version of Zenoh in use, declaring it is an error.
+///
+/// # Example
+///
+/// ```
+/// # use zenoh_flow_commons::{CongestionControl, Priority, QoS};
+/// # let qos_yaml = r#"
+/// priority: real-time
+/// congestion-control: block
+/// reliability: reliable
+/// # "#;
+/// # let qos = serde_yaml::from_str::<QoS>(qos_yaml).unwrap();
+/// # assert_eq!(qos.priority, Priority::RealTime);
+/// # assert_eq!(qos.congestion_control, CongestionControl::Block);
+/// # assert!(serde_yaml::from_str::<QoS>("express: true").is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct QoS {
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub congestion_control: CongestionControl,
+    #[serde(default)]
+    pub reliability: Reliability,
+}
+
+impl QoS {
+    /// Returns `true` if all the fields have their default value.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for QoS {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "priority: {:?}, congestion control: {:?}, reliability: {:?}",
+            self.priority, self.congestion_control, self.reliability
+        )
+    }
+}
+
+/// The priority of the messages, from the highest (`real-time`) to the lowest (`background`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Priority {
+    RealTime,
+    InteractiveHigh,
+    InteractiveLow,
+    DataHigh,
+    #[default]
+    Data,
+    DataLow,
+    Background,
+}
+
+/// What Zenoh does when a message cannot be sent because the network is congested.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum CongestionControl {
+    /// The message is dropped.
+    #[default]
+    Drop,
+    /// The sender waits until the message can be sent.
+    Block,
+}
+
+/// Whether the messages that were lost should be retransmitted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reliability {
+    #[default]
+    BestEffort,
+    Reliable,
+}
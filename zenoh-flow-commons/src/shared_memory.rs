@@ -14,9 +14,10 @@
 
 use std::fmt::Display;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::deserialize::{deserialize_size, deserialize_time};
+use crate::deserialize::{deserialize_size, deserialize_time, duration_schema, size_schema};
 
 /// Structure to configure how Zenoh-Flow uses the [shared memory](https://docs.rs/zenoh-shm/0.10.1-rc/zenoh_shm/)
 /// feature provided by Zenoh.
@@ -26,16 +27,28 @@ use crate::deserialize::{deserialize_size, deserialize_time};
 /// A Zenoh-Flow runtime can be configured to always attempt to send data through shared-memory first. When this feature
 /// is enabled this structure allows tweaking two aspects: (i) the size of the shared memory buffer Zenoh should
 /// allocate and (ii) the back-off period.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SharedMemoryConfiguration {
     /// Size, converted in bytes, of the entire shared memory buffer.
     #[serde(deserialize_with = "deserialize_size")]
+    #[schemars(schema_with = "size_schema")]
     pub size: usize,
     /// Duration, converted in nanoseconds, to wait before retrying the last operation.
     #[serde(deserialize_with = "deserialize_time")]
+    #[schemars(schema_with = "duration_schema")]
     pub backoff: u64,
 }
 
+/// By default, Zenoh allocates a shared memory buffer of 10MiB and the back-off period is 1 millisecond.
+impl Default for SharedMemoryConfiguration {
+    fn default() -> Self {
+        Self {
+            size: 10 * 1024 * 1024,
+            backoff: 1_000_000,
+        }
+    }
+}
+
 // TODO@J-Loudet
 impl Display for SharedMemoryConfiguration {
     fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
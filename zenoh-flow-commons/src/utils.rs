@@ -12,10 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::{
-    io::Read,
-    path::{Path, PathBuf},
-};
+use std::{io::Read, path::Path};
 
 use anyhow::{bail, Context};
 use handlebars::Handlebars;
@@ -33,7 +30,7 @@ use crate::{IMergeOverwrite, Result, Vars};
 /// - ".yml"
 /// - ".yaml"
 /// - ".json"
-pub(crate) fn deserializer<N>(path: &PathBuf) -> Result<fn(&str) -> Result<N>>
+pub(crate) fn deserializer<N>(path: &Path) -> Result<fn(&str) -> Result<N>>
 where
     N: for<'a> Deserialize<'a>,
 {
@@ -101,8 +98,49 @@ where
             path_buf.display()
         ))?;
 
+    try_parse_from_str(&buf, &path_buf, vars)
+}
+
+/// Attempts to parse an instance of `N` from the provided `content`, overwriting (or complementing) the [Vars] declared
+/// in it with the provided `vars`.
+///
+/// The `path` is only used to select the deserializer, based on its extension, and in error messages: the content does
+/// not have to come from a file. This function is notably used to parse the descriptors fetched from a remote location.
+///
+/// # Errors
+///
+/// The parsing can fail for the same reasons as [try_parse_from_file], except those related to the file system.
+pub fn try_parse_from_str<N>(content: &str, path: impl AsRef<Path>, vars: Vars) -> Result<(N, Vars)>
+where
+    N: for<'a> Deserialize<'a>,
+{
+    let path = path.as_ref();
+    let (rendered_descriptor, merged_vars) = try_render_from_str(content, path, vars)?;
+
+    Ok((
+        try_deserialize_from_str::<N>(&rendered_descriptor, path)?,
+        merged_vars,
+    ))
+}
+
+/// Attempts to expand the [Vars] declared in the provided `content`, overwritten (or complemented) by the provided
+/// `vars`, returning the expanded content and the [Vars] that were used.
+///
+/// The expanded content is what [try_parse_from_str] deserializes: positions reported when deserializing it (line,
+/// column) thus refer to the expanded content, which only differs from the original when a variable spans several
+/// lines.
+///
+/// # Errors
+///
+/// The expansion can fail if the extension of the `path` is not supported, if parsing the [Vars] section failed or if
+/// expanding the variables failed.
+pub fn try_render_from_str(
+    content: &str,
+    path: impl AsRef<Path>,
+    vars: Vars,
+) -> Result<(String, Vars)> {
     let merged_vars = vars.merge_overwrite(
-        deserializer::<Vars>(&path_buf)?(&buf).context("Failed to deserialize Vars")?,
+        deserializer::<Vars>(path.as_ref())?(content).context("Failed to deserialize Vars")?,
     );
 
     let mut handlebars = Handlebars::new();
@@ -113,12 +151,23 @@ where
         // that `handlebars` can correctly manipulate it.
         //
         // We have to have this indirection in the structure such that `serde` can correctly deserialise the descriptor.
-        .render_template(buf.as_str(), &(*merged_vars))
+        .render_template(content, &(*merged_vars))
         .context("Failed to expand descriptor")?;
 
-    Ok((
-        (deserializer::<N>(&path_buf))?(&rendered_descriptor)
-            .context(format!("Failed to deserialize {}", &path_buf.display()))?,
-        merged_vars,
-    ))
+    Ok((rendered_descriptor, merged_vars))
+}
+
+/// Attempts to deserialize an instance of `N` from the provided `content`, in which the [Vars] were already expanded
+/// (see [try_render_from_str]).
+///
+/// # Errors
+///
+/// The deserialization can fail if the extension of the `path` is not supported or if parsing an instance of `N`
+/// failed.
+pub fn try_deserialize_from_str<N>(content: &str, path: impl AsRef<Path>) -> Result<N>
+where
+    N: for<'a> Deserialize<'a>,
+{
+    let path = path.as_ref();
+    (deserializer::<N>(path))?(content).context(format!("Failed to deserialize {}", path.display()))
 }
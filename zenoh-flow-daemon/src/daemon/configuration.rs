@@ -12,8 +12,10 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+use std::path::PathBuf;
+
 use serde::Deserialize;
-use zenoh_flow_runtime::Extensions;
+use zenoh_flow_runtime::{Extensions, RecoveryPolicy, RuntimeLossPolicy};
 
 /// The configuration of a Zenoh-Flow Daemon.
 #[derive(Deserialize, Debug)]
@@ -22,4 +24,16 @@ pub struct ZenohFlowConfiguration {
     pub name: String,
     /// Additionally supported [Extensions].
     pub extensions: Option<Extensions>,
+    /// If set to `true`, every library loaded by the embedded Runtime must come with a `sha256` checksum in its
+    /// descriptor. Defaults to `false`.
+    #[serde(default)]
+    pub require_library_checksums: bool,
+    /// The directory in which the embedded Runtime persists its data flow instances. Nothing is persisted if omitted.
+    pub state_directory: Option<PathBuf>,
+    /// What the embedded Runtime does, when it starts, with the instances found in the `state_directory`.
+    #[serde(default)]
+    pub recovery_policy: RecoveryPolicy,
+    /// What the embedded Runtime does with a running instance when another runtime involved in it is lost.
+    #[serde(default)]
+    pub runtime_loss_policy: RuntimeLossPolicy,
 }
@@ -32,10 +32,11 @@ pub use zenoh_flow_runtime::{Extension, Extensions, Runtime};
 pub use self::configuration::ZenohFlowConfiguration;
 use crate::queries::{instances::delete::delete_instance, Origin};
 
-/// A Zenoh-Flow daemon declares 2 queryables:
+/// A Zenoh-Flow daemon spawns (at most) 3 tasks, 2 queryables and 1 publisher:
 /// 1. `zenoh-flow/<uuid>/runtime`
 /// 2. `zenoh-flow/<uuid>/instances`
-const NUMBER_QUERYABLES: usize = 2;
+/// 3. `zenoh-flow/<uuid>/events`
+const NUMBER_TASKS: usize = 3;
 
 /// The Zenoh-Flow `Daemon`, a wrapper around a Zenoh-Flow [Runtime].
 ///
@@ -43,6 +44,8 @@ const NUMBER_QUERYABLES: usize = 2;
 pub struct Daemon {
     abort_tx: Sender<()>,
     abort_ack_rx: Receiver<()>,
+    // The number of tasks that were successfully spawned, i.e. the number of tasks to stop.
+    spawned_tasks: usize,
     runtime: Arc<Runtime>,
 }
 
@@ -63,8 +66,8 @@ impl Daemon {
     ///   [extensions],
     /// - the [extensions] could not be added to the Runtime (see the list of potential reasons
     ///   [here](zenoh_flow_runtime::RuntimeBuilder::add_extensions())),
-    /// - the Zenoh queryables -- one to manage the `instances` and another to manage the `runtime` itself -- could not
-    ///   be created.
+    /// - the Zenoh queryables -- one to manage the `instances` and another to manage the `runtime` itself -- or the
+    ///   publisher of the `events` could not be created.
     ///
     /// [extensions]: Extensions
     /// [runtime]: Runtime
@@ -75,11 +78,17 @@ impl Daemon {
     ) -> Result<Self> {
         let extensions = configuration.extensions.unwrap_or_default();
 
-        let runtime = Runtime::builder(configuration.name)
+        let mut builder = Runtime::builder(configuration.name)
             .add_extensions(extensions)?
-            .session(zenoh_session)
-            .build()
-            .await?;
+            .require_library_checksums(configuration.require_library_checksums)
+            .recovery_policy(configuration.recovery_policy)
+            .runtime_loss_policy(configuration.runtime_loss_policy)
+            .session(zenoh_session);
+        if let Some(directory) = configuration.state_directory {
+            builder = builder.state_directory(directory);
+        }
+
+        let runtime = builder.build().await?;
 
         Daemon::spawn(runtime).await
     }
@@ -89,21 +98,22 @@ impl Daemon {
     /// # Errors
     ///
     /// This function will fail if the Zenoh queryables -- one to manage the `instances` and another to manage the
-    /// `runtime` itself -- could not be created.
+    /// `runtime` itself -- or the publisher of the `events` could not be created.
     ///
     /// [runtime]: Runtime
     pub async fn spawn(runtime: Runtime) -> Result<Self> {
         // Channels to gracefully stop the Zenoh-Flow daemon:
         // - `abort_?x` to tell the queryables that they have to stop,
         // - `abort_ack_?x` for the queryables to inform the runtime that they did stop.
-        let (abort_tx, abort_rx) = flume::bounded::<()>(NUMBER_QUERYABLES);
-        let (abort_ack_tx, abort_ack_rx) = flume::bounded::<()>(NUMBER_QUERYABLES);
+        let (abort_tx, abort_rx) = flume::bounded::<()>(NUMBER_TASKS);
+        let (abort_ack_tx, abort_ack_rx) = flume::bounded::<()>(NUMBER_TASKS);
 
         let runtime = Arc::new(runtime);
 
         let session = runtime.session();
         let abort = abort_rx.clone();
         let abort_ack = abort_ack_tx.clone();
+        let mut spawned_tasks = 0;
 
         if let Err(e) =
             queryables::spawn_runtime_queryable(session.clone(), runtime.clone(), abort, abort_ack)
@@ -114,10 +124,29 @@ impl Daemon {
                 e
             );
             // TODO: Clean everything up before aborting.
+        } else {
+            spawned_tasks += 1;
+        }
+
+        if let Err(e) = queryables::spawn_instances_queryable(
+            session.clone(),
+            runtime.clone(),
+            abort_rx.clone(),
+            abort_ack_tx.clone(),
+        )
+        .await
+        {
+            tracing::error!(
+                "The Zenoh-Flow daemon encountered a fatal error:\n{:?}\nAborting",
+                e
+            );
+            // TODO: Clean everything up before aborting.
+        } else {
+            spawned_tasks += 1;
         }
 
         if let Err(e) =
-            queryables::spawn_instances_queryable(session, runtime.clone(), abort_rx, abort_ack_tx)
+            queryables::spawn_events_publisher(session, runtime.clone(), abort_rx, abort_ack_tx)
                 .await
         {
             tracing::error!(
@@ -125,63 +154,72 @@ impl Daemon {
                 e
             );
             // TODO: Clean everything up before aborting.
+        } else {
+            spawned_tasks += 1;
         }
 
         Ok(Daemon {
             abort_tx,
             abort_ack_rx,
+            spawned_tasks,
             runtime,
         })
     }
 
     /// Stop the Zenoh-Flow daemon.
     ///
-    /// This method will first stop the queryables this daemon declared (to not process new requests) and then delete
-    /// all the data flow instances it manages.
+    /// This method will first stop the queryables this daemon declared (to not process new requests) and then:
+    /// - if a state directory was configured, [stop](Runtime::stop()) the embedded Runtime: the nodes it manages are
+    ///   aborted but the data flow instances are kept in the state directory, to be recovered when the daemon restarts,
+    /// - otherwise, delete all the data flow instances it manages.
     ///
-    /// ⚠️ If a data flow is spanning over multiple Daemons, stopping a single Daemon will delete the data flow instance
-    /// on all the Daemons.
+    /// ⚠️ Without a state directory, if a data flow is spanning over multiple Daemons, stopping a single Daemon will
+    /// delete the data flow instance on all the Daemons.
     pub async fn stop(&self) {
-        for iteration in 0..NUMBER_QUERYABLES {
+        for iteration in 0..self.spawned_tasks {
             tracing::trace!(
                 "Sending abort signal to queryable ({}/{})",
                 iteration + 1,
-                NUMBER_QUERYABLES
+                self.spawned_tasks
             );
             self.abort_tx.send_async(()).await.unwrap_or_else(|e| {
                 tracing::error!(
                     "Failed to send abort signal to queryable ({}/{}): {:?}",
                     iteration + 1,
-                    NUMBER_QUERYABLES,
+                    self.spawned_tasks,
                     e
                 );
             });
         }
 
-        let data_flows = self.runtime.instances_state().await;
-        let delete_requests = data_flows
-            .into_keys()
-            .map(|instance_id| delete_instance(self.runtime.clone(), Origin::Client, instance_id));
+        if self.runtime.persists_instances() {
+            self.runtime.stop().await;
+        } else {
+            let data_flows = self.runtime.instances_state().await;
+            let delete_requests = data_flows.into_keys().map(|instance_id| {
+                delete_instance(self.runtime.clone(), Origin::Client, instance_id)
+            });
 
-        futures::future::join_all(delete_requests).await;
+            futures::future::join_all(delete_requests).await;
+        }
 
         // TODO Introduce a timer: if, for whatever reason, a queryable fails to send an acknowledgement we should not
         // block the stopping procedure.
         //
         // Maybe wait for 60 seconds maximum?
-        for iteration in 0..NUMBER_QUERYABLES {
+        for iteration in 0..self.spawned_tasks {
             self.abort_ack_rx.recv_async().await.unwrap_or_else(|e| {
                 tracing::error!(
                     "Failed to receive abort acknowledgement ({}/{}): {:?}",
                     iteration + 1,
-                    NUMBER_QUERYABLES,
+                    self.spawned_tasks,
                     e
                 );
             });
             tracing::trace!(
                 "Received abort acknowledgement {}/{}",
                 iteration + 1,
-                NUMBER_QUERYABLES
+                self.spawned_tasks
             );
         }
     }
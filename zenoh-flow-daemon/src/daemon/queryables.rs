@@ -16,7 +16,7 @@ use std::sync::Arc;
 
 use anyhow::bail;
 use flume::{Receiver, Sender};
-use futures::select;
+use futures::{select, StreamExt};
 use zenoh::prelude::r#async::*;
 use zenoh_flow_commons::Result;
 use zenoh_flow_runtime::Runtime;
@@ -150,3 +150,68 @@ pub(crate) async fn spawn_runtime_queryable(
 
     Ok(())
 }
+
+/// Spawns an async task to publish, on `zenoh-flow/{runtime_id}/events`, the events of the data flow instances managed
+/// by the runtime.
+///
+/// Each event is published as the JSON serialisation of an [InstanceEvent](zenoh_flow_runtime::InstanceEvent).
+pub(crate) async fn spawn_events_publisher(
+    zenoh_session: Arc<Session>,
+    runtime: Arc<Runtime>,
+    abort_rx: Receiver<()>,
+    abort_ack_tx: Sender<()>,
+) -> Result<()> {
+    let ke_events = selectors::selector_events(runtime.id());
+
+    let publisher = match zenoh_session
+        .declare_publisher(ke_events.clone())
+        .res()
+        .await
+    {
+        Ok(publisher) => {
+            tracing::trace!("declared publisher < {} >", ke_events);
+            publisher
+        }
+        Err(e) => {
+            bail!("Failed to declare Zenoh publisher 'events': {:?}", e)
+        }
+    };
+
+    let mut events = runtime.subscribe_events().fuse();
+
+    async_std::task::spawn(async move {
+        loop {
+            select!(
+                _ = abort_rx.recv_async() => {
+                    tracing::trace!("Received abort signal");
+                    break;
+                }
+
+                event = events.next() => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => {
+                            tracing::error!("Stream of events closed");
+                            break;
+                        }
+                    };
+
+                    match serde_json::to_vec(&event) {
+                        Ok(payload) => {
+                            if let Err(e) = publisher.put(payload).res().await {
+                                tracing::error!("Failed to publish event: {:?}", e);
+                            }
+                        }
+                        Err(e) => tracing::error!("Failed to serialise event: {:?}", e),
+                    }
+                }
+            )
+        }
+
+        abort_ack_tx.send_async(()).await.unwrap_or_else(|e| {
+            tracing::error!("Publisher 'events' failed to acknowledge abort: {:?}", e);
+        });
+    });
+
+    Ok(())
+}
@@ -29,7 +29,7 @@ use anyhow::{anyhow, bail};
 use serde::Deserialize;
 use zenoh::{prelude::*, queryable::Query};
 use zenoh_flow_commons::Result;
-pub use zenoh_flow_runtime::InstanceStatus;
+pub use zenoh_flow_runtime::{InstanceEvent, InstanceEventKind, InstanceStatus};
 
 pub use self::{
     instances::{InstancesQuery, Origin},
@@ -18,6 +18,7 @@ use zenoh_flow_commons::RuntimeId;
 const ZENOH_FLOW: &str = "zenoh-flow";
 const INSTANCES: &str = "instances";
 const RUNTIMES: &str = "runtimes";
+const EVENTS: &str = "events";
 
 /// This function generates an [OwnedKeyExpr] from the provided String.
 ///
@@ -99,3 +100,26 @@ pub fn selector_runtimes(runtime_id: &RuntimeId) -> OwnedKeyExpr {
 pub fn selector_all_runtimes() -> OwnedKeyExpr {
     autocanonize(format!("{ZENOH_FLOW}/*/{RUNTIMES}"))
 }
+
+/// Helper function to generate an [OwnedKeyExpr] on which the provided runtime publishes the
+/// [events](zenoh_flow_runtime::InstanceEvent) of the data flow instances it manages.
+///
+/// The generated key expression has the following structure: `zenoh-flow/{runtime id}/events`.
+///
+/// # Panic
+///
+/// This function will panic in the impossible scenario (although never say never…) where the provided [RuntimeId] would
+/// make the key expression not valid or not canonical.
+pub fn selector_events(runtime_id: &RuntimeId) -> OwnedKeyExpr {
+    autocanonize(format!("{ZENOH_FLOW}/{runtime_id}/{EVENTS}"))
+}
+
+/// Helper function to generate an [OwnedKeyExpr] matching the events published by all the Zenoh-Flow runtimes.
+///
+/// # Panic
+///
+/// This function will panic in the impossible scenario where the key expression we internally rely on is no longer
+/// valid or canonical.
+pub fn selector_all_events() -> OwnedKeyExpr {
+    autocanonize(format!("{ZENOH_FLOW}/*/{EVENTS}"))
+}
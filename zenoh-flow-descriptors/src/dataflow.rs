@@ -17,10 +17,12 @@ use std::{
     sync::Arc,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use zenoh_flow_commons::{Configuration, InstanceId, NodeId, RuntimeId};
 
 use crate::{
+    diagnostics::SourceMap,
     nodes::{operator::OperatorDescriptor, sink::SinkDescriptor, source::SourceDescriptor},
     LinkDescriptor,
 };
@@ -233,7 +235,7 @@ use crate::{
 /// # "#;
 /// # let data_flow_yaml = serde_yaml::from_str::<DataFlowDescriptor>(yaml).unwrap();
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub struct DataFlowDescriptor {
     /// A unique identifier of an instance of this data flow.
     ///
@@ -267,6 +269,9 @@ pub struct DataFlowDescriptor {
     /// a mapping to the Zenoh-Flow runtime that was requested to instantiate the data flow.
     #[serde(default)]
     pub(crate) mapping: HashMap<RuntimeId, HashSet<NodeId>>,
+    // Where the elements of the data flow are declared, if it was loaded through a `DescriptorResolver`.
+    #[serde(skip)]
+    pub(crate) source_map: Option<Arc<SourceMap>>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,424 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{collections::HashMap, fmt::Display, ops::Deref, str::FromStr, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+use yaml_rust2::{
+    parser::{MarkedEventReceiver, Parser},
+    scanner::Marker,
+    Event,
+};
+use zenoh_flow_commons::{NodeId, RuntimeId};
+
+/// The position, in a descriptor, where an element of a data flow is declared.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Location {
+    /// The path of the descriptor if it was read from the file system, its URL otherwise.
+    pub file: Arc<str>,
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column, starting at 1.
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// A `Diagnostic` describes a problem detected in a data flow and, if it is known, where the element at fault is
+/// declared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// *(optional)* The location of the element at fault.
+    ///
+    /// The location is unknown if the element was not parsed from a descriptor (e.g. it was added through code) or if
+    /// the problem concerns the data flow as a whole.
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(message: impl AsRef<str>, location: Option<Location>) -> Self {
+        Self {
+            message: message.as_ref().trim().to_string(),
+            location,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// `Diagnostics` gathers all the problems detected when flattening a data flow.
+///
+/// This is the error returned by [try_flatten](crate::FlattenedDataFlowDescriptor::try_flatten()) when a node could
+/// not be flattened or when the flattened data flow is not valid. It can be retrieved from the returned error with
+/// `downcast_ref::<Diagnostics>()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub(crate) fn push(&mut self, diagnostic: Diagnostic) {
+        self.0.push(diagnostic)
+    }
+}
+
+impl Deref for Diagnostics {
+    type Target = [Diagnostic];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, diagnostic) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}/{}] {}", index + 1, self.0.len(), diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// A step in the path that leads to a value of a descriptor: the key of a mapping or the index of a sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+impl From<&str> for Segment {
+    fn from(key: &str) -> Self {
+        Self::Key(key.to_string())
+    }
+}
+
+impl From<usize> for Segment {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Position {
+    line: usize,
+    column: usize,
+    scalar: Option<String>,
+}
+
+/// A `SourceMap` records where each value of a descriptor is declared.
+///
+/// It is built from the content of the descriptor, once its [Vars](zenoh_flow_commons::Vars) have been expanded. As
+/// JSON is (for what matters to Zenoh-Flow) a subset of YAML, both formats are supported by the same parser.
+///
+/// Building a `SourceMap` never fails: if the content cannot be parsed, the values declared after the error are simply
+/// not located. The deserialization of the descriptor is the one reporting such errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceMap {
+    file: Arc<str>,
+    positions: HashMap<Vec<Segment>, Position>,
+}
+
+impl SourceMap {
+    pub(crate) fn new(url: &Url, content: &str) -> Self {
+        let file: Arc<str> = match url.scheme() {
+            "file" => url.path().into(),
+            _ => url.as_str().into(),
+        };
+
+        let mut builder = SourceMapBuilder::default();
+        let _ = Parser::new(content.chars()).load(&mut builder, false);
+
+        Self {
+            file,
+            positions: builder.positions,
+        }
+    }
+
+    fn location(&self, position: &Position) -> Location {
+        Location {
+            file: self.file.clone(),
+            line: position.line,
+            column: position.column,
+        }
+    }
+
+    /// Returns the location of the value found at the end of the `path`, if there is one.
+    pub(crate) fn locate(&self, path: &[Segment]) -> Option<Location> {
+        self.positions
+            .get(path)
+            .map(|position| self.location(position))
+    }
+
+    /// Returns the location of the identifier of the node declared at position `index` of the `section` (i.e.
+    /// "sources", "operators" or "sinks").
+    pub(crate) fn node(&self, section: &str, index: usize) -> Option<Location> {
+        self.locate(&[section.into(), index.into(), "id".into()])
+    }
+
+    /// Returns the location of the link declared at position `index`.
+    pub(crate) fn link(&self, index: usize) -> Option<Location> {
+        self.locate(&["links".into(), index.into()])
+    }
+
+    /// Returns the locations of the entries of the `mapping` section.
+    pub(crate) fn mapping(&self) -> HashMap<(RuntimeId, NodeId), Location> {
+        self.positions
+            .iter()
+            .filter_map(
+                |(path, position)| match (path.as_slice(), &position.scalar) {
+                    (
+                        [Segment::Key(section), Segment::Key(runtime_id), Segment::Index(_)],
+                        Some(node_id),
+                    ) if section == "mapping" => {
+                        RuntimeId::from_str(runtime_id).ok().map(|runtime_id| {
+                            (
+                                (runtime_id, node_id.as_str().into()),
+                                self.location(position),
+                            )
+                        })
+                    }
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+}
+
+enum Frame {
+    Mapping { key: Option<String>, first: bool },
+    Sequence { index: usize },
+}
+
+// Follows the events emitted by the parser, keeping track of the path leading to the current value.
+#[derive(Default)]
+struct SourceMapBuilder {
+    path: Vec<Segment>,
+    frames: Vec<Frame>,
+    positions: HashMap<Vec<Segment>, Position>,
+}
+
+impl SourceMapBuilder {
+    fn enter(&mut self, mark: Marker, scalar: Option<String>) {
+        match self.frames.last() {
+            Some(Frame::Mapping { key, .. }) => self
+                .path
+                .push(Segment::Key(key.clone().unwrap_or_default())),
+            Some(Frame::Sequence { index }) => self.path.push(Segment::Index(*index)),
+            None => {}
+        }
+
+        // NOTE: The lines of the parser start at 1 while its columns start at 0.
+        self.positions.insert(
+            self.path.clone(),
+            Position {
+                line: mark.line(),
+                column: mark.col() + 1,
+                scalar,
+            },
+        );
+    }
+
+    fn leave(&mut self) {
+        match self.frames.last_mut() {
+            Some(Frame::Mapping { key, .. }) => *key = None,
+            Some(Frame::Sequence { index }) => *index += 1,
+            None => return,
+        }
+
+        self.path.pop();
+    }
+}
+
+impl MarkedEventReceiver for SourceMapBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => {
+                if let Some(Frame::Mapping {
+                    key: key @ None,
+                    first,
+                }) = self.frames.last_mut()
+                {
+                    *key = Some(value);
+
+                    // NOTE: The parser marks a block mapping after its first key, we prefer pointing at the key.
+                    if *first {
+                        *first = false;
+                        if let Some(position) = self.positions.get_mut(&self.path) {
+                            position.line = mark.line();
+                            position.column = mark.col() + 1;
+                        }
+                    }
+                } else {
+                    self.enter(mark, Some(value));
+                    self.leave();
+                }
+            }
+            Event::Alias(_) => {
+                self.enter(mark, None);
+                self.leave();
+            }
+            Event::SequenceStart(..) => {
+                self.enter(mark, None);
+                self.frames.push(Frame::Sequence { index: 0 });
+            }
+            Event::MappingStart(..) => {
+                self.enter(mark, None);
+                self.frames.push(Frame::Mapping {
+                    key: None,
+                    first: true,
+                });
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                self.frames.pop();
+                self.leave();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Where the nodes, links and mapping entries of a flattened data flow are declared.
+///
+/// The locations of the nodes and links are stored in the same order as the nodes and links of the flattened data
+/// flow.
+#[derive(Debug, Default)]
+pub(crate) struct Locations {
+    pub(crate) sources: Vec<Option<Location>>,
+    pub(crate) operators: Vec<Option<Location>>,
+    pub(crate) sinks: Vec<Option<Location>>,
+    pub(crate) links: Vec<Option<Location>>,
+    pub(crate) mapping: HashMap<(RuntimeId, NodeId), Location>,
+}
+
+impl Locations {
+    // The locations of a flattened Operator that is not a Composite: the Operator itself and no link.
+    pub(crate) fn leaf(location: Option<Location>) -> Self {
+        Self {
+            operators: vec![location],
+            ..Self::default()
+        }
+    }
+
+    // Moves the locations of the nodes and links of `other` at the end of `self`, leaving `other` empty.
+    pub(crate) fn append(&mut self, other: &mut Self) {
+        self.sources.append(&mut other.sources);
+        self.operators.append(&mut other.operators);
+        self.sinks.append(&mut other.sinks);
+        self.links.append(&mut other.links);
+        self.mapping.extend(other.mapping.drain());
+    }
+
+    pub(crate) fn source(&self, index: usize) -> Option<Location> {
+        self.sources.get(index).cloned().flatten()
+    }
+
+    pub(crate) fn operator(&self, index: usize) -> Option<Location> {
+        self.operators.get(index).cloned().flatten()
+    }
+
+    pub(crate) fn sink(&self, index: usize) -> Option<Location> {
+        self.sinks.get(index).cloned().flatten()
+    }
+
+    pub(crate) fn link(&self, index: usize) -> Option<Location> {
+        self.links.get(index).cloned().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map() {
+        let url = Url::parse("file:///home/zenoh-flow/data-flow.yml").unwrap();
+        let runtime_id = RuntimeId::rand();
+        let yaml = format!(
+            r#"
+name: test
+sources:
+  - id: source-0
+    descriptor: file:///home/zenoh-flow/source.yml
+links:
+  - from:
+      node: source-0
+      output: out
+    to:
+      node: sink-0
+      input: in
+mapping:
+  {runtime_id}:
+    - source-0
+"#
+        );
+
+        let source_map = SourceMap::new(&url, &yaml);
+        let location = |line, column| {
+            Some(Location {
+                file: "/home/zenoh-flow/data-flow.yml".into(),
+                line,
+                column,
+            })
+        };
+        assert_eq!(source_map.node("sources", 0), location(4, 9));
+        assert_eq!(source_map.node("sources", 1), None);
+        assert_eq!(source_map.link(0), location(7, 5));
+        assert_eq!(
+            source_map.locate(&["links".into(), 0.into(), "to".into(), "input".into()]),
+            location(12, 14)
+        );
+        assert_eq!(
+            source_map.mapping(),
+            HashMap::from([(
+                (runtime_id, NodeId::from("source-0")),
+                location(15, 7).unwrap()
+            )])
+        );
+
+        let json = r#"{
+  "name": "test",
+  "sources": [
+    { "id": "source-0", "descriptor": "file:///home/zenoh-flow/source.yml" }
+  ]
+}"#;
+        let source_map = SourceMap::new(&url, json);
+        assert_eq!(source_map.node("sources", 0), location(4, 13));
+    }
+}
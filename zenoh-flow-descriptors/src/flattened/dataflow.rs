@@ -22,9 +22,10 @@ use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use zenoh_flow_commons::{Configuration, InstanceId, NodeId, Result, RuntimeId, Vars};
 
-use super::validator::Validator;
+use super::validator::{graph::Graph, Validator};
 use crate::{
-    DataFlowDescriptor, FlattenedOperatorDescriptor, FlattenedSinkDescriptor,
+    diagnostics::{Diagnostic, Diagnostics, Locations},
+    DataFlowDescriptor, DescriptorResolver, FlattenedOperatorDescriptor, FlattenedSinkDescriptor,
     FlattenedSourceDescriptor, LinkDescriptor,
 };
 
@@ -99,7 +100,10 @@ impl FlattenedDataFlowDescriptor {
     /// - all nodes, regardless of their type, have a different identifier,
     /// - no node has two inputs or two outputs with the same identifier,
     /// - all outputs are connected to at least one input,
-    /// - all inputs are connected to at least one output.
+    /// - all inputs are connected to at least one output,
+    /// - the mapping only references nodes of the data flow, each mapped to a single runtime,
+    /// - every loop is closed by a link marked as [feedback](crate::FeedbackDescriptor), providing an initial message
+    ///   and / or a capacity, and every link marked as feedback closes a loop.
     ///
     /// # Errors
     ///
@@ -108,23 +112,88 @@ impl FlattenedDataFlowDescriptor {
     /// - The flattening of a Source failed.
     /// - The flattening of a Sink failed.
     /// - The flattened data flow is not valid.
-    pub fn try_flatten(mut data_flow: DataFlowDescriptor, vars: Vars) -> Result<Self> {
+    ///
+    /// The flattening does not stop at the first problem: all of them are gathered in [Diagnostics], the error
+    /// returned, that can be retrieved with `downcast_ref::<Diagnostics>()`. If the data flow was loaded with
+    /// [try_load_data_flow](DescriptorResolver::try_load_data_flow()), each [Diagnostic] also indicates where, in the
+    /// descriptors, the element at fault is declared --- including in the descriptors of composite Operators.
+    pub fn try_flatten(data_flow: DataFlowDescriptor, vars: Vars) -> Result<Self> {
+        Self::try_flatten_with_resolver(data_flow, vars, &DescriptorResolver::default())
+    }
+
+    /// Flatten the provided [DataFlowDescriptor], loading the descriptors it references with the provided `resolver`.
+    ///
+    /// This method behaves exactly as [try_flatten](FlattenedDataFlowDescriptor::try_flatten()) except that it allows
+    /// loading descriptors from locations other than the file system, e.g. Zenoh or an HTTP server. See the
+    /// [DescriptorResolver] for more details.
+    ///
+    /// # Errors
+    ///
+    /// See [try_flatten](FlattenedDataFlowDescriptor::try_flatten()).
+    pub fn try_flatten_with_resolver(
+        mut data_flow: DataFlowDescriptor,
+        vars: Vars,
+        resolver: &DescriptorResolver,
+    ) -> Result<Self> {
+        let source_map = data_flow.source_map.take();
+        let locate = |section: &str, index: usize| {
+            source_map.as_ref().and_then(|map| map.node(section, index))
+        };
+
+        let mut diagnostics = Diagnostics::default();
+        let mut locations = Locations {
+            links: (0..data_flow.links.len())
+                .map(|index| source_map.as_ref().and_then(|map| map.link(index)))
+                .collect(),
+            mapping: source_map
+                .as_ref()
+                .map(|map| map.mapping())
+                .unwrap_or_default(),
+            ..Locations::default()
+        };
+
+        // The identifiers of the nodes that could not be flattened.
+        let mut unflattened = HashSet::new();
+
         let mut flattened_operators = Vec::with_capacity(data_flow.operators.len());
-        for operator_desc in data_flow.operators {
+        for (index, operator_desc) in data_flow.operators.into_iter().enumerate() {
             let operator_id = operator_desc.id.clone();
-            let (mut flat_ops, mut flat_links, patch) = FlattenedOperatorDescriptor::try_flatten(
-                operator_desc,
-                data_flow.configuration.clone(),
-                Configuration::default(),
-                vars.clone(),
-                &mut HashSet::default(),
-            )?;
+            let location = locate("operators", index);
+            let (mut flat_ops, mut flat_links, patch, mut flat_locations) =
+                match FlattenedOperatorDescriptor::try_flatten(
+                    operator_desc,
+                    location.clone(),
+                    data_flow.configuration.clone(),
+                    Configuration::default(),
+                    vars.clone(),
+                    &mut HashSet::default(),
+                    resolver,
+                ) {
+                    Ok(flattened) => flattened,
+                    Err(e) => {
+                        diagnostics.push(Diagnostic::new(format!("{:?}", e), location));
+                        unflattened.insert(operator_id);
+                        continue;
+                    }
+                };
 
             // Update the mapping: removing the id of the composite node & adding the "leaves".
             let flattened_ids: Vec<_> = flat_ops.iter().map(|op| op.id.clone()).collect();
-            for nodes in data_flow.mapping.values_mut() {
+            for (runtime_id, nodes) in data_flow.mapping.iter_mut() {
                 if nodes.remove(&operator_id) {
                     nodes.extend(flattened_ids.clone().into_iter());
+
+                    // The "leaves" are mapped where the composite node is.
+                    if let Some(location) = locations
+                        .mapping
+                        .remove(&(runtime_id.clone(), operator_id.clone()))
+                    {
+                        locations.mapping.extend(
+                            flattened_ids
+                                .iter()
+                                .map(|id| ((runtime_id.clone(), id.clone()), location.clone())),
+                        );
+                    }
                 }
             }
 
@@ -132,31 +201,50 @@ impl FlattenedDataFlowDescriptor {
             flattened_operators.append(&mut flat_ops);
             patch.apply(&mut data_flow.links);
             data_flow.links.append(&mut flat_links);
+            locations.append(&mut flat_locations);
         }
 
-        let sources = data_flow
-            .sources
-            .into_iter()
-            .map(|source_desc| {
-                FlattenedSourceDescriptor::try_flatten(
-                    source_desc,
-                    vars.clone(),
-                    data_flow.configuration.clone(),
-                )
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        let sinks = data_flow
-            .sinks
-            .into_iter()
-            .map(|sink_desc| {
-                FlattenedSinkDescriptor::try_flatten(
-                    sink_desc,
-                    vars.clone(),
-                    data_flow.configuration.clone(),
-                )
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let mut sources = Vec::with_capacity(data_flow.sources.len());
+        for (index, source_desc) in data_flow.sources.into_iter().enumerate() {
+            let location = locate("sources", index);
+            let source_id = source_desc.id.clone();
+            match FlattenedSourceDescriptor::try_flatten(
+                source_desc,
+                vars.clone(),
+                data_flow.configuration.clone(),
+                resolver,
+            ) {
+                Ok(flat_source) => {
+                    sources.push(flat_source);
+                    locations.sources.push(location);
+                }
+                Err(e) => {
+                    diagnostics.push(Diagnostic::new(format!("{:?}", e), location));
+                    unflattened.insert(source_id);
+                }
+            }
+        }
+
+        let mut sinks = Vec::with_capacity(data_flow.sinks.len());
+        for (index, sink_desc) in data_flow.sinks.into_iter().enumerate() {
+            let location = locate("sinks", index);
+            let sink_id = sink_desc.id.clone();
+            match FlattenedSinkDescriptor::try_flatten(
+                sink_desc,
+                vars.clone(),
+                data_flow.configuration.clone(),
+                resolver,
+            ) {
+                Ok(flat_sink) => {
+                    sinks.push(flat_sink);
+                    locations.sinks.push(location);
+                }
+                Err(e) => {
+                    diagnostics.push(Diagnostic::new(format!("{:?}", e), location));
+                    unflattened.insert(sink_id);
+                }
+            }
+        }
 
         let flattened_data_flow = Self {
             id: data_flow.id,
@@ -168,8 +256,19 @@ impl FlattenedDataFlowDescriptor {
             mapping: data_flow.mapping,
         };
 
-        Validator::validate(&flattened_data_flow)
-            .context("The provided data flow does not appear to be valid")?;
+        // NOTE: What could be flattened is still validated, the validator ignoring what relates to the nodes that could
+        //       not be: their absence would be reported on top of the reason why they could not be flattened.
+        if let Err(validation_diagnostics) =
+            Validator::validate(&flattened_data_flow, &locations, &unflattened)
+        {
+            for diagnostic in validation_diagnostics {
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        if !diagnostics.is_empty() {
+            return Err(diagnostics).context("The provided data flow does not appear to be valid");
+        }
 
         Ok(flattened_data_flow)
     }
@@ -186,6 +285,22 @@ impl FlattenedDataFlowDescriptor {
 
         None
     }
+
+    /// Returns the identifiers of the nodes in topological order: a node comes after all the nodes it receives
+    /// messages from, the links marked as [feedback](crate::FeedbackDescriptor) excluded.
+    ///
+    /// The Zenoh-Flow runtime relies on this order to start the nodes, the Sinks first, and to stop them, the Sources
+    /// first.
+    pub fn topological_order(&self) -> Vec<NodeId> {
+        let nodes = self
+            .sources
+            .iter()
+            .map(|source| &source.id)
+            .chain(self.operators.iter().map(|operator| &operator.id))
+            .chain(self.sinks.iter().map(|sink| &sink.id));
+
+        Graph::new(nodes, &self.links).topological_order()
+    }
 }
 
 #[cfg(test)]
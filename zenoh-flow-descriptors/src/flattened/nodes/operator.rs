@@ -19,17 +19,31 @@ use std::{
 };
 
 use anyhow::{bail, Context};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::{Configuration, IMergeOverwrite, NodeId, PortId, Result, Vars};
 
 use crate::{
+    diagnostics::{Location, Locations, SourceMap},
     flattened::{Patch, Substitutions},
-    nodes::operator::{
-        composite::CompositeOperatorDescriptor, CustomOperatorDescriptor, OperatorDescriptor,
-        OperatorVariants,
+    nodes::{
+        builtin::{
+            expression::{ExpressionDescriptor, ExpressionOperatorDescriptor},
+            routing::{
+                MergeDescriptor, MergeOperatorDescriptor, SplitDescriptor, SplitOperatorDescriptor,
+                TeeDescriptor, TeeOperatorDescriptor, ThrottleDescriptor,
+                ThrottleOperatorDescriptor,
+            },
+        },
+        operator::{
+            composite::CompositeOperatorDescriptor, CustomOperatorDescriptor, OperatorDescriptor,
+            OperatorVariants,
+        },
+        Isolation,
     },
-    uri, InputDescriptor, LinkDescriptor, OutputDescriptor,
+    uri::DescriptorResolver,
+    InputDescriptor, LinkDescriptor, OutputDescriptor,
 };
 
 /// A `FlattenedOperatorDescriptor` is a self-contained description of an Operator node.
@@ -39,9 +53,15 @@ pub struct FlattenedOperatorDescriptor {
     pub id: NodeId,
     /// A human-readable description of the Operator.
     pub description: Option<Arc<str>>,
-    /// The path to the implementation of the Operator.
-    #[serde(alias = "Library")]
-    pub library: Url,
+    /// The type of implementation of the Operator, either built-in or a path to a Library.
+    #[serde(flatten)]
+    pub operator: OperatorVariant,
+    /// The expected SHA-256 digest (hexadecimal) of the library implementing the Operator, verified before it is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Arc<str>>,
+    /// How the Operator is isolated from the Zenoh-Flow runtime, see [Isolation].
+    #[serde(default, skip_serializing_if = "Isolation::is_none")]
+    pub isolation: Isolation,
     /// The identifiers of the inputs the Operator uses.
     pub inputs: Vec<PortId>,
     /// The identifiers of the outputs the Operator uses.
@@ -51,12 +71,33 @@ pub struct FlattenedOperatorDescriptor {
     pub configuration: Configuration,
 }
 
+/// ⚠️ This is structure is intended for internal usage.
+///
+/// The implementation of an Operator: either a custom Operator with the location of its implementation, an expression
+/// built-in node with the expressions it evaluates or a routing built-in node (merge, split, tee or throttle).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatorVariant {
+    #[serde(alias = "Library")]
+    Library(Url),
+    Expression(ExpressionDescriptor),
+    Merge(MergeDescriptor),
+    Split(SplitDescriptor),
+    Tee(TeeDescriptor),
+    Throttle(ThrottleDescriptor),
+}
+
 /// The Operator variant after it has been fetched (if it was remote) but before it has been flattened.
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
-enum LocalOperatorVariants {
+pub(crate) enum LocalOperatorVariants {
     Composite(CompositeOperatorDescriptor),
     Custom(CustomOperatorDescriptor),
+    Expression(ExpressionOperatorDescriptor),
+    Merge(MergeOperatorDescriptor),
+    Split(SplitOperatorDescriptor),
+    Tee(TeeOperatorDescriptor),
+    Throttle(ThrottleOperatorDescriptor),
 }
 
 impl Display for FlattenedOperatorDescriptor {
@@ -66,6 +107,26 @@ impl Display for FlattenedOperatorDescriptor {
 }
 
 impl FlattenedOperatorDescriptor {
+    // Creates the descriptor of a built-in Operator: built-ins are not isolated and have no configuration.
+    fn builtin(
+        id: NodeId,
+        description: Option<Arc<str>>,
+        inputs: Vec<PortId>,
+        outputs: Vec<PortId>,
+        operator: OperatorVariant,
+    ) -> Self {
+        Self {
+            id,
+            description,
+            operator,
+            sha256: None,
+            isolation: Isolation::default(),
+            inputs,
+            outputs,
+            configuration: Configuration::default(),
+        }
+    }
+
     /// Attempts to flatten a [OperatorDescriptor] into a [FlattenedOperatorDescriptor].
     ///
     /// If the descriptor needs to be fetched this function will first fetch it, propagate and merge the overwriting
@@ -83,6 +144,10 @@ impl FlattenedOperatorDescriptor {
     ///
     /// Finally, we need to merge the different configurations.
     ///
+    /// The [Locations] of the flattened Operators and of the links of a Composite are returned alongside them, in the
+    /// same order. The `location` is where the Operator is declared: it is the location of the Operator if it is not a
+    /// Composite.
+    ///
     /// # Errors
     ///
     /// The flattening process can fail if:
@@ -92,11 +157,14 @@ impl FlattenedOperatorDescriptor {
     /// - we failed to flatten an Operator within a Composite for any of the above reasons.
     pub(crate) fn try_flatten(
         operator_descriptor: OperatorDescriptor,
+        location: Option<Location>,
         mut outer_configuration: Configuration,
         mut overwritting_configuration: Configuration,
         overwritting_vars: Vars,
         ancestors: &mut HashSet<Url>,
-    ) -> Result<(Vec<Self>, Vec<LinkDescriptor>, Patch)> {
+        resolver: &DescriptorResolver,
+    ) -> Result<(Vec<Self>, Vec<LinkDescriptor>, Patch, Locations)> {
+        let mut source_map: Option<SourceMap> = None;
         let descriptor = match operator_descriptor.variant {
             OperatorVariants::Remote(remote_desc) => {
                 if !ancestors.insert(remote_desc.descriptor.clone()) {
@@ -115,23 +183,34 @@ Possible infinite recursion detected, the following descriptor appears to includ
                     .configuration
                     .merge_overwrite(outer_configuration);
 
-                let (mut descriptor, _) = uri::try_load_descriptor::<LocalOperatorVariants>(
-                    &remote_desc.descriptor,
-                    overwritting_vars.clone(),
-                )
-                .context(format!(
-                    "Failed to load Operator from < {} >",
-                    &remote_desc.descriptor
-                ))?;
+                let (mut descriptor, _, descriptor_source_map) = resolver
+                    .try_load_located_descriptor::<LocalOperatorVariants>(
+                        &remote_desc.descriptor,
+                        overwritting_vars.clone(),
+                    )
+                    .context(format!(
+                        "Failed to load Operator from < {} >",
+                        &remote_desc.descriptor
+                    ))?;
 
                 if let LocalOperatorVariants::Custom(ref mut desc) = descriptor {
                     let description = desc.description.take();
                     desc.description = remote_desc.description.or(description);
                 }
 
+                source_map = Some(descriptor_source_map);
                 descriptor
             }
             OperatorVariants::Custom(custom_desc) => LocalOperatorVariants::Custom(custom_desc),
+            OperatorVariants::Expression(expression_desc) => {
+                LocalOperatorVariants::Expression(expression_desc)
+            }
+            OperatorVariants::Merge(merge_desc) => LocalOperatorVariants::Merge(merge_desc),
+            OperatorVariants::Split(split_desc) => LocalOperatorVariants::Split(split_desc),
+            OperatorVariants::Tee(tee_desc) => LocalOperatorVariants::Tee(tee_desc),
+            OperatorVariants::Throttle(throttle_desc) => {
+                LocalOperatorVariants::Throttle(throttle_desc)
+            }
         };
 
         match descriptor {
@@ -139,7 +218,9 @@ Possible infinite recursion detected, the following descriptor appears to includ
                 vec![Self {
                     id: operator_descriptor.id,
                     description: custom_desc.description,
-                    library: custom_desc.library,
+                    operator: OperatorVariant::Library(custom_desc.library),
+                    sha256: custom_desc.sha256,
+                    isolation: custom_desc.isolation,
                     inputs: custom_desc.inputs,
                     outputs: custom_desc.outputs,
                     // An inline operator's configuration has higher priority than the outer configuration. In turn, the
@@ -152,25 +233,102 @@ Possible infinite recursion detected, the following descriptor appears to includ
                 }],
                 vec![],
                 Patch::default(),
+                Locations::leaf(location),
+            )),
+            LocalOperatorVariants::Expression(expression_desc) => Ok((
+                vec![Self::builtin(
+                    operator_descriptor.id,
+                    expression_desc.description,
+                    vec![expression_desc.expression.input.clone()],
+                    vec![expression_desc.expression.output.clone()],
+                    OperatorVariant::Expression(expression_desc.expression),
+                )],
+                vec![],
+                Patch::default(),
+                Locations::leaf(location),
+            )),
+            LocalOperatorVariants::Merge(merge_desc) => Ok((
+                vec![Self::builtin(
+                    operator_descriptor.id,
+                    merge_desc.description,
+                    merge_desc.merge.inputs.clone(),
+                    vec![merge_desc.merge.output.clone()],
+                    OperatorVariant::Merge(merge_desc.merge),
+                )],
+                vec![],
+                Patch::default(),
+                Locations::leaf(location),
+            )),
+            LocalOperatorVariants::Split(split_desc) => Ok((
+                vec![Self::builtin(
+                    operator_descriptor.id,
+                    split_desc.description,
+                    vec![split_desc.split.input.clone()],
+                    split_desc.split.outputs(),
+                    OperatorVariant::Split(split_desc.split),
+                )],
+                vec![],
+                Patch::default(),
+                Locations::leaf(location),
+            )),
+            LocalOperatorVariants::Tee(tee_desc) => Ok((
+                vec![Self::builtin(
+                    operator_descriptor.id,
+                    tee_desc.description,
+                    vec![tee_desc.tee.input.clone()],
+                    tee_desc
+                        .tee
+                        .outputs
+                        .iter()
+                        .map(|output| output.output.clone())
+                        .collect(),
+                    OperatorVariant::Tee(tee_desc.tee),
+                )],
+                vec![],
+                Patch::default(),
+                Locations::leaf(location),
+            )),
+            LocalOperatorVariants::Throttle(throttle_desc) => Ok((
+                vec![Self::builtin(
+                    operator_descriptor.id,
+                    throttle_desc.description,
+                    vec![throttle_desc.throttle.input.clone()],
+                    vec![throttle_desc.throttle.output.clone()],
+                    OperatorVariant::Throttle(throttle_desc.throttle),
+                )],
+                vec![],
+                Patch::default(),
+                Locations::leaf(location),
             )),
             LocalOperatorVariants::Composite(mut composite_desc) => {
                 let mut flattened_operators = vec![];
+                let mut locations = Locations {
+                    links: (0..composite_desc.links.len())
+                        .map(|index| source_map.as_ref().and_then(|map| map.link(index)))
+                        .collect(),
+                    ..Locations::default()
+                };
 
                 overwritting_configuration =
                     overwritting_configuration.merge_overwrite(outer_configuration);
 
-                for operator_desc in composite_desc.operators {
-                    let (mut flat_ops, mut links, patch) = Self::try_flatten(
+                for (index, operator_desc) in composite_desc.operators.into_iter().enumerate() {
+                    let (mut flat_ops, mut links, patch, mut flat_locations) = Self::try_flatten(
                         operator_desc,
+                        source_map
+                            .as_ref()
+                            .and_then(|map| map.node("operators", index)),
                         composite_desc.configuration.clone(),
                         overwritting_configuration.clone(),
                         overwritting_vars.clone(),
                         ancestors,
+                        resolver,
                     )?;
 
                     flattened_operators.append(&mut flat_ops);
                     patch.apply(&mut composite_desc.links);
                     composite_desc.links.append(&mut links);
+                    locations.append(&mut flat_locations);
                 }
 
                 // We have processed all operators. Time to patch.
@@ -223,6 +381,7 @@ Possible infinite recursion detected, the following descriptor appears to includ
                     flattened_operators,
                     composite_desc.links,
                     Patch::new(subs_inputs, subs_outputs),
+                    locations,
                 ))
             }
         }
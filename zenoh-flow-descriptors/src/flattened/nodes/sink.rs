@@ -15,17 +15,25 @@
 use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use anyhow::Context;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::{Configuration, IMergeOverwrite, NodeId, PortId, Result, Vars};
-use zenoh_keyexpr::OwnedKeyExpr;
 
 use crate::{
     nodes::{
-        builtin::zenoh::ZenohSinkDescriptor,
+        builtin::{
+            file::{FileSinkDescriptor, FileWriterDescriptor},
+            stdio::{StdoutDescriptor, StdoutSinkDescriptor},
+            zenoh::{
+                ZenohPublisherDescriptor, ZenohQueryableDescriptor, ZenohQueryableSinkDescriptor,
+                ZenohSinkDescriptor,
+            },
+        },
         sink::{CustomSinkDescriptor, SinkDescriptor, SinkVariants},
+        Isolation,
     },
-    uri,
+    uri::DescriptorResolver,
 };
 
 /// A `FlattenedSinkDescriptor` is a self-contained description of a Sink node.
@@ -38,6 +46,12 @@ pub struct FlattenedSinkDescriptor {
     /// The type of implementation of the Sink, either built-in or a path to a Library.
     #[serde(flatten)]
     pub sink: SinkVariant,
+    /// The expected SHA-256 digest (hexadecimal) of the library implementing the Sink, verified before it is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Arc<str>>,
+    /// How the Sink is isolated from the Zenoh-Flow runtime, see [Isolation].
+    #[serde(default, skip_serializing_if = "Isolation::is_none")]
+    pub isolation: Isolation,
     /// The identifiers of the inputs the Sink uses.
     pub inputs: Vec<PortId>,
     /// Pairs of `(key, value)` to change the behaviour of the Sink without altering its implementation.
@@ -47,21 +61,29 @@ pub struct FlattenedSinkDescriptor {
 
 /// ⚠️ This is structure is intended for internal usage.
 ///
-/// The implementation of a Sink: either a custom Sink with the location of its implementation or a Zenoh built-in node
-/// with the list of key expressions to which it should publish.
+/// The implementation of a Sink: either a custom Sink with the location of its implementation, a Zenoh built-in node
+/// with the list of key expressions to which it should publish, or on which it should answer queries, or a file or
+/// stdout built-in node.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SinkVariant {
     Library(Url),
-    Zenoh(HashMap<PortId, OwnedKeyExpr>),
+    Zenoh(HashMap<PortId, ZenohPublisherDescriptor>),
+    #[serde(rename = "zenoh-queryables")]
+    ZenohQueryables(HashMap<PortId, ZenohQueryableDescriptor>),
+    File(FileWriterDescriptor),
+    Stdout(StdoutDescriptor),
 }
 
 /// The Sink variant after it has been fetched (if it was remote) but before it has been flattened.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
-enum LocalSinkVariants {
+pub(crate) enum LocalSinkVariants {
     Custom(CustomSinkDescriptor),
     Zenoh(ZenohSinkDescriptor),
+    ZenohQueryables(ZenohQueryableSinkDescriptor),
+    File(FileSinkDescriptor),
+    Stdout(StdoutSinkDescriptor),
 }
 
 impl Display for FlattenedSinkDescriptor {
@@ -85,17 +107,19 @@ impl FlattenedSinkDescriptor {
         sink_desc: SinkDescriptor,
         overwritting_vars: Vars,
         mut overwritting_configuration: Configuration,
+        resolver: &DescriptorResolver,
     ) -> Result<Self> {
         let descriptor = match sink_desc.variant {
             SinkVariants::Remote(remote_desc) => {
-                let (mut descriptor, _) = uri::try_load_descriptor::<LocalSinkVariants>(
-                    &remote_desc.descriptor,
-                    overwritting_vars,
-                )
-                .context(format!(
-                    "[{}] Failed to load sink descriptor from < {} >",
-                    sink_desc.id, &remote_desc.descriptor
-                ))?;
+                let (mut descriptor, _) = resolver
+                    .try_load_descriptor::<LocalSinkVariants>(
+                        &remote_desc.descriptor,
+                        overwritting_vars,
+                    )
+                    .context(format!(
+                        "[{}] Failed to load sink descriptor from < {} >",
+                        sink_desc.id, &remote_desc.descriptor
+                    ))?;
 
                 overwritting_configuration = remote_desc
                     .configuration
@@ -109,6 +133,11 @@ impl FlattenedSinkDescriptor {
                 descriptor
             }
             SinkVariants::Zenoh(zenoh_desc) => LocalSinkVariants::Zenoh(zenoh_desc),
+            SinkVariants::ZenohQueryables(zenoh_desc) => {
+                LocalSinkVariants::ZenohQueryables(zenoh_desc)
+            }
+            SinkVariants::File(file_desc) => LocalSinkVariants::File(file_desc),
+            SinkVariants::Stdout(stdout_desc) => LocalSinkVariants::Stdout(stdout_desc),
             SinkVariants::Custom(custom_desc) => {
                 overwritting_configuration = custom_desc
                     .clone()
@@ -123,6 +152,8 @@ impl FlattenedSinkDescriptor {
                 id: sink_desc.id,
                 description: custom_sink.description,
                 sink: SinkVariant::Library(custom_sink.library),
+                sha256: custom_sink.sha256,
+                isolation: custom_sink.isolation,
                 inputs: custom_sink.inputs,
                 configuration: overwritting_configuration
                     .merge_overwrite(custom_sink.configuration),
@@ -132,6 +163,35 @@ impl FlattenedSinkDescriptor {
                 description: zenoh_desc.description,
                 inputs: zenoh_desc.publishers.keys().cloned().collect(),
                 sink: SinkVariant::Zenoh(zenoh_desc.publishers),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSinkVariants::ZenohQueryables(zenoh_desc) => Ok(Self {
+                id: sink_desc.id,
+                description: zenoh_desc.description,
+                inputs: zenoh_desc.queryables.keys().cloned().collect(),
+                sink: SinkVariant::ZenohQueryables(zenoh_desc.queryables),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSinkVariants::File(file_desc) => Ok(Self {
+                id: sink_desc.id,
+                description: file_desc.description,
+                inputs: vec![file_desc.file.input.clone()],
+                sink: SinkVariant::File(file_desc.file),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSinkVariants::Stdout(stdout_desc) => Ok(Self {
+                id: sink_desc.id,
+                description: stdout_desc.description,
+                inputs: vec![stdout_desc.stdout.input.clone()],
+                sink: SinkVariant::Stdout(stdout_desc.stdout),
+                sha256: None,
+                isolation: Isolation::default(),
                 configuration: Configuration::default(),
             }),
         }
@@ -15,17 +15,27 @@
 use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use anyhow::Context;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::{Configuration, IMergeOverwrite, NodeId, PortId, Result, Vars};
-use zenoh_keyexpr::OwnedKeyExpr;
 
 use crate::{
     nodes::{
-        builtin::zenoh::ZenohSourceDescriptor,
+        builtin::{
+            file::{FileReaderDescriptor, FileSourceDescriptor},
+            replay::{ReplayDescriptor, ReplaySourceDescriptor},
+            stdio::{StdinDescriptor, StdinSourceDescriptor},
+            ticker::{TickerDescriptor, TickerSourceDescriptor},
+            zenoh::{
+                ZenohQuerierDescriptor, ZenohQuerierSourceDescriptor, ZenohSourceDescriptor,
+                ZenohSubscriberDescriptor,
+            },
+        },
         source::{CustomSourceDescriptor, SourceDescriptor, SourceVariants},
+        Isolation,
     },
-    uri,
+    uri::DescriptorResolver,
 };
 
 /// A `FlattenedSourceDescriptor` is a self-contained description of a Source node.
@@ -38,6 +48,12 @@ pub struct FlattenedSourceDescriptor {
     /// The type of implementation of the Source, either built-in or a path to a Library.
     #[serde(flatten)]
     pub source: SourceVariant,
+    /// The expected SHA-256 digest (hexadecimal) of the library implementing the Source, verified before it is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Arc<str>>,
+    /// How the Source is isolated from the Zenoh-Flow runtime, see [Isolation].
+    #[serde(default, skip_serializing_if = "Isolation::is_none")]
+    pub isolation: Isolation,
     /// The identifiers of the outputs the Source uses.
     pub outputs: Vec<PortId>,
     /// Pairs of `(key, value)` to change the behaviour of the Source without altering its implementation.
@@ -47,21 +63,32 @@ pub struct FlattenedSourceDescriptor {
 
 /// ⚠️ This is structure is intended for internal usage.
 ///
-/// The implementation of a Source: either a custom Source with the location of its implementation or a Zenoh built-in
-/// node with the list of key expressions to which it should subscribe.
+/// The implementation of a Source: either a custom Source with the location of its implementation, a Zenoh built-in
+/// node with the list of subscribers, or queriers, it should declare or a file, stdin, ticker or replay built-in node.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SourceVariant {
     Library(Url),
-    Zenoh(HashMap<PortId, OwnedKeyExpr>),
+    Zenoh(HashMap<PortId, ZenohSubscriberDescriptor>),
+    #[serde(rename = "zenoh-queriers")]
+    ZenohQueriers(HashMap<PortId, ZenohQuerierDescriptor>),
+    File(FileReaderDescriptor),
+    Stdin(StdinDescriptor),
+    Ticker(TickerDescriptor),
+    Replay(ReplayDescriptor),
 }
 
 /// The Source variant after it has been fetched (if it was remote) but before it has been flattened.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
-enum LocalSourceVariants {
+pub(crate) enum LocalSourceVariants {
     Custom(CustomSourceDescriptor),
     Zenoh(ZenohSourceDescriptor),
+    ZenohQueriers(ZenohQuerierSourceDescriptor),
+    File(FileSourceDescriptor),
+    Stdin(StdinSourceDescriptor),
+    Ticker(TickerSourceDescriptor),
+    Replay(ReplaySourceDescriptor),
 }
 
 impl Display for FlattenedSourceDescriptor {
@@ -85,17 +112,19 @@ impl FlattenedSourceDescriptor {
         source_desc: SourceDescriptor,
         overwritting_vars: Vars,
         mut overwritting_configuration: Configuration,
+        resolver: &DescriptorResolver,
     ) -> Result<Self> {
         let descriptor = match source_desc.variant {
             SourceVariants::Remote(remote_desc) => {
-                let (mut descriptor, _) = uri::try_load_descriptor::<LocalSourceVariants>(
-                    &remote_desc.descriptor,
-                    overwritting_vars,
-                )
-                .context(format!(
-                    "[{}] Failed to load source descriptor from < {} >",
-                    source_desc.id, &remote_desc.descriptor
-                ))?;
+                let (mut descriptor, _) = resolver
+                    .try_load_descriptor::<LocalSourceVariants>(
+                        &remote_desc.descriptor,
+                        overwritting_vars,
+                    )
+                    .context(format!(
+                        "[{}] Failed to load source descriptor from < {} >",
+                        source_desc.id, &remote_desc.descriptor
+                    ))?;
                 overwritting_configuration = remote_desc
                     .configuration
                     .merge_overwrite(overwritting_configuration);
@@ -108,6 +137,13 @@ impl FlattenedSourceDescriptor {
                 descriptor
             }
             SourceVariants::Zenoh(zenoh_desc) => LocalSourceVariants::Zenoh(zenoh_desc),
+            SourceVariants::ZenohQueriers(zenoh_desc) => {
+                LocalSourceVariants::ZenohQueriers(zenoh_desc)
+            }
+            SourceVariants::File(file_desc) => LocalSourceVariants::File(file_desc),
+            SourceVariants::Stdin(stdin_desc) => LocalSourceVariants::Stdin(stdin_desc),
+            SourceVariants::Ticker(ticker_desc) => LocalSourceVariants::Ticker(ticker_desc),
+            SourceVariants::Replay(replay_desc) => LocalSourceVariants::Replay(replay_desc),
             SourceVariants::Custom(custom_desc) => {
                 overwritting_configuration = custom_desc
                     .clone()
@@ -123,6 +159,8 @@ impl FlattenedSourceDescriptor {
                 id: source_desc.id,
                 description: custom_source.description,
                 source: SourceVariant::Library(custom_source.library),
+                sha256: custom_source.sha256,
+                isolation: custom_source.isolation,
                 outputs: custom_source.outputs,
                 configuration: overwritting_configuration
                     .merge_overwrite(custom_source.configuration),
@@ -132,6 +170,53 @@ impl FlattenedSourceDescriptor {
                 description: zenoh_desc.description,
                 outputs: zenoh_desc.subscribers.keys().cloned().collect(),
                 source: SourceVariant::Zenoh(zenoh_desc.subscribers),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSourceVariants::ZenohQueriers(zenoh_desc) => Ok(Self {
+                id: source_desc.id,
+                description: zenoh_desc.description,
+                outputs: zenoh_desc.queriers.keys().cloned().collect(),
+                source: SourceVariant::ZenohQueriers(zenoh_desc.queriers),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSourceVariants::File(file_desc) => Ok(Self {
+                id: source_desc.id,
+                description: file_desc.description,
+                outputs: vec![file_desc.file.output.clone()],
+                source: SourceVariant::File(file_desc.file),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSourceVariants::Stdin(stdin_desc) => Ok(Self {
+                id: source_desc.id,
+                description: stdin_desc.description,
+                outputs: vec![stdin_desc.stdin.output.clone()],
+                source: SourceVariant::Stdin(stdin_desc.stdin),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSourceVariants::Ticker(ticker_desc) => Ok(Self {
+                id: source_desc.id,
+                description: ticker_desc.description,
+                outputs: vec![ticker_desc.ticker.output.clone()],
+                source: SourceVariant::Ticker(ticker_desc.ticker),
+                sha256: None,
+                isolation: Isolation::default(),
+                configuration: Configuration::default(),
+            }),
+            LocalSourceVariants::Replay(replay_desc) => Ok(Self {
+                id: source_desc.id,
+                description: replay_desc.description,
+                outputs: vec![replay_desc.replay.output.clone()],
+                source: SourceVariant::Replay(replay_desc.replay),
+                sha256: None,
+                isolation: Isolation::default(),
                 configuration: Configuration::default(),
             }),
         }
@@ -20,14 +20,14 @@ use std::{
 use serde_json::json;
 use url::Url;
 use uuid::Uuid;
-use zenoh_flow_commons::{NodeId, RuntimeId, Vars};
+use zenoh_flow_commons::{CongestionControl, NodeId, Priority, QoS, RuntimeId, Vars};
 
 use crate::{
-    flattened::nodes::{sink::SinkVariant, source::SourceVariant},
-    uri::try_load_descriptor,
-    DataFlowDescriptor, FlattenedDataFlowDescriptor, FlattenedOperatorDescriptor,
-    FlattenedSinkDescriptor, FlattenedSourceDescriptor, InputDescriptor, LinkDescriptor,
-    OutputDescriptor,
+    flattened::nodes::{operator::OperatorVariant, sink::SinkVariant, source::SourceVariant},
+    DataFlowDescriptor, DescriptorResolver, Diagnostics, FlattenedDataFlowDescriptor,
+    FlattenedOperatorDescriptor, FlattenedSinkDescriptor, FlattenedSourceDescriptor,
+    InputDescriptor, Isolation, LinkDescriptor, MergeOrder, OutputDescriptor, Pacing, ReadMode,
+    ThrottlePolicy,
 };
 
 const BASE_DIR: &str = "./tests/descriptors";
@@ -49,19 +49,20 @@ fn test_flatten_descriptor() {
     let runtime_2 = RuntimeId::rand();
     let runtime_composite = RuntimeId::rand();
 
-    let (descriptor, vars) = try_load_descriptor::<DataFlowDescriptor>(
-        &Url::parse(&format!("file://{}/data-flow.yml", base_dir)).unwrap(),
-        Vars::from([
-            ("BASE_DIR", base_dir.as_str()),
-            ("RUNTIME_1", format!("{}", runtime_1).as_str()),
-            ("RUNTIME_2", format!("{}", runtime_2).as_str()),
-            (
-                "RUNTIME_COMPOSITE",
-                format!("{}", runtime_composite).as_str(),
-            ),
-        ]),
-    )
-    .expect("Failed to load DataFlowDescriptor");
+    let (descriptor, vars) = DescriptorResolver::default()
+        .try_load_descriptor::<DataFlowDescriptor>(
+            &Url::parse(&format!("file://{}/data-flow.yml", base_dir)).unwrap(),
+            Vars::from([
+                ("BASE_DIR", base_dir.as_str()),
+                ("RUNTIME_1", format!("{}", runtime_1).as_str()),
+                ("RUNTIME_2", format!("{}", runtime_2).as_str()),
+                (
+                    "RUNTIME_COMPOSITE",
+                    format!("{}", runtime_composite).as_str(),
+                ),
+            ]),
+        )
+        .expect("Failed to load DataFlowDescriptor");
 
     let flatten = FlattenedDataFlowDescriptor::try_flatten(descriptor, vars).unwrap();
 
@@ -71,6 +72,8 @@ fn test_flatten_descriptor() {
             description: Some("source".into()),
             outputs: vec!["source-out".into()],
             source: SourceVariant::Library(Url::parse("file://source.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer" }).into(),
         },
         FlattenedSourceDescriptor {
@@ -78,6 +81,8 @@ fn test_flatten_descriptor() {
             description: Some("source".into()),
             outputs: vec!["source-out".into()],
             source: SourceVariant::Library(Url::parse("file://source.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer" }).into(),
         },
         FlattenedSourceDescriptor {
@@ -88,6 +93,8 @@ fn test_flatten_descriptor() {
                 "source-composite-out-2".into(),
             ],
             source: SourceVariant::Library(Url::parse("file://source-composite.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer", "bar": "re-reverse" }).into(),
         },
     ];
@@ -111,7 +118,9 @@ fn test_flatten_descriptor() {
             description: Some("Outer description".into()),
             inputs: vec!["operator-in".into()],
             outputs: vec!["operator-out".into()],
-            library: Url::parse("file://operator.so").unwrap(),
+            operator: OperatorVariant::Library(Url::parse("file://operator.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer" }).into(),
         },
         FlattenedOperatorDescriptor {
@@ -119,7 +128,9 @@ fn test_flatten_descriptor() {
             description: Some("operator".into()),
             inputs: vec!["operator-in".into()],
             outputs: vec!["operator-out".into()],
-            library: Url::parse("file://operator.so").unwrap(),
+            operator: OperatorVariant::Library(Url::parse("file://operator.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer" }).into(),
         },
         /*
@@ -136,7 +147,9 @@ fn test_flatten_descriptor() {
             description: Some("leaf-operator-1".into()),
             inputs: vec!["sub-operator-1-in-1".into(), "sub-operator-1-in-2".into()],
             outputs: vec!["sub-operator-1-out".into()],
-            library: Url::parse("file://sub-operator-1.so").unwrap(),
+            operator: OperatorVariant::Library(Url::parse("file://sub-operator-1.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration:
                 json!({ "foo": "global-outer", "quux": "global-inner", "bar": "composite-outer" })
                     .into(),
@@ -152,7 +165,9 @@ fn test_flatten_descriptor() {
             description: Some("sub-leaf-operator-1".into()),
             inputs: vec!["sub-sub-operator-1-in".into()],
             outputs: vec!["sub-sub-operator-1-out".into()],
-            library: Url::parse("file://sub-sub-operator-1.so").unwrap(),
+            operator: OperatorVariant::Library(Url::parse("file://sub-sub-operator-1.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration:
                 json!({ "foo": "global-outer", "quux": "global-inner", "bar": "composite-outer", "buzz": "composite-inner", "baz": "leaf" }).into(),
         },
@@ -164,7 +179,9 @@ fn test_flatten_descriptor() {
             description: Some("sub-leaf-operator-2".into()),
             inputs: vec!["sub-sub-operator-2-in".into()],
             outputs: vec!["sub-sub-operator-2-out".into()],
-            library: Url::parse("file://sub-sub-operator-2.so").unwrap(),
+            operator: OperatorVariant::Library(Url::parse("file://sub-sub-operator-2.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration:
                 json!({ "foo": "global-outer", "quux": "global-inner", "bar": "composite-outer", "buzz": "composite-inner" }).into(),
         },
@@ -176,7 +193,9 @@ fn test_flatten_descriptor() {
             description: Some("leaf-operator-2".into()),
             inputs: vec!["sub-operator-2-in".into()],
             outputs: vec!["sub-operator-2-out-1".into(), "sub-operator-2-out-2".into()],
-            library: Url::parse("file://sub-operator-2.so").unwrap(),
+            operator: OperatorVariant::Library(Url::parse("file://sub-operator-2.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration:
                 json!({ "foo": "global-outer", "quux": "global-inner", "bar": "composite-outer" }).into(),
         },
@@ -202,6 +221,8 @@ fn test_flatten_descriptor() {
             description: Some("sink".into()),
             inputs: vec!["sink-in".into()],
             sink: SinkVariant::Library(Url::parse("file://sink.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer" }).into(),
         },
         FlattenedSinkDescriptor {
@@ -209,6 +230,8 @@ fn test_flatten_descriptor() {
             description: Some("sink".into()),
             inputs: vec!["sink-in".into()],
             sink: SinkVariant::Library(Url::parse("file://sink.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer" }).into(),
         },
         FlattenedSinkDescriptor {
@@ -216,6 +239,8 @@ fn test_flatten_descriptor() {
             description: Some("composite-sink".into()),
             inputs: vec!["sink-composite-in-1".into(), "sink-composite-in-2".into()],
             sink: SinkVariant::Library(Url::parse("file://sink-composite.so").unwrap()),
+            sha256: None,
+            isolation: Isolation::None,
             configuration: json!({ "foo": "global-outer", "bar": "reverse" }).into(),
         },
     ];
@@ -340,11 +365,12 @@ fn test_detect_recursion() {
     let base_dir = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), BASE_DIR);
     let url = Url::parse(&format!("{}{}/data-flow-recursion.yml", SCHEME, base_dir)).unwrap();
 
-    let (descriptor, vars) = try_load_descriptor::<DataFlowDescriptor>(
-        &url,
-        Vars::from([("BASE_DIR", base_dir.as_str()), ("SCHEME", SCHEME)]),
-    )
-    .expect("Failed to parse descriptor");
+    let (descriptor, vars) = DescriptorResolver::default()
+        .try_load_descriptor::<DataFlowDescriptor>(
+            &url,
+            Vars::from([("BASE_DIR", base_dir.as_str()), ("SCHEME", SCHEME)]),
+        )
+        .expect("Failed to parse descriptor");
     assert!(FlattenedDataFlowDescriptor::try_flatten(descriptor, vars).is_err());
 }
 
@@ -357,11 +383,12 @@ fn test_duplicate_composite_at_same_level_not_detected_as_recursion() {
     ))
     .unwrap();
 
-    let (descriptor, vars) = try_load_descriptor::<DataFlowDescriptor>(
-        &path,
-        Vars::from([("BASE_DIR", base_dir.as_str()), ("SCHEME", SCHEME)]),
-    )
-    .expect("Failed to parse descriptor");
+    let (descriptor, vars) = DescriptorResolver::default()
+        .try_load_descriptor::<DataFlowDescriptor>(
+            &path,
+            Vars::from([("BASE_DIR", base_dir.as_str()), ("SCHEME", SCHEME)]),
+        )
+        .expect("Failed to parse descriptor");
 
     assert!(FlattenedDataFlowDescriptor::try_flatten(descriptor, vars).is_ok());
 }
@@ -384,6 +411,7 @@ operators:
   - id: operator-1
     description: operator-1
     library: "file:///home/zenoh-flow/liboperator.so"
+    isolation: process
     inputs:
       - in-0
     outputs:
@@ -395,6 +423,7 @@ sinks:
   - id: sink-2
     description: sink-2
     library: "file:///home/zenoh-flow/libsink.so"
+    sha256: d18a363d51cc1e48db65a94fa9c870b492d1198649391cf7cb1f7d2f0a0143b6
     inputs:
       - in-1
     configuration:
@@ -429,6 +458,13 @@ links:
     assert_eq!(flat_flow_yaml, flat_flow_json);
     assert!(flat_flow_json.id.is_none());
     assert!(flat_flow_yaml.mapping.is_empty());
+    assert_eq!(flat_flow_json.operators[0].isolation, Isolation::Process);
+    assert_eq!(flat_flow_json.sources[0].isolation, Isolation::None);
+    assert_eq!(
+        flat_flow_json.sinks[0].sha256.as_deref(),
+        Some("d18a363d51cc1e48db65a94fa9c870b492d1198649391cf7cb1f7d2f0a0143b6")
+    );
+    assert!(flat_flow_json.sources[0].sha256.is_none());
 }
 
 #[test]
@@ -486,3 +522,864 @@ links:
     );
     assert!(flat_flow_yaml.mapping.is_empty());
 }
+
+#[test]
+fn test_zenoh_sink_qos() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+      - out-1
+
+sinks:
+  - id: zenoh-sink
+    description: zenoh-sink
+    zenoh-publishers:
+      status: rt/status
+      cmd_vel:
+        key-expr: rt/**/**/cmd_vel
+        encoding: application/json
+        dynamic-suffix: true
+        qos:
+          priority: real-time
+          congestion-control: block
+
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: zenoh-sink
+      input: cmd_vel
+    qos:
+      priority: data-high
+
+  - from:
+      node: source-0
+      output: out-1
+    to:
+      node: zenoh-sink
+      input: status
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let link = flat_flow
+        .links
+        .iter()
+        .find(|link| link.to.input.as_ref() == "cmd_vel")
+        .unwrap();
+    assert_eq!(link.qos.priority, Priority::DataHigh);
+    let publishers = match &flat_flow.sinks[0].sink {
+        SinkVariant::Zenoh(publishers) => publishers,
+        _ => panic!("Expected a Zenoh Sink"),
+    };
+    let status = &publishers[&"status".into()];
+    assert!(status.qos.is_default());
+    assert_eq!(status.encoding, None);
+    assert!(!status.dynamic_suffix);
+    let cmd_vel = &publishers[&"cmd_vel".into()];
+    assert_eq!(cmd_vel.encoding.as_deref(), Some("application/json"));
+    assert!(cmd_vel.dynamic_suffix);
+    // The key expression is canonised.
+    assert_eq!(cmd_vel.key_expr.as_str(), "rt/**/cmd_vel");
+    assert_eq!(
+        cmd_vel.qos,
+        QoS {
+            priority: Priority::RealTime,
+            congestion_control: CongestionControl::Block,
+            ..Default::default()
+        }
+    );
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+}
+
+#[test]
+fn test_zenoh_source_sample_timestamp() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: zenoh-source
+    description: zenoh-source
+    zenoh-subscribers:
+      status: rt/*/status
+      cmd_vel:
+        key-expr: rt/**/**/cmd_vel
+        use-sample-timestamp: true
+
+sinks:
+  - id: sink-0
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-0
+      - in-1
+
+links:
+  - from:
+      node: zenoh-source
+      output: cmd_vel
+    to:
+      node: sink-0
+      input: in-0
+
+  - from:
+      node: zenoh-source
+      output: status
+    to:
+      node: sink-0
+      input: in-1
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let subscribers = match &flat_flow.sources[0].source {
+        SourceVariant::Zenoh(subscribers) => subscribers,
+        _ => panic!("Expected a Zenoh Source"),
+    };
+    assert!(!subscribers[&"status".into()].use_sample_timestamp);
+    let cmd_vel = &subscribers[&"cmd_vel".into()];
+    // The key expression is canonised.
+    assert_eq!(cmd_vel.key_expr.as_str(), "rt/**/cmd_vel");
+    assert!(cmd_vel.use_sample_timestamp);
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+}
+
+#[test]
+fn test_zenoh_queriers() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: zenoh-querier
+    description: zenoh-querier
+    zenoh-queriers:
+      status: rt/*/status
+      temperatures:
+        selector: home/**/**/temperature?_time=[now(-1m)..]
+        period: 1s
+        timeout: 500ms
+        use-sample-timestamp: true
+
+sinks:
+  - id: sink-0
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-0
+      - in-1
+
+links:
+  - from:
+      node: zenoh-querier
+      output: status
+    to:
+      node: sink-0
+      input: in-0
+
+  - from:
+      node: zenoh-querier
+      output: temperatures
+    to:
+      node: sink-0
+      input: in-1
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let queriers = match &flat_flow.sources[0].source {
+        SourceVariant::ZenohQueriers(queriers) => queriers,
+        _ => panic!("Expected a Zenoh querier Source"),
+    };
+    let status = &queriers[&"status".into()];
+    assert_eq!(status.selector.as_ref(), "rt/*/status");
+    assert_eq!(status.period, None);
+    assert_eq!(status.timeout, None);
+    assert!(!status.use_sample_timestamp);
+    let temperatures = &queriers[&"temperatures".into()];
+    // Only the key expression of the selector is canonised.
+    assert_eq!(
+        temperatures.selector.as_ref(),
+        "home/**/temperature?_time=[now(-1m)..]"
+    );
+    assert_eq!(temperatures.period, Some(1_000_000));
+    assert_eq!(temperatures.timeout, Some(500_000));
+    assert!(temperatures.use_sample_timestamp);
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("period: 1s", "period: 0s")
+    )
+    .is_err());
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("timeout: 500ms", "timeout: 0ms")
+    )
+    .is_err());
+}
+
+#[test]
+fn test_zenoh_queryables() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+      - out-1
+
+sinks:
+  - id: zenoh-queryable
+    description: zenoh-queryable
+    zenoh-queryables:
+      status: rt/status
+      cmd_vel:
+        key-expr: rt/**/**/cmd_vel
+        history: 10
+
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: zenoh-queryable
+      input: cmd_vel
+
+  - from:
+      node: source-0
+      output: out-1
+    to:
+      node: zenoh-queryable
+      input: status
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let queryables = match &flat_flow.sinks[0].sink {
+        SinkVariant::ZenohQueryables(queryables) => queryables,
+        _ => panic!("Expected a Zenoh queryable Sink"),
+    };
+    assert_eq!(queryables[&"status".into()].history, 1);
+    let cmd_vel = &queryables[&"cmd_vel".into()];
+    // The key expression is canonised.
+    assert_eq!(cmd_vel.key_expr.as_str(), "rt/**/cmd_vel");
+    assert_eq!(cmd_vel.history, 10);
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+
+    let empty_history = flow_yaml.replace("history: 10", "history: 0");
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(&empty_history).is_err());
+}
+
+#[test]
+fn test_builtin_io_nodes() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: file-reader
+    description: file-reader
+    file:
+      path: /home/zenoh-flow/video.raw
+      read: chunks
+      chunk-size: 64KiB
+      output: frames
+
+  - id: stdin
+    stdin: {}
+
+  - id: ticker
+    ticker:
+      period: 100ms
+
+  - id: replay
+    replay:
+      path: /var/log/zenoh-flow/source-out.zfrec
+      pacing:
+        scaled: 2.0
+
+sinks:
+  - id: file-writer
+    file:
+      path: /var/log/zenoh-flow/frames.raw
+      newline: false
+      rotation:
+        max-size: 10MiB
+      input: frames
+
+  - id: stdout
+    stdout: {}
+
+  - id: sink-0
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-0
+      - in-1
+
+links:
+  - from:
+      node: file-reader
+      output: frames
+    to:
+      node: file-writer
+      input: frames
+
+  - from:
+      node: stdin
+      output: out
+    to:
+      node: stdout
+      input: in
+
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: sink-0
+      input: in-0
+
+  - from:
+      node: replay
+      output: out
+    to:
+      node: sink-0
+      input: in-1
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let source = |id: &str| {
+        flat_flow
+            .sources
+            .iter()
+            .find(|source| source.id.as_ref() == id)
+            .unwrap()
+    };
+    let sink = |id: &str| {
+        flat_flow
+            .sinks
+            .iter()
+            .find(|sink| sink.id.as_ref() == id)
+            .unwrap()
+    };
+
+    match &source("file-reader").source {
+        SourceVariant::File(file) => {
+            assert_eq!(file.read, ReadMode::Chunks);
+            assert_eq!(file.chunk_size, 64 * 1024);
+        }
+        _ => panic!("Expected a file Source"),
+    }
+    assert_eq!(source("file-reader").outputs, vec!["frames".into()]);
+    assert_eq!(source("stdin").outputs, vec!["out".into()]);
+    match &source("ticker").source {
+        SourceVariant::Ticker(ticker) => assert_eq!(ticker.period, 100_000),
+        _ => panic!("Expected a ticker Source"),
+    }
+    match &source("replay").source {
+        SourceVariant::Replay(replay) => assert_eq!(replay.pacing, Pacing::Scaled(2.0)),
+        _ => panic!("Expected a replay Source"),
+    }
+    assert_eq!(source("replay").outputs, vec!["out".into()]);
+
+    match &sink("file-writer").sink {
+        SinkVariant::File(file) => {
+            assert!(!file.newline);
+            let rotation = file.rotation.unwrap();
+            assert_eq!(rotation.max_size, 10 * 1024 * 1024);
+            assert_eq!(rotation.max_files, 5);
+        }
+        _ => panic!("Expected a file Sink"),
+    }
+    match &sink("stdout").sink {
+        SinkVariant::Stdout(stdout) => assert!(stdout.newline),
+        _ => panic!("Expected a stdout Sink"),
+    }
+    assert_eq!(sink("stdout").inputs, vec!["in".into()]);
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("chunk-size: 64KiB", "chunk-size: 0")
+    )
+    .is_err());
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("scaled: 2.0", "scaled: -1.0")
+    )
+    .is_err());
+}
+
+#[test]
+fn test_expression_operator() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+
+operators:
+  - id: fast-robots
+    expression:
+      filter: $.speed > 1.5 && $.status == 'ok'
+      map:
+        id: $.robot.id
+        speed: $.speed * 3.6
+      input: robots
+
+sinks:
+  - id: sink-0
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-0
+
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: fast-robots
+      input: robots
+
+  - from:
+      node: fast-robots
+      output: out
+    to:
+      node: sink-0
+      input: in-0
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let operator = &flat_flow.operators[0];
+    assert_eq!(operator.inputs, vec!["robots".into()]);
+    assert_eq!(operator.outputs, vec!["out".into()]);
+    match &operator.operator {
+        OperatorVariant::Expression(expression) => {
+            let filter = expression.filter.as_ref().unwrap();
+            assert!(filter.is_satisfied_by(&json!({ "speed": 2, "status": "ok" })));
+            assert!(!filter.is_satisfied_by(&json!({ "speed": 2, "status": "ko" })));
+        }
+        _ => panic!("Expected an expression Operator"),
+    }
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+
+    // Invalid expressions are rejected when the descriptor is parsed.
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("$.speed * 3.6", "$.speed *")
+    )
+    .is_err());
+}
+
+#[test]
+fn test_routing_operators() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+      - out-1
+
+operators:
+  - id: merge
+    merge:
+      inputs: [left, right]
+      order: timestamp
+      window: 50ms
+
+  - id: split
+    split:
+      routes:
+        - output: hot
+          when: $.temperature > 30
+        - output: warm
+          when: $.temperature > 20
+      otherwise: cold
+      first-match: true
+
+  - id: tee
+    tee:
+      outputs:
+        - archive
+        - output: ui
+          lossy: true
+          max-pending: 8
+
+  - id: throttle
+    throttle:
+      max-messages: 10
+      period: 1s
+      policy: delay
+
+sinks:
+  - id: sink-0
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - hot
+      - warm
+      - cold
+      - archive
+
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: merge
+      input: left
+  - from:
+      node: source-0
+      output: out-1
+    to:
+      node: merge
+      input: right
+  - from:
+      node: merge
+      output: out
+    to:
+      node: throttle
+      input: in
+  - from:
+      node: throttle
+      output: out
+    to:
+      node: tee
+      input: in
+  - from:
+      node: tee
+      output: archive
+    to:
+      node: sink-0
+      input: archive
+  - from:
+      node: tee
+      output: ui
+    to:
+      node: split
+      input: in
+  - from:
+      node: split
+      output: hot
+    to:
+      node: sink-0
+      input: hot
+  - from:
+      node: split
+      output: warm
+    to:
+      node: sink-0
+      input: warm
+  - from:
+      node: split
+      output: cold
+    to:
+      node: sink-0
+      input: cold
+"#;
+
+    let flat_flow = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .unwrap();
+
+    let operator = |id: &str| {
+        flat_flow
+            .operators
+            .iter()
+            .find(|operator| operator.id.as_ref() == id)
+            .unwrap()
+    };
+
+    assert_eq!(
+        operator("merge").inputs,
+        vec!["left".into(), "right".into()]
+    );
+    match &operator("merge").operator {
+        OperatorVariant::Merge(merge) => {
+            assert_eq!(merge.order, MergeOrder::Timestamp);
+            assert_eq!(merge.window, 50_000);
+        }
+        _ => panic!("Expected a merge Operator"),
+    }
+
+    assert_eq!(
+        operator("split").outputs,
+        vec!["hot".into(), "warm".into(), "cold".into()]
+    );
+    match &operator("split").operator {
+        OperatorVariant::Split(split) => assert!(split.first_match),
+        _ => panic!("Expected a split Operator"),
+    }
+
+    match &operator("tee").operator {
+        OperatorVariant::Tee(tee) => {
+            assert!(!tee.outputs[0].lossy);
+            assert_eq!(tee.outputs[0].max_pending, 1);
+            assert!(tee.outputs[1].lossy);
+            assert_eq!(tee.outputs[1].max_pending, 8);
+        }
+        _ => panic!("Expected a tee Operator"),
+    }
+
+    match &operator("throttle").operator {
+        OperatorVariant::Throttle(throttle) => {
+            assert_eq!(throttle.max_messages, 10);
+            assert_eq!(throttle.period, 1_000_000);
+            assert_eq!(throttle.policy, ThrottlePolicy::Delay);
+        }
+        _ => panic!("Expected a throttle Operator"),
+    }
+
+    let json_string_flow = serde_json::to_string(&flat_flow).unwrap();
+    assert_eq!(
+        flat_flow,
+        serde_json::from_str::<FlattenedDataFlowDescriptor>(&json_string_flow).unwrap()
+    );
+
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("max-messages: 10", "max-messages: 0")
+    )
+    .is_err());
+    assert!(serde_yaml::from_str::<DataFlowDescriptor>(
+        &flow_yaml.replace("inputs: [left, right]", "inputs: []")
+    )
+    .is_err());
+}
+
+#[test]
+fn test_mapping_validation() {
+    let base_dir = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), BASE_DIR);
+    let runtime_1 = RuntimeId::rand();
+    let runtime_2 = RuntimeId::rand();
+
+    let try_flatten_with_mapping = |node_id: &str, runtime_id: &RuntimeId| {
+        let (mut descriptor, vars) = DescriptorResolver::default()
+            .try_load_descriptor::<DataFlowDescriptor>(
+                &Url::parse(&format!("file://{}/data-flow.yml", base_dir)).unwrap(),
+                Vars::from([
+                    ("BASE_DIR", base_dir.as_str()),
+                    ("RUNTIME_1", format!("{}", runtime_1).as_str()),
+                    ("RUNTIME_2", format!("{}", runtime_2).as_str()),
+                    ("RUNTIME_COMPOSITE", format!("{}", runtime_2).as_str()),
+                ]),
+            )
+            .expect("Failed to load DataFlowDescriptor");
+        descriptor
+            .mapping
+            .entry(runtime_id.clone())
+            .or_default()
+            .insert(node_id.into());
+
+        FlattenedDataFlowDescriptor::try_flatten(descriptor, vars)
+    };
+
+    let res = try_flatten_with_mapping("source-l", &runtime_1);
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains(
+        "The mapping references the following node(s) that are not part of the data flow:"
+    ));
+    assert!(format!("{:?}", res).contains(&format!("- source-l (runtime: {runtime_1})")));
+
+    let res = try_flatten_with_mapping("operator-composite>sub-operator-composite", &runtime_1);
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains(
+        "< operator-composite>sub-operator-composite > is a composite Operator, it was replaced by its Operators: \
+         operator-composite>sub-operator-composite>sub-sub-operator-1, \
+         operator-composite>sub-operator-composite>sub-sub-operator-2"
+    ));
+
+    let res = try_flatten_with_mapping("sub-operator-1", &runtime_1);
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains("did you mean: operator-composite>sub-operator-1?"));
+
+    let res = try_flatten_with_mapping("source-1", &runtime_2);
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains(
+        "A node can only be mapped to a single runtime, the following node(s) are mapped to several:"
+    ));
+    let mut runtimes = [runtime_1.to_string(), runtime_2.to_string()];
+    runtimes.sort();
+    assert!(format!("{:?}", res).contains(&format!("- source-1: {}", runtimes.join(", "))));
+
+    // The Operators of a composite Operator are mapped to the runtime of the composite Operator.
+    let res = try_flatten_with_mapping("operator-composite>sub-operator-1", &runtime_1);
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains("- operator-composite>sub-operator-1: "));
+}
+
+#[test]
+fn test_diagnostics() {
+    let base_dir = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), BASE_DIR);
+    let (descriptor, vars) = DescriptorResolver::default()
+        .try_load_data_flow(
+            &Url::parse(&format!("file://{}/data-flow-invalid.yml", base_dir)).unwrap(),
+            Vars::from([("BASE_DIR", base_dir.as_str())]),
+        )
+        .expect("Failed to load DataFlowDescriptor");
+
+    let error = FlattenedDataFlowDescriptor::try_flatten(descriptor, vars)
+        .expect_err("The data flow should not be valid");
+    let diagnostics = error
+        .downcast_ref::<Diagnostics>()
+        .expect("The problems should be reported as Diagnostics");
+
+    let location_of = |message: &str| {
+        let diagnostic = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.message.contains(message))
+            .unwrap_or_else(|| panic!("Missing diagnostic: {message}\n{diagnostics}"));
+        let location = diagnostic.location.as_ref().expect("Missing location");
+        (
+            location.file.rsplit('/').next().unwrap().to_string(),
+            location.line,
+            location.column,
+        )
+    };
+
+    assert_eq!(diagnostics.len(), 4, "{diagnostics}");
+
+    // The second declaration of `source-1` is the one reported.
+    assert_eq!(
+        location_of("Two nodes share the same identifier: < source-1 >"),
+        ("data-flow-invalid.yml".to_string(), 11, 9)
+    );
+    assert_eq!(
+        location_of(
+            "Node < source-1 > declares the following output (at least) twice: < source-out >"
+        ),
+        ("data-flow-invalid.yml".to_string(), 11, 9)
+    );
+
+    // The elements of a composite Operator are located in its descriptor.
+    assert_eq!(
+        location_of("Does it declare an output named < sub-operator-1-ouuuuut >?"),
+        ("operator-composite-invalid.yml".to_string(), 28, 5)
+    );
+    assert_eq!(
+        location_of("- operator-composite>sub-operator-1: sub-operator-1-out"),
+        ("operator-composite-invalid.yml".to_string(), 17, 9)
+    );
+}
+
+#[test]
+fn test_diagnostics_unflattened_node() {
+    let flow_yaml = r#"
+name: test-flow
+
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+      - out-1
+
+sinks:
+  - id: sink-0
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-0
+      - in-1
+
+  - id: sink-missing
+    descriptor: "file:///home/zenoh-flow/does-not-exist.yml"
+
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: sink-0
+      input: in-0
+
+  - from:
+      node: source-0
+      output: out-1
+    to:
+      node: sink-missing
+      input: in
+
+mapping:
+  a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6:
+    - sink-missing
+"#;
+
+    let error = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str::<DataFlowDescriptor>(flow_yaml).unwrap(),
+        Vars::default(),
+    )
+    .expect_err("The data flow should not be valid");
+    let diagnostics = error
+        .downcast_ref::<Diagnostics>()
+        .expect("The problems should be reported as Diagnostics");
+
+    // The Sink that could not be flattened is reported, what could be flattened is validated and nothing is reported
+    // about the links and mapping entries of the missing Sink.
+    assert_eq!(diagnostics.len(), 2, "{diagnostics}");
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.message.contains("does-not-exist.yml")));
+    assert!(diagnostics.iter().any(|diagnostic| diagnostic
+        .message
+        .contains("The following inputs are not connected:\n- sink-0: in-1")));
+}
@@ -0,0 +1,198 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use zenoh_flow_commons::NodeId;
+
+use crate::LinkDescriptor;
+
+/// The directed graph formed by the nodes of a data flow and the links connecting them.
+///
+/// Unless stated otherwise, the links marked as [feedback](crate::FeedbackDescriptor) are ignored: they are expected
+/// to close the loops of the data flow.
+pub(crate) struct Graph<'a> {
+    // NOTE: A `BTreeMap` is used such that the results do not depend on the order in which the nodes were declared.
+    links: BTreeMap<&'a NodeId, Vec<&'a LinkDescriptor>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+impl<'a> Graph<'a> {
+    pub(crate) fn new(
+        nodes: impl IntoIterator<Item = &'a NodeId>,
+        links: &'a [LinkDescriptor],
+    ) -> Self {
+        let mut graph: BTreeMap<_, Vec<_>> =
+            nodes.into_iter().map(|node| (node, Vec::new())).collect();
+        for link in links {
+            graph.entry(&link.from.node).or_default().push(link);
+        }
+
+        Self { links: graph }
+    }
+
+    // Returns the links leaving `node`, the ones marked as feedback included only if `with_feedback` is true.
+    fn outgoing(
+        &self,
+        node: &NodeId,
+        with_feedback: bool,
+    ) -> impl Iterator<Item = &'a LinkDescriptor> + '_ {
+        self.links
+            .get(node)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |link| with_feedback || link.feedback.is_none())
+    }
+
+    /// Returns the links forming a cycle, the first one leaving the node where the cycle starts and the last one
+    /// reaching it, or `None` if there are no cycles.
+    pub(crate) fn find_cycle(&self) -> Option<Vec<&'a LinkDescriptor>> {
+        let mut visits = HashMap::with_capacity(self.links.len());
+
+        for node in self.links.keys() {
+            if !visits.contains_key(node) {
+                if let Some(cycle) = self.visit(node, &mut visits) {
+                    return Some(cycle);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Depth-first visit of the graph from `start`: a cycle exists if we reach a node whose visit is in progress.
+    //
+    // NOTE: The visit is iterative such that a long chain of nodes cannot overflow the stack. For each node whose visit
+    //       is in progress, the `stack` holds the links that remain to be followed and the `path` the link that led to
+    //       it.
+    fn visit(
+        &self,
+        start: &'a NodeId,
+        visits: &mut HashMap<&'a NodeId, Visit>,
+    ) -> Option<Vec<&'a LinkDescriptor>> {
+        let mut path: Vec<&'a LinkDescriptor> = Vec::new();
+        let mut stack = vec![(
+            start,
+            self.outgoing(start, false).collect::<Vec<_>>().into_iter(),
+        )];
+        visits.insert(start, Visit::InProgress);
+
+        while let Some((node, links)) = stack.last_mut() {
+            let node = *node;
+            let Some(link) = links.next() else {
+                visits.insert(node, Visit::Done);
+                stack.pop();
+                path.pop();
+                continue;
+            };
+
+            match visits.get(&link.to.node) {
+                Some(Visit::InProgress) => {
+                    path.push(link);
+                    let start = path
+                        .iter()
+                        .position(|link_in_path| link_in_path.from.node == link.to.node)
+                        .expect("the node whose visit is in progress should be on the path");
+                    return Some(path[start..].to_vec());
+                }
+                Some(Visit::Done) => {}
+                None => {
+                    path.push(link);
+                    visits.insert(&link.to.node, Visit::InProgress);
+                    stack.push((
+                        &link.to.node,
+                        self.outgoing(&link.to.node, false)
+                            .collect::<Vec<_>>()
+                            .into_iter(),
+                    ));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns `true` if `to` can be reached from `from`, following all the links, feedback included.
+    pub(crate) fn reaches(&self, from: &NodeId, to: &NodeId) -> bool {
+        let mut visited = HashSet::from([from]);
+        let mut to_visit = VecDeque::from([from]);
+
+        while let Some(node) = to_visit.pop_front() {
+            for link in self.outgoing(node, true) {
+                if &link.to.node == to {
+                    return true;
+                }
+
+                if visited.insert(&link.to.node) {
+                    to_visit.push_back(&link.to.node);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Returns the nodes in topological order: a node comes after all the nodes it receives messages from.
+    ///
+    /// Among the nodes that could come next, the one with the smallest identifier is selected first such that the
+    /// order is deterministic. If the graph contains cycles, the nodes that could not be ordered come last.
+    pub(crate) fn topological_order(&self) -> Vec<NodeId> {
+        let mut incoming: HashMap<&NodeId, usize> =
+            self.links.keys().map(|node| (*node, 0)).collect();
+        for node in self.links.keys() {
+            for link in self.outgoing(node, false) {
+                *incoming.entry(&link.to.node).or_default() += 1;
+            }
+        }
+
+        let mut ready: BTreeSet<&NodeId> = incoming
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(node, _)| *node)
+            .collect();
+        let mut order = Vec::with_capacity(incoming.len());
+
+        while let Some(node) = ready.pop_first() {
+            order.push(node.clone());
+            for link in self.outgoing(node, false) {
+                let count = incoming
+                    .get_mut(&link.to.node)
+                    .expect("all the nodes reached by a link should be counted");
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(&link.to.node);
+                }
+            }
+        }
+
+        if order.len() < incoming.len() {
+            let ordered: HashSet<_> = order.iter().cloned().collect();
+            let mut remaining: Vec<_> = incoming
+                .into_keys()
+                .filter(|node| !ordered.contains(*node))
+                .cloned()
+                .collect();
+            remaining.sort();
+            order.append(&mut remaining);
+        }
+
+        order
+    }
+}
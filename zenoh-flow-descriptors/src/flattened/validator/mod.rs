@@ -12,175 +12,499 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::collections::HashSet;
+pub(crate) mod graph;
 
-use anyhow::bail;
-use zenoh_flow_commons::{NodeId, PortId, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use crate::FlattenedDataFlowDescriptor;
+use url::Url;
+use zenoh_flow_commons::{NodeId, PortId};
+
+use self::graph::Graph;
+use crate::{
+    diagnostics::{Diagnostic, Diagnostics, Location, Locations},
+    FlattenedDataFlowDescriptor, Isolation, LinkDescriptor, OperatorVariant, SinkVariant,
+    SourceVariant,
+};
 
 #[derive(Default)]
 pub(crate) struct Validator<'a> {
     node_ids: HashSet<&'a NodeId>,
+    node_locations: HashMap<&'a NodeId, Location>,
     outputs: HashSet<(&'a NodeId, &'a PortId)>,
     inputs: HashSet<(&'a NodeId, &'a PortId)>,
+    unflattened: HashSet<&'a NodeId>,
+    diagnostics: Diagnostics,
 }
 
 impl<'a> Validator<'a> {
-    pub(crate) fn validate_node_id(&mut self, node_id: &'a NodeId) -> Result<()> {
+    fn report(&mut self, message: impl AsRef<str>, location: Option<Location>) {
+        self.diagnostics.push(Diagnostic::new(message, location));
+    }
+
+    pub(crate) fn validate_node_id(&mut self, node_id: &'a NodeId, location: Option<Location>) {
         if !self.node_ids.insert(node_id) {
-            bail!(
-                "Two nodes share the same identifier: < {} >. The identifiers must be unique.",
-                node_id
+            self.report(
+                format!(
+                    "Two nodes share the same identifier: < {} >. The identifiers must be unique.",
+                    node_id
+                ),
+                location,
             );
+        } else if let Some(location) = location {
+            self.node_locations.insert(node_id, location);
         }
-
-        Ok(())
     }
 
-    pub(crate) fn validate_input(&mut self, node_id: &'a NodeId, input: &'a PortId) -> Result<()> {
+    pub(crate) fn validate_input(
+        &mut self,
+        node_id: &'a NodeId,
+        input: &'a PortId,
+        location: &Option<Location>,
+    ) {
         if !self.inputs.insert((node_id, input)) {
-            bail!(
-                "Node < {} > declares the following input (at least) twice: < {} >",
-                node_id,
-                input
+            self.report(
+                format!(
+                    "Node < {} > declares the following input (at least) twice: < {} >",
+                    node_id, input
+                ),
+                location.clone(),
             );
         }
-
-        Ok(())
     }
 
     pub(crate) fn validate_output(
         &mut self,
         node_id: &'a NodeId,
         output: &'a PortId,
-    ) -> Result<()> {
+        location: &Option<Location>,
+    ) {
         if !self.outputs.insert((node_id, output)) {
-            bail!(
-                "Node < {} > declares the following output (at least) twice: < {} >",
-                node_id,
-                output
+            self.report(
+                format!(
+                    "Node < {} > declares the following output (at least) twice: < {} >",
+                    node_id, output
+                ),
+                location.clone(),
             );
         }
-
-        Ok(())
     }
 
-    pub(crate) fn validate(data_flow: &FlattenedDataFlowDescriptor) -> Result<()> {
-        let mut this = Validator::default();
+    pub(crate) fn validate_isolation(
+        &mut self,
+        node_id: &'a NodeId,
+        isolation: Isolation,
+        library: Option<&Url>,
+        location: &Option<Location>,
+    ) {
+        if let Err(e) = isolation.check(library) {
+            self.report(
+                format!(
+                    "Node < {} > cannot be declared with `isolation: process`: {}",
+                    node_id, e
+                ),
+                location.clone(),
+            );
+        }
+    }
 
-        if data_flow.sources.is_empty() {
-            bail!("A data flow must specify at least ONE Source.");
+    // Validates the data flow, reporting all the problems detected, where the elements at fault are declared according
+    // to the `locations`.
+    //
+    // The nodes that could not be flattened, in `unflattened`, are absent from the data flow: the links and mapping
+    // entries that reference them are not reported and neither is the absence of Sources, Sinks or loops.
+    pub(crate) fn validate(
+        data_flow: &'a FlattenedDataFlowDescriptor,
+        locations: &Locations,
+        unflattened: &'a HashSet<NodeId>,
+    ) -> std::result::Result<(), Diagnostics> {
+        let mut this = Validator {
+            unflattened: unflattened.iter().collect(),
+            ..Validator::default()
+        };
+        let incomplete = !unflattened.is_empty();
+
+        if data_flow.sources.is_empty() && !incomplete {
+            this.report("A data flow must specify at least ONE Source.", None);
         }
 
-        if data_flow.sinks.is_empty() {
-            bail!("A data flow must specify at least ONE Sink.");
+        if data_flow.sinks.is_empty() && !incomplete {
+            this.report("A data flow must specify at least ONE Sink.", None);
         }
 
-        for flat_source in &data_flow.sources {
-            this.validate_node_id(&flat_source.id)?;
+        for (index, flat_source) in data_flow.sources.iter().enumerate() {
+            let location = locations.source(index);
+            this.validate_node_id(&flat_source.id, location.clone());
+            let library = match &flat_source.source {
+                SourceVariant::Library(library) => Some(library),
+                _ => None,
+            };
+            this.validate_isolation(&flat_source.id, flat_source.isolation, library, &location);
 
             for output in flat_source.outputs.iter() {
-                this.validate_output(&flat_source.id, output)?;
+                this.validate_output(&flat_source.id, output, &location);
             }
         }
 
-        for flat_operator in &data_flow.operators {
-            this.validate_node_id(&flat_operator.id)?;
+        for (index, flat_operator) in data_flow.operators.iter().enumerate() {
+            let location = locations.operator(index);
+            this.validate_node_id(&flat_operator.id, location.clone());
+            let library = match &flat_operator.operator {
+                OperatorVariant::Library(library) => Some(library),
+                _ => None,
+            };
+            this.validate_isolation(
+                &flat_operator.id,
+                flat_operator.isolation,
+                library,
+                &location,
+            );
 
             for output in flat_operator.outputs.iter() {
-                this.validate_output(&flat_operator.id, output)?;
+                this.validate_output(&flat_operator.id, output, &location);
             }
 
             for input in flat_operator.inputs.iter() {
-                this.validate_input(&flat_operator.id, input)?;
+                this.validate_input(&flat_operator.id, input, &location);
             }
         }
 
-        for flat_sink in &data_flow.sinks {
-            this.validate_node_id(&flat_sink.id)?;
+        for (index, flat_sink) in data_flow.sinks.iter().enumerate() {
+            let location = locations.sink(index);
+            this.validate_node_id(&flat_sink.id, location.clone());
+            let library = match &flat_sink.sink {
+                SinkVariant::Library(library) => Some(library),
+                _ => None,
+            };
+            this.validate_isolation(&flat_sink.id, flat_sink.isolation, library, &location);
 
             for input in flat_sink.inputs.iter() {
-                this.validate_input(&flat_sink.id, input)?;
+                this.validate_input(&flat_sink.id, input, &location);
             }
         }
 
-        let mut unused_inputs = this.inputs.clone();
-        let mut unused_outputs = this.outputs.clone();
+        this.validate_mapping(data_flow, locations);
+        this.validate_links(data_flow, locations);
+        this.validate_topology(data_flow, locations);
 
-        for link in data_flow.links.iter() {
-            if !this.outputs.contains(&(&link.from.node, &link.from.output)) {
-                bail!(
-                    r#"
+        if this.diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(this.diagnostics)
+        }
+    }
+
+    // Validates the links of the data flow: they must connect existing ports, all ports must be connected and an input
+    // can only be connected once.
+    fn validate_links(
+        &mut self,
+        data_flow: &'a FlattenedDataFlowDescriptor,
+        locations: &Locations,
+    ) {
+        let mut unused_inputs = self.inputs.clone();
+        let mut unused_outputs = self.outputs.clone();
+        let mut reported_inputs = HashSet::new();
+
+        for (index, link) in data_flow.links.iter().enumerate() {
+            if !self.outputs.contains(&(&link.from.node, &link.from.output))
+                && !self.unflattened.contains(&link.from.node)
+            {
+                self.report(
+                    format!(
+                        r#"
 The following `from` section of this link does not exist:
 {}
 
 Does the node < {} > exist?
 Does it declare an output named < {} >?
 "#,
-                    link,
-                    link.from.node,
-                    link.from.output
+                        link, link.from.node, link.from.output
+                    ),
+                    locations.link(index),
                 );
             }
             unused_outputs.remove(&(&link.from.node, &link.from.output));
 
-            if !this.inputs.contains(&(&link.to.node, &link.to.input)) {
-                bail!(
-                    r#"
+            if !self.inputs.contains(&(&link.to.node, &link.to.input)) {
+                if self.unflattened.contains(&link.to.node) {
+                    continue;
+                }
+                self.report(
+                    format!(
+                        r#"
 The following `to` section of this link does not exist:
 {}
 
 Does the node < {} > exist?
 Does it declare an input named < {} >?
 "#,
-                    link,
-                    link.to.node,
-                    link.to.input
+                        link, link.to.node, link.to.input
+                    ),
+                    locations.link(index),
                 );
+                continue;
             }
 
             // Contrary to outputs, there cannot be multiple incoming links pointing to a single input.
-            if !unused_inputs.remove(&(&link.to.node, &link.to.input)) {
+            if !unused_inputs.remove(&(&link.to.node, &link.to.input))
+                && reported_inputs.insert(&link.to)
+            {
                 let links = data_flow
                     .links
                     .iter()
                     .filter(|&l| l.to == link.to)
                     .collect::<Vec<_>>();
 
-                bail!(
-                    r#"
+                self.report(
+                    format!(
+                        r#"
 An Input can only receive data from a single Output.
 We have detected several links that point the same Input < {} >:
 
 {:?}
 "#,
-                    link.to,
-                    links
-                )
+                        link.to, links
+                    ),
+                    locations.link(index),
+                );
             }
         }
 
-        if !unused_inputs.is_empty() {
-            let mut error_message = "The following inputs are not connected: ".to_string();
-            for (node, input) in unused_inputs {
-                error_message = format!("{}\n- {}: {}", error_message, node, input);
+        // NOTE: The `BTreeMap` is there to produce deterministic diagnostics, one per node.
+        let mut unused_inputs_per_node: BTreeMap<&NodeId, BTreeSet<&PortId>> = BTreeMap::new();
+        for (node, input) in unused_inputs {
+            unused_inputs_per_node
+                .entry(node)
+                .or_default()
+                .insert(input);
+        }
+
+        for (node, inputs) in unused_inputs_per_node {
+            let mut message = "The following inputs are not connected:".to_string();
+            for input in inputs {
+                message = format!("{}\n- {}: {}", message, node, input);
             }
 
-            bail!(error_message);
+            self.report(message, self.node_locations.get(node).cloned());
         }
 
-        if !unused_outputs.is_empty() {
-            let mut error_message = "The following outputs are not connected:".to_string();
-            for (node, output) in unused_outputs {
-                error_message = format!("{}\n- {}: {}", error_message, node, output);
+        let mut unused_outputs_per_node: BTreeMap<&NodeId, BTreeSet<&PortId>> = BTreeMap::new();
+        for (node, output) in unused_outputs {
+            unused_outputs_per_node
+                .entry(node)
+                .or_default()
+                .insert(output);
+        }
+
+        for (node, outputs) in unused_outputs_per_node {
+            let mut message = "The following outputs are not connected:".to_string();
+            for output in outputs {
+                message = format!("{}\n- {}: {}", message, node, output);
             }
 
-            bail!(error_message);
+            self.report(message, self.node_locations.get(node).cloned());
+        }
+    }
+
+    // Validates the mapping of the data flow: all the nodes it references must exist and must be assigned to a single
+    // runtime.
+    fn validate_mapping(&mut self, data_flow: &FlattenedDataFlowDescriptor, locations: &Locations) {
+        // NOTE: The `BTreeMap` and the sorts are there to produce deterministic diagnostics.
+        let mut assignments: BTreeMap<&NodeId, Vec<String>> = BTreeMap::new();
+        let mut assignment_locations: HashMap<&NodeId, Vec<(String, Location)>> = HashMap::new();
+        for (runtime_id, nodes) in data_flow.mapping.iter() {
+            for node_id in nodes {
+                assignments
+                    .entry(node_id)
+                    .or_default()
+                    .push(runtime_id.to_string());
+
+                if let Some(location) = locations
+                    .mapping
+                    .get(&(runtime_id.clone(), node_id.clone()))
+                {
+                    assignment_locations
+                        .entry(node_id)
+                        .or_default()
+                        .push((runtime_id.to_string(), location.clone()));
+                }
+            }
         }
 
-        Ok(())
+        // The location of the first (in the order of the runtimes) mapping entry of the node.
+        let location = |node_id: &NodeId| {
+            assignment_locations
+                .get(node_id)
+                .and_then(|entries| entries.iter().min_by(|a, b| a.0.cmp(&b.0)))
+                .map(|(_, location)| location.clone())
+        };
+
+        for (node_id, runtimes) in assignments.iter_mut() {
+            runtimes.sort();
+
+            if self.unflattened.contains(*node_id) {
+                continue;
+            }
+
+            if !self.node_ids.contains(*node_id) {
+                self.report(
+                    format!(
+                        r#"
+The mapping references the following node(s) that are not part of the data flow:
+- {} (runtime: {}){}
+"#,
+                        node_id,
+                        runtimes.join(", "),
+                        self.hint(node_id)
+                    ),
+                    location(node_id),
+                );
+            } else if runtimes.len() > 1 {
+                self.report(
+                    format!(
+                        r#"
+A node can only be mapped to a single runtime, the following node(s) are mapped to several:
+- {}: {}
+
+Note that the Operators of a composite Operator are mapped to the runtime of the composite Operator.
+"#,
+                        node_id,
+                        runtimes.join(", ")
+                    ),
+                    location(node_id),
+                );
+            }
+        }
     }
+
+    // Returns a hint explaining why `node_id`, referenced in the mapping, is not part of the data flow.
+    fn hint(&self, node_id: &NodeId) -> String {
+        let mut nested: Vec<&str> = self
+            .node_ids
+            .iter()
+            .filter_map(|id| id.strip_prefix(&format!("{node_id}>")))
+            .collect();
+        if !nested.is_empty() {
+            nested.sort();
+            return format!(
+                "\n  < {} > is a composite Operator, it was replaced by its Operators: {}",
+                node_id,
+                nested
+                    .iter()
+                    .map(|nested_id| format!("{node_id}>{nested_id}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        let mut candidates: Vec<&str> = self
+            .node_ids
+            .iter()
+            .filter(|id| id.ends_with(&format!(">{node_id}")))
+            .map(|id| id.as_ref())
+            .collect();
+        if !candidates.is_empty() {
+            candidates.sort();
+            return format!(
+                "\n  The Operators of a composite Operator are prefixed with its identifier, did you mean: {}?",
+                candidates.join(", ")
+            );
+        }
+
+        String::default()
+    }
+
+    // Validates the loops of the data flow: they must all be closed by a link marked as feedback and such links must
+    // not lead to a deadlock.
+    fn validate_topology(
+        &mut self,
+        data_flow: &FlattenedDataFlowDescriptor,
+        locations: &Locations,
+    ) {
+        let graph = Graph::new(self.node_ids.iter().copied(), &data_flow.links);
+
+        for (index, link) in data_flow.links.iter().enumerate() {
+            let Some(feedback) = &link.feedback else {
+                continue;
+            };
+
+            if feedback.initial.is_none() && feedback.capacity.is_none() {
+                self.report(
+                    format!(
+                        r#"
+The following link is marked as feedback but provides neither an `initial` message nor a `capacity`:
+{}
+
+Without one of them, the nodes of the loop could wait for one another forever.
+"#,
+                        link
+                    ),
+                    locations.link(index),
+                );
+            }
+
+            if feedback.capacity == Some(0) {
+                self.report(
+                    format!(
+                        r#"
+The `capacity` of the following feedback link cannot be null:
+{}
+"#,
+                        link
+                    ),
+                    locations.link(index),
+                );
+            }
+
+            // NOTE: The loop could go through a node that could not be flattened.
+            if self.unflattened.is_empty() && !graph.reaches(&link.to.node, &link.from.node) {
+                self.report(
+                    format!(
+                        r#"
+The following link is marked as feedback but does not close a loop:
+{}
+
+Is the `feedback` section on the right link?
+"#,
+                        link
+                    ),
+                    locations.link(index),
+                );
+            }
+        }
+
+        if let Some(cycle) = graph.find_cycle() {
+            let location = data_flow
+                .links
+                .iter()
+                .position(|link| std::ptr::eq(link, cycle[0]))
+                .and_then(|index| locations.link(index));
+
+            self.report(
+                format!(
+                    r#"
+The data flow contains a loop, the nodes forming it could wait for one another forever:
+{}
+
+If this loop is intended, mark one of its links as feedback by providing an `initial` message and / or a `capacity`:
+
+feedback:
+  capacity: 1
+"#,
+                    format_cycle(&cycle)
+                ),
+                location,
+            );
+        }
+    }
+}
+
+// Displays the links forming a cycle, one per line.
+fn format_cycle(cycle: &[&LinkDescriptor]) -> String {
+    cycle
+        .iter()
+        .map(|link| format!("- {}", link))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
@@ -12,9 +12,9 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use zenoh_flow_commons::Vars;
+use zenoh_flow_commons::{Result, Vars};
 
-use crate::FlattenedDataFlowDescriptor;
+use crate::{Diagnostics, FlattenedDataFlowDescriptor};
 
 #[test]
 fn test_valid_data_flow() {
@@ -930,3 +930,304 @@ links:
     assert!(format!("{:?}", res)
         .contains("We have detected several links that point the same Input < sink-0.in >:"));
 }
+
+// A data flow where `operator-1` sends back, on the `loop` input of `operator-0`, what it computes.
+//
+// The `{source_feedback}` and `{loop_feedback}` placeholders are replaced with the feedback sections of, respectively,
+// the link leaving the Source and the link closing the loop.
+fn loop_data_flow(
+    source_feedback: &str,
+    loop_feedback: &str,
+) -> Result<FlattenedDataFlowDescriptor> {
+    let yaml = r#"
+name: data flow with a loop
+
+sources:
+  - id: source-0
+    library: file:///home/zenoh-flow/source.so
+    outputs:
+      - out
+
+operators:
+  - id: operator-0
+    library: file:///home/zenoh-flow/operator.so
+    inputs:
+      - in
+      - loop
+    outputs:
+      - out
+
+  - id: operator-1
+    library: file:///home/zenoh-flow/operator.so
+    inputs:
+      - in
+    outputs:
+      - out
+
+sinks:
+  - id: sink-0
+    library: file:///home/zenoh-flow/sink.so
+    inputs:
+      - in
+
+links:
+  - from:
+      node: source-0
+      output: out
+    to:
+      node: operator-0
+      input: in
+{source_feedback}
+
+  - from:
+      node: operator-0
+      output: out
+    to:
+      node: operator-1
+      input: in
+
+  - from:
+      node: operator-1
+      output: out
+    to:
+      node: operator-0
+      input: loop
+{loop_feedback}
+
+  - from:
+      node: operator-1
+      output: out
+    to:
+      node: sink-0
+      input: in
+"#
+    .replace("{source_feedback}", source_feedback)
+    .replace("{loop_feedback}", loop_feedback);
+
+    FlattenedDataFlowDescriptor::try_flatten(serde_yaml::from_str(&yaml).unwrap(), Vars::default())
+}
+
+#[test]
+fn test_loop_without_feedback() {
+    let res = loop_data_flow("", "");
+
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains("The data flow contains a loop"));
+    assert!(format!("{:?}", res).contains("- operator-0.out => operator-1.in"));
+    assert!(format!("{:?}", res).contains("- operator-1.out => operator-0.loop"));
+}
+
+#[test]
+fn test_loop_with_feedback() {
+    let data_flow = loop_data_flow(
+        "",
+        r#"    feedback:
+      capacity: 4"#,
+    )
+    .unwrap();
+    let order: Vec<String> = data_flow
+        .topological_order()
+        .iter()
+        .map(|node| node.to_string())
+        .collect();
+    assert_eq!(
+        order,
+        vec!["source-0", "operator-0", "operator-1", "sink-0"]
+    );
+
+    assert!(loop_data_flow(
+        "",
+        r#"    feedback:
+      initial:
+        setpoint: 0.0"#,
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_invalid_feedback() {
+    let res = loop_data_flow("", "    feedback: {}");
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains("provides neither an `initial` message nor a `capacity`"));
+
+    let res = loop_data_flow(
+        "",
+        r#"    feedback:
+      capacity: 0"#,
+    );
+    assert!(res.is_err());
+    assert!(format!("{:?}", res)
+        .contains("The `capacity` of the following feedback link cannot be null"));
+
+    let res = loop_data_flow(
+        r#"    feedback:
+      capacity: 4"#,
+        "",
+    );
+    assert!(res.is_err());
+    assert!(format!("{:?}", res).contains("is marked as feedback but does not close a loop"));
+    assert!(format!("{:?}", res).contains("source-0.out => operator-0.in"));
+}
+
+#[test]
+fn test_unsupported_isolation() {
+    let yaml = r#"
+name: data flow with unsupported isolation
+
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    outputs:
+      - out
+
+operators:
+  - id: static-operator
+    library: builtin://my-operator
+    isolation: process
+    inputs:
+      - in
+    outputs:
+      - out
+
+sinks:
+  - id: wasm-sink
+    library: file:///home/zenoh-flow/sink.wasm
+    isolation: process
+    inputs:
+      - in
+
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: static-operator
+      input: in
+  - from:
+      node: static-operator
+      output: out
+    to:
+      node: wasm-sink
+      input: in
+"#;
+
+    let error = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str(yaml).unwrap(),
+        Vars::default(),
+    )
+    .expect_err("Static and WebAssembly nodes cannot be isolated");
+    let diagnostics = error
+        .downcast_ref::<Diagnostics>()
+        .expect("The problems should be reported as Diagnostics");
+    let messages = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 2, "{diagnostics}");
+    assert!(messages.contains(
+        &"Node < static-operator > cannot be declared with `isolation: process`: A node registered in the runtime (< builtin://my-operator >) cannot be isolated in a process"
+    ));
+    assert!(messages
+        .iter()
+        .any(|message| message.starts_with("Node < wasm-sink >")));
+}
+
+#[test]
+fn test_all_problems_reported() {
+    let yaml = r#"
+name: data flow with several problems
+
+sources:
+  - id: source-0
+    library: file:///home/zenoh-flow/source.so
+    outputs:
+      - out-0
+
+operators:
+  - id: source-0
+    library: file:///home/zenoh-flow/operator.so
+    inputs:
+      - in-0
+    outputs:
+      - out-0
+
+sinks: []
+
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: sink-0
+      input: in-0
+"#;
+
+    let res = FlattenedDataFlowDescriptor::try_flatten(
+        serde_yaml::from_str(yaml).unwrap(),
+        Vars::default(),
+    );
+    let error = res.expect_err("The data flow should not be valid");
+    let diagnostics = error
+        .downcast_ref::<Diagnostics>()
+        .expect("The problems should be reported as Diagnostics");
+
+    let messages = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 5, "{diagnostics}");
+    assert!(messages.contains(&"A data flow must specify at least ONE Sink."));
+    assert!(messages.contains(
+        &"Two nodes share the same identifier: < source-0 >. The identifiers must be unique."
+    ));
+    assert!(messages
+        .contains(&"Node < source-0 > declares the following output (at least) twice: < out-0 >"));
+    assert!(messages
+        .iter()
+        .any(|message| message.contains("Does the node < sink-0 > exist?")));
+    assert!(messages.contains(&"The following inputs are not connected:\n- source-0: in-0"));
+
+    // The data flow was not loaded from a descriptor: the problems cannot be located.
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.location.is_none()));
+}
+
+#[test]
+fn test_find_cycle_long_chain() {
+    use zenoh_flow_commons::NodeId;
+
+    use super::graph::Graph;
+    use crate::{InputDescriptor, LinkDescriptor, OutputDescriptor};
+
+    // A chain long enough to overflow the stack of the thread if the graph was visited recursively, closed by a link
+    // that forms a cycle.
+    const LENGTH: usize = 200_000;
+    let nodes: Vec<NodeId> = (0..LENGTH)
+        .map(|index| NodeId::from(format!("node-{index}").as_str()))
+        .collect();
+    let mut links: Vec<LinkDescriptor> = (1..LENGTH)
+        .map(|index| {
+            LinkDescriptor::new(
+                OutputDescriptor::new(nodes[index - 1].to_string(), "out"),
+                InputDescriptor::new(nodes[index].to_string(), "in"),
+            )
+        })
+        .collect();
+
+    assert!(Graph::new(nodes.iter(), &links).find_cycle().is_none());
+
+    links.push(LinkDescriptor::new(
+        OutputDescriptor::new(nodes[LENGTH - 1].to_string(), "out"),
+        InputDescriptor::new(nodes[1].to_string(), "in"),
+    ));
+    let graph = Graph::new(nodes.iter(), &links);
+    let cycle = graph
+        .find_cycle()
+        .expect("The chain should contain a cycle");
+    assert_eq!(cycle.len(), LENGTH - 1);
+    assert_eq!(cycle[0].from.node, nodes[1]);
+    assert_eq!(cycle[cycle.len() - 1].to.node, nodes[1]);
+}
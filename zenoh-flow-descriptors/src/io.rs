@@ -12,12 +12,13 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::fmt;
+use std::{fmt, path::PathBuf};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "shared-memory")]
 use zenoh_flow_commons::SharedMemoryConfiguration;
-use zenoh_flow_commons::{NodeId, PortId};
+use zenoh_flow_commons::{BatchingConfiguration, Compression, NodeId, PortId, QoS};
 
 /// An `InputDescriptor` uniquely describes an Input port of a Zenoh-Flow node.
 ///
@@ -31,7 +32,7 @@ use zenoh_flow_commons::{NodeId, PortId};
 /// # "#;
 /// # serde_yaml::from_str::<InputDescriptor>(input_desc).unwrap();
 /// ```
-#[derive(Debug, Hash, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Hash, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
 pub struct InputDescriptor {
     pub node: NodeId,
     pub input: PortId,
@@ -64,7 +65,7 @@ impl InputDescriptor {
 /// # "#;
 /// # serde_yaml::from_str::<OutputDescriptor>(output_desc).unwrap();
 /// ```
-#[derive(Debug, Clone, Hash, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct OutputDescriptor {
     pub node: NodeId,
     pub output: PortId,
@@ -90,7 +91,15 @@ impl OutputDescriptor {
 /// A link is composed of:
 /// - an [OutputDescriptor],
 /// - an [InputDescriptor],
-/// - *(optional, disabled by default)* Zenoh shared-memory parameters.
+/// - *(optional, disabled by default)* Zenoh shared-memory parameters,
+/// - *(optional)* the path of a file in which to record the messages flowing through the link,
+/// - *(optional)* the [QoS] with which Zenoh delivers the messages, if the nodes run on different runtimes,
+/// - *(optional)* the [Compression] of the messages sent over Zenoh, if the nodes run on different runtimes,
+/// - *(optional, disabled by default)* the [batching](BatchingConfiguration) of the messages sent over Zenoh, if the
+///   nodes run on different runtimes (a batched link does not use the shared memory),
+/// - *(optional, disabled by default)* the size of the buffer used to put back in order the messages received over
+///   Zenoh, if the nodes run on different runtimes,
+/// - *(optional)* the [feedback](FeedbackDescriptor) section of a link closing an intended loop.
 ///
 /// # Example
 ///
@@ -104,16 +113,51 @@ impl OutputDescriptor {
 /// to:
 ///   node : Sink
 ///   input : i-sink
+/// recording: /var/log/zenoh-flow/operator.zfrec
+/// qos:
+///   priority: interactive-high
+///   congestion-control: block
+/// compression: lz4
+/// batching:
+///   max-size: 16KiB
+///   max-latency: 1ms
+/// reorder_buffer: 16
 /// # "#;
 /// # serde_yaml::from_str::<LinkDescriptor>(link_desc).unwrap();
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub struct LinkDescriptor {
     pub from: OutputDescriptor,
     pub to: InputDescriptor,
     #[cfg(feature = "shared-memory")]
     #[serde(default, alias = "shm", alias = "shared-memory")]
     pub shared_memory: Option<SharedMemoryConfiguration>,
+    /// The file in which the messages flowing through this link are recorded, on the runtime of the `from` node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording: Option<PathBuf>,
+    /// The quality of service of the link, only applied if its nodes are on different runtimes.
+    #[serde(default, skip_serializing_if = "QoS::is_default")]
+    pub qos: QoS,
+    /// The compression of the messages, only applied if its nodes are on different runtimes.
+    #[serde(default, skip_serializing_if = "Compression::is_none")]
+    pub compression: Compression,
+    /// The batching of the messages, only applied if its nodes are on different runtimes.
+    ///
+    /// The batches are never sent through Zenoh's shared memory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batching: Option<BatchingConfiguration>,
+    /// The number of messages held, waiting for the ones that were sent before them, only applied if its nodes are on
+    /// different runtimes.
+    #[serde(
+        default,
+        alias = "reorder-buffer",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(rename = "reorder-buffer")]
+    pub reorder_buffer: Option<usize>,
+    /// The feedback section of the link, if it closes an intended loop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<FeedbackDescriptor>,
 }
 
 impl std::fmt::Display for LinkDescriptor {
@@ -129,6 +173,12 @@ impl LinkDescriptor {
             to,
             #[cfg(feature = "shared-memory")]
             shared_memory: None,
+            recording: None,
+            qos: QoS::default(),
+            compression: Compression::None,
+            batching: None,
+            reorder_buffer: None,
+            feedback: None,
         }
     }
 
@@ -138,3 +188,37 @@ impl LinkDescriptor {
         self
     }
 }
+
+/// A `FeedbackDescriptor` marks a link as closing an intended loop, e.g. to feed back the output of a controller.
+///
+/// A data flow containing a loop is otherwise rejected: the nodes forming it could wait for one another forever. To
+/// prevent that, a feedback link must provide:
+/// - an `initial` message, sent on the link when the data flow is created, that starts the loop, and / or
+/// - a `capacity`, the maximum number of messages the link holds: when the link is full, the node sending on it waits
+///   instead of flooding the loop.
+///
+/// The payload of the initial message is the JSON serialisation of the provided value.
+///
+/// Note that the links marked as feedback are ignored when computing the order in which the nodes are started and
+/// stopped.
+///
+/// # Example
+///
+/// ```
+/// # use zenoh_flow_descriptors::FeedbackDescriptor;
+/// # let feedback_desc = r#"
+/// initial:
+///   setpoint: 0.0
+/// capacity: 8
+/// # "#;
+/// # serde_yaml::from_str::<FeedbackDescriptor>(feedback_desc).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FeedbackDescriptor {
+    /// The value of the message sent on the link when the data flow is created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<serde_json::Value>,
+    /// The maximum number of messages the link holds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<usize>,
+}
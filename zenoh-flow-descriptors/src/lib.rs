@@ -35,20 +35,48 @@
 //! [FlattenedDataFlowDescriptor].
 
 pub(crate) mod dataflow;
+pub(crate) mod diagnostics;
 pub(crate) mod flattened;
 pub(crate) mod io;
 pub(crate) mod nodes;
+pub(crate) mod schema;
 pub(crate) mod uri;
 
 pub use self::{
     dataflow::DataFlowDescriptor,
+    diagnostics::{Diagnostic, Diagnostics, Location},
     flattened::{
         dataflow::FlattenedDataFlowDescriptor,
         nodes::{
-            operator::FlattenedOperatorDescriptor,
+            operator::{FlattenedOperatorDescriptor, OperatorVariant},
             sink::{FlattenedSinkDescriptor, SinkVariant},
             source::{FlattenedSourceDescriptor, SourceVariant},
         },
     },
-    io::{InputDescriptor, LinkDescriptor, OutputDescriptor},
+    io::{FeedbackDescriptor, InputDescriptor, LinkDescriptor, OutputDescriptor},
+    nodes::{
+        builtin::{
+            expression::{Expression, ExpressionDescriptor, Projection},
+            file::{FileReaderDescriptor, FileRotation, FileWriterDescriptor, ReadMode},
+            replay::{Pacing, ReplayDescriptor},
+            routing::{
+                MergeDescriptor, MergeOrder, SplitDescriptor, SplitRoute, TeeDescriptor,
+                TeeOutputDescriptor, ThrottleDescriptor, ThrottlePolicy,
+            },
+            stdio::{StdinDescriptor, StdoutDescriptor},
+            ticker::TickerDescriptor,
+            zenoh::{
+                ZenohPublisherDescriptor, ZenohQuerierDescriptor, ZenohQueryableDescriptor,
+                ZenohSubscriberDescriptor,
+            },
+        },
+        Isolation,
+    },
+    schema::{data_flow_schema, operator_schema, sink_schema, source_schema},
+    uri::{DescriptorResolver, FileResolver, UriResolver},
 };
+
+#[cfg(feature = "http")]
+pub use self::uri::HttpResolver;
+#[cfg(feature = "zenoh")]
+pub use self::uri::ZenohResolver;
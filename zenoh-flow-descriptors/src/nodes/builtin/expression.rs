@@ -0,0 +1,122 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use zenoh_flow_commons::PortId;
+pub use zenoh_flow_expression::Expression;
+
+use super::{default_input, default_output};
+
+/// An `ExpressionOperatorDescriptor` filters and / or reshapes the JSON messages it receives on its single input with
+/// [expressions](Expression) and sends the result on its single output.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: Fast robots, speed in km/h
+/// expression:
+///   filter: $.speed > 1.5 && $.status == 'ok'
+///   map:
+///     id: $.robot.id
+///     speed: $.speed * 3.6
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExpressionOperatorDescriptor {
+    pub description: Option<Arc<str>>,
+    pub expression: ExpressionDescriptor,
+}
+
+/// An `ExpressionDescriptor` describes the behaviour of an expression built-in Operator.
+///
+/// Each message received on the `input` (named `in` by default) is parsed as JSON, a message that is not valid JSON
+/// being dropped (and a warning logged). If a `filter` is provided, the message is dropped unless the filter evaluates
+/// to `true`. If a `map` is provided, the message sent on the `output` (named `out` by default) is the result of its
+/// evaluation, with the timestamp and metadata of the message it was computed from, otherwise the message is forwarded
+/// untouched.
+///
+/// A `map` is either a single expression or a mapping of field names to expressions, in which case a JSON object is
+/// built.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExpressionDescriptor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Expression>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<Projection>,
+    #[serde(default = "default_input")]
+    pub input: PortId,
+    #[serde(default = "default_output")]
+    pub output: PortId,
+}
+
+/// The projection applied by an expression built-in Operator: either a single expression or an object whose fields are
+/// the results of expressions.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Projection {
+    Value(Expression),
+    Fields(BTreeMap<String, Expression>),
+}
+
+impl Projection {
+    /// Evaluates the projection on the provided JSON `value`.
+    pub fn evaluate(&self, value: &Value) -> Value {
+        self.evaluate_with_attachment(value, &Value::Null)
+    }
+
+    /// Evaluates the projection on the provided JSON `value` and `attachment`, see [Expression].
+    pub fn evaluate_with_attachment(&self, value: &Value, attachment: &Value) -> Value {
+        match self {
+            Projection::Value(expression) => expression.evaluate_with_attachment(value, attachment),
+            Projection::Fields(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(field, expression)| {
+                        (
+                            field.clone(),
+                            expression.evaluate_with_attachment(value, attachment),
+                        )
+                    })
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Projection;
+
+    #[test]
+    fn test_projection() {
+        let projection: Projection = serde_yaml::from_str(
+            r#"
+id: $.robot.id
+speed: $.speed * 3.6
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            projection.evaluate(&json!({ "robot": { "id": "robot-1" }, "speed": 2 })),
+            json!({ "id": "robot-1", "speed": 7.2 })
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{path::PathBuf, sync::Arc};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zenoh_flow_commons::{size_schema, PortId};
+
+use super::{default_input, default_newline, default_output, deserialize_non_null_size};
+
+/// A `FileSourceDescriptor` reads a file and sends its content on a single output, either line by line or by chunks of
+/// bytes.
+///
+/// Once the end of the file is reached, the Source no longer sends anything.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My file reader
+/// file:
+///   path: /var/log/robot.log
+/// ```
+///
+/// ```yaml
+/// description: My file reader
+/// file:
+///   path: /home/zenoh-flow/video.raw
+///   read: chunks
+///   chunk-size: 64KiB
+///   output: frames
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileSourceDescriptor {
+    pub description: Option<Arc<str>>,
+    pub file: FileReaderDescriptor,
+}
+
+/// A `FileReaderDescriptor` describes how a file built-in Source reads its file: the path of the file, if it is read
+/// line by line or by chunks of `chunk-size` bytes and the output on which its content is sent.
+///
+/// When read line by line, the line terminator (`\n` or `\r\n`) is not included in the messages. The last chunk of a
+/// file can be smaller than `chunk-size`.
+///
+/// By default, a file is read line by line, a chunk is 4KiB and the output is named `out`.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FileReaderDescriptor {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub read: ReadMode,
+    #[serde(
+        default = "default_chunk_size",
+        deserialize_with = "deserialize_non_null_size"
+    )]
+    #[schemars(schema_with = "size_schema")]
+    pub chunk_size: u64,
+    #[serde(default = "default_output")]
+    pub output: PortId,
+}
+
+/// How a file built-in Source reads its file.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadMode {
+    /// One message per line.
+    #[default]
+    Lines,
+    /// One message per chunk of `chunk-size` bytes.
+    Chunks,
+}
+
+fn default_chunk_size() -> u64 {
+    4096
+}
+
+/// A `FileSinkDescriptor` appends the messages it receives on its single input to a file, optionally rotating it when it
+/// grows too large.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My file writer
+/// file:
+///   path: /var/log/zenoh-flow/detections.log
+///   rotation:
+///     max-size: 10MiB
+///     max-files: 3
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileSinkDescriptor {
+    pub description: Option<Arc<str>>,
+    pub file: FileWriterDescriptor,
+}
+
+/// A `FileWriterDescriptor` describes how a file built-in Sink writes its file: the path of the file, the rotation
+/// policy (if any), if a newline is written after each message and the input from which the messages are received.
+///
+/// The file is created if it does not exist and the messages are always appended to it. By default, a newline is
+/// written after each message and the input is named `in`.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FileWriterDescriptor {
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<FileRotation>,
+    #[serde(default = "default_newline")]
+    pub newline: bool,
+    #[serde(default = "default_input")]
+    pub input: PortId,
+}
+
+/// A `FileRotation` indicates the size, in bytes, above which a file is rotated and how many rotated files are kept.
+///
+/// When the file `<path>` is rotated, it is renamed `<path>.1`, `<path>.1` is renamed `<path>.2` and so on, up to
+/// `<path>.<max-files>` that is overwritten. By default, 5 rotated files are kept.
+///
+/// A message is never split: if a single message is larger than `max-size`, it is written in its own file.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FileRotation {
+    #[serde(deserialize_with = "deserialize_non_null_size")]
+    #[schemars(schema_with = "size_schema")]
+    pub max_size: u64,
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_files() -> usize {
+    5
+}
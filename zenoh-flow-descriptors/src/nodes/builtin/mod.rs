@@ -12,4 +12,83 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+pub(crate) mod expression;
+pub(crate) mod file;
+pub(crate) mod replay;
+pub(crate) mod routing;
+pub(crate) mod stdio;
+pub(crate) mod ticker;
 pub(crate) mod zenoh;
+
+use serde::{Deserialize, Deserializer, Serializer};
+use zenoh_flow_commons::{deserialize_size, parse_time, serialize_time, PortId};
+
+// The optional durations are serialised in a format that `zenoh_flow_commons::deserialize_time` accepts.
+pub(crate) fn serialize_optional_time<S: Serializer>(
+    micros: &Option<u64>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match micros {
+        Some(micros) => serialize_time(micros, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+// Deserialises a period, in *microseconds*, that cannot be null.
+pub(crate) fn deserialize_period<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let period: String = Deserialize::deserialize(deserializer)?;
+    match parse_time(&period).map_err(serde::de::Error::custom)? {
+        0 => Err(serde::de::Error::custom(format!(
+            "The period < {} > cannot be null",
+            period
+        ))),
+        micros => Ok(micros),
+    }
+}
+
+// Implements `JsonSchema` for a descriptor that is deserialised `try_from` one of its textual representations: its
+// schema is the one of the textual representations.
+macro_rules! schema_from_entry {
+    ($descriptor:ident, $entry:ident) => {
+        impl schemars::JsonSchema for $descriptor {
+            fn schema_name() -> String {
+                stringify!($descriptor).into()
+            }
+
+            fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                $entry::json_schema(gen)
+            }
+        }
+    };
+}
+pub(crate) use schema_from_entry;
+
+// Deserialises a size, in bytes, that cannot be null, see `zenoh_flow_commons::deserialize_size`.
+pub(crate) fn deserialize_non_null_size<'de, D>(
+    deserializer: D,
+) -> std::result::Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserialize_size(deserializer)? {
+        0 => Err(serde::de::Error::custom("A size cannot be null")),
+        bytes => Ok(bytes as u64),
+    }
+}
+
+// The port of the built-in Sources that have a single output.
+pub(crate) fn default_output() -> PortId {
+    "out".into()
+}
+
+// The port of the built-in Sinks that have a single input.
+pub(crate) fn default_input() -> PortId {
+    "in".into()
+}
+
+pub(crate) fn default_newline() -> bool {
+    true
+}
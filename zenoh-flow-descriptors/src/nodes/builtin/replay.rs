@@ -0,0 +1,85 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{path::PathBuf, sync::Arc};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use zenoh_flow_commons::PortId;
+
+use super::default_output;
+
+/// A `ReplaySourceDescriptor` sends, on a single output, the messages of a recording.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My replay
+/// replay:
+///   path: /var/log/zenoh-flow/source-out.zfrec
+///   pacing:
+///     scaled: 2.0
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReplaySourceDescriptor {
+    pub description: Option<Arc<str>>,
+    pub replay: ReplayDescriptor,
+}
+
+/// A `ReplayDescriptor` describes the recording a replay built-in Source reads, how it spaces the messages it sends and
+/// the output, named `out` by default, on which it does.
+///
+/// Each recorded message is sent unaltered (timestamp included). Once the end of the recording is reached, the Source
+/// no longer sends anything.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReplayDescriptor {
+    pub path: PathBuf,
+    #[serde(default, deserialize_with = "deserialize_pacing")]
+    pub pacing: Pacing,
+    #[serde(default = "default_output")]
+    pub output: PortId,
+}
+
+/// How a replay built-in Source spaces the messages it sends.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Pacing {
+    /// The messages are spaced as they were recorded.
+    #[default]
+    Original,
+    /// The messages are sent as fast as possible.
+    Fastest,
+    /// The original pacing is accelerated by the provided factor (2.0 = twice as fast).
+    Scaled(f64),
+}
+
+// NOTE: `deserialize_pacing` rejects the factors that are not finite, hence `NaN`.
+impl Eq for Pacing {}
+
+// Deserialises a `Pacing`, rejecting the scaling factors that are not strictly positive and finite.
+fn deserialize_pacing<'de, D>(deserializer: D) -> std::result::Result<Pacing, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Pacing::deserialize(deserializer)? {
+        Pacing::Scaled(factor) if !factor.is_finite() || factor <= 0.0 => {
+            Err(serde::de::Error::custom(format!(
+                "The scaling factor < {} > must be strictly positive",
+                factor
+            )))
+        }
+        pacing => Ok(pacing),
+    }
+}
@@ -0,0 +1,323 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zenoh_flow_commons::{serialize_time, PortId};
+
+use super::{
+    default_input, default_output, deserialize_period, expression::Expression, schema_from_entry,
+};
+
+/// A `MergeOperatorDescriptor` forwards, on a single output, the messages it receives on any of its inputs.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: All the detections, oldest first
+/// merge:
+///   inputs:
+///     - front-camera
+///     - rear-camera
+///   order: timestamp
+///   window: 20ms
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MergeOperatorDescriptor {
+    pub description: Option<Arc<str>>,
+    pub merge: MergeDescriptor,
+}
+
+/// A `MergeDescriptor` describes the inputs of a merge built-in Operator, the output (named `out` by default) on which
+/// the messages are forwarded and the order in which they are.
+///
+/// When ordered by timestamp, each message is held during the `window` (10ms by default) such that the messages
+/// received within that duration are forwarded in the order of their timestamp. A message received more than `window`
+/// after a message with a greater timestamp is still forwarded, out of order.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct MergeDescriptor {
+    #[serde(deserialize_with = "deserialize_merge_inputs")]
+    #[schemars(length(min = 1))]
+    pub inputs: Vec<PortId>,
+    #[serde(default = "default_output")]
+    pub output: PortId,
+    #[serde(default)]
+    pub order: MergeOrder,
+    #[schemars(schema_with = "zenoh_flow_commons::duration_schema")]
+    #[serde(
+        default = "default_window",
+        deserialize_with = "deserialize_period",
+        serialize_with = "serialize_time"
+    )]
+    pub window: u64,
+}
+
+/// The order in which a merge built-in Operator forwards the messages.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeOrder {
+    /// As soon as they are received.
+    #[default]
+    Arrival,
+    /// By increasing timestamp, within a window.
+    Timestamp,
+}
+
+fn default_window() -> u64 {
+    10_000
+}
+
+fn deserialize_merge_inputs<'de, D>(deserializer: D) -> std::result::Result<Vec<PortId>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let inputs = Vec::<PortId>::deserialize(deserializer)?;
+    if inputs.is_empty() {
+        return Err(serde::de::Error::custom(
+            "The `inputs` of a merge cannot be empty",
+        ));
+    }
+
+    Ok(inputs)
+}
+
+/// A `SplitOperatorDescriptor` routes the messages it receives on its single input to its outputs based on
+/// [expressions](Expression) evaluated on their payload, parsed as JSON, and their attachment.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: Route the robots by speed
+/// split:
+///   routes:
+///     - output: fast
+///       when: $.speed > 1.5
+///     - output: kitchen
+///       when: "@.key-expr == 'home/kitchen/robot'"
+///   otherwise: others
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SplitOperatorDescriptor {
+    pub description: Option<Arc<str>>,
+    pub split: SplitDescriptor,
+}
+
+/// A `SplitDescriptor` describes the input (named `in` by default) of a split built-in Operator and its routes.
+///
+/// A message is sent on the output of every route whose condition evaluates to `true` or, if `first-match` is set, only
+/// on the output of the first of them, in the order of declaration. If no condition is satisfied, the message is sent
+/// on the `otherwise` output if one is declared, otherwise it is dropped.
+///
+/// If the payload of a message is not valid JSON, `$` is `null`: only its attachment can be used to route it.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SplitDescriptor {
+    #[serde(default = "default_input")]
+    pub input: PortId,
+    pub routes: Vec<SplitRoute>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otherwise: Option<PortId>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub first_match: bool,
+}
+
+impl SplitDescriptor {
+    /// Returns the outputs of the split built-in Operator, in the order of declaration and without duplicates.
+    pub fn outputs(&self) -> Vec<PortId> {
+        let mut outputs: Vec<PortId> = Vec::with_capacity(self.routes.len() + 1);
+        for output in self
+            .routes
+            .iter()
+            .map(|route| &route.output)
+            .chain(self.otherwise.iter())
+        {
+            if !outputs.contains(output) {
+                outputs.push(output.clone());
+            }
+        }
+
+        outputs
+    }
+}
+
+/// A route of a split built-in Operator: the output on which a message is sent if the condition evaluates to `true`.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SplitRoute {
+    pub output: PortId,
+    pub when: Expression,
+}
+
+/// A `TeeOperatorDescriptor` duplicates, on all its outputs, the messages it receives on its single input.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: Archive everything, monitor what we can
+/// tee:
+///   outputs:
+///     - archive
+///     - output: monitor
+///       lossy: true
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TeeOperatorDescriptor {
+    pub description: Option<Arc<str>>,
+    pub tee: TeeDescriptor,
+}
+
+/// A `TeeDescriptor` describes the input (named `in` by default) and the outputs of a tee built-in Operator.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TeeDescriptor {
+    #[serde(default = "default_input")]
+    pub input: PortId,
+    pub outputs: Vec<TeeOutputDescriptor>,
+}
+
+/// An output of a tee built-in Operator.
+///
+/// A lossy output never slows down the Operator: a message is dropped, for that output, if `max-pending` messages (1 by
+/// default) previously sent on it were not yet received by a downstream node. The other outputs wait for the
+/// downstream nodes.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", try_from = "TeeOutputEntry")]
+pub struct TeeOutputDescriptor {
+    pub output: PortId,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lossy: bool,
+    pub max_pending: usize,
+}
+
+/// The textual representations of an output of a tee: either its name alone or with its options.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum TeeOutputEntry {
+    Output(PortId),
+    Configured {
+        output: PortId,
+        #[serde(default)]
+        lossy: bool,
+        #[serde(default = "default_max_pending", rename = "max-pending")]
+        max_pending: usize,
+    },
+}
+
+fn default_max_pending() -> usize {
+    1
+}
+
+schema_from_entry!(TeeOutputDescriptor, TeeOutputEntry);
+
+impl TryFrom<TeeOutputEntry> for TeeOutputDescriptor {
+    type Error = String;
+
+    fn try_from(entry: TeeOutputEntry) -> std::result::Result<Self, Self::Error> {
+        let (output, lossy, max_pending) = match entry {
+            TeeOutputEntry::Output(output) => (output, false, default_max_pending()),
+            TeeOutputEntry::Configured {
+                output,
+                lossy,
+                max_pending,
+            } => (output, lossy, max_pending),
+        };
+
+        if max_pending == 0 {
+            return Err(format!(
+                "The `max-pending` of the output < {} > cannot be null",
+                output
+            ));
+        }
+
+        Ok(Self {
+            output,
+            lossy,
+            max_pending,
+        })
+    }
+}
+
+/// A `ThrottleOperatorDescriptor` caps the rate at which the messages it receives on its single input are forwarded on
+/// its single output.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: At most 10 messages per second
+/// throttle:
+///   max-messages: 10
+///   period: 1s
+///   policy: delay
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ThrottleOperatorDescriptor {
+    pub description: Option<Arc<str>>,
+    pub throttle: ThrottleDescriptor,
+}
+
+/// A `ThrottleDescriptor` describes the input (named `in` by default), the output (named `out` by default) and the rate
+/// limit of a throttle built-in Operator: at most `max-messages` per `period` (1s by default), in microseconds.
+///
+/// The limit is enforced with a token bucket: up to `max-messages` can be forwarded in a burst, after which the
+/// messages are forwarded at a steady rate. When the limit is reached, the messages are either dropped (the default) or
+/// delayed, in which case they queue up on the input.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ThrottleDescriptor {
+    #[serde(default = "default_input")]
+    pub input: PortId,
+    #[serde(default = "default_output")]
+    pub output: PortId,
+    #[serde(deserialize_with = "deserialize_max_messages")]
+    #[schemars(range(min = 1))]
+    pub max_messages: u32,
+    #[schemars(schema_with = "zenoh_flow_commons::duration_schema")]
+    #[serde(
+        default = "default_throttle_period",
+        deserialize_with = "deserialize_period",
+        serialize_with = "serialize_time"
+    )]
+    pub period: u64,
+    #[serde(default)]
+    pub policy: ThrottlePolicy,
+}
+
+/// What a throttle built-in Operator does with the messages exceeding its rate limit.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThrottlePolicy {
+    /// The messages are dropped.
+    #[default]
+    Drop,
+    /// The messages are forwarded once the rate allows it.
+    Delay,
+}
+
+fn default_throttle_period() -> u64 {
+    1_000_000
+}
+
+fn deserialize_max_messages<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match u32::deserialize(deserializer)? {
+        0 => Err(serde::de::Error::custom(
+            "The `max-messages` of a throttle cannot be null",
+        )),
+        max_messages => Ok(max_messages),
+    }
+}
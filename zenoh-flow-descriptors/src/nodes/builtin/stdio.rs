@@ -0,0 +1,80 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zenoh_flow_commons::PortId;
+
+use super::{default_input, default_newline, default_output};
+
+/// A `StdinSourceDescriptor` reads the standard input of the Zenoh-Flow runtime and sends each line on a single output.
+///
+/// Once the standard input is closed, the Source no longer sends anything.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My stdin reader
+/// stdin:
+///   output: commands
+/// ```
+///
+/// As all its options have a default value, it can also be declared with:
+///
+/// ```yaml
+/// stdin: {}
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StdinSourceDescriptor {
+    pub description: Option<Arc<str>>,
+    pub stdin: StdinDescriptor,
+}
+
+/// A `StdinDescriptor` describes the output, named `out` by default, on which a stdin built-in Source sends the lines it
+/// reads. The line terminator (`\n` or `\r\n`) is not included in the messages.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct StdinDescriptor {
+    #[serde(default = "default_output")]
+    pub output: PortId,
+}
+
+/// A `StdoutSinkDescriptor` writes the messages it receives on its single input to the standard output of the Zenoh-Flow
+/// runtime.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My stdout writer
+/// stdout:
+///   input: detections
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StdoutSinkDescriptor {
+    pub description: Option<Arc<str>>,
+    pub stdout: StdoutDescriptor,
+}
+
+/// A `StdoutDescriptor` describes the input, named `in` by default, from which a stdout built-in Sink receives the
+/// messages it writes and if a newline is written after each of them (the default).
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct StdoutDescriptor {
+    #[serde(default = "default_newline")]
+    pub newline: bool,
+    #[serde(default = "default_input")]
+    pub input: PortId,
+}
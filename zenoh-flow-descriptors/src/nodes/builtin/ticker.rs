@@ -0,0 +1,55 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zenoh_flow_commons::{serialize_time, PortId};
+
+use super::{default_output, deserialize_period};
+
+/// A `TickerSourceDescriptor` sends, on a single output, a message every `period`.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My ticker
+/// ticker:
+///   period: 100ms
+///   output: tick
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TickerSourceDescriptor {
+    pub description: Option<Arc<str>>,
+    pub ticker: TickerDescriptor,
+}
+
+/// A `TickerDescriptor` describes the period, in microseconds, at which a ticker built-in Source sends a message and the
+/// output, named `out` by default, on which it does.
+///
+/// The payload of each message is the number of the tick, starting at 0, encoded as a little-endian `u64`. If the
+/// Source falls behind (e.g. it was paused), the missed ticks are skipped rather than sent in a burst.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TickerDescriptor {
+    #[schemars(schema_with = "zenoh_flow_commons::duration_schema")]
+    #[serde(
+        deserialize_with = "deserialize_period",
+        serialize_with = "serialize_time"
+    )]
+    pub period: u64,
+    #[serde(default = "default_output")]
+    pub output: PortId,
+}
@@ -12,15 +12,15 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::{collections::HashMap, sync::Arc};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize};
-use zenoh_flow_commons::PortId;
+use zenoh_flow_commons::{parse_time, PortId, QoS};
 use zenoh_keyexpr::OwnedKeyExpr;
 
+use super::{schema_from_entry, serialize_optional_time};
+
 /// A `ZenohSourceDescriptor` encapsulates one or more subscriber(s).
 ///
 /// For each key expression provided, an output with the exact same value will be generated.
@@ -39,11 +39,210 @@ use zenoh_keyexpr::OwnedKeyExpr;
 ///   "cmd_vel": "rt/*/cmd_vel"
 ///   "status": "rt/*/status"
 /// ```
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+///
+/// A subscriber can also be configured to use the timestamp of the Zenoh samples it receives as the timestamp of the
+/// messages it sends, in which case its key expression is given with `key-expr`:
+///
+/// ```yaml
+/// zenoh-subscribers:
+///   "status": "rt/*/status"
+///   "cmd_vel":
+///     key-expr: "rt/*/cmd_vel"
+///     use-sample-timestamp: true
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct ZenohSourceDescriptor {
     pub description: Option<Arc<str>>,
-    #[serde(deserialize_with = "deserialize_canon", alias = "zenoh-subscribers")]
-    pub subscribers: HashMap<PortId, OwnedKeyExpr>,
+    #[serde(
+        deserialize_with = "deserialize_subscribers",
+        alias = "zenoh-subscribers"
+    )]
+    #[schemars(rename = "zenoh-subscribers")]
+    pub subscribers: HashMap<PortId, ZenohSubscriberDescriptor>,
+}
+
+/// A `ZenohSubscriberDescriptor` describes a subscriber of a Zenoh built-in Source: the (canonical) key expression on
+/// which it subscribes and whether the timestamp of the samples it receives should be used as the timestamp of the
+/// messages it sends.
+///
+/// If `use-sample-timestamp` is set but a sample has no timestamp (i.e. the Zenoh router did not add one), the
+/// timestamp of the message is generated by the Zenoh-Flow runtime.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", try_from = "SubscriberEntry")]
+pub struct ZenohSubscriberDescriptor {
+    pub key_expr: OwnedKeyExpr,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub use_sample_timestamp: bool,
+}
+
+/// The textual representations of a subscriber: either its key expression alone or with its options.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum SubscriberEntry {
+    KeyExpr(String),
+    Configured {
+        #[serde(rename = "key-expr")]
+        key_expr: String,
+        #[serde(default, rename = "use-sample-timestamp")]
+        use_sample_timestamp: bool,
+    },
+}
+
+schema_from_entry!(ZenohSubscriberDescriptor, SubscriberEntry);
+
+impl TryFrom<SubscriberEntry> for ZenohSubscriberDescriptor {
+    type Error = String;
+
+    fn try_from(entry: SubscriberEntry) -> std::result::Result<Self, Self::Error> {
+        let (key_expr, use_sample_timestamp) = match entry {
+            SubscriberEntry::KeyExpr(key_expr) => (key_expr, false),
+            SubscriberEntry::Configured {
+                key_expr,
+                use_sample_timestamp,
+            } => (key_expr, use_sample_timestamp),
+        };
+
+        Ok(Self {
+            key_expr: OwnedKeyExpr::autocanonize(key_expr.clone()).map_err(|e| {
+                format!(
+                    "Failed to autocanonize key expression < {} >:\n{:?}",
+                    key_expr, e
+                )
+            })?,
+            use_sample_timestamp,
+        })
+    }
+}
+
+/// A `ZenohQuerierSourceDescriptor` encapsulates one or more querier(s).
+///
+/// For each selector provided, an output with the exact same name as the port will be generated. Each reply received
+/// to a query is sent, as a separate message, on that output.
+///
+/// A querier either queries once, when the Source is started, or periodically if a `period` is provided. The `timeout`
+/// of a query defaults to the one of Zenoh.
+///
+/// As for a subscriber, a querier can be configured to use the timestamp of the replies it receives as the timestamp of
+/// the messages it sends with `use-sample-timestamp`.
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My zenoh querier
+/// zenoh-queriers:
+///   last-status: "rt/*/status"
+///   temperatures:
+///     selector: "home/*/temperature?_time=[now(-1m)..]"
+///     period: 1s
+///     timeout: 500ms
+///     use-sample-timestamp: true
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ZenohQuerierSourceDescriptor {
+    pub description: Option<Arc<str>>,
+    #[serde(alias = "zenoh-queriers")]
+    #[schemars(rename = "zenoh-queriers")]
+    pub queriers: HashMap<PortId, ZenohQuerierDescriptor>,
+}
+
+/// A `ZenohQuerierDescriptor` describes a querier of a Zenoh built-in Source: the selector it queries (with its key
+/// expression in canonical form), the period, in microseconds, at which it does, the timeout, in microseconds, of its
+/// queries and whether the timestamp of the replies it receives should be used as the timestamp of the messages it
+/// sends.
+///
+/// If no period is provided, the selector is queried once, when the Source is started. Neither the period nor the
+/// timeout can be null.
+///
+/// If `use-sample-timestamp` is set but a reply has no timestamp, the timestamp of the message is generated by the
+/// Zenoh-Flow runtime.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", try_from = "QuerierEntry")]
+pub struct ZenohQuerierDescriptor {
+    pub selector: Arc<str>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_time"
+    )]
+    pub period: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_time"
+    )]
+    pub timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub use_sample_timestamp: bool,
+}
+
+/// The textual representations of a querier: either its selector alone or with its options.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum QuerierEntry {
+    Selector(String),
+    Configured {
+        selector: String,
+        #[serde(default)]
+        period: Option<String>,
+        #[serde(default)]
+        timeout: Option<String>,
+        #[serde(default, rename = "use-sample-timestamp")]
+        use_sample_timestamp: bool,
+    },
+}
+
+schema_from_entry!(ZenohQuerierDescriptor, QuerierEntry);
+
+impl TryFrom<QuerierEntry> for ZenohQuerierDescriptor {
+    type Error = String;
+
+    fn try_from(entry: QuerierEntry) -> std::result::Result<Self, Self::Error> {
+        let (selector, period, timeout, use_sample_timestamp) = match entry {
+            QuerierEntry::Selector(selector) => (selector, None, None, false),
+            QuerierEntry::Configured {
+                selector,
+                period,
+                timeout,
+                use_sample_timestamp,
+            } => (selector, period, timeout, use_sample_timestamp),
+        };
+
+        // Only the key expression of the selector is autocanonized, its parameters are left untouched.
+        let (key_expr, parameters) = match selector.split_once('?') {
+            Some((key_expr, parameters)) => (key_expr, Some(parameters)),
+            None => (selector.as_str(), None),
+        };
+        let key_expr = OwnedKeyExpr::autocanonize(key_expr.to_string()).map_err(|e| {
+            format!(
+                "Failed to autocanonize the key expression of the selector < {} >:\n{:?}",
+                selector, e
+            )
+        })?;
+
+        let period = period.as_deref().map(parse_time).transpose()?;
+        if period == Some(0) {
+            return Err(format!(
+                "The period of the querier on < {} > cannot be null",
+                selector
+            ));
+        }
+
+        let timeout = timeout.as_deref().map(parse_time).transpose()?;
+        if timeout == Some(0) {
+            return Err(format!(
+                "The timeout of the querier on < {} > cannot be null",
+                selector
+            ));
+        }
+
+        Ok(Self {
+            selector: match parameters {
+                Some(parameters) => format!("{key_expr}?{parameters}").into(),
+                None => key_expr.to_string().into(),
+            },
+            period,
+            timeout,
+            use_sample_timestamp,
+        })
+    }
 }
 
 /// A `ZenohSinkDescriptor` encapsulates one or more publisher(s).
@@ -65,44 +264,243 @@ pub(crate) struct ZenohSourceDescriptor {
 ///   cmd_vel: rt/cmd_vel
 ///   status: rt/status
 /// ```
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+///
+/// A publisher can also be configured with a [QoS], the encoding of its publications and whether the key expression
+/// of each publication is suffixed with the key suffix of the message. In that case, its key expression is given with
+/// `key-expr`:
+///
+/// ```yaml
+/// description: My zenoh sink
+/// zenoh-publishers:
+///   status: rt/status
+///   cmd_vel:
+///     key-expr: rt/cmd_vel
+///     encoding: application/json
+///     qos:
+///       priority: real-time
+///       congestion-control: block
+///   telemetry:
+///     key-expr: rt/devices
+///     dynamic-suffix: true
+/// ```
+///
+/// With the above configuration, a message sent on the `telemetry` port with the key suffix `robot-1/battery` is
+/// published on `rt/devices/robot-1/battery`.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct ZenohSinkDescriptor {
     pub description: Option<Arc<str>>,
-    #[serde(deserialize_with = "deserialize_canon", alias = "zenoh-publishers")]
-    pub publishers: HashMap<PortId, OwnedKeyExpr>,
+    #[serde(
+        deserialize_with = "deserialize_publishers",
+        alias = "zenoh-publishers"
+    )]
+    #[schemars(rename = "zenoh-publishers")]
+    pub publishers: HashMap<PortId, ZenohPublisherDescriptor>,
 }
 
-// Transforms a HashMap<String, String> into a HashMap<PortId, OwnedKeyExpr>.
-fn deserialize_canon<'de, D>(
+/// A `ZenohPublisherDescriptor` describes a publisher of a Zenoh built-in Sink: the (canonical) key expression on which
+/// it publishes, the quality of service with which it does, the encoding of its publications and whether the key
+/// expression is suffixed, for each message, with the key suffix of that message.
+///
+/// If no encoding is provided, the encoding of the Zenoh sample the message was created from (if any) is used.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", try_from = "PublisherEntry")]
+pub struct ZenohPublisherDescriptor {
+    pub key_expr: OwnedKeyExpr,
+    #[serde(default, skip_serializing_if = "QoS::is_default")]
+    pub qos: QoS,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Arc<str>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dynamic_suffix: bool,
+}
+
+/// The textual representations of a publisher: either its key expression alone or with its options.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum PublisherEntry {
+    KeyExpr(String),
+    Configured {
+        #[serde(rename = "key-expr")]
+        key_expr: String,
+        #[serde(flatten)]
+        options: PublisherOptions,
+    },
+}
+
+/// The options of a publisher, see [ZenohPublisherDescriptor].
+#[derive(Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "kebab-case")]
+struct PublisherOptions {
+    #[serde(default)]
+    qos: QoS,
+    #[serde(default)]
+    encoding: Option<Arc<str>>,
+    #[serde(default)]
+    dynamic_suffix: bool,
+}
+
+impl ZenohPublisherDescriptor {
+    fn new(key_expr: OwnedKeyExpr, options: PublisherOptions) -> Self {
+        Self {
+            key_expr,
+            qos: options.qos,
+            encoding: options.encoding,
+            dynamic_suffix: options.dynamic_suffix,
+        }
+    }
+}
+
+schema_from_entry!(ZenohPublisherDescriptor, PublisherEntry);
+
+impl TryFrom<PublisherEntry> for ZenohPublisherDescriptor {
+    type Error = String;
+
+    fn try_from(entry: PublisherEntry) -> std::result::Result<Self, Self::Error> {
+        let (key_expr, options) = match entry {
+            PublisherEntry::KeyExpr(key_expr) => (key_expr, PublisherOptions::default()),
+            PublisherEntry::Configured { key_expr, options } => (key_expr, options),
+        };
+
+        let canonical_key_expr = OwnedKeyExpr::autocanonize(key_expr.clone()).map_err(|e| {
+            format!(
+                "Failed to autocanonize key expression < {} >:\n{:?}",
+                key_expr, e
+            )
+        })?;
+
+        Ok(Self::new(canonical_key_expr, options))
+    }
+}
+
+/// A `ZenohQueryableSinkDescriptor` encapsulates one or more queryable(s).
+///
+/// For each key expression provided, an input with the exact same name as the port will be generated. The last
+/// `history` messages received on that input (by default, only the last one) are kept and sent, along with their
+/// timestamp, as replies to the queries made on the key expression.
+///
+/// Each message is a distinct reply on the same key expression. Zenoh consolidates, by default, the replies received
+/// on a key expression: only the most recent is then kept. To receive the complete history, the queries must be made
+/// with the consolidation mode `None` (e.g. `session.get(key_expr).consolidation(ConsolidationMode::None)`).
+///
+/// # Examples
+///
+/// ```yaml
+/// description: My zenoh queryable
+/// zenoh-queryables:
+///   status: rt/status
+///   cmd_vel:
+///     key-expr: rt/cmd_vel
+///     history: 10
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ZenohQueryableSinkDescriptor {
+    pub description: Option<Arc<str>>,
+    #[serde(alias = "zenoh-queryables")]
+    #[schemars(rename = "zenoh-queryables")]
+    pub queryables: HashMap<PortId, ZenohQueryableDescriptor>,
+}
+
+/// A `ZenohQueryableDescriptor` describes a queryable of a Zenoh built-in Sink: the (canonical) key expression on which
+/// it is declared and the number of messages it keeps to answer the queries.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", try_from = "QueryableEntry")]
+pub struct ZenohQueryableDescriptor {
+    pub key_expr: OwnedKeyExpr,
+    pub history: usize,
+}
+
+/// The textual representations of a queryable: either its key expression alone or with its history.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum QueryableEntry {
+    KeyExpr(String),
+    Configured {
+        #[serde(rename = "key-expr")]
+        key_expr: String,
+        #[serde(default)]
+        history: Option<usize>,
+    },
+}
+
+schema_from_entry!(ZenohQueryableDescriptor, QueryableEntry);
+
+impl TryFrom<QueryableEntry> for ZenohQueryableDescriptor {
+    type Error = String;
+
+    fn try_from(entry: QueryableEntry) -> std::result::Result<Self, Self::Error> {
+        let (key_expr, history) = match entry {
+            QueryableEntry::KeyExpr(key_expr) => (key_expr, None),
+            QueryableEntry::Configured { key_expr, history } => (key_expr, history),
+        };
+
+        if history == Some(0) {
+            return Err(format!(
+                "The history of the queryable on < {} > cannot be empty",
+                key_expr
+            ));
+        }
+
+        Ok(Self {
+            key_expr: OwnedKeyExpr::autocanonize(key_expr.clone()).map_err(|e| {
+                format!(
+                    "Failed to autocanonize key expression < {} >:\n{:?}",
+                    key_expr, e
+                )
+            })?,
+            history: history.unwrap_or(1),
+        })
+    }
+}
+
+// Deserializes the publishers, each through its `PublisherEntry`, and warns if two of them share the same canonical
+// key expression.
+fn deserialize_publishers<'de, D>(
     deserializer: D,
-) -> std::result::Result<HashMap<PortId, OwnedKeyExpr>, D::Error>
+) -> std::result::Result<HashMap<PortId, ZenohPublisherDescriptor>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let key_expressions: HashMap<String, String> =
+    let publishers: HashMap<PortId, ZenohPublisherDescriptor> =
         serde::de::Deserialize::deserialize(deserializer)?;
-    let mut h_map = HashMap::with_capacity(key_expressions.len());
-    let mut h_set = HashSet::with_capacity(key_expressions.len());
+    warn_shared_canonical_forms(
+        publishers
+            .iter()
+            .map(|(port_id, publisher)| (port_id, &publisher.key_expr)),
+    );
 
-    for (port_id, key_expr) in key_expressions {
-        let owned_canon_ke = OwnedKeyExpr::autocanonize(key_expr.clone()).map_err(|e| {
-            serde::de::Error::custom(format!(
-                "Failed to autocanonize key expression < {} >:\n{:?}",
-                key_expr.clone(),
-                e
-            ))
-        })?;
+    Ok(publishers)
+}
+
+// Deserializes the subscribers, each through its `SubscriberEntry`, and warns if two of them share the same canonical
+// key expression.
+fn deserialize_subscribers<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<PortId, ZenohSubscriberDescriptor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let subscribers: HashMap<PortId, ZenohSubscriberDescriptor> =
+        serde::de::Deserialize::deserialize(deserializer)?;
+    warn_shared_canonical_forms(
+        subscribers
+            .iter()
+            .map(|(port_id, subscriber)| (port_id, &subscriber.key_expr)),
+    );
 
-        if !h_set.insert(owned_canon_ke.clone()) {
-            let (duplicate, _) = h_map
-                .iter()
-                .find(|(_, owned_ke)| owned_canon_ke == **owned_ke)
-                .unwrap();
+    Ok(subscribers)
+}
+
+// Warns if two (canonical) key expressions are the same.
+fn warn_shared_canonical_forms<'a>(
+    key_expressions: impl Iterator<Item = (&'a PortId, &'a OwnedKeyExpr)>,
+) {
+    let mut h_map: HashMap<&OwnedKeyExpr, &PortId> = HashMap::new();
+
+    for (port_id, key_expr) in key_expressions {
+        if let Some(duplicate) = h_map.insert(key_expr, port_id) {
             tracing::warn!(
                 r#"
-The following two key expressions share the same canonical form ( {} ):
-- {}
-- {}
+The key expressions of the ports < {} > and < {} > share the same canonical form ( {} ).
 
 They will thus **both** receive the same publications.
 If this is a desired behaviour, you can safely ignore this message.
@@ -110,14 +508,10 @@ If this is a desired behaviour, you can safely ignore this message.
 For more details, see:
 https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Key%20Expressions.md#canon-forms
 "#,
-                owned_canon_ke,
-                key_expr,
+                port_id,
                 duplicate,
+                key_expr,
             );
         }
-
-        h_map.insert(port_id.into(), owned_canon_ke);
     }
-
-    Ok(h_map)
 }
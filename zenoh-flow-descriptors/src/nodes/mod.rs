@@ -19,11 +19,84 @@ pub(crate) mod source;
 
 use std::sync::Arc;
 
+use anyhow::bail;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
-use zenoh_flow_commons::Configuration;
+use zenoh_flow_commons::{Configuration, Result};
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// How a node is isolated from the Zenoh-Flow runtime that manages it.
+///
+/// By default, nodes are loaded in the process of the Zenoh-Flow runtime. A crash in a node (e.g. a segmentation fault
+/// in a library relying on C code) thus brings down the runtime and all the data flows it manages.
+///
+/// With `process` isolation, the node is instead loaded in a child helper process and its inputs and outputs are
+/// bridged to the runtime. If the child exits, the node is reported as failed and the runtime keeps running.
+///
+/// # Example
+///
+/// ```yaml
+/// id: my-operator-1
+/// library: file:///home/zenoh-flow/libmy_operator.so
+/// isolation: process
+/// inputs:
+///   - in-1
+/// outputs:
+///   - out-1
+/// ```
+///
+/// Only nodes implemented by a shared library can be isolated: built-in nodes, nodes registered in the runtime
+/// (`builtin://` or `static://`) and WebAssembly modules cannot.
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Isolation {
+    /// The node is loaded in the process of the Zenoh-Flow runtime.
+    #[default]
+    None,
+    /// The node is loaded in a dedicated child process.
+    Process,
+}
+
+impl Isolation {
+    /// Returns `true` if this is the default isolation, i.e. none.
+    pub fn is_none(&self) -> bool {
+        *self == Isolation::None
+    }
+
+    /// Checks that a node, implemented by the `library` (`None` for a built-in node), supports this isolation.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the node is isolated in a process but it is a built-in node, a node
+    /// registered in the runtime or a WebAssembly module.
+    pub fn check(&self, library: Option<&Url>) -> Result<()> {
+        if self.is_none() {
+            return Ok(());
+        }
+
+        let Some(library) = library else {
+            bail!("A built-in node cannot be isolated in a process")
+        };
+
+        match library.scheme() {
+            "builtin" | "static" => {
+                bail!(
+                    "A node registered in the runtime (< {} >) cannot be isolated in a process",
+                    library
+                )
+            }
+            _ if library.path().ends_with(".wasm") => {
+                bail!(
+                    "A WebAssembly module (< {} >) cannot be isolated in a process, it already runs in a sandbox",
+                    library
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct RemoteNodeDescriptor {
     pub descriptor: Url,
     pub description: Option<Arc<str>>,
@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use zenoh_flow_commons::{Configuration, NodeId, PortId};
 
@@ -30,7 +31,7 @@ use crate::{
 /// node: my-operator
 /// output: out
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub(crate) struct CompositeOutputDescriptor {
     pub id: PortId,
     pub node: NodeId,
@@ -55,7 +56,7 @@ impl From<CompositeOutputDescriptor> for OutputDescriptor {
 /// node: my-operator
 /// input: in
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq)]
 pub(crate) struct CompositeInputDescriptor {
     pub id: PortId,
     pub node: NodeId,
@@ -108,7 +109,7 @@ impl From<CompositeInputDescriptor> for InputDescriptor {
 ///     node: InnerOperator2
 ///     output: out-1
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CompositeOperatorDescriptor {
     pub description: Arc<str>,
     pub inputs: Vec<CompositeInputDescriptor>,
@@ -16,17 +16,29 @@ pub(crate) mod composite;
 
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::{Configuration, NodeId, PortId};
 
-use super::RemoteNodeDescriptor;
+use super::{
+    builtin::{
+        expression::ExpressionOperatorDescriptor,
+        routing::{
+            MergeOperatorDescriptor, SplitOperatorDescriptor, TeeOperatorDescriptor,
+            ThrottleOperatorDescriptor,
+        },
+    },
+    Isolation, RemoteNodeDescriptor,
+};
 
 /// An `OperatorDescriptor` uniquely identifies and configures an Operator.
 ///
 /// Zenoh-Flow supports several ways of declaring a Operator:
 /// - by importing a "remote" descriptor (e.g. located in another descriptor file),
-/// - with an inline declaration.
+/// - with an inline declaration,
+/// - with an inline declaration of an expression built-in, filtering and / or reshaping JSON messages,
+/// - with an inline declaration of a routing built-in: merge, split, tee or throttle.
 ///
 /// It is not possible to define an `Operator` inside your code base. This structure was made to be parsed from a
 /// configuration file. You should instead use a [FlattenedOperatorDescriptor](crate::FlattenedOperatorDescriptor).
@@ -82,24 +94,81 @@ use super::RemoteNodeDescriptor;
 /// configuration:
 ///   answer: 1
 /// ```
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+///
+/// ## Inline declaration: expression built-in operator
+///
+/// ```yaml
+/// id: my-operator-1
+/// description: Fast robots, speed in km/h
+/// expression:
+///   filter: $.speed > 1.5
+///   map:
+///     id: $.robot.id
+///     speed: $.speed * 3.6
+/// ```
+///
+/// ## Inline declaration: routing built-in operators
+///
+/// ```yaml
+/// id: my-merge-1
+/// merge:
+///   inputs:
+///     - in-1
+///     - in-2
+///   order: timestamp
+/// ```
+///
+/// ```yaml
+/// id: my-split-1
+/// split:
+///   routes:
+///     - output: fast
+///       when: $.speed > 1.5
+///   otherwise: slow
+/// ```
+///
+/// ```yaml
+/// id: my-tee-1
+/// tee:
+///   outputs:
+///     - archive
+///     - output: monitor
+///       lossy: true
+/// ```
+///
+/// ```yaml
+/// id: my-throttle-1
+/// throttle:
+///   max-messages: 10
+///   period: 1s
+/// ```
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct OperatorDescriptor {
     pub id: NodeId,
     #[serde(flatten)]
     pub variant: OperatorVariants,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub(crate) enum OperatorVariants {
     Remote(RemoteNodeDescriptor),
     Custom(CustomOperatorDescriptor),
+    Expression(ExpressionOperatorDescriptor),
+    Merge(MergeOperatorDescriptor),
+    Split(SplitOperatorDescriptor),
+    Tee(TeeOperatorDescriptor),
+    Throttle(ThrottleOperatorDescriptor),
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CustomOperatorDescriptor {
     pub description: Option<Arc<str>>,
     pub library: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Arc<str>>,
+    #[serde(default, skip_serializing_if = "Isolation::is_none")]
+    pub isolation: Isolation,
     pub inputs: Vec<PortId>,
     pub outputs: Vec<PortId>,
     #[serde(default)]
@@ -14,19 +14,25 @@
 
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::{Configuration, NodeId, PortId};
 
-use super::RemoteNodeDescriptor;
-use crate::nodes::builtin::zenoh::ZenohSinkDescriptor;
+use super::{Isolation, RemoteNodeDescriptor};
+use crate::nodes::builtin::{
+    file::FileSinkDescriptor,
+    stdio::StdoutSinkDescriptor,
+    zenoh::{ZenohQueryableSinkDescriptor, ZenohSinkDescriptor},
+};
 
 /// A `SinkDescriptor` uniquely identifies a Sink.
 ///
 /// Zenoh-Flow supports several ways of declaring a Sink:
 /// - by importing a "remote" descriptor (e.g. located in another descriptor file),
 /// - with an inline declaration,
-/// - with an inline declaration of a Zenoh built-in, listing on which key expressions to publish.
+/// - with an inline declaration of a Zenoh built-in, listing on which key expressions to publish or to answer queries,
+/// - with an inline declaration of a file or stdout built-in.
 ///
 /// # ⚠️ Caveat: `NodeId` and `PortId`
 ///
@@ -66,25 +72,64 @@ use crate::nodes::builtin::zenoh::ZenohSinkDescriptor;
 ///   key_0: key/expr/0
 ///   key_1: key/expr/1
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+///
+/// ### Zenoh built-in queryable Sink
+///
+/// ```yaml
+/// id: my-queryable-0
+/// description: My zenoh queryable
+/// zenoh-queryables:
+///   key_0: key/expr/0
+///   key_1:
+///     key-expr: key/expr/1
+///     history: 10
+/// ```
+///
+/// ### File built-in Sink
+///
+/// ```yaml
+/// id: my-file-0
+/// description: My file writer
+/// file:
+///   path: /var/log/zenoh-flow/my-sink.log
+///   rotation:
+///     max-size: 10MiB
+/// ```
+///
+/// ### Stdout built-in Sink
+///
+/// ```yaml
+/// id: my-stdout-0
+/// description: My stdout writer
+/// stdout:
+///   input: in
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct SinkDescriptor {
     pub id: NodeId,
     #[serde(flatten)]
     pub variant: SinkVariants,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub(crate) enum SinkVariants {
     Zenoh(ZenohSinkDescriptor),
+    ZenohQueryables(ZenohQueryableSinkDescriptor),
+    File(FileSinkDescriptor),
+    Stdout(StdoutSinkDescriptor),
     Remote(RemoteNodeDescriptor),
     Custom(CustomSinkDescriptor),
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CustomSinkDescriptor {
     pub description: Option<Arc<str>>,
     pub library: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Arc<str>>,
+    #[serde(default, skip_serializing_if = "Isolation::is_none")]
+    pub isolation: Isolation,
     pub inputs: Vec<PortId>,
     #[serde(default)]
     pub configuration: Configuration,
@@ -14,19 +14,27 @@
 
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::{Configuration, NodeId, PortId};
 
-use super::RemoteNodeDescriptor;
-use crate::nodes::builtin::zenoh::ZenohSourceDescriptor;
+use super::{Isolation, RemoteNodeDescriptor};
+use crate::nodes::builtin::{
+    file::FileSourceDescriptor,
+    replay::ReplaySourceDescriptor,
+    stdio::StdinSourceDescriptor,
+    ticker::TickerSourceDescriptor,
+    zenoh::{ZenohQuerierSourceDescriptor, ZenohSourceDescriptor},
+};
 
 /// A `SourceDescriptor` uniquely identifies a Source.
 ///
 /// Zenoh-Flow supports several ways of declaring a Source:
 /// - by importing a "remote" descriptor (e.g. located in another descriptor file),
 /// - with an inline declaration,
-/// - with an inline declaration of a Zenoh built-in.
+/// - with an inline declaration of a Zenoh built-in,
+/// - with an inline declaration of a file, stdin, ticker or replay built-in.
 ///
 /// # ⚠️ Caveat: `NodeId` and `PortId`
 ///
@@ -66,25 +74,84 @@ use crate::nodes::builtin::zenoh::ZenohSourceDescriptor;
 ///   ke-0: key/expr/0
 ///   ke-1: key/expr/1
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+///
+/// ### Zenoh built-in querier Source
+///
+/// ```yaml
+/// id: my-querier-0
+/// description: My zenoh querier
+/// zenoh-queriers:
+///   ke-0: key/expr/0
+///   ke-1:
+///     selector: key/expr/1
+///     period: 1s
+/// ```
+///
+/// ### File built-in Source
+///
+/// ```yaml
+/// id: my-file-0
+/// description: My file reader
+/// file:
+///   path: /var/log/robot.log
+///   read: lines
+/// ```
+///
+/// ### Stdin built-in Source
+///
+/// ```yaml
+/// id: my-stdin-0
+/// description: My stdin reader
+/// stdin:
+///   output: out
+/// ```
+///
+/// ### Ticker built-in Source
+///
+/// ```yaml
+/// id: my-ticker-0
+/// description: My ticker
+/// ticker:
+///   period: 100ms
+/// ```
+///
+/// ### Replay built-in Source
+///
+/// ```yaml
+/// id: my-replay-0
+/// description: My replay
+/// replay:
+///   path: /var/log/zenoh-flow/source-out.zfrec
+///   pacing: fastest
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct SourceDescriptor {
     pub id: NodeId,
     #[serde(flatten)]
     pub variant: SourceVariants,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub(crate) enum SourceVariants {
     Zenoh(ZenohSourceDescriptor),
+    ZenohQueriers(ZenohQuerierSourceDescriptor),
+    File(FileSourceDescriptor),
+    Stdin(StdinSourceDescriptor),
+    Ticker(TickerSourceDescriptor),
+    Replay(ReplaySourceDescriptor),
     Remote(RemoteNodeDescriptor),
     Custom(CustomSourceDescriptor),
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, JsonSchema, Serialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CustomSourceDescriptor {
     pub description: Option<Arc<str>>,
     pub library: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Arc<str>>,
+    #[serde(default, skip_serializing_if = "Isolation::is_none")]
+    pub isolation: Isolation,
     pub outputs: Vec<PortId>,
     #[serde(default)]
     pub configuration: Configuration,
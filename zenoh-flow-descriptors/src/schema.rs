@@ -0,0 +1,133 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The [JSON Schemas](https://json-schema.org) of the descriptors, such that editors can validate and autocomplete
+//! them.
+//!
+//! A schema is generated for the descriptor of a data flow and for the descriptors of each kind of node that can be
+//! imported from another file. The built-in nodes and, for the Operators, the composite Operators are covered.
+//!
+//! # Caveat: vars
+//!
+//! The [Vars](zenoh_flow_commons::Vars) are substituted *before* a descriptor is parsed. A value that is only known
+//! after the substitution (e.g. a number given as `{{ RATE }}`) can thus be reported as invalid by an editor.
+
+use std::collections::HashMap;
+
+use schemars::{gen::SchemaSettings, schema::RootSchema, JsonSchema};
+
+use crate::{
+    flattened::nodes::{
+        operator::LocalOperatorVariants, sink::LocalSinkVariants, source::LocalSourceVariants,
+    },
+    DataFlowDescriptor,
+};
+
+// A descriptor, as written in a file: it can declare its own `vars`.
+//
+// This structure is only used to generate the JSON Schemas, its fields are never read.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct DescriptorFile<N> {
+    #[schemars(flatten)]
+    descriptor: N,
+    /// Pairs of `(key, value)` substituted, in the descriptor, wherever `{{ key }}` appears.
+    #[schemars(default)]
+    vars: HashMap<String, String>,
+}
+
+/// Returns the JSON Schema of the descriptor file of type `N`, with the provided `title` and `description`.
+fn schema_for<N: JsonSchema>(title: &str, description: &str) -> RootSchema {
+    let mut schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<DescriptorFile<N>>();
+    let metadata = schema.schema.metadata();
+    metadata.title = Some(title.into());
+    metadata.description = Some(description.into());
+
+    schema
+}
+
+/// Returns the JSON Schema of a [DataFlowDescriptor].
+pub fn data_flow_schema() -> RootSchema {
+    schema_for::<DataFlowDescriptor>(
+        "DataFlowDescriptor",
+        "The descriptor of a Zenoh-Flow data flow.",
+    )
+}
+
+/// Returns the JSON Schema of the descriptor of a Source, as imported by a data flow through its `descriptor` field.
+pub fn source_schema() -> RootSchema {
+    schema_for::<LocalSourceVariants>("SourceDescriptor", "The descriptor of a Zenoh-Flow Source.")
+}
+
+/// Returns the JSON Schema of the descriptor of an Operator, as imported by a data flow (or a composite Operator)
+/// through its `descriptor` field.
+pub fn operator_schema() -> RootSchema {
+    schema_for::<LocalOperatorVariants>(
+        "OperatorDescriptor",
+        "The descriptor of a Zenoh-Flow Operator, possibly composite.",
+    )
+}
+
+/// Returns the JSON Schema of the descriptor of a Sink, as imported by a data flow through its `descriptor` field.
+pub fn sink_schema() -> RootSchema {
+    schema_for::<LocalSinkVariants>("SinkDescriptor", "The descriptor of a Zenoh-Flow Sink.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schemas() {
+        let data_flow = serde_json::to_value(data_flow_schema()).unwrap();
+        assert_eq!(data_flow["title"], "DataFlowDescriptor");
+        for property in ["name", "sources", "operators", "sinks", "links", "vars"] {
+            assert!(
+                data_flow["properties"].get(property).is_some(),
+                "Missing property < {property} >"
+            );
+        }
+
+        let data_flow = data_flow.to_string();
+        for built_in in [
+            "zenoh-subscribers",
+            "zenoh-queriers",
+            "zenoh-publishers",
+            "zenoh-queryables",
+            "ticker",
+            "replay",
+            "expression",
+            "throttle",
+        ] {
+            assert!(
+                data_flow.contains(&format!("\"{built_in}\"")),
+                "Missing built-in < {built_in} >"
+            );
+        }
+
+        let operator = serde_json::to_value(operator_schema()).unwrap().to_string();
+        for composite_field in ["operators", "links", "inputs", "outputs"] {
+            assert!(operator.contains(&format!("\"{composite_field}\"")));
+        }
+
+        let source = serde_json::to_value(source_schema()).unwrap();
+        assert!(source["properties"].get("vars").is_some());
+        assert!(source.to_string().contains("\"zenoh-subscribers\""));
+
+        let sink = serde_json::to_value(sink_schema()).unwrap();
+        assert!(sink.to_string().contains("\"zenoh-publishers\""));
+    }
+}
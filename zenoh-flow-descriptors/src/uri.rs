@@ -12,20 +12,586 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use anyhow::{bail, Context};
+#[cfg(any(feature = "http", feature = "zenoh"))]
+use std::time::Duration;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Context};
 use serde::Deserialize;
 use url::Url;
-use zenoh_flow_commons::{try_parse_from_file, Result, Vars};
-
-pub(crate) fn try_load_descriptor<N>(url: &Url, vars: Vars) -> Result<(N, Vars)>
-where
-    N: for<'a> Deserialize<'a>,
-{
-    match url.scheme() {
-        "file" => try_parse_from_file::<N>(url.path(), vars).context(format!(
+use zenoh_flow_commons::{
+    try_deserialize_from_str, try_parse_from_str, try_render_from_str, Result, Vars,
+};
+
+use crate::{diagnostics::SourceMap, DataFlowDescriptor};
+
+/// A `UriResolver` fetches the content of the descriptors located at the URLs of a scheme.
+///
+/// Zenoh-Flow provides resolvers for the `file`, `http` and `https` (feature `http`) and `zenoh` (feature `zenoh`)
+/// schemes. Other schemes can be supported by implementing this trait and registering the implementation on a
+/// [DescriptorResolver].
+pub trait UriResolver: Send + Sync {
+    /// Returns the content of the descriptor located at `url`.
+    ///
+    /// # Errors
+    ///
+    /// This method should fail if the descriptor could not be fetched.
+    fn fetch(&self, url: &Url) -> Result<String>;
+}
+
+/// A `FileResolver` reads the descriptors located on the file system of the machine, i.e. with a `file://` URL.
+pub struct FileResolver;
+
+impl UriResolver for FileResolver {
+    fn fetch(&self, url: &Url) -> Result<String> {
+        std::fs::read_to_string(url.path()).context(format!(
             "Failed to load descriptor from file:\n{}",
             url.path()
-        )),
-        _ => bail!("Unsupported URL scheme < {} >", url.scheme(),),
+        ))
+    }
+}
+
+/// An `HttpResolver` fetches the descriptors with an HTTP `GET` request, i.e. with an `http://` or `https://` URL.
+#[cfg(feature = "http")]
+pub struct HttpResolver {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "http")]
+impl HttpResolver {
+    /// Creates an `HttpResolver` whose requests fail if they are not completed within `timeout`.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpResolver {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(10))
+    }
+}
+
+#[cfg(feature = "http")]
+impl UriResolver for HttpResolver {
+    fn fetch(&self, url: &Url) -> Result<String> {
+        self.agent
+            .get(url.as_str())
+            .call()
+            .map_err(|e| anyhow!("Failed to fetch descriptor from < {} >:\n{}", url, e))?
+            .into_string()
+            .context(format!("Failed to read descriptor from < {} >", url))
+    }
+}
+
+/// A `ZenohResolver` fetches the descriptors by querying Zenoh, typically a storage, i.e. with a `zenoh://` URL.
+///
+/// The key expression queried is formed by the host and the path of the URL: `zenoh://zf/descriptors/operator.yaml`
+/// queries the key expression `zf/descriptors/operator.yaml`. The query of the URL, if any, is forwarded as the
+/// parameters of the selector. The first successful reply is used as the content of the descriptor.
+///
+/// # Blocking
+///
+/// As every [UriResolver], the `ZenohResolver` blocks the thread on which it fetches a descriptor, for up to its
+/// `timeout`. From asynchronous code, the descriptors should be loaded and flattened in a blocking task (e.g. with
+/// `async_std::task::spawn_blocking`) such that the executor, on which the Zenoh session may depend, is not blocked.
+#[cfg(feature = "zenoh")]
+pub struct ZenohResolver {
+    session: Arc<zenoh::Session>,
+    timeout: Duration,
+}
+
+#[cfg(feature = "zenoh")]
+impl ZenohResolver {
+    /// Creates a `ZenohResolver` that queries Zenoh through the provided `session`.
+    pub fn new(session: Arc<zenoh::Session>) -> Self {
+        Self {
+            session,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Sets the duration after which the queries are considered as failed if no reply was received.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[cfg(feature = "zenoh")]
+impl UriResolver for ZenohResolver {
+    fn fetch(&self, url: &Url) -> Result<String> {
+        use zenoh::prelude::sync::*;
+
+        let key_expr = format!("{}{}", url.host_str().unwrap_or_default(), url.path());
+        let selector = match url.query() {
+            Some(parameters) => format!("{}?{}", key_expr, parameters),
+            None => key_expr,
+        };
+
+        let replies = self
+            .session
+            .get(selector.as_str())
+            .timeout(self.timeout)
+            .res_sync()
+            .map_err(|e| anyhow!("Failed to query < {} >:\n{:?}", selector, e))?;
+
+        let mut errors = Vec::new();
+        while let Ok(reply) = replies.recv() {
+            match reply.sample {
+                Ok(sample) => {
+                    return String::from_utf8(sample.payload.contiguous().to_vec()).context(
+                        format!(
+                            "The descriptor received from < {} > is not valid UTF-8",
+                            selector
+                        ),
+                    )
+                }
+                Err(value) => errors.push(value.to_string()),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Err(anyhow!("No reply received when querying < {} >", selector)),
+            false => Err(anyhow!(
+                "Only error replies were received when querying < {} >:\n- {}",
+                selector,
+                errors.join("\n- ")
+            )),
+        }
+    }
+}
+
+/// A `DescriptorResolver` loads the descriptors that a data flow references through their URL.
+///
+/// Fetching the content of a descriptor is delegated to the [UriResolver] registered for the scheme of its URL. By
+/// default, the `file` scheme and, if the feature `http` is enabled, the `http` and `https` schemes are supported.
+///
+/// The descriptors are cached:
+/// - in memory, for as long as the `DescriptorResolver` (or one of its clones) lives, such that a descriptor referenced
+///   several times is fetched only once,
+/// - optionally, in a local directory (see [with_cache_dir](DescriptorResolver::with_cache_dir())) for the descriptors
+///   that are not on the file system, such that they can still be loaded if their location is unreachable.
+#[derive(Clone)]
+pub struct DescriptorResolver {
+    resolvers: HashMap<Arc<str>, Arc<dyn UriResolver>>,
+    cache_dir: Option<PathBuf>,
+    cache: Arc<Mutex<HashMap<Url, Arc<str>>>>,
+}
+
+impl Default for DescriptorResolver {
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut resolver = Self {
+            resolvers: HashMap::default(),
+            cache_dir: None,
+            cache: Arc::new(Mutex::new(HashMap::default())),
+        }
+        .with_resolver("file", FileResolver);
+
+        #[cfg(feature = "http")]
+        {
+            resolver = resolver
+                .with_resolver("http", HttpResolver::default())
+                .with_resolver("https", HttpResolver::default());
+        }
+
+        resolver
+    }
+}
+
+impl DescriptorResolver {
+    /// Registers the `resolver` for the URLs of the provided `scheme`, replacing the previous one, if any.
+    pub fn with_resolver(
+        mut self,
+        scheme: impl Into<Arc<str>>,
+        resolver: impl UriResolver + 'static,
+    ) -> Self {
+        self.resolvers.insert(scheme.into(), Arc::new(resolver));
+        self
+    }
+
+    /// Registers a [ZenohResolver] for the `zenoh` scheme, querying Zenoh through the provided `session`.
+    #[cfg(feature = "zenoh")]
+    pub fn with_zenoh(self, session: Arc<zenoh::Session>) -> Self {
+        self.with_resolver("zenoh", ZenohResolver::new(session))
+    }
+
+    /// Sets the local directory in which the descriptors fetched from a remote location are cached.
+    ///
+    /// A cached descriptor is only used if it could not be fetched.
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Attempts to load an instance of `N` from the descriptor located at `url`, overwriting (or complementing) the
+    /// [Vars] it declares with the provided `vars`.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if:
+    /// - no [UriResolver] is registered for the scheme of the URL,
+    /// - the descriptor could not be fetched and is not in the local cache,
+    /// - the descriptor could not be parsed, see [try_parse_from_str].
+    pub fn try_load_descriptor<N>(&self, url: &Url, vars: Vars) -> Result<(N, Vars)>
+    where
+        N: for<'a> Deserialize<'a>,
+    {
+        let content = self.fetch(url)?;
+        try_parse_from_str::<N>(&content, url.path(), vars)
+            .context(format!("Failed to parse descriptor from < {} >", url))
+    }
+
+    /// Attempts to load the [DataFlowDescriptor] located at `url`, overwriting (or complementing) the [Vars] it
+    /// declares with the provided `vars`.
+    ///
+    /// Contrary to [try_load_descriptor](DescriptorResolver::try_load_descriptor()), the returned descriptor remembers
+    /// where its nodes, links and mapping entries are declared: the [Diagnostics](crate::Diagnostics) reported when
+    /// flattening it point to their location.
+    ///
+    /// # Errors
+    ///
+    /// See [try_load_descriptor](DescriptorResolver::try_load_descriptor()).
+    pub fn try_load_data_flow(&self, url: &Url, vars: Vars) -> Result<(DataFlowDescriptor, Vars)> {
+        let (mut data_flow, vars, source_map) =
+            self.try_load_located_descriptor::<DataFlowDescriptor>(url, vars)?;
+        data_flow.source_map = Some(Arc::new(source_map));
+
+        Ok((data_flow, vars))
+    }
+
+    // Loads an instance of `N` as `try_load_descriptor` does, alongside the `SourceMap` of its descriptor.
+    pub(crate) fn try_load_located_descriptor<N>(
+        &self,
+        url: &Url,
+        vars: Vars,
+    ) -> Result<(N, Vars, SourceMap)>
+    where
+        N: for<'a> Deserialize<'a>,
+    {
+        let content = self.fetch(url)?;
+        let (rendered_content, vars) = try_render_from_str(&content, url.path(), vars)
+            .context(format!("Failed to parse descriptor from < {} >", url))?;
+        let descriptor = try_deserialize_from_str::<N>(&rendered_content, url.path())
+            .context(format!("Failed to parse descriptor from < {} >", url))?;
+
+        Ok((descriptor, vars, SourceMap::new(url, &rendered_content)))
+    }
+
+    fn fetch(&self, url: &Url) -> Result<Arc<str>> {
+        if let Some(content) = self
+            .cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(url)
+        {
+            return Ok(content.clone());
+        }
+
+        let resolver = self
+            .resolvers
+            .get(url.scheme())
+            .ok_or_else(|| anyhow!("Unsupported URL scheme < {} >", url.scheme()))?;
+
+        let content: Arc<str> = match resolver.fetch(url) {
+            Ok(content) => {
+                self.try_store(url, &content);
+                content.into()
+            }
+            Err(e) => match self.try_load_cached(url) {
+                Some(content) => {
+                    tracing::warn!(
+                        "Failed to fetch descriptor from < {} >, using its cached version instead:\n{:?}",
+                        url,
+                        e
+                    );
+                    content.into()
+                }
+                None => return Err(e),
+            },
+        };
+
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(url.clone(), content.clone());
+
+        Ok(content)
+    }
+
+    // Returns the path, in the local cache, of the descriptor located at `url`, if it should be cached.
+    fn cache_path(&self, url: &Url) -> Option<PathBuf> {
+        if url.scheme() == "file" {
+            return None;
+        }
+
+        self.cache_dir
+            .as_ref()
+            .map(|cache_dir| cache_dir.join(cache_file_name(url)))
+    }
+
+    // NOTE: Failing to cache a descriptor does not prevent using it, hence only a warning is logged.
+    fn try_store(&self, url: &Url, content: &str) {
+        let Some(path) = self.cache_path(url) else {
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, content));
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to cache descriptor from < {} > in < {} >: {:?}",
+                url,
+                path.display(),
+                e
+            );
+        }
+    }
+
+    fn try_load_cached(&self, url: &Url) -> Option<String> {
+        std::fs::read_to_string(self.cache_path(url)?).ok()
+    }
+}
+
+// Returns the name of the file, in the local cache, of the descriptor located at `url`.
+//
+// All the characters that are not alphanumeric, `-`, `_` or `.` are percent-encoded such that two different URLs cannot
+// be cached in the same file.
+fn cache_file_name(url: &Url) -> String {
+    url.as_str()
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread::JoinHandle,
+    };
+
+    use url::Url;
+    use zenoh_flow_commons::Vars;
+
+    use super::DescriptorResolver;
+    use crate::{DataFlowDescriptor, FlattenedDataFlowDescriptor, OperatorVariant};
+
+    const OPERATOR: &str = r#"
+description: remote operator
+library: "file:///home/zenoh-flow/liboperator.so"
+inputs:
+  - in
+outputs:
+  - out
+"#;
+
+    fn data_flow(operator: &Url) -> DataFlowDescriptor {
+        serde_yaml::from_str(&format!(
+            r#"
+name: remote-flow
+
+sources:
+  - id: source
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out
+
+operators:
+  - id: operator-1
+    descriptor: "{operator}"
+  - id: operator-2
+    descriptor: "{operator}"
+
+sinks:
+  - id: sink
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-1
+      - in-2
+
+links:
+  - from:
+      node: source
+      output: out
+    to:
+      node: operator-1
+      input: in
+  - from:
+      node: source
+      output: out
+    to:
+      node: operator-2
+      input: in
+  - from:
+      node: operator-1
+      output: out
+    to:
+      node: sink
+      input: in-1
+  - from:
+      node: operator-2
+      output: out
+    to:
+      node: sink
+      input: in-2
+"#
+        ))
+        .unwrap()
+    }
+
+    fn assert_remote_operators(flat_flow: &FlattenedDataFlowDescriptor) {
+        assert_eq!(flat_flow.operators.len(), 2);
+        for operator in flat_flow.operators.iter() {
+            assert_eq!(operator.description.as_deref(), Some("remote operator"));
+            assert!(matches!(operator.operator, OperatorVariant::Library(_)));
+        }
+    }
+
+    // Serves, over HTTP, the `content` to the `requests` first requests it receives.
+    fn serve(content: &'static str, requests: usize) -> (Url, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/operator.yml",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+
+        let handle = std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let read = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..read]);
+                }
+
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    content.len(),
+                    content
+                )
+                .unwrap();
+            }
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn test_http_resolver_and_cache() {
+        let cache_dir = std::env::temp_dir().join(format!("zenoh-flow-{}", uuid::Uuid::new_v4()));
+        // The server answers a single request: the second operator is loaded from the in-memory cache.
+        let (url, server) = serve(OPERATOR, 1);
+
+        let resolver = DescriptorResolver::default().with_cache_dir(&cache_dir);
+        let flat_flow = FlattenedDataFlowDescriptor::try_flatten_with_resolver(
+            data_flow(&url),
+            Vars::default(),
+            &resolver,
+        )
+        .unwrap();
+        assert_remote_operators(&flat_flow);
+        server.join().unwrap();
+
+        // The server is no longer reachable: the operators are loaded from the local cache.
+        let flat_flow = FlattenedDataFlowDescriptor::try_flatten_with_resolver(
+            data_flow(&url),
+            Vars::default(),
+            &DescriptorResolver::default().with_cache_dir(&cache_dir),
+        )
+        .unwrap();
+        assert_remote_operators(&flat_flow);
+
+        assert!(
+            FlattenedDataFlowDescriptor::try_flatten(data_flow(&url), Vars::default()).is_err()
+        );
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_zenoh_resolver() {
+        use zenoh::prelude::sync::*;
+
+        let mut config = zenoh::config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let session = zenoh::open(config).res_sync().unwrap().into_arc();
+
+        // A minimal in-process storage: it keeps the last publication on each key expression and answers the queries
+        // with it.
+        let storage: Arc<Mutex<HashMap<String, Vec<u8>>>> = Arc::new(Mutex::new(HashMap::new()));
+        let storage_sub = storage.clone();
+        let _subscriber = session
+            .declare_subscriber("zf/descriptors/**")
+            .callback(move |sample| {
+                storage_sub.lock().unwrap().insert(
+                    sample.key_expr.to_string(),
+                    sample.payload.contiguous().to_vec(),
+                );
+            })
+            .res_sync()
+            .unwrap();
+        let _queryable = session
+            .declare_queryable("zf/descriptors/**")
+            .callback(move |query| {
+                let stored = storage
+                    .lock()
+                    .unwrap()
+                    .get(query.key_expr().as_str())
+                    .cloned();
+                if let Some(payload) = stored {
+                    query
+                        .reply(Ok(Sample::new(query.key_expr().clone(), payload)))
+                        .res_sync()
+                        .unwrap();
+                }
+            })
+            .res_sync()
+            .unwrap();
+
+        session
+            .put("zf/descriptors/operator.yml", OPERATOR)
+            .res_sync()
+            .unwrap();
+
+        let resolver = DescriptorResolver::default().with_zenoh(session.clone());
+        let flat_flow = FlattenedDataFlowDescriptor::try_flatten_with_resolver(
+            data_flow(&Url::parse("zenoh://zf/descriptors/operator.yml").unwrap()),
+            Vars::default(),
+            &resolver,
+        )
+        .unwrap();
+        assert_remote_operators(&flat_flow);
+
+        let resolver = DescriptorResolver::default().with_resolver(
+            "zenoh",
+            super::ZenohResolver::new(session).timeout(std::time::Duration::from_millis(100)),
+        );
+        assert!(FlattenedDataFlowDescriptor::try_flatten_with_resolver(
+            data_flow(&Url::parse("zenoh://zf/descriptors/missing.yml").unwrap()),
+            Vars::default(),
+            &resolver,
+        )
+        .is_err());
     }
 }
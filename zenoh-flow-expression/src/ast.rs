@@ -0,0 +1,183 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use serde_json::{Number, Value};
+
+/// The values a path can start from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Root {
+    Payload,
+    Attachment,
+}
+
+pub(crate) struct Roots<'a> {
+    pub(crate) payload: &'a Value,
+    pub(crate) attachment: &'a Value,
+}
+
+/// The segments of a path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Field(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BinaryOperator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Lower,
+    LowerOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Ast {
+    Literal(Value),
+    Path(Root, Vec<Segment>),
+    Not(Box<Ast>),
+    Negate(Box<Ast>),
+    Binary(BinaryOperator, Box<Ast>, Box<Ast>),
+}
+
+impl<'a> Roots<'a> {
+    fn get(&self, root: Root) -> &'a Value {
+        match root {
+            Root::Payload => self.payload,
+            Root::Attachment => self.attachment,
+        }
+    }
+}
+
+impl Ast {
+    pub(crate) fn evaluate(&self, roots: &Roots) -> Value {
+        match self {
+            Ast::Literal(value) => value.clone(),
+            Ast::Path(root, segments) => segments
+                .iter()
+                .try_fold(roots.get(*root), |value, segment| match segment {
+                    Segment::Field(field) => value.get(field),
+                    Segment::Index(index) => value.get(index),
+                })
+                .cloned()
+                .unwrap_or(Value::Null),
+            Ast::Not(operand) => Value::Bool(operand.evaluate(roots) != Value::Bool(true)),
+            Ast::Negate(operand) => arithmetic(
+                BinaryOperator::Subtract,
+                &Value::from(0),
+                &operand.evaluate(roots),
+            ),
+            Ast::Binary(operator, lhs, rhs) => {
+                // `&&` and `||` are short-circuiting.
+                match operator {
+                    BinaryOperator::And => {
+                        return Value::Bool(
+                            lhs.evaluate(roots) == Value::Bool(true)
+                                && rhs.evaluate(roots) == Value::Bool(true),
+                        )
+                    }
+                    BinaryOperator::Or => {
+                        return Value::Bool(
+                            lhs.evaluate(roots) == Value::Bool(true)
+                                || rhs.evaluate(roots) == Value::Bool(true),
+                        )
+                    }
+                    _ => (),
+                }
+
+                let (lhs, rhs) = (lhs.evaluate(roots), rhs.evaluate(roots));
+                match operator {
+                    BinaryOperator::Equal => Value::Bool(equal(&lhs, &rhs)),
+                    BinaryOperator::NotEqual => Value::Bool(!equal(&lhs, &rhs)),
+                    BinaryOperator::Lower
+                    | BinaryOperator::LowerOrEqual
+                    | BinaryOperator::Greater
+                    | BinaryOperator::GreaterOrEqual => Value::Bool(compare(*operator, &lhs, &rhs)),
+                    _ => arithmetic(*operator, &lhs, &rhs),
+                }
+            }
+        }
+    }
+}
+
+fn equal(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Number(lhs), Value::Number(rhs)) => lhs.as_f64() == rhs.as_f64(),
+        _ => lhs == rhs,
+    }
+}
+
+fn compare(operator: BinaryOperator, lhs: &Value, rhs: &Value) -> bool {
+    let ordering = match (lhs, rhs) {
+        (Value::Number(lhs), Value::Number(rhs)) => lhs.as_f64().partial_cmp(&rhs.as_f64()),
+        (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    };
+
+    match ordering {
+        Some(ordering) => match operator {
+            BinaryOperator::Lower => ordering.is_lt(),
+            BinaryOperator::LowerOrEqual => ordering.is_le(),
+            BinaryOperator::Greater => ordering.is_gt(),
+            BinaryOperator::GreaterOrEqual => ordering.is_ge(),
+            _ => unreachable!("{operator:?} is not a comparison"),
+        },
+        None => false,
+    }
+}
+
+fn arithmetic(operator: BinaryOperator, lhs: &Value, rhs: &Value) -> Value {
+    match (lhs, rhs) {
+        (Value::String(lhs), Value::String(rhs)) if operator == BinaryOperator::Add => {
+            Value::String(format!("{lhs}{rhs}"))
+        }
+        (Value::Number(lhs), Value::Number(rhs)) => {
+            if let (Some(lhs), Some(rhs)) = (lhs.as_i64(), rhs.as_i64()) {
+                let result = match operator {
+                    BinaryOperator::Add => lhs.checked_add(rhs),
+                    BinaryOperator::Subtract => lhs.checked_sub(rhs),
+                    BinaryOperator::Multiply => lhs.checked_mul(rhs),
+                    BinaryOperator::Remainder => lhs.checked_rem(rhs),
+                    _ => None,
+                };
+                if let Some(result) = result {
+                    return Value::from(result);
+                }
+            }
+
+            let (Some(lhs), Some(rhs)) = (lhs.as_f64(), rhs.as_f64()) else {
+                return Value::Null;
+            };
+            let result = match operator {
+                BinaryOperator::Add => lhs + rhs,
+                BinaryOperator::Subtract => lhs - rhs,
+                BinaryOperator::Multiply => lhs * rhs,
+                BinaryOperator::Divide => lhs / rhs,
+                BinaryOperator::Remainder => lhs % rhs,
+                _ => unreachable!("{operator:?} is not an arithmetic operator"),
+            };
+            // NOTE: JSON cannot represent infinite numbers or NaN, `from_f64` returns `None` for them.
+            Number::from_f64(result).map_or(Value::Null, Value::Number)
+        }
+        _ => Value::Null,
+    }
+}
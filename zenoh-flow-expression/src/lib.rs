@@ -0,0 +1,221 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! This crate exposes the [Expression]s evaluated by the expression and split built-in Operators of Zenoh-Flow: their
+//! parser and their evaluator.
+//!
+//! ⚠️ This crate is intended for internal usage within Zenoh-Flow. All structures that are exposed in public
+//! facing API are re-exposed in the relevant crates.
+
+mod ast;
+mod parser;
+
+use std::{fmt::Debug, sync::Arc};
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ast::{Ast, Roots},
+    parser::Parser,
+};
+
+/// An `Expression` is evaluated on a JSON value and produces a JSON value.
+///
+/// # Syntax
+///
+/// - Literals: numbers (`42`, `-1.5`), strings (`'ok'` or `"ok"`), `true`, `false` and `null`.
+/// - Paths, starting at the root value `$`: `$.robot.id`, `$.wheels[0]`, `$['field with spaces']`. A path that does
+///   not exist evaluates to `null`. As field names can contain `-` (e.g. `$.cmd-vel`), a subtraction of a path must be
+///   surrounded by spaces: `$.speed - 1`.
+/// - Paths, starting at the attachment `@`, to access the metadata of a message: `@.key-expr` and `@.encoding` if the
+///   message was created from a Zenoh sample, `@.key-suffix` if it was given one.
+/// - Operators, from the lowest to the highest precedence: `||`, `&&`, `==` and `!=`, `<`, `<=`, `>` and `>=`, `+` and
+///   `-`, `*`, `/` and `%`, then the unary `!` and `-`. Parentheses group sub-expressions.
+///
+/// # Semantics
+///
+/// - `&&`, `||` and `!` only consider `true` as true: `!null` is `true`.
+/// - `==` and `!=` compare any values, numbers being compared by value (`1 == 1.0`).
+/// - `<`, `<=`, `>` and `>=` compare numbers or strings; comparing values of other types is `false`.
+/// - Arithmetic operators apply to numbers, `+` also concatenates strings. Any other combination is `null`. The result
+///   is an integer if both operands are integers and the operation does not overflow (except for `/`).
+#[derive(Clone)]
+pub struct Expression {
+    source: Arc<str>,
+    ast: Arc<Ast>,
+}
+
+impl Expression {
+    /// Evaluates the expression on the provided JSON `value`, the attachment being `null`.
+    pub fn evaluate(&self, value: &Value) -> Value {
+        self.evaluate_with_attachment(value, &Value::Null)
+    }
+
+    /// Evaluates the expression on the provided JSON `value` and `attachment`.
+    pub fn evaluate_with_attachment(&self, value: &Value, attachment: &Value) -> Value {
+        self.ast.evaluate(&Roots {
+            payload: value,
+            attachment,
+        })
+    }
+
+    /// Returns `true` if the expression evaluates to `true` on the provided JSON `value`, the attachment being `null`.
+    pub fn is_satisfied_by(&self, value: &Value) -> bool {
+        self.evaluate(value) == Value::Bool(true)
+    }
+
+    /// Returns `true` if the expression evaluates to `true` on the provided JSON `value` and `attachment`.
+    pub fn is_satisfied_with_attachment(&self, value: &Value, attachment: &Value) -> bool {
+        self.evaluate_with_attachment(value, attachment) == Value::Bool(true)
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = String;
+
+    fn try_from(source: String) -> std::result::Result<Self, Self::Error> {
+        let ast = Parser::try_new(&source)
+            .and_then(|parser| parser.parse())
+            .map_err(|e| format!("Invalid expression < {} >: {}", source, e))?;
+
+        Ok(Self {
+            source: source.into(),
+            ast: Arc::new(ast),
+        })
+    }
+}
+
+impl std::str::FromStr for Expression {
+    type Err = String;
+
+    fn from_str(source: &str) -> std::result::Result<Self, Self::Err> {
+        Self::try_from(source.to_string())
+    }
+}
+
+impl Debug for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.source)
+    }
+}
+
+// NOTE: Two expressions are equal if they have the same source, which is also how they are serialised.
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Expression {}
+
+impl Serialize for Expression {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::try_from(source).map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for Expression {
+    fn schema_name() -> String {
+        "Expression".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "An expression evaluated on the JSON payload `$` and the attachment `@` of a message, e.g. \
+                     `$.speed > 1.5 && @.key-expr == 'rt/robot-1/status'`."
+                        .into(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Expression;
+
+    fn evaluate(expression: &str) -> serde_json::Value {
+        let value = json!({
+            "robot": { "id": "robot-1", "wheels": [1.5, 2] },
+            "speed": 2,
+            "status": "ok",
+            "field with spaces": true,
+        });
+        expression.parse::<Expression>().unwrap().evaluate(&value)
+    }
+
+    #[test]
+    fn test_expressions() {
+        assert_eq!(evaluate("$.robot.id"), json!("robot-1"));
+        assert_eq!(evaluate("$.robot.wheels[0]"), json!(1.5));
+        assert_eq!(evaluate("$['field with spaces']"), json!(true));
+        assert_eq!(evaluate("$.robot.missing"), json!(null));
+        assert_eq!(evaluate("$.speed * 3 + 1"), json!(7));
+        assert_eq!(evaluate("($.speed + 1) * 3"), json!(9));
+        assert_eq!(evaluate("$.speed / 4"), json!(0.5));
+        assert_eq!(evaluate("-$.speed"), json!(-2));
+        assert_eq!(evaluate("$.robot.id + '/' + $.status"), json!("robot-1/ok"));
+        assert_eq!(evaluate("$.speed + 'km/h'"), json!(null));
+        assert_eq!(evaluate("$.speed == 2.0 && $.status != 'ko'"), json!(true));
+        assert_eq!(evaluate("$.robot.wheels[1] >= 2 || $.missing"), json!(true));
+        assert_eq!(evaluate("$.status < 'oj'"), json!(false));
+        assert_eq!(evaluate("$.status > 1"), json!(false));
+        assert_eq!(evaluate("!$.missing"), json!(true));
+        assert_eq!(evaluate("$.speed / 0"), json!(null));
+        assert_eq!(evaluate("@.key-expr"), json!(null));
+        assert_eq!(
+            "@.key-expr == 'rt/robot-1/status' && $.status == 'ok'"
+                .parse::<Expression>()
+                .unwrap()
+                .evaluate_with_attachment(
+                    &json!({ "status": "ok" }),
+                    &json!({ "key-expr": "rt/robot-1/status" })
+                ),
+            json!(true)
+        );
+
+        for invalid in [
+            "$.",
+            "$.speed >",
+            "($.speed",
+            "$.speed = 2",
+            "$[-1]",
+            "'ok",
+            "#",
+        ] {
+            assert!(invalid.parse::<Expression>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use serde_json::{Number, Value};
+
+use crate::ast::{Ast, BinaryOperator, Root, Segment};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dollar,
+    At,
+    Dot,
+    LeftBracket,
+    RightBracket,
+    LeftParenthesis,
+    RightParenthesis,
+    Identifier(String),
+    Literal(Value),
+    Operator(&'static str),
+}
+
+/// A recursive descent parser of expressions.
+pub(crate) struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+}
+
+// The operators, the longest first such that `<=` is not tokenised as `<` followed by `=`.
+const OPERATORS: [&str; 15] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!", "=",
+];
+
+impl Parser {
+    pub(crate) fn try_new(source: &str) -> std::result::Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some(&(offset, c)) = chars.peek() {
+            let token = match c {
+                c if c.is_whitespace() => {
+                    chars.next();
+                    continue;
+                }
+                '$' | '@' | '.' | '[' | ']' | '(' | ')' => {
+                    chars.next();
+                    match c {
+                        '$' => Token::Dollar,
+                        '@' => Token::At,
+                        '.' => Token::Dot,
+                        '[' => Token::LeftBracket,
+                        ']' => Token::RightBracket,
+                        '(' => Token::LeftParenthesis,
+                        _ => Token::RightParenthesis,
+                    }
+                }
+                '\'' | '"' => {
+                    chars.next();
+                    let mut string = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '\\')) => match chars.next() {
+                                Some((_, escaped)) => string.push(escaped),
+                                None => break,
+                            },
+                            Some((_, end)) if end == c => break,
+                            Some((_, character)) => string.push(character),
+                            None => return Err(format!("unterminated string at {offset}")),
+                        }
+                    }
+                    Token::Literal(Value::String(string))
+                }
+                c if c.is_ascii_digit() => {
+                    let mut number = String::new();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E') {
+                            break;
+                        }
+                        number.push(c);
+                        chars.next();
+                    }
+                    let value = match number.parse::<i64>() {
+                        Ok(integer) => Value::from(integer),
+                        Err(_) => number
+                            .parse::<f64>()
+                            .ok()
+                            .and_then(Number::from_f64)
+                            .map(Value::Number)
+                            .ok_or_else(|| format!("invalid number < {number} > at {offset}"))?,
+                    };
+                    Token::Literal(value)
+                }
+                c if c.is_alphabetic() || c == '_' => {
+                    let mut identifier = String::new();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if !(c.is_alphanumeric() || c == '_' || c == '-') {
+                            break;
+                        }
+                        identifier.push(c);
+                        chars.next();
+                    }
+                    match identifier.as_str() {
+                        "true" => Token::Literal(Value::Bool(true)),
+                        "false" => Token::Literal(Value::Bool(false)),
+                        "null" => Token::Literal(Value::Null),
+                        _ => Token::Identifier(identifier),
+                    }
+                }
+                _ => {
+                    let operator = OPERATORS
+                        .iter()
+                        .find(|operator| source[offset..].starts_with(**operator))
+                        .ok_or_else(|| format!("unexpected character < {c} > at {offset}"))?;
+                    for _ in 0..operator.len() {
+                        chars.next();
+                    }
+                    Token::Operator(operator)
+                }
+            };
+
+            tokens.push((offset, token));
+        }
+
+        Ok(Self {
+            tokens,
+            position: 0,
+        })
+    }
+
+    pub(crate) fn parse(mut self) -> std::result::Result<Ast, String> {
+        let ast = self.parse_binary(0)?;
+        match self.tokens.get(self.position) {
+            Some((offset, token)) => Err(format!("unexpected {token:?} at {offset}")),
+            None => Ok(ast),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> std::result::Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(_, token)| token.clone())
+            .ok_or_else(|| "unexpected end of expression".to_string())?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> std::result::Result<(), String> {
+        let offset = self.tokens.get(self.position).map(|(offset, _)| *offset);
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(format!(
+                "expected {expected:?}, found {token:?} at {}",
+                offset.unwrap_or_default()
+            )),
+        }
+    }
+
+    // The binary operators grouped by precedence, the lowest first.
+    const PRECEDENCE: [&'static [(&'static str, BinaryOperator)]; 6] = [
+        &[("||", BinaryOperator::Or)],
+        &[("&&", BinaryOperator::And)],
+        &[
+            ("==", BinaryOperator::Equal),
+            ("!=", BinaryOperator::NotEqual),
+        ],
+        &[
+            ("<", BinaryOperator::Lower),
+            ("<=", BinaryOperator::LowerOrEqual),
+            (">", BinaryOperator::Greater),
+            (">=", BinaryOperator::GreaterOrEqual),
+        ],
+        &[("+", BinaryOperator::Add), ("-", BinaryOperator::Subtract)],
+        &[
+            ("*", BinaryOperator::Multiply),
+            ("/", BinaryOperator::Divide),
+            ("%", BinaryOperator::Remainder),
+        ],
+    ];
+
+    fn parse_binary(&mut self, level: usize) -> std::result::Result<Ast, String> {
+        let Some(operators) = Self::PRECEDENCE.get(level) else {
+            return self.parse_unary();
+        };
+
+        let mut lhs = self.parse_binary(level + 1)?;
+        while let Some(Token::Operator(symbol)) = self.peek() {
+            let Some((_, operator)) = operators.iter().find(|(s, _)| s == symbol) else {
+                break;
+            };
+            let operator = *operator;
+            self.position += 1;
+            let rhs = self.parse_binary(level + 1)?;
+            lhs = Ast::Binary(operator, Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> std::result::Result<Ast, String> {
+        match self.peek() {
+            Some(Token::Operator("!")) => {
+                self.position += 1;
+                Ok(Ast::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::Operator("-")) => {
+                self.position += 1;
+                Ok(Ast::Negate(Box::new(self.parse_unary()?)))
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> std::result::Result<Ast, String> {
+        let offset = self
+            .tokens
+            .get(self.position)
+            .map(|(offset, _)| *offset)
+            .unwrap_or_default();
+
+        match self.next()? {
+            Token::Literal(value) => Ok(Ast::Literal(value)),
+            Token::LeftParenthesis => {
+                let ast = self.parse_binary(0)?;
+                self.expect(Token::RightParenthesis)?;
+                Ok(ast)
+            }
+            token @ (Token::Dollar | Token::At) => {
+                let root = if token == Token::Dollar {
+                    Root::Payload
+                } else {
+                    Root::Attachment
+                };
+                let mut segments = Vec::new();
+                loop {
+                    match self.peek() {
+                        Some(Token::Dot) => {
+                            self.position += 1;
+                            match self.next()? {
+                                Token::Identifier(field) => segments.push(Segment::Field(field)),
+                                token => return Err(format!("expected a field, found {token:?}")),
+                            }
+                        }
+                        Some(Token::LeftBracket) => {
+                            self.position += 1;
+                            match self.next()? {
+                                Token::Literal(Value::String(field)) => {
+                                    segments.push(Segment::Field(field))
+                                }
+                                Token::Literal(Value::Number(index)) if index.is_u64() => segments
+                                    .push(Segment::Index(
+                                        index.as_u64().unwrap_or_default() as usize
+                                    )),
+                                token => {
+                                    return Err(format!(
+                                        "expected a field or an index, found {token:?}"
+                                    ))
+                                }
+                            }
+                            self.expect(Token::RightBracket)?;
+                        }
+                        _ => break,
+                    }
+                }
+                Ok(Ast::Path(root, segments))
+            }
+            token => Err(format!("unexpected {token:?} at {offset}")),
+        }
+    }
+}
@@ -26,7 +26,11 @@ use crate::prelude::{Context, Inputs, Node, Outputs};
 /// This constant is used by the procedural macros [export_operator](crate::prelude::export_operator),
 /// [export_source](crate::prelude::export_source) and [export_sink](crate::prelude::export_sink). A Zenoh-Flow runtime
 /// will compare its value of this constant to the value that all node it will dynamically load expose.
-pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+///
+/// The version of the crate is followed by the version of the layout of the types shared with the nodes (e.g.
+/// [LinkMessage](crate::prelude::LinkMessage)). The latter is bumped whenever one of these types changes, even if the
+/// version of the crate does not, such that a node compiled against a previous layout is rejected.
+pub const CORE_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "+abi.2");
 
 /// (⚙️ *internal)* Constant used to check if a node was compiled with the same version of the Rust compiler than the
 /// Zenoh-Flow runtime managing it.
@@ -19,7 +19,10 @@ use flume::TryRecvError;
 use uhlc::Timestamp;
 use zenoh_flow_commons::{PortId, Result};
 
-use crate::messages::{Data, DeserializerFn, LinkMessage};
+use crate::{
+    messages::{Data, DeserializerFn, LinkMessage},
+    trace::MessageTracer,
+};
 
 /// The `Inputs` structure contains all the inputs created for a [Sink](crate::prelude::Sink) or an
 /// [Operator](crate::prelude::Operator).
@@ -52,6 +55,7 @@ use crate::messages::{Data, DeserializerFn, LinkMessage};
 #[derive(Default)]
 pub struct Inputs {
     pub(crate) hmap: HashMap<PortId, flume::Receiver<LinkMessage>>,
+    pub(crate) tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 // Dereferencing on the internal `HashMap` allows users to call all the methods implemented on it: `keys()` for one.
@@ -69,6 +73,11 @@ impl Inputs {
         self.hmap.entry(port_id).or_insert(rx);
     }
 
+    /// Sets the [MessageTracer] notified of every message received by the inputs taken afterwards.
+    pub fn set_tracer(&mut self, tracer: Arc<dyn MessageTracer>) {
+        self.tracer = Some(tracer);
+    }
+
     /// Returns an Input builder for the provided `port_id`, if an input was declared with this exact name in the
     /// descriptor of the node, otherwise returns `None`.
     ///
@@ -110,6 +119,7 @@ impl Inputs {
             .map(|receiver| InputBuilder {
                 port_id: port_id.as_ref().into(),
                 receiver,
+                tracer: self.tracer.clone(),
             })
     }
 }
@@ -128,6 +138,7 @@ impl Inputs {
 pub struct InputBuilder {
     pub(crate) port_id: PortId,
     pub(crate) receiver: flume::Receiver<LinkMessage>,
+    pub(crate) tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 impl InputBuilder {
@@ -158,6 +169,7 @@ impl InputBuilder {
         InputRaw {
             port_id: self.port_id,
             receiver: self.receiver,
+            tracer: self.tracer,
         }
     }
 
@@ -203,6 +215,7 @@ impl InputBuilder {
 pub struct InputRaw {
     pub(crate) port_id: PortId,
     pub(crate) receiver: flume::Receiver<LinkMessage>,
+    pub(crate) tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 impl InputRaw {
@@ -227,7 +240,7 @@ impl InputRaw {
     /// An error is returned if the associated channel is disconnected.
     pub fn try_recv(&self) -> Result<Option<LinkMessage>> {
         match self.receiver.try_recv() {
-            Ok(message) => Ok(Some(message)),
+            Ok(message) => Ok(Some(self.traced(message))),
             Err(e) => match e {
                 TryRecvError::Empty => Ok(None),
                 TryRecvError::Disconnected => {
@@ -247,10 +260,22 @@ impl InputRaw {
     ///
     /// An error is returned if a channel was disconnected.
     pub async fn recv(&self) -> Result<LinkMessage> {
-        self.receiver.recv_async().await.map_err(|_| {
-            tracing::error!("Link disconnected: {}", self.port_id);
-            anyhow!("Disconnected")
-        })
+        self.receiver
+            .recv_async()
+            .await
+            .map(|message| self.traced(message))
+            .map_err(|_| {
+                tracing::error!("Link disconnected: {}", self.port_id);
+                anyhow!("Disconnected")
+            })
+    }
+
+    // Notifies the tracer, if any, that `message` was received.
+    fn traced(&self, message: LinkMessage) -> LinkMessage {
+        if let Some(tracer) = &self.tracer {
+            tracer.on_recv(&self.port_id, message.trace_context.as_ref());
+        }
+        message
     }
 }
 
@@ -303,7 +328,9 @@ impl<T: Send + Sync + 'static> Input<T> {
     /// - a channel was disconnected,
     /// - Zenoh-Flow failed at interpreting the received data as an instance of `T`.
     pub async fn recv(&self) -> Result<(Data<T>, Timestamp)> {
-        let LinkMessage { payload, timestamp } = self.input_raw.recv().await?;
+        let LinkMessage {
+            payload, timestamp, ..
+        } = self.input_raw.recv().await?;
         Ok((
             Data::try_from_payload(payload, self.deserializer.clone())?,
             timestamp,
@@ -332,7 +359,10 @@ impl<T: Send + Sync + 'static> Input<T> {
     ///
     /// Note that if some channels are disconnected, for each of such channel an error is logged.
     pub fn try_recv(&self) -> Result<Option<(Data<T>, Timestamp)>> {
-        if let Some(LinkMessage { payload, timestamp }) = self.input_raw.try_recv()? {
+        if let Some(LinkMessage {
+            payload, timestamp, ..
+        }) = self.input_raw.try_recv()?
+        {
             return Ok(Some((
                 Data::try_from_payload(payload, self.deserializer.clone())?,
                 timestamp,
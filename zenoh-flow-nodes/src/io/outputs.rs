@@ -19,7 +19,10 @@ use flume::Sender;
 use uhlc::{Timestamp, HLC};
 use zenoh_flow_commons::{PortId, Result};
 
-use crate::messages::{Data, LinkMessage, Payload, SerializerFn};
+use crate::{
+    messages::{Data, LinkMessage, Payload, SampleMetadata, SerializerFn},
+    trace::{MessageTracer, TraceContext},
+};
 
 /// The [Outputs] structure contains all the outputs created for a [Source](crate::prelude::Source) or an
 /// [Operator](crate::prelude::Operator).
@@ -34,6 +37,7 @@ use crate::messages::{Data, LinkMessage, Payload, SerializerFn};
 pub struct Outputs {
     pub(crate) hmap: HashMap<PortId, Vec<flume::Sender<LinkMessage>>>,
     pub(crate) hlc: Arc<HLC>,
+    pub(crate) tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 // Dereferencing on the internal [HashMap] allows users to call all the methods implemented on it: `keys()` for one.
@@ -50,9 +54,15 @@ impl Outputs {
         Self {
             hmap: HashMap::default(),
             hlc,
+            tracer: None,
         }
     }
 
+    /// Sets the [MessageTracer] providing the trace context of every message sent by the outputs taken afterwards.
+    pub fn set_tracer(&mut self, tracer: Arc<dyn MessageTracer>) {
+        self.tracer = Some(tracer);
+    }
+
     /// Insert the `flume::Sender` in the [Outputs], creating the entry if needed in the internal
     /// `HashMap`.
     pub fn insert(&mut self, port_id: PortId, tx: Sender<LinkMessage>) {
@@ -105,6 +115,7 @@ impl Outputs {
                 port_id: port_id.as_ref().into(),
                 senders,
                 hlc: Arc::clone(&self.hlc),
+                tracer: self.tracer.clone(),
             })
     }
 }
@@ -122,6 +133,7 @@ pub struct OutputBuilder {
     pub(crate) port_id: PortId,
     pub(crate) senders: Vec<flume::Sender<LinkMessage>>,
     pub(crate) hlc: Arc<HLC>,
+    pub(crate) tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 impl OutputBuilder {
@@ -154,6 +166,7 @@ impl OutputBuilder {
             port_id: self.port_id,
             senders: self.senders,
             hlc: self.hlc,
+            tracer: self.tracer,
         }
     }
 
@@ -215,6 +228,7 @@ pub struct OutputRaw {
     pub(crate) port_id: PortId,
     pub(crate) senders: Vec<flume::Sender<LinkMessage>>,
     pub(crate) hlc: Arc<HLC>,
+    pub(crate) tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 impl OutputRaw {
@@ -224,6 +238,13 @@ impl OutputRaw {
             .unwrap_or_else(|| self.hlc.new_timestamp())
     }
 
+    // Asks the tracer, if any, for the trace context of the message about to be sent.
+    fn make_trace_context(&self) -> Option<TraceContext> {
+        self.tracer
+            .as_ref()
+            .and_then(|tracer| tracer.on_send(&self.port_id))
+    }
+
     /// Returns the port id associated with this Output.
     pub fn port_id(&self) -> &PortId {
         &self.port_id
@@ -234,6 +255,16 @@ impl OutputRaw {
         self.senders.len()
     }
 
+    /// Returns the largest number of messages, sent on any of the channels associated with this Output, that were not
+    /// yet received by the downstream Nodes.
+    pub fn pending(&self) -> usize {
+        self.senders
+            .iter()
+            .map(|sender| sender.len())
+            .max()
+            .unwrap_or_default()
+    }
+
     /// Attempt to forward, *synchronously*, the message to the downstream Nodes.
     ///
     /// # Asynchronous alternative: `forward`
@@ -291,6 +322,9 @@ impl OutputRaw {
         let message = LinkMessage {
             payload: payload.into(),
             timestamp: self.make_timestamp(timestamp),
+            trace_context: self.make_trace_context(),
+            sample_metadata: None,
+            key_suffix: None,
         };
 
         self.try_forward(message)
@@ -347,6 +381,69 @@ impl OutputRaw {
         let message = LinkMessage {
             payload: payload.into(),
             timestamp: self.make_timestamp(timestamp),
+            trace_context: self.make_trace_context(),
+            sample_metadata: None,
+            key_suffix: None,
+        };
+
+        self.forward(message).await
+    }
+
+    /// Send, *asynchronously*, the `data` on all channels to the downstream Nodes, along with the [SampleMetadata] of
+    /// the Zenoh sample it was received in.
+    ///
+    /// If no `timestamp` is provided, the current timestamp — as per the [HLC](uhlc::HLC) used by
+    /// the Zenoh-Flow daemon running this Node — is taken.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while sending the message on a channel, Zenoh-Flow still tries to send it on the remaining
+    /// channels. For each failing channel, an error is logged and counted for.
+    pub async fn send_with_metadata(
+        &self,
+        payload: impl Into<Payload>,
+        timestamp: Option<u64>,
+        sample_metadata: SampleMetadata,
+    ) -> Result<()> {
+        let message = LinkMessage {
+            payload: payload.into(),
+            timestamp: self.make_timestamp(timestamp),
+            trace_context: self.make_trace_context(),
+            sample_metadata: Some(sample_metadata),
+            key_suffix: None,
+        };
+
+        self.forward(message).await
+    }
+
+    /// Send, *asynchronously*, the `data` on all channels to the downstream Nodes, along with the suffix of the key
+    /// expression on which a built-in Zenoh Sink should publish it.
+    ///
+    /// The suffix is attached to the message, not to its payload: it only reaches a built-in Zenoh Sink if the message
+    /// is forwarded to it untouched, i.e. directly or through built-in Operators that forward messages (e.g. merge,
+    /// split, tee, throttle or expression). A user Operator in between sends new messages, without suffix, unless it
+    /// forwards the message it received (see [forward](OutputRaw::forward)) or calls this method again with the
+    /// [key_suffix](LinkMessage::key_suffix) of that message.
+    ///
+    /// If no `timestamp` is provided, the current timestamp — as per the [HLC](uhlc::HLC) used by
+    /// the Zenoh-Flow daemon running this Node — is taken.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while sending the message on a channel, Zenoh-Flow still tries to send it on the remaining
+    /// channels. For each failing channel, an error is logged and counted for.
+    pub async fn send_with_key_suffix(
+        &self,
+        payload: impl Into<Payload>,
+        timestamp: Option<u64>,
+        key_suffix: impl Into<Arc<str>>,
+    ) -> Result<()> {
+        let message = LinkMessage {
+            payload: payload.into(),
+            timestamp: self.make_timestamp(timestamp),
+            trace_context: self.make_trace_context(),
+            sample_metadata: None,
+            key_suffix: Some(key_suffix.into()),
         };
 
         self.forward(message).await
@@ -384,6 +481,9 @@ impl<T: Send + Sync + 'static> Output<T> {
         Ok(LinkMessage {
             payload,
             timestamp: self.make_timestamp(timestamp),
+            trace_context: self.make_trace_context(),
+            sample_metadata: None,
+            key_suffix: None,
         })
     }
 
@@ -421,6 +521,26 @@ impl<T: Send + Sync + 'static> Output<T> {
         self.output_raw
             .try_forward(self.construct_message(data, timestamp)?)
     }
+
+    /// Send, *asynchronously*, the provided `data` to downstream node(s), along with the suffix of the key expression
+    /// on which a built-in Zenoh Sink should publish it.
+    ///
+    /// If no `timestamp` is provided, the current timestamp (as per the [HLC](uhlc::HLC) used by the Zenoh-Flow runtime
+    /// managing this node) is taken.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the send operation failed.
+    pub async fn send_with_key_suffix(
+        &self,
+        data: impl Into<Data<T>>,
+        timestamp: Option<u64>,
+        key_suffix: impl Into<Arc<str>>,
+    ) -> Result<()> {
+        let mut message = self.construct_message(data, timestamp)?;
+        message.key_suffix = Some(key_suffix.into());
+        self.output_raw.forward(message).await
+    }
 }
 
 #[cfg(test)]
@@ -50,6 +50,7 @@ fn test_typed_input<T: Send + Sync + Clone + std::fmt::Debug + PartialEq + 'stat
     let input_raw = InputRaw {
         port_id: "test-id".into(),
         receiver: rx,
+        tracer: None,
     };
 
     let input = Input {
@@ -12,14 +12,21 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use zenoh_flow_commons::PortId;
 
 use super::Outputs;
-use crate::messages::{LinkMessage, Payload};
+use crate::{
+    io::Inputs,
+    messages::{LinkMessage, Payload, SampleMetadata},
+    trace::{MessageTracer, TraceContext},
+};
 
 /// Test that the Output behaves as expected for the provided data and serialiser:
 /// 1. the `serialiser` is correctly type-erased yet still produces the correct output,
@@ -57,6 +64,7 @@ fn test_typed_output<T: Send + Sync + Clone + std::fmt::Debug + PartialEq + 'sta
     let mut outputs = Outputs {
         hmap: HashMap::from([(key.clone(), vec![tx])]),
         hlc: Arc::new(hlc),
+        tracer: None,
     };
 
     let output = outputs
@@ -146,3 +154,115 @@ fn test_protobuf_prost() {
 
     test_typed_output(expected_data, expected_serialized, serializer)
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/// TRACING
+
+/// A tracer attaching, to every message sent, the context of the last message received.
+#[derive(Debug, Default)]
+struct RelayTracer {
+    last: Mutex<Option<TraceContext>>,
+}
+
+impl MessageTracer for RelayTracer {
+    fn on_recv(&self, _port: &PortId, context: Option<&TraceContext>) {
+        *self.last.lock().unwrap() = context.copied();
+    }
+
+    fn on_send(&self, _port: &PortId) -> Option<TraceContext> {
+        *self.last.lock().unwrap()
+    }
+}
+
+#[test]
+fn test_tracer() {
+    let hlc = Arc::new(uhlc::HLC::default());
+    let tracer = Arc::new(RelayTracer::default());
+    let context = TraceContext {
+        trace_id: [1; 16],
+        span_id: [2; 8],
+        trace_flags: 1,
+    };
+
+    let (tx_in, rx_in) = flume::unbounded::<LinkMessage>();
+    let mut inputs = Inputs::default();
+    inputs.insert("in".into(), rx_in);
+    inputs.set_tracer(tracer.clone());
+    let input = inputs.take("in").unwrap().raw();
+
+    let (tx_out, rx_out) = flume::unbounded::<LinkMessage>();
+    let mut outputs = Outputs::new(hlc.clone());
+    outputs.insert("out".into(), tx_out);
+    outputs.set_tracer(tracer);
+    let output = outputs.take("out").unwrap().raw();
+
+    let mut message = LinkMessage::new(Payload::from(vec![0u8]), hlc.new_timestamp());
+    message.set_trace_context(Some(context));
+    tx_in.send(message).unwrap();
+
+    input.try_recv().unwrap().unwrap();
+    output.try_send(vec![1u8], None).unwrap();
+    let sent = rx_out.try_recv().unwrap();
+    assert_eq!(sent.trace_context(), Some(&context));
+
+    // The context travels with the serialised message.
+    let (mut message_buffer, mut payload_buffer) = (Vec::new(), Vec::new());
+    sent.serialize_bincode_into(&mut message_buffer, &mut payload_buffer)
+        .unwrap();
+    let deserialized: LinkMessage = bincode::deserialize(&message_buffer).unwrap();
+    assert_eq!(deserialized.trace_context(), Some(&context));
+}
+
+#[test]
+fn test_sample_metadata() {
+    let hlc = Arc::new(uhlc::HLC::default());
+    let (tx, rx) = flume::unbounded::<LinkMessage>();
+    let mut outputs = Outputs::new(hlc);
+    outputs.insert("out".into(), tx);
+    let output = outputs.take("out").unwrap().raw();
+
+    let metadata = SampleMetadata {
+        key_expr: "rt/robot-1/status".into(),
+        encoding: "application/json".into(),
+    };
+    futures::executor::block_on(output.send_with_metadata(vec![1u8], Some(42), metadata.clone()))
+        .unwrap();
+
+    let sent = rx.try_recv().unwrap();
+    assert_eq!(sent.timestamp().get_time().as_u64(), 42);
+    assert_eq!(sent.sample_metadata(), Some(&metadata));
+
+    // The metadata travels with the serialised message.
+    let (mut message_buffer, mut payload_buffer) = (Vec::new(), Vec::new());
+    sent.serialize_bincode_into(&mut message_buffer, &mut payload_buffer)
+        .unwrap();
+    let deserialized: LinkMessage = bincode::deserialize(&message_buffer).unwrap();
+    assert_eq!(deserialized.sample_metadata(), Some(&metadata));
+}
+
+#[test]
+fn test_key_suffix() {
+    let hlc = Arc::new(uhlc::HLC::default());
+    let (tx, rx) = flume::unbounded::<LinkMessage>();
+    let mut outputs = Outputs::new(hlc);
+    outputs.insert("out".into(), tx);
+    let output = outputs
+        .take("out")
+        .unwrap()
+        .typed(|buffer: &mut Vec<u8>, data: &u64| {
+            buffer.extend_from_slice(&data.to_le_bytes());
+            Ok(())
+        });
+
+    futures::executor::block_on(output.send_with_key_suffix(42u64, None, "robot-1")).unwrap();
+
+    let sent = rx.try_recv().unwrap();
+    assert_eq!(sent.key_suffix(), Some("robot-1"));
+
+    // The suffix travels with the serialised message.
+    let (mut message_buffer, mut payload_buffer) = (Vec::new(), Vec::new());
+    sent.serialize_bincode_into(&mut message_buffer, &mut payload_buffer)
+        .unwrap();
+    let deserialized: LinkMessage = bincode::deserialize(&message_buffer).unwrap();
+    assert_eq!(deserialized.key_suffix(), Some("robot-1"));
+}
@@ -33,11 +33,13 @@ pub(crate) mod context;
 pub(crate) mod declaration;
 pub(crate) mod io;
 pub(crate) mod messages;
+pub(crate) mod trace;
 pub(crate) mod traits;
 
 pub use self::{
     declaration::{NodeDeclaration, OperatorFn, SinkFn, SourceFn, CORE_VERSION, RUSTC_VERSION},
     io::{InputBuilder, OutputBuilder},
+    trace::{MessageTracer, TraceContext},
 };
 
 /// This module expose all the structures required to implement a Zenoh-Flow node.
@@ -52,7 +54,7 @@ pub mod prelude {
     pub use crate::{
         context::Context,
         io::{Input, InputRaw, Inputs, Output, OutputRaw, Outputs},
-        messages::{Data, LinkMessage, Payload},
+        messages::{Data, LinkMessage, Payload, SampleMetadata},
         traits::{Node, Operator, SendSyncAny, Sink, Source},
     };
 }
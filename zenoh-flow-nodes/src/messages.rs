@@ -19,7 +19,7 @@ use serde::{Deserialize, Serialize};
 use uhlc::Timestamp;
 use zenoh_flow_commons::Result;
 
-use crate::traits::SendSyncAny;
+use crate::{trace::TraceContext, traits::SendSyncAny};
 
 /// `SerializerFn` is a type-erased version of the serialiser function provided by node developer.
 ///
@@ -138,11 +138,29 @@ impl From<LinkMessage> for Payload {
     }
 }
 
-/// A message send on a Zenoh-Flow link: a [Payload] and a [Timestamp].
+/// The metadata of the Zenoh sample from which a built-in Zenoh Source created a [LinkMessage].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleMetadata {
+    /// The key expression on which the sample was published. It differs from the key expression of the subscriber when
+    /// the latter contains wildcards.
+    pub key_expr: Arc<str>,
+    /// The encoding of the sample, as declared by its publisher.
+    pub encoding: Arc<str>,
+}
+
+/// A message send on a Zenoh-Flow link: a [Payload], a [Timestamp] and, optionally, the [TraceContext] of the span
+/// that produced it, the [SampleMetadata] of the Zenoh sample it was created from and the suffix of the key expression
+/// on which a built-in Zenoh Sink should publish it.
+//
+// NOTE: The messages are exchanged with dynamically loaded nodes, the version of the layout in the `CORE_VERSION` has to
+//       be bumped whenever a field is changed.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LinkMessage {
     pub(crate) payload: Payload,
     pub(crate) timestamp: Timestamp,
+    pub(crate) trace_context: Option<TraceContext>,
+    pub(crate) sample_metadata: Option<SampleMetadata>,
+    pub(crate) key_suffix: Option<Arc<str>>,
 }
 
 impl Ord for LinkMessage {
@@ -167,7 +185,13 @@ impl Eq for LinkMessage {}
 
 impl LinkMessage {
     pub fn new(payload: Payload, timestamp: Timestamp) -> Self {
-        Self { payload, timestamp }
+        Self {
+            payload,
+            timestamp,
+            trace_context: None,
+            sample_metadata: None,
+            key_suffix: None,
+        }
     }
 
     /// Creates a new message from serialised data.
@@ -177,6 +201,9 @@ impl LinkMessage {
         Self {
             payload: Payload::Bytes(Arc::new(data)),
             timestamp,
+            trace_context: None,
+            sample_metadata: None,
+            key_suffix: None,
         }
     }
 
@@ -187,6 +214,11 @@ impl LinkMessage {
         &self.payload
     }
 
+    /// Replace the [Payload] of this message, keeping its timestamp and metadata.
+    pub fn set_payload(&mut self, payload: Payload) {
+        self.payload = payload;
+    }
+
     /// Return the [Timestamp] associated with this message.
     #[deprecated(
         since = "0.6.0-alpha.3",
@@ -203,6 +235,43 @@ impl LinkMessage {
         &self.timestamp
     }
 
+    /// Return the [TraceContext] attached to this message, if any.
+    pub fn trace_context(&self) -> Option<&TraceContext> {
+        self.trace_context.as_ref()
+    }
+
+    /// Attach the provided [TraceContext] to this message, replacing the previous one.
+    pub fn set_trace_context(&mut self, trace_context: Option<TraceContext>) {
+        self.trace_context = trace_context;
+    }
+
+    /// Return the [SampleMetadata] of the Zenoh sample this message was created from, if any.
+    ///
+    /// Only the messages sent by a built-in Zenoh Source carry such metadata.
+    pub fn sample_metadata(&self) -> Option<&SampleMetadata> {
+        self.sample_metadata.as_ref()
+    }
+
+    /// Attach the provided [SampleMetadata] to this message, replacing the previous one.
+    pub fn set_sample_metadata(&mut self, sample_metadata: Option<SampleMetadata>) {
+        self.sample_metadata = sample_metadata;
+    }
+
+    /// Return the suffix of the key expression on which a built-in Zenoh Sink should publish this message, if any.
+    pub fn key_suffix(&self) -> Option<&str> {
+        self.key_suffix.as_deref()
+    }
+
+    /// Set the suffix of the key expression on which a built-in Zenoh Sink should publish this message, replacing the
+    /// previous one.
+    ///
+    /// The suffix is only taken into account by the publishers configured with `dynamic-suffix`. It is kept by the
+    /// built-in Operators that forward messages but lost through a user Operator, that sends new messages, unless the
+    /// latter sets it again (see `OutputRaw::send_with_key_suffix`).
+    pub fn set_key_suffix(&mut self, key_suffix: Option<Arc<str>>) {
+        self.key_suffix = key_suffix;
+    }
+
     /// Serialises the [LinkMessage] using [bincode] into the given `buffer`.
     ///
     /// The `inner_buffer` is used to serialise (if need be) the [Payload] contained inside the
@@ -233,6 +302,9 @@ impl LinkMessage {
                 let serialized_message = Self {
                     payload: Payload::Bytes(Arc::new(payload_buffer.clone())),
                     timestamp: self.timestamp,
+                    trace_context: self.trace_context,
+                    sample_metadata: self.sample_metadata.clone(),
+                    key_suffix: self.key_suffix.clone(),
                 };
 
                 bincode::serialize_into(message_buffer, &serialized_message)
@@ -0,0 +1,83 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+use zenoh_flow_commons::PortId;
+
+/// A `TraceContext` identifies, following the [W3C Trace Context](https://www.w3.org/TR/trace-context/)
+/// specification, the span that produced a [LinkMessage](crate::prelude::LinkMessage).
+///
+/// It is attached to a message when it is sent and travels with it, including over Zenoh, such that the receiving
+/// node can continue the trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub trace_flags: u8,
+}
+
+impl TraceContext {
+    /// Returns the `traceparent` header, as defined by the W3C, of this context.
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{:02x}",
+            hex(&self.trace_id),
+            hex(&self.span_id),
+            self.trace_flags
+        )
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// A `MessageTracer` is notified of every message received and sent by a node.
+///
+/// A Zenoh-Flow runtime can set one per node in its [Inputs](crate::prelude::Inputs) and
+/// [Outputs](crate::prelude::Outputs) — it is then shared by all the ports of the node. It is what allows continuing,
+/// in the messages a node sends, the trace of the last message it received.
+///
+/// The methods are called from the code of the node: they should not block.
+pub trait MessageTracer: Debug + Send + Sync {
+    /// Called when the input `port` receives a message, with the context attached to it (if any).
+    fn on_recv(&self, port: &PortId, context: Option<&TraceContext>);
+
+    /// Called when the output `port` sends a new message, returns the context to attach to it.
+    fn on_send(&self, port: &PortId) -> Option<TraceContext>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TraceContext;
+
+    #[test]
+    fn test_traceparent() {
+        let context = TraceContext {
+            trace_id: [
+                0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e,
+                0x47, 0x36,
+            ],
+            span_id: [0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7],
+            trace_flags: 1,
+        };
+
+        assert_eq!(
+            context.traceparent(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+    }
+}
@@ -15,7 +15,7 @@
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
-use zenoh_flow_commons::NodeId;
+use zenoh_flow_commons::{BatchingConfiguration, Compression, NodeId, QoS};
 use zenoh_keyexpr::OwnedKeyExpr;
 
 /// A `SenderRecord` describes the sending end of a "Zenoh connection" between Zenoh-Flow runtimes.
@@ -29,6 +29,12 @@ use zenoh_keyexpr::OwnedKeyExpr;
 pub struct SenderRecord {
     pub(crate) id: NodeId,
     pub(crate) resource: OwnedKeyExpr,
+    #[serde(default)]
+    pub(crate) qos: QoS,
+    #[serde(default)]
+    pub(crate) compression: Compression,
+    #[serde(default)]
+    pub(crate) batching: Option<BatchingConfiguration>,
 }
 
 impl Display for SenderRecord {
@@ -45,6 +51,21 @@ impl SenderRecord {
     pub fn resource(&self) -> &OwnedKeyExpr {
         &self.resource
     }
+
+    /// Returns the quality of service of the link this connector is part of.
+    pub fn qos(&self) -> &QoS {
+        &self.qos
+    }
+
+    /// Returns the compression applied to the messages before they are published.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Returns how the messages are batched before they are published, if they are.
+    pub fn batching(&self) -> Option<&BatchingConfiguration> {
+        self.batching.as_ref()
+    }
 }
 
 /// A `ReceiverRecord` describes the receiving end of a "Zenoh connection" between Zenoh-Flow runtimes.
@@ -58,6 +79,10 @@ impl SenderRecord {
 pub struct ReceiverRecord {
     pub(crate) id: NodeId,
     pub(crate) resource: OwnedKeyExpr,
+    #[serde(default)]
+    pub(crate) qos: QoS,
+    #[serde(default)]
+    pub(crate) reorder_buffer: usize,
 }
 
 impl Display for ReceiverRecord {
@@ -78,4 +103,16 @@ impl ReceiverRecord {
     pub fn resource(&self) -> &OwnedKeyExpr {
         &self.resource
     }
+
+    /// Returns the quality of service of the link this connector is part of.
+    pub fn qos(&self) -> &QoS {
+        &self.qos
+    }
+
+    /// Returns the number of messages that can be held while waiting for the ones that were sent before them.
+    ///
+    /// `0` means that the messages are forwarded as they arrive.
+    pub fn reorder_buffer(&self) -> usize {
+        self.reorder_buffer
+    }
 }
@@ -20,10 +20,11 @@ use std::{
 use anyhow::{anyhow, bail, Context};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use zenoh_flow_commons::{InstanceId, NodeId, Result, RuntimeId};
+use zenoh_flow_commons::{Compression, InstanceId, NodeId, QoS, Result, RuntimeId};
 use zenoh_flow_descriptors::{
-    FlattenedDataFlowDescriptor, FlattenedOperatorDescriptor, FlattenedSinkDescriptor,
-    FlattenedSourceDescriptor, InputDescriptor, LinkDescriptor, OutputDescriptor,
+    FeedbackDescriptor, FlattenedDataFlowDescriptor, FlattenedOperatorDescriptor,
+    FlattenedSinkDescriptor, FlattenedSourceDescriptor, InputDescriptor, LinkDescriptor,
+    OutputDescriptor,
 };
 use zenoh_keyexpr::OwnedKeyExpr;
 
@@ -54,6 +55,8 @@ pub struct DataFlowRecord {
     pub(crate) receivers: HashMap<NodeId, ReceiverRecord>,
     pub(crate) links: Vec<LinkDescriptor>,
     pub(crate) mapping: HashMap<RuntimeId, HashSet<NodeId>>,
+    #[serde(default)]
+    pub(crate) topological_order: Vec<NodeId>,
 }
 
 impl DataFlowRecord {
@@ -90,6 +93,7 @@ impl DataFlowRecord {
         } = data_flow.clone();
 
         let id = id.unwrap_or_else(|| Uuid::new_v4().into());
+        let flattened_order = data_flow.topological_order();
 
         // Nodes that are not running on the same runtime need to be connected.
         let mut additional_links = Vec::default();
@@ -197,6 +201,19 @@ Caused by:
                     to: input,
                     #[cfg(feature = "shared-memory")]
                     shared_memory: link.shared_memory,
+                    // NOTE: The recording, if any, stays on the link of the `from` node.
+                    recording: None,
+                    // NOTE: The QoS is applied by the connectors.
+                    qos: QoS::default(),
+                    compression: Compression::None,
+                    batching: None,
+                    reorder_buffer: None,
+                    // NOTE: The initial message, if any, is sent by the runtime of the `to` node.
+                    feedback: link.feedback.clone(),
+                });
+                link.feedback = link.feedback.take().map(|feedback| FeedbackDescriptor {
+                    initial: None,
+                    ..feedback
                 });
 
                 senders.insert(
@@ -204,6 +221,9 @@ Caused by:
                     SenderRecord {
                         id: sender_id.clone(),
                         resource: key_expression.clone(),
+                        qos: link.qos,
+                        compression: link.compression,
+                        batching: link.batching,
                     },
                 );
                 additional_mappings
@@ -216,6 +236,8 @@ Caused by:
                     ReceiverRecord {
                         id: receiver_id.clone(),
                         resource: key_expression,
+                        qos: link.qos,
+                        reorder_buffer: link.reorder_buffer.unwrap_or(0),
                     },
                 );
                 additional_mappings
@@ -235,6 +257,30 @@ Caused by:
                     .extend(nodes);
             });
 
+        // The Receiver(s) feeding a node come right before it and the Sender(s) it feeds right after it.
+        let mut topological_order =
+            Vec::with_capacity(flattened_order.len() + senders.len() + receivers.len());
+        let mut ordered = HashSet::with_capacity(topological_order.capacity());
+        for node_id in flattened_order {
+            let receivers_before = links
+                .iter()
+                .filter(|link| link.to.node == node_id && receivers.contains_key(&link.from.node))
+                .map(|link| &link.from.node);
+            let senders_after = links
+                .iter()
+                .filter(|link| link.from.node == node_id && senders.contains_key(&link.to.node))
+                .map(|link| &link.to.node);
+
+            for connected_node_id in receivers_before
+                .chain(std::iter::once(&node_id))
+                .chain(senders_after)
+            {
+                if ordered.insert(connected_node_id.clone()) {
+                    topological_order.push(connected_node_id.clone());
+                }
+            }
+        }
+
         Ok(Self {
             id,
             name,
@@ -245,6 +291,7 @@ Caused by:
             receivers,
             links,
             mapping,
+            topological_order,
         })
     }
 
@@ -289,6 +336,15 @@ Caused by:
         &self.links
     }
 
+    /// Returns the identifiers of the nodes of the data flow, connectors included, in topological order: a node comes
+    /// after all the nodes it receives messages from, the links marked as
+    /// [feedback](zenoh_flow_descriptors::FeedbackDescriptor) excluded.
+    ///
+    /// See [FlattenedDataFlowDescriptor::topological_order()].
+    pub fn topological_order(&self) -> &[NodeId] {
+        &self.topological_order
+    }
+
     /// Returns the set of [Source(s)](FlattenedSourceDescriptor) of the data flow.
     ///
     /// A `Source` will feed external data in the data flow to be processed by downstream nodes.
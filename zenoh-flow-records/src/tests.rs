@@ -14,7 +14,10 @@
 
 use std::collections::{HashMap, HashSet};
 
-use zenoh_flow_commons::{NodeId, RuntimeId, Vars};
+use zenoh_flow_commons::{
+    BatchingConfiguration, Compression, CongestionControl, NodeId, Priority, QoS, Reliability,
+    RuntimeId, Vars,
+};
 use zenoh_flow_descriptors::{
     DataFlowDescriptor, FlattenedDataFlowDescriptor, InputDescriptor, LinkDescriptor,
     OutputDescriptor,
@@ -191,6 +194,15 @@ links:
     to:
      node: operator-1
      input: in-1
+    qos:
+      priority: real-time
+      congestion-control: block
+      reliability: reliable
+    compression: lz4
+    batching:
+      max-size: 1KiB
+      max-latency: 1ms
+    reorder_buffer: 4
 
   - from:
      node: operator-1
@@ -219,6 +231,16 @@ mapping:
     assert_eq!(2, record.senders.len());
     assert_eq!(4, record.links.len());
 
+    let qos_thing_edge = QoS {
+        priority: Priority::RealTime,
+        congestion_control: CongestionControl::Block,
+        reliability: Reliability::Reliable,
+    };
+    let batching_thing_edge = BatchingConfiguration {
+        max_size: 1_024,
+        max_latency: 1_000,
+    };
+
     // assert the connectors
     let key_expr_thing_edge =
         OwnedKeyExpr::autocanonize(format!("{}/source-0/out-0", record.instance_id())).unwrap();
@@ -228,6 +250,9 @@ mapping:
         Some(&SenderRecord {
             id: sender_thing_edge.clone(),
             resource: key_expr_thing_edge.clone(),
+            qos: qos_thing_edge,
+            compression: Compression::Lz4,
+            batching: Some(batching_thing_edge),
         }),
         record.senders.get(&sender_thing_edge)
     );
@@ -235,6 +260,8 @@ mapping:
         Some(&ReceiverRecord {
             id: receiver_thing_edge.clone(),
             resource: key_expr_thing_edge.clone(),
+            qos: qos_thing_edge,
+            reorder_buffer: 4,
         }),
         record.receivers.get(&receiver_thing_edge)
     );
@@ -247,6 +274,9 @@ mapping:
         Some(&SenderRecord {
             id: sender_edge_default.clone(),
             resource: key_expr_edge_default.clone(),
+            qos: QoS::default(),
+            compression: Compression::None,
+            batching: None,
         }),
         record.senders.get(&sender_edge_default)
     );
@@ -254,6 +284,8 @@ mapping:
         Some(&ReceiverRecord {
             id: receiver_edge_default.clone(),
             resource: key_expr_edge_default.clone(),
+            qos: QoS::default(),
+            reorder_buffer: 0,
         }),
         record.receivers.get(&receiver_edge_default)
     );
@@ -270,6 +302,12 @@ mapping:
         },
         #[cfg(feature = "shared-memory")]
         shared_memory: None,
+        recording: None,
+        qos: qos_thing_edge,
+        compression: Compression::Lz4,
+        batching: Some(batching_thing_edge),
+        reorder_buffer: Some(4),
+        feedback: None,
     };
     assert!(record.links.contains(&link_thing));
 
@@ -284,6 +322,12 @@ mapping:
         },
         #[cfg(feature = "shared-memory")]
         shared_memory: None,
+        recording: None,
+        qos: QoS::default(),
+        compression: Compression::None,
+        batching: None,
+        reorder_buffer: None,
+        feedback: None,
     };
     assert!(record.links.contains(&link_egde_1));
 
@@ -298,6 +342,12 @@ mapping:
         },
         #[cfg(feature = "shared-memory")]
         shared_memory: None,
+        recording: None,
+        qos: QoS::default(),
+        compression: Compression::None,
+        batching: None,
+        reorder_buffer: None,
+        feedback: None,
     };
     assert!(record.links.contains(&link_edge_2));
 
@@ -312,6 +362,12 @@ mapping:
         },
         #[cfg(feature = "shared-memory")]
         shared_memory: None,
+        recording: None,
+        qos: QoS::default(),
+        compression: Compression::None,
+        batching: None,
+        reorder_buffer: None,
+        feedback: None,
     };
     assert!(record.links.contains(&link_default));
 
@@ -320,23 +376,37 @@ mapping:
         HashMap::from([
             (
                 runtime_thing,
-                HashSet::from(["source-0".into(), sender_thing_edge])
+                HashSet::from(["source-0".into(), sender_thing_edge.clone()])
             ),
             (
                 runtime_edge,
                 HashSet::from([
                     "operator-1".into(),
-                    receiver_thing_edge,
-                    sender_edge_default
+                    receiver_thing_edge.clone(),
+                    sender_edge_default.clone()
                 ])
             ),
             (
                 default_runtime,
-                HashSet::from(["sink-2".into(), receiver_edge_default])
+                HashSet::from(["sink-2".into(), receiver_edge_default.clone()])
             )
         ]),
         record.mapping
     );
+
+    // assert the topological order: the connectors surround the nodes they are connected to
+    assert_eq!(
+        vec![
+            "source-0".into(),
+            sender_thing_edge,
+            receiver_thing_edge,
+            "operator-1".into(),
+            sender_edge_default,
+            receiver_edge_default,
+            "sink-2".into(),
+        ],
+        record.topological_order
+    );
 }
 
 #[test]
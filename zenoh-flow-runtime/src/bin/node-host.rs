@@ -0,0 +1,25 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Helper executable in which a Zenoh-Flow runtime loads the nodes declared with `isolation: process`.
+//!
+//! It is not meant to be launched manually, see the `isolation` module of the `zenoh-flow-runtime` crate.
+
+#[async_std::main]
+async fn main() {
+    if let Err(e) = zenoh_flow_runtime::isolation::run_node_host().await {
+        eprintln!("{e:?}");
+        std::process::exit(1);
+    }
+}
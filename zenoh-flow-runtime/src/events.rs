@@ -0,0 +1,207 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use uhlc::{Timestamp, HLC};
+use zenoh_flow_commons::{InstanceId, NodeId, RuntimeId};
+
+/// The number of events a subscriber can lag behind before the next events are dropped for that subscriber.
+const EVENTS_CAPACITY: usize = 1024;
+
+/// An `InstanceEvent` notifies a change in the life cycle of a [DataFlowInstance](crate::DataFlowInstance) managed by
+/// a Zenoh-Flow [runtime](crate::Runtime).
+///
+/// See [subscribe_events](crate::Runtime::subscribe_events()) to receive them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceEvent {
+    /// The identifier of the runtime that emitted this event.
+    pub runtime_id: RuntimeId,
+    /// The identifier of the data flow instance this event concerns.
+    pub instance_id: InstanceId,
+    /// When this event happened, according to the [HLC] of the runtime.
+    pub timestamp: Timestamp,
+    /// What happened.
+    pub kind: InstanceEventKind,
+}
+
+/// The different events in the life cycle of a [DataFlowInstance](crate::DataFlowInstance).
+///
+/// Except for `NodeError` and `RuntimeLost`/`RuntimeRecovered` (when they do not change the state), each event matches
+/// a transition of its [InstanceState](crate::InstanceState).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceEventKind {
+    /// The runtime started loading the nodes of the data flow it manages.
+    Created { name: Arc<str> },
+    /// The runtime successfully loaded all the nodes it manages.
+    Loaded,
+    /// The runtime (re)started all the nodes it manages.
+    Started,
+    /// The runtime aborted all the nodes it manages.
+    Aborted,
+    /// The runtime failed to load at least one of the nodes it manages.
+    Failed { reason: String },
+    /// The runtime deleted the instance.
+    Deleted,
+    /// Another runtime involved in the data flow was lost. See the [RuntimeLossPolicy](crate::RuntimeLossPolicy) for
+    /// what follows.
+    RuntimeLost { runtime: RuntimeId },
+    /// A runtime that was lost is back.
+    RuntimeRecovered { runtime: RuntimeId },
+    /// A call to the `iteration` method of a node returned an error.
+    ///
+    /// The errors of a node are reported at most once per second: `suppressed` counts the errors it returned, since
+    /// the previous `NodeError` event, that were not reported.
+    NodeError {
+        node: NodeId,
+        error: String,
+        #[serde(default)]
+        suppressed: u64,
+    },
+}
+
+impl Display for InstanceEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstanceEventKind::Created { name } => write!(f, "created ({name})"),
+            InstanceEventKind::Loaded => write!(f, "loaded"),
+            InstanceEventKind::Started => write!(f, "started"),
+            InstanceEventKind::Aborted => write!(f, "aborted"),
+            InstanceEventKind::Failed { reason } => write!(f, "failed:\n{reason}"),
+            InstanceEventKind::Deleted => write!(f, "deleted"),
+            InstanceEventKind::RuntimeLost { runtime } => write!(f, "runtime < {runtime} > lost"),
+            InstanceEventKind::RuntimeRecovered { runtime } => {
+                write!(f, "runtime < {runtime} > recovered")
+            }
+            InstanceEventKind::NodeError {
+                node,
+                error,
+                suppressed,
+            } => {
+                write!(f, "node < {node} > failed:\n{error}")?;
+                if *suppressed > 0 {
+                    write!(f, "\n({suppressed} errors since the previous report)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for InstanceEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} - {}",
+            self.timestamp.get_time(),
+            self.instance_id,
+            self.kind
+        )
+    }
+}
+
+/// The `EventsHub` forwards the events of a runtime to all its subscribers.
+///
+/// A subscriber that does not keep up misses the events emitted while its channel is full, the runtime is never
+/// blocked. The subscribers that were dropped are removed when the next event is published.
+#[derive(Clone)]
+pub(crate) struct EventsHub {
+    runtime_id: RuntimeId,
+    hlc: Arc<HLC>,
+    subscribers: Arc<Mutex<Vec<flume::Sender<InstanceEvent>>>>,
+}
+
+impl EventsHub {
+    pub(crate) fn new(runtime_id: RuntimeId, hlc: Arc<HLC>) -> Self {
+        Self {
+            runtime_id,
+            hlc,
+            subscribers: Arc::new(Mutex::new(Vec::default())),
+        }
+    }
+
+    /// Registers a new subscriber, returning the channel on which it will receive the events.
+    pub(crate) fn subscribe(&self) -> flume::Receiver<InstanceEvent> {
+        let (tx, rx) = flume::bounded(EVENTS_CAPACITY);
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(tx);
+        rx
+    }
+
+    /// Timestamps the event and sends it to all the subscribers.
+    pub(crate) fn publish(&self, instance_id: &InstanceId, kind: InstanceEventKind) {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let event = InstanceEvent {
+            runtime_id: self.runtime_id.clone(),
+            instance_id: instance_id.clone(),
+            timestamp: self.hlc.new_timestamp(),
+            kind,
+        };
+
+        subscribers.retain(|subscriber| match subscriber.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(flume::TrySendError::Full(_)) => {
+                tracing::warn!("A subscriber is lagging behind, dropping event: {}", event);
+                true
+            }
+            Err(flume::TrySendError::Disconnected(_)) => false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uhlc::HLC;
+    use zenoh_flow_commons::{InstanceId, RuntimeId};
+
+    use super::{EventsHub, InstanceEventKind};
+
+    #[test]
+    fn test_events_hub() {
+        let hub = EventsHub::new(RuntimeId::rand(), Arc::new(HLC::default()));
+        let instance_id = InstanceId::from(uuid::Uuid::new_v4());
+
+        // No subscriber: nothing happens.
+        hub.publish(&instance_id, InstanceEventKind::Loaded);
+
+        let first = hub.subscribe();
+        let second = hub.subscribe();
+        hub.publish(&instance_id, InstanceEventKind::Started);
+        drop(second);
+        hub.publish(&instance_id, InstanceEventKind::Aborted);
+
+        assert_eq!(first.try_recv().unwrap().kind, InstanceEventKind::Started);
+        let aborted = first.try_recv().unwrap();
+        assert_eq!(aborted.kind, InstanceEventKind::Aborted);
+        assert_eq!(aborted.instance_id, instance_id);
+        assert!(first.try_recv().is_err());
+        assert_eq!(hub.subscribers.lock().unwrap().len(), 1);
+    }
+}
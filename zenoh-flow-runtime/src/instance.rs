@@ -12,6 +12,8 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+#[cfg(feature = "zenoh")]
+use std::sync::Arc;
 use std::{collections::HashMap, fmt::Display, ops::Deref};
 
 use serde::{Deserialize, Serialize};
@@ -20,6 +22,11 @@ use zenoh_flow_commons::{NodeId, Result, RuntimeId};
 use zenoh_flow_records::DataFlowRecord;
 
 use crate::runners::Runner;
+#[cfg(feature = "zenoh")]
+use crate::{
+    runners::sequence::{ConnectorCounters, ConnectorMetrics},
+    runtime::InstanceLiveliness,
+};
 
 /// A `DataFlowInstance` keeps track of the parts of a data flow managed by the Zenoh-Flow runtime.
 ///
@@ -31,6 +38,10 @@ pub struct DataFlowInstance {
     pub(crate) state: InstanceState,
     pub(crate) record: DataFlowRecord,
     pub(crate) runners: HashMap<NodeId, Runner>,
+    #[cfg(feature = "zenoh")]
+    pub(crate) liveliness: Option<InstanceLiveliness>,
+    #[cfg(feature = "zenoh")]
+    pub(crate) connector_counters: HashMap<NodeId, Arc<ConnectorCounters>>,
 }
 
 /// The different states of a [DataFlowInstance].
@@ -59,6 +70,14 @@ pub enum InstanceState {
     ///
     /// [runtime]: crate::Runtime
     Running(Timestamp),
+    /// A [runtime] listing a [DataFlowInstance] in the `Degraded` state still runs all the nodes it manages but lost
+    /// contact with (at least) one of the other runtimes involved in it. These runtimes are listed.
+    ///
+    /// A `Degraded` data flow goes back to the `Running` state once all these runtimes are back. It can also be
+    /// aborted or deleted. See the [RuntimeLossPolicy](crate::RuntimeLossPolicy) for the alternatives to this state.
+    ///
+    /// [runtime]: crate::Runtime
+    Degraded((Timestamp, Vec<RuntimeId>)),
     /// A [runtime] listing a [DataFlowInstance] in the `Aborted` state has abruptly stopped all the nodes it manages.
     ///
     /// An `Aborted` data flow can be restarted or deleted.
@@ -80,6 +99,15 @@ impl Display for InstanceState {
             InstanceState::Creating(ts) => write!(f, "Creation started on {}", ts.get_time()),
             InstanceState::Loaded(ts) => write!(f, "Loaded on {}", ts.get_time()),
             InstanceState::Running(ts) => write!(f, "Running since {}", ts.get_time()),
+            InstanceState::Degraded((ts, lost)) => write!(
+                f,
+                "Degraded since {}, lost runtime(s): {}",
+                ts.get_time(),
+                lost.iter()
+                    .map(|runtime_id| runtime_id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
             InstanceState::Aborted(ts) => write!(f, "Aborted on {}", ts.get_time()),
             InstanceState::Failed((ts, reason)) => {
                 write!(f, "Failed on {} with:\n{}", ts.get_time(), reason)
@@ -122,13 +150,49 @@ impl DataFlowInstance {
             state: InstanceState::Creating(hlc.new_timestamp()),
             record,
             runners: HashMap::default(),
+            #[cfg(feature = "zenoh")]
+            liveliness: None,
+            #[cfg(feature = "zenoh")]
+            connector_counters: HashMap::default(),
+        }
+    }
+
+    /// Returns the identifiers of the nodes managed by this runtime, in the [topological
+    /// order](DataFlowRecord::topological_order()) of the data flow.
+    ///
+    /// The nodes that do not appear in that order (e.g. the record was persisted before it was computed) come last.
+    fn ordered_nodes(&self) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self
+            .record
+            .topological_order()
+            .iter()
+            .filter(|node_id| self.runners.contains_key(*node_id))
+            .cloned()
+            .collect();
+        if nodes.len() < self.runners.len() {
+            let mut remaining: Vec<NodeId> = self
+                .runners
+                .keys()
+                .filter(|node_id| !nodes.contains(node_id))
+                .cloned()
+                .collect();
+            remaining.sort();
+            nodes.append(&mut remaining);
         }
+
+        nodes
     }
 
     /// (re-)Starts the `DataFlowInstance`.
     ///
+    /// The nodes are started in reverse topological order, the Sinks first, such that a node is running before the
+    /// nodes that send it messages.
+    ///
     /// The [hlc](HLC) is required to keep track of when this call was made.
     ///
+    /// Starting a `DataFlowInstance` that is already running, or degraded, does nothing: in particular, a degraded
+    /// instance only goes back to the `Running` state once all the runtimes it lost are back.
+    ///
     /// # Errors
     ///
     /// This method can fail when attempting to re-start: when re-starting a data flow, the method
@@ -136,9 +200,18 @@ impl DataFlowInstance {
     ///
     /// [on_resume]: zenoh_flow_nodes::prelude::Node::on_resume()
     pub async fn start(&mut self, hlc: &HLC) -> Result<()> {
-        for (node_id, runner) in self.runners.iter_mut() {
-            runner.start().await?;
-            tracing::trace!("Started node < {} >", node_id);
+        if matches!(
+            self.state,
+            InstanceState::Running(_) | InstanceState::Degraded(_)
+        ) {
+            return Ok(());
+        }
+
+        for node_id in self.ordered_nodes().iter().rev() {
+            if let Some(runner) = self.runners.get_mut(node_id) {
+                runner.start().await?;
+                tracing::trace!("Started node < {} >", node_id);
+            }
         }
 
         self.state = InstanceState::Running(hlc.new_timestamp());
@@ -147,11 +220,16 @@ impl DataFlowInstance {
 
     /// Aborts the `DataFlowInstance`.
     ///
+    /// The nodes are aborted in topological order, the Sources first, such that a node no longer receives messages once
+    /// it is aborted.
+    ///
     /// The [hlc](HLC) is required to keep track of when this call was made.
     pub async fn abort(&mut self, hlc: &HLC) {
-        for (node_id, runner) in self.runners.iter_mut() {
-            runner.abort().await;
-            tracing::trace!("Aborted node < {} >", node_id);
+        for node_id in self.ordered_nodes() {
+            if let Some(runner) = self.runners.get_mut(&node_id) {
+                runner.abort().await;
+                tracing::trace!("Aborted node < {} >", node_id);
+            }
         }
 
         self.state = InstanceState::Aborted(hlc.new_timestamp());
@@ -162,6 +240,16 @@ impl DataFlowInstance {
         &self.state
     }
 
+    /// Returns the [metrics](ConnectorMetrics) of the connectors receiving, from other runtimes, the messages of this
+    /// `DataFlowInstance`.
+    #[cfg(feature = "zenoh")]
+    pub fn connector_metrics(&self) -> HashMap<NodeId, ConnectorMetrics> {
+        self.connector_counters
+            .iter()
+            .map(|(receiver_id, counters)| (receiver_id.clone(), counters.snapshot()))
+            .collect()
+    }
+
     /// Returns the [status](InstanceStatus) of this `DataFlowInstance`.
     ///
     /// This structure was intended as a way to retrieve and display information about the instance. This is what the
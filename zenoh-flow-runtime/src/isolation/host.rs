@@ -0,0 +1,244 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{anyhow, bail, Context as _};
+use libloading::Library;
+use uhlc::{Timestamp, HLC};
+use zenoh_flow_commons::{Configuration, PortId, Result};
+use zenoh_flow_nodes::{
+    prelude::{Context, Inputs, LinkMessage, Outputs},
+    OperatorFn, SinkFn, SourceFn,
+};
+
+use super::{read_frame, write_frame, Frame, NodeSpec};
+use crate::{
+    loader::{check_digest, checksum_path, sha256_digest, try_get_constructor, NodeSymbol},
+    runners::Runner,
+};
+
+type Senders = HashMap<PortId, flume::Sender<LinkMessage>>;
+type Receivers = HashMap<PortId, flume::Receiver<LinkMessage>>;
+
+/// Loads and runs a single node isolated from its Zenoh-Flow runtime, communicating with the runtime over the
+/// standard input and output of the current process.
+///
+/// Before the node is loaded, the standard output is redirected to the standard error: only the frames are sent to the
+/// runtime, whatever the node writes on its standard output.
+///
+/// This function is the entry point of the [helper executable](super::HELPER_NAME) and returns once the runtime closes
+/// the standard input.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - the first frame received is not a `Spec` frame,
+/// - the node could not be loaded,
+/// - the communication with the runtime failed.
+pub async fn run_node_host() -> Result<()> {
+    let mut stdin = async_std::io::stdin();
+    let mut stdout = take_stdout()?;
+
+    let spec = match read_frame(&mut stdin).await? {
+        Some(Frame::Spec(spec)) => spec,
+        frame => bail!("Expected a `Spec` frame, received: {:?}", frame),
+    };
+
+    // NOTE: The HLC of the helper has its own identifier, such that the timestamps it generates cannot collide with the
+    // ones of the runtime.
+    let hlc = Arc::new(HLC::default());
+    update_hlc(&hlc, &spec.timestamp);
+
+    let (mut runner, senders, receivers) = match try_load_node(&spec, hlc.clone()).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            write_frame(&mut stdout, &Frame::Error(format!("{e:?}"))).await?;
+            return Err(e);
+        }
+    };
+    write_frame(&mut stdout, &Frame::Ready).await?;
+
+    // NOTE: All the outputs share the standard output, the frames are thus funnelled through a channel such that they
+    // are written one after the other.
+    let (frames_tx, frames_rx) = flume::unbounded::<Frame>();
+    for (port, rx) in receivers {
+        let frames_tx = frames_tx.clone();
+        async_std::task::spawn(async move {
+            let mut message_buffer = Vec::new();
+            let mut payload_buffer = Vec::new();
+            while let Ok(message) = rx.recv_async().await {
+                if let Err(e) =
+                    message.serialize_bincode_into(&mut message_buffer, &mut payload_buffer)
+                {
+                    tracing::error!("[{}] Failed to serialise message: {:?}", port, e);
+                    continue;
+                }
+
+                let frame = Frame::Message {
+                    port: port.clone(),
+                    message: message_buffer.clone(),
+                };
+                if frames_tx.send_async(frame).await.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+    drop(frames_tx);
+
+    async_std::task::spawn(async move {
+        while let Ok(frame) = frames_rx.recv_async().await {
+            if let Err(e) = write_frame(&mut stdout, &frame).await {
+                tracing::error!("Failed to write frame: {:?}", e);
+                return;
+            }
+        }
+    });
+
+    while let Some(frame) = read_frame(&mut stdin).await? {
+        match frame {
+            Frame::Start => runner.start().await?,
+            Frame::Abort => runner.abort().await,
+            Frame::Message { port, message } => {
+                let sender = senders.get(&port).ok_or_else(|| {
+                    anyhow!("Received a message for an unknown input < {} >", port)
+                })?;
+                let message = bincode::deserialize::<LinkMessage>(&message)
+                    .context("Failed to deserialise message")?;
+                update_hlc(&hlc, message.timestamp());
+                sender.send_async(message).await?;
+            }
+            frame => bail!("Unexpected frame: {:?}", frame),
+        }
+    }
+
+    runner.abort().await;
+    Ok(())
+}
+
+/// Updates the HLC of the helper with a timestamp generated by the runtime (or by another node).
+fn update_hlc(hlc: &HLC, timestamp: &Timestamp) {
+    if let Err(e) = hlc.update_with_timestamp(timestamp) {
+        tracing::warn!("Failed to update the HLC: {}", e);
+    }
+}
+
+/// Returns a handle on the standard output, through which the frames are sent, after having redirected the standard
+/// output of the process to its standard error.
+///
+/// The node is loaded in the current process: without the redirection, anything it writes on the standard output (a
+/// `println!`, a logger...) would corrupt the frames.
+#[cfg(unix)]
+fn take_stdout() -> Result<async_std::fs::File> {
+    use std::{io::Write, os::fd::AsFd};
+
+    std::io::stdout().flush()?;
+    let frames = std::io::stdout()
+        .as_fd()
+        .try_clone_to_owned()
+        .context("Failed to duplicate the standard output")?;
+
+    // SAFETY: Both file descriptors are valid for the lifetime of the process, `dup2` atomically replaces the standard
+    // output by a copy of the standard error.
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+        return Err(std::io::Error::last_os_error())
+            .context("Failed to redirect the standard output to the standard error");
+    }
+
+    Ok(std::fs::File::from(frames).into())
+}
+
+// NOTE: Without `dup2`, what the node writes on its standard output is interleaved with the frames.
+#[cfg(not(unix))]
+fn take_stdout() -> Result<async_std::io::Stdout> {
+    Ok(async_std::io::stdout())
+}
+
+/// Loads the node described in the [NodeSpec], returning its [Runner], the channels feeding its inputs and the
+/// channels receiving what it sends on its outputs.
+async fn try_load_node(spec: &NodeSpec, hlc: Arc<HLC>) -> Result<(Runner, Senders, Receivers)> {
+    let configuration: Configuration =
+        serde_json::from_str::<serde_json::Value>(&spec.configuration)
+            .context("Failed to deserialise the configuration")?
+            .into();
+
+    let mut senders = Senders::default();
+    let mut inputs = Inputs::default();
+    for port in spec.inputs.iter() {
+        let (tx, rx) = flume::unbounded();
+        inputs.insert(port.clone(), rx);
+        senders.insert(port.clone(), tx);
+    }
+
+    let mut receivers = Receivers::default();
+    let mut outputs = Outputs::new(hlc);
+    for port in spec.outputs.iter() {
+        let (tx, rx) = flume::unbounded();
+        outputs.insert(port.clone(), tx);
+        receivers.insert(port.clone(), rx);
+    }
+
+    let checksum_path = checksum_path(&spec.library_path, &spec.rust_library_path);
+    let digest = sha256_digest(checksum_path)?;
+    check_digest(checksum_path, &digest, Some(&spec.sha256), true)
+        .context("The library changed since it was verified by the runtime")?;
+
+    let library = unsafe {
+        Arc::new(Library::new(&spec.rust_library_path).context(format!(
+            "libloading::Library::new failed:\n{}",
+            spec.rust_library_path.display()
+        ))?)
+    };
+
+    let context = Context::new(
+        spec.flow_name.clone().into(),
+        spec.instance_id.clone(),
+        spec.runtime_id.clone(),
+        Arc::new(spec.library_path.clone()),
+        spec.node_id.clone(),
+    );
+
+    let (node, library) = match spec.node_symbol {
+        NodeSymbol::Source => {
+            let (constructor, library) =
+                try_get_constructor::<SourceFn>(library, &spec.node_symbol)?;
+            (
+                (constructor)(context, configuration, outputs).await?,
+                library,
+            )
+        }
+        NodeSymbol::Operator => {
+            let (constructor, library) =
+                try_get_constructor::<OperatorFn>(library, &spec.node_symbol)?;
+            (
+                (constructor)(context, configuration, inputs, outputs).await?,
+                library,
+            )
+        }
+        NodeSymbol::Sink => {
+            let (constructor, library) = try_get_constructor::<SinkFn>(library, &spec.node_symbol)?;
+            (
+                (constructor)(context, configuration, inputs).await?,
+                library,
+            )
+        }
+    };
+
+    Ok((
+        Runner::new(spec.node_id.clone(), node, Some(library)),
+        senders,
+        receivers,
+    ))
+}
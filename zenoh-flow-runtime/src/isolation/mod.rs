@@ -0,0 +1,222 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Support for nodes isolated in their own process.
+//!
+//! A node declared with `isolation: process` is not loaded in the process of the Zenoh-Flow runtime. Instead, the
+//! runtime spawns the [HELPER_NAME] executable which loads the node and exchanges with the runtime over its standard
+//! input and output. The standard error of the helper is inherited from the runtime and, such that the node cannot
+//! corrupt the exchanges, what the node writes on the standard output of the helper is redirected to it.
+//!
+//! The helper executable is produced by this crate: its `main` function only calls [run_node_host].
+//!
+//! # Protocol
+//!
+//! Every exchange is a *frame*: the length of its content, encoded as an unsigned 32 bits little-endian integer,
+//! followed by its content, a [bincode] serialised [Frame]. The content of a frame cannot exceed [MAX_FRAME_SIZE]
+//! bytes.
+//!
+//! 1. The runtime sends a `Spec` frame describing the node to load.
+//! 2. The helper answers either `Ready`, or `Error` (and exits) if it failed to load the node.
+//! 3. The runtime then sends `Start` / `Abort` frames to control the execution of the node, and `Message` frames for
+//!    each message received on one of its inputs.
+//! 4. The helper sends a `Message` frame for every message the node produces on one of its outputs.
+//!
+//! The helper exits as soon as its standard input is closed.
+
+mod host;
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{bail, Context};
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use serde::{Deserialize, Serialize};
+use uhlc::Timestamp;
+use zenoh_flow_commons::{InstanceId, NodeId, PortId, Result, RuntimeId};
+
+pub use self::host::run_node_host;
+use crate::loader::NodeSymbol;
+
+/// The name of the executable in which isolated nodes are loaded.
+pub const HELPER_NAME: &str = "zenoh-flow-node-host";
+
+/// The maximum size, in bytes, of the content of a frame.
+pub const MAX_FRAME_SIZE: usize = 256 * 1024 * 1024;
+
+/// Returns the default location of the helper executable.
+///
+/// The helper is first looked for in the directory of the current executable. If it is not found there, its name is
+/// returned as is and will be resolved through the `PATH` environment variable.
+pub(crate) fn default_helper_path() -> PathBuf {
+    let helper_name = format!("{HELPER_NAME}{}", std::env::consts::EXE_SUFFIX);
+
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&helper_name)))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from(helper_name))
+}
+
+/// Everything the helper needs to know to load an isolated node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct NodeSpec {
+    pub(crate) node_symbol: NodeSymbol,
+    pub(crate) flow_name: String,
+    pub(crate) instance_id: InstanceId,
+    pub(crate) runtime_id: RuntimeId,
+    /// A timestamp generated by the HLC of the runtime.
+    ///
+    /// The helper timestamps the messages the node produces with its own HLC, whose identifier is distinct from the one
+    /// of the runtime. This HLC is updated with this timestamp and with the timestamp of every message the node
+    /// receives, the runtime does the same with the messages the node produces.
+    pub(crate) timestamp: Timestamp,
+    pub(crate) node_id: NodeId,
+    /// The location of the implementation of the node, exposed to the node through its `Context`.
+    pub(crate) library_path: PathBuf,
+    /// The location of the shared library exposing the symbols Zenoh-Flow expects.
+    pub(crate) rust_library_path: PathBuf,
+    /// The SHA-256 digest of the library, as verified by the runtime. The helper checks it again, right before loading
+    /// the library, such that it cannot be replaced in between.
+    pub(crate) sha256: Arc<str>,
+    /// The JSON representation of the `Configuration` of the node.
+    pub(crate) configuration: String,
+    pub(crate) inputs: Vec<PortId>,
+    pub(crate) outputs: Vec<PortId>,
+}
+
+/// The frames exchanged between a Zenoh-Flow runtime and the helper process of an isolated node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum Frame {
+    Spec(NodeSpec),
+    Ready,
+    Error(String),
+    Start,
+    Abort,
+    /// A [bincode] serialised `LinkMessage` received on (or sent by) the port.
+    Message {
+        port: PortId,
+        message: Vec<u8>,
+    },
+}
+
+/// Reads a single [Frame], returning `None` if the stream was closed.
+///
+/// # Errors
+///
+/// This function will return an error if the stream was closed in the middle of a frame, if the announced size of its
+/// content exceeds [MAX_FRAME_SIZE] or if its content could not be deserialised.
+pub(crate) async fn read_frame(reader: &mut (impl AsyncRead + Unpin)) -> Result<Option<Frame>> {
+    let mut len = [0u8; 4];
+    if let Err(e) = reader.read_exact(&mut len).await {
+        if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            return Ok(None);
+        }
+        return Err(e.into());
+    }
+
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_SIZE {
+        bail!(
+            "Frame of {} bytes exceeds the maximum size of {} bytes",
+            len,
+            MAX_FRAME_SIZE
+        )
+    }
+
+    // NOTE: The buffer grows as the content is received, the announced size is not trusted for the allocation.
+    let mut buffer = Vec::new();
+    reader.take(len as u64).read_to_end(&mut buffer).await?;
+    if buffer.len() != len {
+        bail!("Stream closed in the middle of a frame")
+    }
+
+    bincode::deserialize(&buffer)
+        .map(Some)
+        .context("Failed to deserialise frame")
+}
+
+/// Writes a single [Frame] and flushes the stream.
+///
+/// # Errors
+///
+/// This function will return an error if the frame could not be serialised, if its content exceeds [MAX_FRAME_SIZE] or
+/// if it could not be written.
+pub(crate) async fn write_frame(
+    writer: &mut (impl AsyncWrite + Unpin),
+    frame: &Frame,
+) -> Result<()> {
+    let content = bincode::serialize(frame).context("Failed to serialise frame")?;
+    if content.len() > MAX_FRAME_SIZE {
+        bail!(
+            "Frame of {} bytes exceeds the maximum size of {} bytes",
+            content.len(),
+            MAX_FRAME_SIZE
+        )
+    }
+    let len = content.len() as u32;
+
+    let mut buffer = Vec::with_capacity(content.len() + 4);
+    buffer.extend_from_slice(&len.to_le_bytes());
+    buffer.extend_from_slice(&content);
+
+    writer.write_all(&buffer).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::io::Cursor;
+
+    use super::{read_frame, write_frame, Frame, MAX_FRAME_SIZE};
+
+    #[async_std::test]
+    async fn test_frames() {
+        let mut stream = Cursor::new(Vec::new());
+        write_frame(&mut stream, &Frame::Start).await.unwrap();
+        write_frame(
+            &mut stream,
+            &Frame::Message {
+                port: "out".into(),
+                message: vec![1, 2, 3],
+            },
+        )
+        .await
+        .unwrap();
+
+        stream.set_position(0);
+        assert!(matches!(
+            read_frame(&mut stream).await.unwrap(),
+            Some(Frame::Start)
+        ));
+        match read_frame(&mut stream).await.unwrap() {
+            Some(Frame::Message { port, message }) => {
+                assert_eq!(port, "out".into());
+                assert_eq!(message, vec![1, 2, 3]);
+            }
+            frame => panic!("Unexpected frame: {frame:?}"),
+        }
+        assert!(read_frame(&mut stream).await.unwrap().is_none());
+
+        // A truncated frame is an error, not the end of the stream.
+        let mut stream = Cursor::new(vec![10, 0, 0, 0, 1]);
+        assert!(read_frame(&mut stream).await.is_err());
+
+        // A frame announcing a content larger than the maximum is rejected before it is read.
+        let mut stream = Cursor::new(((MAX_FRAME_SIZE + 1) as u32).to_le_bytes().to_vec());
+        assert!(
+            format!("{:?}", read_frame(&mut stream).await.unwrap_err()).contains("maximum size")
+        );
+    }
+}
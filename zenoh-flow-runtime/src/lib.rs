@@ -17,24 +17,47 @@
 //! If the feature `zenoh` is enabled (it is by default), this crate additionally re-exports the structures from
 //! [Zenoh](zenoh) that allow opening a [Session](zenoh::Session) *asynchronously*.
 //!
+//! If the feature `opentelemetry` is enabled, the messages exchanged by the nodes are traced with OpenTelemetry, see
+//! the `telemetry` module.
+//!
 //! Users interested in exposing a Zenoh-Flow runtime should find everything in the [Runtime] and [RuntimeBuilder].
 //!
 //! Users interested in fetching the state of a data flow instance should look into the [DataFlowInstance],
 //! [InstanceState] and [InstanceStatus] structures. These structures are leveraged by the `zfctl` command line tool.
+//! Rather than polling these structures, one can be notified of every change through the stream of [InstanceEvent]
+//! returned by [Runtime::subscribe_events()].
+
+mod events;
+pub use self::events::{InstanceEvent, InstanceEventKind};
 
 mod instance;
 pub use self::instance::{DataFlowInstance, InstanceState, InstanceStatus};
 
+pub mod isolation;
+
 mod loader;
 pub use self::loader::{Extension, Extensions};
 
 #[cfg(feature = "shared-memory")]
 mod shared_memory;
 
+#[cfg(feature = "opentelemetry")]
+pub mod telemetry;
+
+#[cfg(feature = "zenoh")]
+pub mod wire;
+
+pub mod recording;
+
 mod runners;
 
 mod runtime;
-pub use runtime::{DataFlowErr, Runtime, RuntimeBuilder};
+#[cfg(feature = "zenoh")]
+pub use runtime::RuntimeLossPolicy;
+
+#[cfg(feature = "zenoh")]
+pub use runners::sequence::ConnectorMetrics;
+pub use runtime::{DataFlowErr, RecoveryPolicy, Runtime, RuntimeBuilder};
 
 /// A re-export of the Zenoh structures needed to open a [Session](zenoh::Session) asynchronously.
 #[cfg(feature = "zenoh")]
@@ -0,0 +1,171 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{fs::File, io::Read, path::Path, sync::Arc};
+
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
+use zenoh_flow_commons::Result;
+
+/// Computes the SHA-256 digest of the file located at `path`, returning its lowercase hexadecimal representation.
+///
+/// # Errors
+///
+/// This function will return an error if the file could not be read.
+pub(crate) fn sha256_digest(path: &Path) -> Result<Arc<str>> {
+    let mut file = File::open(path).context(format!(
+        "Failed to open file to compute its digest:\n{}",
+        path.display()
+    ))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .context(format!("Failed to read file:\n{}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()).into())
+}
+
+/// Returns the path of the file whose digest is verified: the one the node is loaded from.
+///
+/// For a native shared library, it is the (canonicalized) `rust_library_path`, i.e. the file actually opened by
+/// `libloading`. For a node relying on an [Extension](super::Extension), the `rust_library_path` points to the wrapper
+/// provided by the configuration of the runtime: it is then the `library_path`, the implementation read by that
+/// wrapper.
+pub(crate) fn checksum_path<'a>(library_path: &'a Path, rust_library_path: &'a Path) -> &'a Path {
+    if library_path
+        .extension()
+        .is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION)
+    {
+        rust_library_path
+    } else {
+        library_path
+    }
+}
+
+/// Checks that the `digest` of the library located at `path` matches the `expected` one.
+///
+/// The comparison is case insensitive.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - no digest is `expected` while checksums are `required`,
+/// - the digests do not match.
+pub(crate) fn check_digest(
+    path: &Path,
+    digest: &str,
+    expected: Option<&str>,
+    required: bool,
+) -> Result<()> {
+    match expected {
+        Some(expected) => {
+            if !expected.eq_ignore_ascii_case(digest) {
+                bail!(
+                    r#"
+The SHA-256 digest of the library does not match the one provided in the descriptor, refusing to load it:
+- library:  {}
+- expected: {}
+- found:    {}
+"#,
+                    path.display(),
+                    expected,
+                    digest
+                )
+            }
+        }
+        None => {
+            if required {
+                bail!(
+                    r#"
+This Zenoh-Flow runtime requires a `sha256` checksum for every library but none was provided for:
+{}
+"#,
+                    path.display()
+                )
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the digest of the library located at `path` and checks that it matches the `expected` one, returning it.
+///
+/// See [check_digest] for the possible errors.
+pub(crate) fn verify_checksum(
+    path: &Path,
+    expected: Option<&str>,
+    required: bool,
+) -> Result<Arc<str>> {
+    let digest = sha256_digest(path)?;
+    check_digest(path, &digest, expected, required)?;
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, path::PathBuf};
+
+    use super::{checksum_path, verify_checksum};
+
+    #[test]
+    fn test_verify_checksum() {
+        let path = std::env::temp_dir().join(format!("zf-checksum-{}.so", uuid::Uuid::new_v4()));
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(b"zenoh-flow")
+            .unwrap();
+
+        let expected = "d18a363d51cc1e48db65a94fa9c870b492d1198649391cf7cb1f7d2f0a0143b6";
+        let digest = verify_checksum(&path, None, false).unwrap();
+        assert_eq!(&*digest, expected);
+
+        assert!(verify_checksum(&path, Some(expected), true).is_ok());
+        assert!(verify_checksum(&path, Some(&expected.to_uppercase()), true).is_ok());
+        assert!(verify_checksum(&path, None, true).is_err());
+        assert!(verify_checksum(
+            &path,
+            Some("0000000000000000000000000000000000000000000000000000000000000000"),
+            false
+        )
+        .is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_path() {
+        let library = PathBuf::from(format!(
+            "/home/zenoh-flow/libnode.{}",
+            std::env::consts::DLL_EXTENSION
+        ));
+        let canonical = PathBuf::from(format!(
+            "/opt/zenoh-flow/libnode.{}",
+            std::env::consts::DLL_EXTENSION
+        ));
+        assert_eq!(checksum_path(&library, &canonical), canonical);
+
+        let script = PathBuf::from("/home/zenoh-flow/node.py");
+        let wrapper = PathBuf::from("/opt/zenoh-flow/libpython_wrapper.so");
+        assert_eq!(checksum_path(&script, &wrapper), script);
+    }
+}
@@ -12,25 +12,32 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+mod checksum;
 mod extensions;
 
 use std::{
     collections::HashMap,
     ops::{Deref, DerefMut},
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
 };
 
 use anyhow::{anyhow, bail, Context};
 use libloading::Library;
+use serde::{Deserialize, Serialize};
 use url::Url;
 use zenoh_flow_commons::Result;
-use zenoh_flow_nodes::{NodeDeclaration, CORE_VERSION, RUSTC_VERSION};
+use zenoh_flow_nodes::{
+    NodeDeclaration, OperatorFn, SinkFn, SourceFn, CORE_VERSION, RUSTC_VERSION,
+};
 
+use self::checksum::verify_checksum;
+pub(crate) use self::checksum::{check_digest, checksum_path, sha256_digest};
 pub use self::extensions::{Extension, Extensions};
 
 /// NodeSymbol groups the symbol we must find in the shared library we load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum NodeSymbol {
     Source,
     Operator,
@@ -118,12 +125,56 @@ pub(crate) fn try_get_constructor<N>(
     Ok((decl.constructor, library))
 }
 
+/// The constructors of the nodes that were registered, at compile time, in the Zenoh-Flow runtime.
+///
+/// These nodes are referenced in the descriptors through the `builtin://` or `static://` schemes, followed by the name
+/// under which they were registered. For instance, a Source registered as `my-source` is referenced as:
+///
+/// ```yaml
+/// library: builtin://my-source
+/// ```
+#[derive(Default)]
+pub(crate) struct StaticNodes {
+    pub(crate) sources: HashMap<Arc<str>, SourceFn>,
+    pub(crate) operators: HashMap<Arc<str>, OperatorFn>,
+    pub(crate) sinks: HashMap<Arc<str>, SinkFn>,
+}
+
+/// Trait allowing the [Loader] to retrieve, from the [StaticNodes], the constructor matching the type of node it is
+/// loading.
+pub(crate) trait StaticConstructor: Sized + Copy {
+    fn get(static_nodes: &StaticNodes, name: &str) -> Option<Self>;
+}
+
+impl StaticConstructor for SourceFn {
+    fn get(static_nodes: &StaticNodes, name: &str) -> Option<Self> {
+        static_nodes.sources.get(name).copied()
+    }
+}
+
+impl StaticConstructor for OperatorFn {
+    fn get(static_nodes: &StaticNodes, name: &str) -> Option<Self> {
+        static_nodes.operators.get(name).copied()
+    }
+}
+
+impl StaticConstructor for SinkFn {
+    fn get(static_nodes: &StaticNodes, name: &str) -> Option<Self> {
+        static_nodes.sinks.get(name).copied()
+    }
+}
+
+/// A loaded library: the path of the implementation of the node, the library and its SHA-256 digest.
+type LoadedLibrary = (Arc<PathBuf>, Arc<Library>, Arc<str>);
+
 /// The dynamic library loader.
 ///
 /// This structure is responsible for:
 /// 1. loading the shared libraries containing the implementation of the nodes,
 /// 2. keeping track of these libraries to avoid loading several times the same one,
-/// 3. leveraging the [Extension]s to load "non-standard" node implementation.
+/// 3. leveraging the [Extension]s to load "non-standard" node implementation,
+/// 4. retrieving the constructors of the nodes that were statically registered (see [StaticNodes]),
+/// 5. verifying the SHA-256 digest of the libraries, when one is provided in the descriptor.
 ///
 /// Note that "non-standard" libraries are libraries that have an extension that is different than
 /// [DLL_EXTENSION](std::env::consts::DLL_EXTENSION) --- e.g. different than `.so` on Linux-based systems.
@@ -134,6 +185,8 @@ pub(crate) fn try_get_constructor<N>(
 /// - it will check that the node implementation was using the same version of the Zenoh-Flow library than the
 ///   Zenoh-Flow runtime it belongs to.
 ///
+/// If `require_checksums` is set, the loader refuses to load any library for which no digest was provided.
+///
 /// To do these checks, the loader is expecting to find specific symbols (different for each type of node). These
 /// symbols are automatically exported via the respective procedural macros: [export_source], [export_operator],
 /// [export_sink].
@@ -144,7 +197,9 @@ pub(crate) fn try_get_constructor<N>(
 #[derive(Default)]
 pub(crate) struct Loader {
     pub(crate) extensions: Extensions,
-    pub(crate) libraries: HashMap<Url, (Arc<PathBuf>, Arc<Library>)>,
+    pub(crate) libraries: HashMap<Url, LoadedLibrary>,
+    pub(crate) static_nodes: StaticNodes,
+    pub(crate) require_checksums: bool,
 }
 
 impl Deref for Loader {
@@ -193,7 +248,7 @@ impl Loader {
     pub(crate) fn remove_unused_libraries(&mut self) {
         let number_libraries = self.libraries.len();
         self.libraries
-            .retain(|_, (_, library)| Arc::strong_count(library) > 1);
+            .retain(|_, (_, library, _)| Arc::strong_count(library) > 1);
         tracing::trace!(
             "Removed {} unused libraries.",
             number_libraries - self.libraries.len()
@@ -205,30 +260,45 @@ impl Loader {
     /// This method will first look into its cache of shared libraries and check if it does not already know of a
     /// library with the same Url.
     ///
-    /// If it does then it will reuse this library.
+    /// If it does then it will reuse this library, after checking that the digest it had when it was loaded matches
+    /// the `sha256` provided.
+    ///
+    /// If not, it will attempt to load it and check its compatibility. The digest of the library is verified *before*
+    /// it is loaded.
     ///
-    /// If not, it will attempt to load it and check its compatibility.
+    /// If the scheme of the Url is either "builtin://" or "static://", no library is loaded: the constructor is
+    /// instead looked up among the [StaticNodes] and `None` is returned in place of the library.
     ///
     /// # Errors
     ///
     /// This method can fail for the following reasons:
-    /// - the provided Url specifies a scheme that we do not support (for now only "file://" is supported),
+    /// - the provided Url specifies a scheme that we do not support (for now only "file://", "builtin://" and
+    ///   "static://" are supported),
+    /// - no node was registered under the name provided in a "builtin://" or "static://" Url,
+    /// - the SHA-256 digest of the library does not match the `sha256` provided, or none was provided while checksums
+    ///   are required,
     /// - we failed to load the library from the provided Url (e.g. file not found),
     /// - the library does not expose the correct symbol or is not compatible with this Zenoh-Flow runtime.
-    pub(crate) fn try_load_constructor<C>(
+    pub(crate) fn try_load_constructor<C: StaticConstructor>(
         &mut self,
         url: &Url,
+        sha256: Option<&str>,
         node_symbol: &NodeSymbol,
-    ) -> Result<(C, Arc<PathBuf>, Arc<Library>)> {
-        if let Some((path, library)) = self.libraries.get(url) {
+    ) -> Result<(C, Arc<PathBuf>, Option<Arc<Library>>)> {
+        if let Some((path, library, digest)) = self.libraries.get(url) {
+            check_digest(path, digest, sha256, self.require_checksums)?;
             let (constructor, library) = try_get_constructor::<C>(library.clone(), node_symbol)?;
-            return Ok((constructor, path.clone(), library));
+            return Ok((constructor, path.clone(), Some(library)));
         }
 
-        let (path, library) = match url.scheme() {
+        let (path, library, digest) = match url.scheme() {
             "file" => self
-                .try_load_library_from_uri(url.path(), node_symbol)
+                .try_load_library_from_uri(url.path(), sha256, node_symbol)
                 .context(format!("Failed to load library from file:\n{}", url.path()))?,
+            "builtin" | "static" => {
+                let constructor = self.try_get_static_constructor::<C>(url)?;
+                return Ok((constructor, Arc::new(PathBuf::from(url.as_str())), None));
+            }
             _ => bail!(
                 "Unsupported scheme < {} > while trying to load node:\n{}",
                 url.scheme(),
@@ -238,9 +308,93 @@ impl Loader {
 
         let (constructor, library) = try_get_constructor::<C>(library, node_symbol)?;
         self.libraries
-            .insert(url.clone(), (path.clone(), library.clone()));
+            .insert(url.clone(), (path.clone(), library.clone(), digest));
+
+        Ok((constructor, path, Some(library)))
+    }
+
+    /// Given a "builtin://" or "static://" [Url], attempts to retrieve the constructor that was registered under the
+    /// name it contains.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the Url does not contain a name or if no node of the expected type was
+    /// registered under that name.
+    fn try_get_static_constructor<C: StaticConstructor>(&self, url: &Url) -> Result<C> {
+        let name = match url.host_str() {
+            Some(name) if !name.is_empty() => name,
+            _ => bail!(
+                "Missing the name of the statically registered node in:\n{}",
+                url
+            ),
+        };
+
+        C::get(&self.static_nodes, name).ok_or_else(|| {
+            anyhow!(
+                r#"
+No node was statically registered under the name < {} > (with the correct type):
+{}
+
+Did you forget to call `register_source`, `register_operator` or `register_sink` on the `RuntimeBuilder`?
+"#,
+                name,
+                url
+            )
+        })
+    }
+
+    /// Given a [Url] and a [NodeSymbol], returns the path of the implementation of the node, the path of the shared
+    /// library exposing the symbols Zenoh-Flow expects and the SHA-256 digest of the library, *without* loading it.
+    ///
+    /// The digest is the one of the file the node is loaded from (see `checksum_path`): the child process loading the
+    /// node checks it again before doing so.
+    ///
+    /// This method is used when the node is not loaded in the process of the Zenoh-Flow runtime but in an isolated
+    /// child process.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    /// - the scheme of the Url is not "file://",
+    /// - the paths could not be resolved (see [try_resolve_library_paths](Loader::try_resolve_library_paths)),
+    /// - the SHA-256 digest of the library does not match the `sha256` provided, or none was provided while checksums
+    ///   are required.
+    pub(crate) fn try_resolve_library(
+        &self,
+        url: &Url,
+        sha256: Option<&str>,
+        node_symbol: &NodeSymbol,
+    ) -> Result<(PathBuf, PathBuf, Arc<str>)> {
+        if url.scheme() != "file" {
+            bail!(
+                "Unsupported scheme < {} > for a node isolated in its own process:\n{}",
+                url.scheme(),
+                url
+            );
+        }
+
+        let (library_path, rust_library_path) =
+            self.try_resolve_library_paths(url.path(), node_symbol)?;
+        let digest = verify_checksum(
+            checksum_path(&library_path, &rust_library_path),
+            sha256,
+            self.require_checksums,
+        )?;
 
-        Ok((constructor, path, library))
+        Ok((library_path, rust_library_path, digest))
+    }
+
+    /// Verifies the SHA-256 digest of the file located at `path`, see [try_load_constructor](Loader::try_load_constructor).
+    ///
+    /// This method is used for the nodes that are not loaded through `libloading`, e.g. WebAssembly modules.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the digests do not match, or if none was provided while checksums are
+    /// required.
+    #[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+    pub(crate) fn verify_checksum(&self, path: &Path, sha256: Option<&str>) -> Result<()> {
+        verify_checksum(path, sha256, self.require_checksums).map(|_| ())
     }
 
     /// Given the string representation of a path, attempts to load a library.
@@ -250,14 +404,52 @@ impl Loader {
     /// # Errors
     ///
     /// This method can fail if:
-    /// - the extension of the path is not supported (i.e. not [DLL_EXTENSION] and not in the [Extensions]),
-    /// - there is no file in the provided path,
+    /// - the paths could not be resolved (see [try_resolve_library_paths](Loader::try_resolve_library_paths)),
+    /// - the SHA-256 digest of the library does not match the `sha256` provided, or none was provided while checksums
+    ///   are required,
     /// - the libloading crate failed to create a `Library` using the provided path.
     pub(crate) fn try_load_library_from_uri(
         &self,
         path: &str,
+        sha256: Option<&str>,
         node_symbol: &NodeSymbol,
-    ) -> Result<(Arc<PathBuf>, Arc<Library>)> {
+    ) -> Result<LoadedLibrary> {
+        let (library_path, rust_library_path) =
+            self.try_resolve_library_paths(path, node_symbol)?;
+        let digest = verify_checksum(
+            checksum_path(&library_path, &rust_library_path),
+            sha256,
+            self.require_checksums,
+        )?;
+
+        #[cfg(any(target_family = "unix", target_family = "windows"))]
+        Ok((
+            Arc::new(library_path),
+            unsafe {
+                Arc::new(Library::new(&rust_library_path).context(format!(
+                    "libloading::Library::new failed:\n{}",
+                    rust_library_path.display()
+                ))?)
+            },
+            digest,
+        ))
+    }
+
+    /// Given the string representation of a path, returns the path of the implementation of the node and the
+    /// (canonicalized) path of the shared library exposing the symbols Zenoh-Flow expects.
+    ///
+    /// This method will look at the file extension to determine if it should leverage the [Extensions] or not.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    /// - the extension of the path is not supported (i.e. not [DLL_EXTENSION] and not in the [Extensions]),
+    /// - there is no file in the provided path.
+    fn try_resolve_library_paths(
+        &self,
+        path: &str,
+        node_symbol: &NodeSymbol,
+    ) -> Result<(PathBuf, PathBuf)> {
         let library_path = PathBuf::from_str(path)
             .context(format!("Failed to convert path to a `PathBuf`:\n{}", path))?;
 
@@ -298,12 +490,61 @@ impl Loader {
             rust_library_path.display()
         ))?;
 
-        #[cfg(any(target_family = "unix", target_family = "windows"))]
-        Ok((Arc::new(library_path), unsafe {
-            Arc::new(Library::new(&rust_library_path).context(format!(
-                "libloading::Library::new failed:\n{}",
-                rust_library_path.display()
-            ))?)
-        }))
+        Ok((library_path, rust_library_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use url::Url;
+    use zenoh_flow_nodes::{prelude::Node, OperatorFn, SinkFn, SourceFn};
+
+    use super::{Loader, NodeSymbol};
+
+    struct Dummy;
+
+    #[async_trait]
+    impl Node for Dummy {
+        async fn iteration(&self) -> zenoh_flow_commons::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_load_static_constructor() {
+        let mut loader = Loader::default();
+        loader.static_nodes.sinks.insert(
+            "dummy".into(),
+            (|_, _, _| Box::pin(async { Ok(Arc::new(Dummy) as Arc<dyn Node>) })) as SinkFn,
+        );
+
+        for url in ["builtin://dummy", "static://dummy"] {
+            let url = Url::parse(url).unwrap();
+            let (_, path, library) = loader
+                .try_load_constructor::<SinkFn>(&url, None, &NodeSymbol::Sink)
+                .expect("Failed to retrieve statically registered Sink");
+            assert!(library.is_none());
+            assert_eq!(path.to_str(), Some(url.as_str()));
+        }
+
+        // Not registered under that name.
+        assert!(loader
+            .try_load_constructor::<SinkFn>(
+                &Url::parse("builtin://unknown").unwrap(),
+                None,
+                &NodeSymbol::Sink
+            )
+            .is_err());
+        // Registered but as a Sink, not a Source nor an Operator.
+        let url = Url::parse("builtin://dummy").unwrap();
+        assert!(loader
+            .try_load_constructor::<SourceFn>(&url, None, &NodeSymbol::Source)
+            .is_err());
+        assert!(loader
+            .try_load_constructor::<OperatorFn>(&url, None, &NodeSymbol::Operator)
+            .is_err());
     }
 }
@@ -0,0 +1,352 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Recording of the messages flowing through a link.
+//!
+//! A link is recorded by adding, in its descriptor, the path of the file in which its messages should be written:
+//!
+//! ```yaml
+//! from:
+//!   node: source
+//!   output: out
+//! to:
+//!   node: sink
+//!   input: in
+//! recording: /var/log/zenoh-flow/source-out.zfrec
+//! ```
+//!
+//! The file is created (or truncated) when the data flow is loaded, on the runtime that manages the node of the
+//! `from` side of the link.
+//!
+//! # Replay
+//!
+//! A recording can be fed back into a data flow with the `replay` built-in Source. It sends each recorded message,
+//! unaltered (timestamp included), on its output (named `out` by default):
+//!
+//! ```yaml
+//! id: replay
+//! replay:
+//!   path: /var/log/zenoh-flow/source-out.zfrec
+//!   # Either `original` (the default): the messages are spaced as they were recorded,
+//!   # `fastest`: the messages are sent as fast as possible,
+//!   # or `scaled: <factor>`: the original pacing is accelerated by `factor` (2.0 = twice as fast).
+//!   pacing:
+//!     scaled: 2.0
+//!   output: out
+//! ```
+//!
+//! Once the end of the recording is reached, the Source stops sending messages.
+//!
+//! # Format
+//!
+//! A recording starts with a header: the bytes [MAGIC] followed by a single byte, the [VERSION] of the format.
+//!
+//! The header is followed by one entry per message, in the order in which they were sent on the link:
+//!
+//! | Field     | Size (bytes) | Content                                                               |
+//! |-----------|--------------|-----------------------------------------------------------------------|
+//! | timestamp | 8            | The time of the timestamp of the message (NTP64), little-endian.      |
+//! | length    | 4            | The length of the message, little-endian.                             |
+//! | message   | `length`     | The [bincode] serialisation of the message (a `LinkMessage`).         |
+//!
+//! The payload of a message is always written serialised, using the serialiser of the node that produced it.
+//!
+//! The recordings in the version `2` of the format, whose messages only carry their payload, timestamp and trace
+//! context, can still be read.
+
+use std::{fs::File, io::Write, path::Path};
+
+use anyhow::{bail, Context};
+use async_std::io::{BufReader, BufWriter};
+use futures::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use zenoh_flow_commons::Result;
+use zenoh_flow_nodes::{
+    prelude::{LinkMessage, Payload, Timestamp},
+    TraceContext,
+};
+
+/// The bytes every recording starts with.
+pub const MAGIC: &[u8; 5] = b"ZFREC";
+
+/// The version of the format of the recordings written by this runtime.
+pub const VERSION: u8 = 3;
+
+/// Deserialises a message serialised by a runtime whose messages only carried their payload, timestamp and trace
+/// context, i.e. in the version `2` of the recordings and in the version `1` of the wire format.
+pub(crate) fn deserialize_traced(bytes: &[u8]) -> bincode::Result<LinkMessage> {
+    bincode::deserialize::<(Payload, Timestamp, Option<TraceContext>)>(bytes).map(
+        |(payload, timestamp, trace_context)| {
+            let mut message = LinkMessage::new(payload, timestamp);
+            message.set_trace_context(trace_context);
+            message
+        },
+    )
+}
+
+/// Creates (or truncates) the recording file at `path` and writes its header.
+///
+/// # Errors
+///
+/// This function will return an error if the file could not be created or written.
+pub(crate) fn try_create_recording(path: &Path) -> Result<File> {
+    let mut file = File::create(path).context(format!(
+        "Failed to create the recording file:\n{}",
+        path.display()
+    ))?;
+    file.write_all(MAGIC)?;
+    file.write_all(&[VERSION])?;
+
+    Ok(file)
+}
+
+/// Spawns a task that writes every message received on `rx` in the recording `file` before forwarding it to `tx`.
+///
+/// The task stops when all the senders of `rx` are dropped, i.e. when the data flow instance is deleted.
+pub(crate) fn spawn_recorder(
+    file: File,
+    rx: flume::Receiver<LinkMessage>,
+    tx: flume::Sender<LinkMessage>,
+) {
+    async_std::task::spawn(async move {
+        let mut writer = BufWriter::new(async_std::fs::File::from(file));
+        let mut message_buffer = Vec::new();
+        let mut payload_buffer = Vec::new();
+        let mut recording = true;
+
+        while let Ok(message) = rx.recv_async().await {
+            if recording {
+                if let Err(e) = write_entry(
+                    &mut writer,
+                    &message,
+                    &mut message_buffer,
+                    &mut payload_buffer,
+                )
+                .await
+                {
+                    tracing::error!("Failed to record message, stopping the recording: {:?}", e);
+                    recording = false;
+                }
+
+                // NOTE: Flushing only when no other message is waiting avoids a system call per message under load
+                // while ensuring that the file is up to date as soon as the link is idle.
+                if recording && rx.is_empty() {
+                    if let Err(e) = writer.flush().await {
+                        tracing::error!("Failed to flush the recording, stopping it: {:?}", e);
+                        recording = false;
+                    }
+                }
+            }
+
+            if tx.send_async(message).await.is_err() {
+                break;
+            }
+        }
+
+        let _ = writer.flush().await;
+    });
+}
+
+async fn write_entry(
+    writer: &mut BufWriter<async_std::fs::File>,
+    message: &LinkMessage,
+    message_buffer: &mut Vec<u8>,
+    payload_buffer: &mut Vec<u8>,
+) -> Result<()> {
+    message.serialize_bincode_into(message_buffer, payload_buffer)?;
+    let length = u32::try_from(message_buffer.len()).context("Message is too large")?;
+
+    writer
+        .write_all(&message.timestamp().get_time().as_u64().to_le_bytes())
+        .await?;
+    writer.write_all(&length.to_le_bytes()).await?;
+    writer.write_all(message_buffer).await?;
+
+    Ok(())
+}
+
+/// Reads, sequentially, the entries of a recording.
+pub(crate) struct RecordingReader<R> {
+    reader: BufReader<R>,
+    version: u8,
+}
+
+impl RecordingReader<async_std::fs::File> {
+    /// Opens the recording at `path`, checking its header.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the file could not be opened or if it is not a recording in a supported
+    /// version.
+    pub(crate) async fn try_open(path: &Path) -> Result<Self> {
+        let file = async_std::fs::File::open(path).await.context(format!(
+            "Failed to open the recording file:\n{}",
+            path.display()
+        ))?;
+
+        Self::try_new(file)
+            .await
+            .context(format!("Invalid recording file:\n{}", path.display()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> RecordingReader<R> {
+    /// Wraps the `reader`, checking the header of the recording.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the header could not be read or is not valid.
+    pub(crate) async fn try_new(reader: R) -> Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut header = [0u8; 6];
+        reader
+            .read_exact(&mut header)
+            .await
+            .context("Failed to read the header")?;
+
+        if &header[..5] != MAGIC {
+            bail!("Missing the magic bytes < ZFREC >")
+        }
+        let version = header[5];
+        if version != VERSION && version != 2 {
+            bail!(
+                "Unsupported version < {} >, expected < 2 > or < {} >",
+                version,
+                VERSION
+            )
+        }
+
+        Ok(Self { reader, version })
+    }
+
+    /// Returns the next entry of the recording: the time (NTP64) of its timestamp and the message, or `None` once the
+    /// end of the recording is reached.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the recording is truncated or an entry could not be deserialised.
+    pub(crate) async fn next_entry(&mut self) -> Result<Option<(u64, LinkMessage)>> {
+        let mut time = [0u8; 8];
+        if let Err(e) = self.reader.read_exact(&mut time).await {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                return Ok(None);
+            }
+            return Err(e.into());
+        }
+
+        let mut length = [0u8; 4];
+        self.reader
+            .read_exact(&mut length)
+            .await
+            .context("Truncated entry")?;
+        let mut message = vec![0u8; u32::from_le_bytes(length) as usize];
+        self.reader
+            .read_exact(&mut message)
+            .await
+            .context("Truncated entry")?;
+
+        let message = if self.version == VERSION {
+            bincode::deserialize(&message)
+        } else {
+            deserialize_traced(&message)
+        }
+        .context("Failed to deserialise message")?;
+
+        Ok(Some((u64::from_le_bytes(time), message)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uhlc::HLC;
+    use zenoh_flow_nodes::{
+        prelude::{LinkMessage, Payload},
+        TraceContext,
+    };
+
+    use super::{spawn_recorder, try_create_recording, RecordingReader, MAGIC};
+
+    #[async_std::test]
+    async fn test_record_and_read() {
+        let path =
+            std::env::temp_dir().join(format!("zf-recording-{}.zfrec", uuid::Uuid::new_v4()));
+        let file = try_create_recording(&path).unwrap();
+
+        let (tap_tx, tap_rx) = flume::unbounded();
+        let (tx, rx) = flume::unbounded();
+        spawn_recorder(file, tap_rx, tx);
+
+        let hlc = Arc::new(HLC::default());
+        let messages = (0u8..3)
+            .map(|i| LinkMessage::new(Payload::from(vec![i]), hlc.new_timestamp()))
+            .collect::<Vec<_>>();
+        for message in messages.iter() {
+            tap_tx.send_async(message.clone()).await.unwrap();
+        }
+        drop(tap_tx);
+
+        // The messages are forwarded, unaltered, and the channel is closed once the recorder stops.
+        for message in messages.iter() {
+            assert_eq!(
+                rx.recv_async().await.unwrap().timestamp(),
+                message.timestamp()
+            );
+        }
+        assert!(rx.recv_async().await.is_err());
+
+        let mut reader = RecordingReader::try_open(&path).await.unwrap();
+        for (i, message) in messages.iter().enumerate() {
+            let (time, recorded) = reader.next_entry().await.unwrap().unwrap();
+            assert_eq!(time, message.timestamp().get_time().as_u64());
+            assert_eq!(recorded.timestamp(), message.timestamp());
+            assert_eq!(*recorded.payload().try_as_bytes().unwrap(), vec![i as u8]);
+        }
+        assert!(reader.next_entry().await.unwrap().is_none());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(RecordingReader::try_new(&b"ZFREC\x01"[..]).await.is_err());
+        assert!(RecordingReader::try_new(&b"ZFREC\x04"[..]).await.is_err());
+    }
+
+    #[async_std::test]
+    async fn test_read_version_2() {
+        let hlc = HLC::default();
+        let timestamp = hlc.new_timestamp();
+        let trace_context = TraceContext {
+            trace_id: [1; 16],
+            span_id: [2; 8],
+            trace_flags: 1,
+        };
+        let message =
+            bincode::serialize(&(Payload::from(vec![42u8]), timestamp, Some(trace_context)))
+                .unwrap();
+        let recording = [
+            &MAGIC[..],
+            &[2],
+            &timestamp.get_time().as_u64().to_le_bytes(),
+            &(message.len() as u32).to_le_bytes(),
+            &message,
+        ]
+        .concat();
+
+        let mut reader = RecordingReader::try_new(&recording[..]).await.unwrap();
+        let (time, recorded) = reader.next_entry().await.unwrap().unwrap();
+        assert_eq!(time, timestamp.get_time().as_u64());
+        assert_eq!(recorded.timestamp(), &timestamp);
+        assert_eq!(recorded.trace_context(), Some(&trace_context));
+        assert_eq!(*recorded.payload().try_as_bytes().unwrap(), vec![42u8]);
+        assert!(reader.next_entry().await.unwrap().is_none());
+    }
+}
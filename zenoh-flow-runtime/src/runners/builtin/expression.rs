@@ -0,0 +1,218 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use anyhow::Context as _;
+use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_descriptors::ExpressionDescriptor;
+use zenoh_flow_nodes::prelude::{InputRaw, Inputs, LinkMessage, Node, OutputRaw, Outputs, Payload};
+
+/// Returns the attachment of the `message`, i.e. the JSON object accessed through `@` in an expression.
+///
+/// The attachment contains the key expression and encoding of the Zenoh sample the message was created from and the key
+/// suffix of the message, when they are set.
+pub(crate) fn attachment(message: &LinkMessage) -> serde_json::Value {
+    let mut attachment = serde_json::Map::new();
+    if let Some(metadata) = message.sample_metadata() {
+        attachment.insert("key-expr".into(), metadata.key_expr.as_ref().into());
+        attachment.insert("encoding".into(), metadata.encoding.as_ref().into());
+    }
+    if let Some(suffix) = message.key_suffix() {
+        attachment.insert("key-suffix".into(), suffix.into());
+    }
+
+    serde_json::Value::Object(attachment)
+}
+
+/// An `ExpressionOperator` filters and / or reshapes the JSON messages it receives, see [ExpressionDescriptor].
+pub(crate) struct ExpressionOperator {
+    id: NodeId,
+    descriptor: ExpressionDescriptor,
+    input: InputRaw,
+    output: OutputRaw,
+}
+
+impl ExpressionOperator {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &ExpressionDescriptor,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let input = inputs
+            .take(descriptor.input.as_ref())
+            .context(format!(
+                r#"
+[built-in expression operator: {}] Zenoh-Flow encountered a fatal internal error.
+No Input was created for port: < {} >.
+"#,
+                id, descriptor.input
+            ))?
+            .raw();
+
+        let output = outputs
+            .take(descriptor.output.as_ref())
+            .context(format!(
+                r#"
+[built-in expression operator: {}] Zenoh-Flow encountered a fatal internal error.
+No Output was created for port: < {} >.
+"#,
+                id, descriptor.output
+            ))?
+            .raw();
+
+        Ok(Self {
+            id: id.clone(),
+            descriptor: descriptor.clone(),
+            input,
+            output,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for ExpressionOperator {
+    // The iteration of an expression Operator waits for a message, drops it if it is not valid JSON or if it does not
+    // satisfy the filter and, otherwise, forwards it or the result of the projection while keeping its timestamp and
+    // metadata.
+    async fn iteration(&self) -> Result<()> {
+        let mut message = self.input.recv().await?;
+        let payload = message.payload().try_as_bytes()?;
+        let value = match serde_json::from_slice::<serde_json::Value>(&payload) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!(
+                    "[built-in expression operator: {}] The message is not valid JSON, dropping it: {:?}",
+                    self.id,
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        let attachment = attachment(&message);
+
+        if let Some(filter) = &self.descriptor.filter {
+            if !filter.is_satisfied_with_attachment(&value, &attachment) {
+                return Ok(());
+            }
+        }
+
+        match &self.descriptor.map {
+            Some(projection) => {
+                let projected =
+                    serde_json::to_vec(&projection.evaluate_with_attachment(&value, &attachment))?;
+                message.set_payload(Payload::from(projected));
+                self.output.forward(message).await
+            }
+            None => self.output.forward(message).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use uhlc::HLC;
+    use zenoh_flow_descriptors::ExpressionDescriptor;
+    use zenoh_flow_nodes::prelude::{Inputs, LinkMessage, Node, Outputs, Payload, SampleMetadata};
+
+    use super::ExpressionOperator;
+
+    #[async_std::test]
+    async fn test_expression_operator() {
+        let hlc = Arc::new(HLC::default());
+        let mut inputs = Inputs::default();
+        let (in_tx, in_rx) = flume::unbounded();
+        inputs.insert("in".into(), in_rx);
+        let mut outputs = Outputs::new(hlc.clone());
+        let (out_tx, out_rx) = flume::unbounded();
+        outputs.insert("out".into(), out_tx);
+
+        let descriptor: ExpressionDescriptor = serde_yaml::from_str(
+            r#"
+filter: $.speed > 1
+map:
+  id: $.id
+  speed: $.speed * 2
+"#,
+        )
+        .unwrap();
+        let operator =
+            ExpressionOperator::try_new(&"expression".into(), &descriptor, inputs, outputs)
+                .unwrap();
+
+        for (value, expected) in [
+            (json!({ "id": "slow", "speed": 1 }), None),
+            (
+                json!({ "id": "fast", "speed": 2 }),
+                Some(json!({ "id": "fast", "speed": 4 })),
+            ),
+        ] {
+            let timestamp = hlc.new_timestamp();
+            in_tx
+                .send_async(LinkMessage::new(
+                    Payload::from(serde_json::to_vec(&value).unwrap()),
+                    timestamp,
+                ))
+                .await
+                .unwrap();
+            operator.iteration().await.unwrap();
+
+            match expected {
+                Some(expected) => {
+                    let message = out_rx.try_recv().unwrap();
+                    assert_eq!(*message.timestamp(), timestamp);
+                    assert_eq!(
+                        serde_json::from_slice::<serde_json::Value>(
+                            &message.payload().try_as_bytes().unwrap()
+                        )
+                        .unwrap(),
+                        expected
+                    );
+                }
+                None => assert!(out_rx.try_recv().is_err()),
+            }
+        }
+
+        in_tx
+            .send_async(LinkMessage::new(
+                Payload::from(b"not json".to_vec()),
+                hlc.new_timestamp(),
+            ))
+            .await
+            .unwrap();
+        operator.iteration().await.unwrap();
+        assert!(out_rx.try_recv().is_err());
+
+        // The projected message keeps the metadata of the message it was computed from.
+        let mut message = LinkMessage::new(
+            Payload::from(serde_json::to_vec(&json!({ "id": "fast", "speed": 3 })).unwrap()),
+            hlc.new_timestamp(),
+        );
+        let metadata = SampleMetadata {
+            key_expr: "rt/robot-1/status".into(),
+            encoding: "application/json".into(),
+        };
+        message.set_sample_metadata(Some(metadata.clone()));
+        message.set_key_suffix(Some("robot-1".into()));
+        in_tx.send_async(message).await.unwrap();
+        operator.iteration().await.unwrap();
+
+        let message = out_rx.try_recv().unwrap();
+        assert_eq!(message.sample_metadata(), Some(&metadata));
+        assert_eq!(message.key_suffix(), Some("robot-1"));
+    }
+}
@@ -0,0 +1,360 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context as _};
+use async_std::{
+    fs::{File, OpenOptions},
+    io::{prelude::*, BufReader},
+    sync::Mutex,
+};
+use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_descriptors::{FileReaderDescriptor, FileRotation, FileWriterDescriptor, ReadMode};
+use zenoh_flow_nodes::prelude::{InputRaw, Inputs, Node, OutputRaw, Outputs};
+
+/// The state of the reading of a file.
+struct Reader {
+    reader: BufReader<File>,
+    // NOTE: The bytes read are accumulated in the buffer such that nothing is lost if the Source is aborted in the
+    // middle of a line or of a chunk.
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+/// A `FileSource` sends the content of a file, line by line or by chunks of bytes, on its output.
+pub(crate) struct FileSource {
+    id: NodeId,
+    descriptor: FileReaderDescriptor,
+    output: OutputRaw,
+    reader: Mutex<Reader>,
+}
+
+impl FileSource {
+    pub(crate) async fn try_new(
+        id: &NodeId,
+        descriptor: &FileReaderDescriptor,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let output = outputs
+            .take(descriptor.output.as_ref())
+            .context(format!(
+                r#"
+[built-in file source: {}] Zenoh-Flow encountered a fatal internal error.
+No Output was created for port: < {} >.
+"#,
+                id, descriptor.output
+            ))?
+            .raw();
+
+        let file = File::open(&descriptor.path).await.context(format!(
+            "[built-in file source: {}] Failed to open < {} >",
+            id,
+            descriptor.path.display()
+        ))?;
+
+        Ok(Self {
+            id: id.clone(),
+            descriptor: descriptor.clone(),
+            output,
+            reader: Mutex::new(Reader {
+                reader: BufReader::new(file),
+                buffer: Vec::new(),
+                finished: false,
+            }),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for FileSource {
+    async fn iteration(&self) -> Result<()> {
+        let mut reader = self.reader.lock().await;
+        if reader.finished {
+            drop(reader);
+            futures::future::pending::<()>().await;
+            return Ok(());
+        }
+
+        let Reader {
+            reader,
+            buffer,
+            finished,
+        } = &mut *reader;
+
+        let read = match self.descriptor.read {
+            ReadMode::Lines => reader.read_until(b'\n', buffer).await,
+            ReadMode::Chunks => {
+                let remaining = self.descriptor.chunk_size - buffer.len() as u64;
+                (&mut *reader).take(remaining).read_to_end(buffer).await
+            }
+        };
+
+        if let Err(e) = read {
+            *finished = true;
+            return Err(anyhow!(e).context(format!(
+                "[built-in file source: {}] Failed to read < {} >, stopping",
+                self.id,
+                self.descriptor.path.display()
+            )));
+        }
+
+        if buffer.is_empty() {
+            tracing::info!(
+                "[built-in file source: {}] Reached the end of < {} >",
+                self.id,
+                self.descriptor.path.display()
+            );
+            *finished = true;
+            return Ok(());
+        }
+
+        let mut payload = std::mem::take(buffer);
+        if self.descriptor.read == ReadMode::Lines {
+            trim_line_terminator(&mut payload);
+        }
+
+        self.output.send(payload, None).await
+    }
+}
+
+/// Removes the trailing `\n` or `\r\n` of a line.
+pub(crate) fn trim_line_terminator(line: &mut Vec<u8>) {
+    if line.last() == Some(&b'\n') {
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+    }
+}
+
+/// The file a [FileSink] writes to and its current size.
+struct Writer {
+    file: File,
+    size: u64,
+}
+
+/// A `FileSink` appends the messages it receives to a file, rotating it if it grows too large.
+pub(crate) struct FileSink {
+    id: NodeId,
+    descriptor: FileWriterDescriptor,
+    input: InputRaw,
+    writer: Mutex<Writer>,
+}
+
+impl FileSink {
+    pub(crate) async fn try_new(
+        id: &NodeId,
+        descriptor: &FileWriterDescriptor,
+        mut inputs: Inputs,
+    ) -> Result<Self> {
+        let input = inputs
+            .take(descriptor.input.as_ref())
+            .context(format!(
+                r#"
+[built-in file sink: {}] Zenoh-Flow encountered a fatal internal error.
+No Input was created for port: < {} >.
+"#,
+                id, descriptor.input
+            ))?
+            .raw();
+
+        let file = open_append(&descriptor.path).await.context(format!(
+            "[built-in file sink: {}] Failed to open < {} >",
+            id,
+            descriptor.path.display()
+        ))?;
+        let size = file.metadata().await?.len();
+
+        Ok(Self {
+            id: id.clone(),
+            descriptor: descriptor.clone(),
+            input,
+            writer: Mutex::new(Writer { file, size }),
+        })
+    }
+
+    /// Renames `<path>` into `<path>.1`, `<path>.1` into `<path>.2`, etc. and creates a new, empty, `<path>`.
+    async fn rotate(&self, writer: &mut Writer, rotation: &FileRotation) -> Result<()> {
+        let path = &self.descriptor.path;
+        writer.file.flush().await?;
+
+        if rotation.max_files == 0 {
+            async_std::fs::remove_file(path).await?;
+        } else {
+            for index in (1..rotation.max_files).rev() {
+                let rotated = rotated_path(path, index);
+                if rotated.exists() {
+                    async_std::fs::rename(&rotated, rotated_path(path, index + 1)).await?;
+                }
+            }
+            async_std::fs::rename(path, rotated_path(path, 1)).await?;
+        }
+
+        writer.file = open_append(path).await?;
+        writer.size = 0;
+
+        Ok(())
+    }
+}
+
+async fn open_append(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = OsString::from(path.as_os_str());
+    rotated.push(format!(".{index}"));
+    PathBuf::from(rotated)
+}
+
+#[async_trait::async_trait]
+impl Node for FileSink {
+    async fn iteration(&self) -> Result<()> {
+        let message = self.input.recv().await?;
+        let payload = message.payload().try_as_bytes()?;
+        let size = payload.len() as u64 + u64::from(self.descriptor.newline);
+
+        let mut writer = self.writer.lock().await;
+        if let Some(rotation) = &self.descriptor.rotation {
+            if writer.size > 0 && writer.size + size > rotation.max_size {
+                self.rotate(&mut writer, rotation).await.context(format!(
+                    "[built-in file sink: {}] Failed to rotate < {} >",
+                    self.id,
+                    self.descriptor.path.display()
+                ))?;
+            }
+        }
+
+        writer.file.write_all(&payload).await?;
+        if self.descriptor.newline {
+            writer.file.write_all(b"\n").await?;
+        }
+        writer.file.flush().await?;
+        writer.size += size;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uhlc::HLC;
+    use zenoh_flow_descriptors::{
+        FileReaderDescriptor, FileRotation, FileWriterDescriptor, ReadMode,
+    };
+    use zenoh_flow_nodes::prelude::{Inputs, LinkMessage, Node, Outputs, Payload};
+
+    use super::{rotated_path, FileSink, FileSource};
+
+    #[async_std::test]
+    async fn test_file_sink_and_source() {
+        let path = std::env::temp_dir().join(format!("zf-file-{}.log", uuid::Uuid::new_v4()));
+        let hlc = Arc::new(HLC::default());
+
+        let mut inputs = Inputs::default();
+        let (in_tx, in_rx) = flume::unbounded();
+        inputs.insert("in".into(), in_rx);
+        let sink = FileSink::try_new(
+            &"file-sink".into(),
+            &FileWriterDescriptor {
+                path: path.clone(),
+                rotation: Some(FileRotation {
+                    max_size: 8,
+                    max_files: 1,
+                }),
+                newline: true,
+                input: "in".into(),
+            },
+            inputs,
+        )
+        .await
+        .unwrap();
+
+        // Each line is 4 bytes long (newline included): two lines fit in a file, the third triggers a rotation.
+        for line in ["abc", "def", "ghi"] {
+            in_tx
+                .send_async(LinkMessage::new(
+                    Payload::from(line.as_bytes().to_vec()),
+                    hlc.new_timestamp(),
+                ))
+                .await
+                .unwrap();
+            sink.iteration().await.unwrap();
+        }
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "ghi\n");
+        assert_eq!(
+            std::fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+            "abc\ndef\n"
+        );
+
+        let read = |read, output: &str| {
+            let mut outputs = Outputs::new(hlc.clone());
+            let (out_tx, out_rx) = flume::unbounded();
+            outputs.insert(output.into(), out_tx);
+            (
+                FileReaderDescriptor {
+                    path: rotated_path(&path, 1),
+                    read,
+                    chunk_size: 3,
+                    output: output.into(),
+                },
+                outputs,
+                out_rx,
+            )
+        };
+
+        let (descriptor, outputs, out_rx) = read(ReadMode::Lines, "lines");
+        let source = FileSource::try_new(&"file-source".into(), &descriptor, outputs)
+            .await
+            .unwrap();
+        for expected in ["abc", "def"] {
+            source.iteration().await.unwrap();
+            let message = out_rx.try_recv().unwrap();
+            assert_eq!(
+                *message.payload().try_as_bytes().unwrap(),
+                expected.as_bytes()
+            );
+        }
+        // End of the file: the Source no longer sends anything.
+        source.iteration().await.unwrap();
+        assert!(out_rx.try_recv().is_err());
+
+        let (descriptor, outputs, out_rx) = read(ReadMode::Chunks, "chunks");
+        let source = FileSource::try_new(&"file-source".into(), &descriptor, outputs)
+            .await
+            .unwrap();
+        for expected in ["abc", "\nde", "f\n"] {
+            source.iteration().await.unwrap();
+            let message = out_rx.try_recv().unwrap();
+            assert_eq!(
+                *message.payload().try_as_bytes().unwrap(),
+                expected.as_bytes()
+            );
+        }
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(rotated_path(&path, 1)).unwrap();
+    }
+}
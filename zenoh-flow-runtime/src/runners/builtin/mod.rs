@@ -12,5 +12,12 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+pub(crate) mod expression;
+pub(crate) mod file;
+pub(crate) mod replay;
+pub(crate) mod routing;
+pub(crate) mod stdio;
+pub(crate) mod ticker;
+
 #[cfg(feature = "zenoh")]
 pub(crate) mod zenoh;
@@ -0,0 +1,197 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use async_std::sync::Mutex;
+use uhlc::NTP64;
+use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_descriptors::{Pacing, ReplayDescriptor};
+use zenoh_flow_nodes::prelude::{Node, OutputRaw, Outputs};
+
+use crate::recording::RecordingReader;
+
+/// The state of a replay: where it is in the recording and when it started.
+struct Replay {
+    reader: RecordingReader<async_std::fs::File>,
+    // The time of the first recorded message and the instant at which it was sent.
+    origin: Option<(NTP64, Instant)>,
+    finished: bool,
+}
+
+/// A `ReplaySource` sends, on its output, the messages of a [recording](crate::recording).
+pub(crate) struct ReplaySource {
+    output: OutputRaw,
+    pacing: Pacing,
+    replay: Mutex<Replay>,
+}
+
+impl ReplaySource {
+    pub(crate) async fn try_new(
+        id: &NodeId,
+        descriptor: &ReplayDescriptor,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let output = outputs
+            .take(descriptor.output.as_ref())
+            .context(format!(
+                r#"
+[built-in replay source: {}] Zenoh-Flow encountered a fatal internal error.
+No Output was created for port: < {} >.
+"#,
+                id, descriptor.output
+            ))?
+            .raw();
+
+        let reader = RecordingReader::try_open(&descriptor.path)
+            .await
+            .context(format!(
+                "[built-in replay source: {}] Failed to open the recording",
+                id
+            ))?;
+
+        Ok(Self {
+            output,
+            pacing: descriptor.pacing,
+            replay: Mutex::new(Replay {
+                reader,
+                origin: None,
+                finished: false,
+            }),
+        })
+    }
+
+    /// Returns how long to wait, after `started`, before sending a message recorded `elapsed` after the first one.
+    fn delay(&self, elapsed: Duration) -> Option<Duration> {
+        match self.pacing {
+            Pacing::Original => Some(elapsed),
+            Pacing::Fastest => None,
+            Pacing::Scaled(factor) => Some(elapsed.div_f64(factor)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for ReplaySource {
+    async fn iteration(&self) -> Result<()> {
+        let mut replay = self.replay.lock().await;
+        if replay.finished {
+            drop(replay);
+            futures::future::pending::<()>().await;
+            return Ok(());
+        }
+
+        let (time, message) = match replay.reader.next_entry().await {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                tracing::info!("Reached the end of the recording");
+                replay.finished = true;
+                return Ok(());
+            }
+            Err(e) => {
+                replay.finished = true;
+                return Err(e.context("Failed to read the recording, stopping the replay"));
+            }
+        };
+
+        let time = NTP64(time);
+        let (origin, started) = *replay.origin.get_or_insert((time, Instant::now()));
+        // NOTE: Messages recorded "before" the first one (e.g. produced on another runtime) are sent right away.
+        let elapsed = if time > origin {
+            (time - origin).to_duration()
+        } else {
+            Duration::ZERO
+        };
+
+        if let Some(delay) = self.delay(elapsed) {
+            let now = started.elapsed();
+            if delay > now {
+                async_std::task::sleep(delay - now).await;
+            }
+        }
+
+        self.output.forward(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use uhlc::HLC;
+    use zenoh_flow_descriptors::{Pacing, ReplayDescriptor};
+    use zenoh_flow_nodes::prelude::{LinkMessage, Node, Outputs, Payload};
+
+    use super::ReplaySource;
+    use crate::recording::{spawn_recorder, try_create_recording};
+
+    #[async_std::test]
+    async fn test_replay() {
+        let path = std::env::temp_dir().join(format!("zf-replay-{}.zfrec", uuid::Uuid::new_v4()));
+        let (tap_tx, tap_rx) = flume::unbounded();
+        let (tx, rx) = flume::unbounded();
+        spawn_recorder(try_create_recording(&path).unwrap(), tap_rx, tx);
+
+        let hlc = Arc::new(HLC::default());
+        for i in 0u8..3 {
+            tap_tx
+                .send_async(LinkMessage::new(
+                    Payload::from(vec![i]),
+                    hlc.new_timestamp(),
+                ))
+                .await
+                .unwrap();
+        }
+        drop(tap_tx);
+        while rx.recv_async().await.is_ok() {}
+
+        let mut outputs = Outputs::new(hlc);
+        let (out_tx, out_rx) = flume::unbounded();
+        outputs.insert("out".into(), out_tx);
+
+        let descriptor: ReplayDescriptor = serde_json::from_value(serde_json::json!({
+            "path": path,
+            "pacing": "fastest",
+        }))
+        .unwrap();
+        assert_eq!(descriptor.output.as_ref(), "out");
+        let replay = ReplaySource::try_new(&"replay".into(), &descriptor, outputs)
+            .await
+            .unwrap();
+        assert_eq!(replay.pacing, Pacing::Fastest);
+
+        for i in 0u8..3 {
+            replay.iteration().await.unwrap();
+            let message = out_rx.try_recv().unwrap();
+            assert_eq!(*message.payload().try_as_bytes().unwrap(), vec![i]);
+        }
+
+        // End of the recording: the Source no longer sends anything.
+        replay.iteration().await.unwrap();
+        assert!(
+            async_std::future::timeout(Duration::from_millis(50), replay.iteration())
+                .await
+                .is_err()
+        );
+        assert!(out_rx.try_recv().is_err());
+
+        assert!(serde_json::from_value::<ReplayDescriptor>(
+            serde_json::json!({ "path": path, "pacing": { "scaled": 0.0 } })
+        )
+        .is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,578 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use async_std::sync::Mutex;
+use futures::future::select_all;
+use zenoh_flow_commons::{NodeId, PortId, Result};
+use zenoh_flow_descriptors::{
+    MergeDescriptor, MergeOrder, SplitDescriptor, TeeDescriptor, TeeOutputDescriptor,
+    ThrottleDescriptor, ThrottlePolicy,
+};
+use zenoh_flow_nodes::prelude::{
+    InputRaw, Inputs, LinkMessage, Node, OutputRaw, Outputs, Timestamp,
+};
+
+use super::expression::attachment;
+
+fn take_input(kind: &str, id: &NodeId, inputs: &mut Inputs, port: &PortId) -> Result<InputRaw> {
+    Ok(inputs
+        .take(port.as_ref())
+        .context(format!(
+            r#"
+[built-in {}: {}] Zenoh-Flow encountered a fatal internal error.
+No Input was created for port: < {} >.
+"#,
+            kind, id, port
+        ))?
+        .raw())
+}
+
+fn take_output(kind: &str, id: &NodeId, outputs: &mut Outputs, port: &PortId) -> Result<OutputRaw> {
+    Ok(outputs
+        .take(port.as_ref())
+        .context(format!(
+            r#"
+[built-in {}: {}] Zenoh-Flow encountered a fatal internal error.
+No Output was created for port: < {} >.
+"#,
+            kind, id, port
+        ))?
+        .raw())
+}
+
+/// A message held by a [MergeOperator] ordering by timestamp.
+struct Held {
+    timestamp: Timestamp,
+    // Distinguishes, and keeps in order of arrival, messages with the same timestamp.
+    arrival: u64,
+    deadline: Instant,
+    message: LinkMessage,
+}
+
+impl PartialEq for Held {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Held {}
+
+impl PartialOrd for Held {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Held {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.timestamp, self.arrival).cmp(&(other.timestamp, other.arrival))
+    }
+}
+
+#[derive(Default)]
+struct MergeState {
+    // The input polled first, such that no input is starved when several are ready.
+    first: usize,
+    arrivals: u64,
+    held: BinaryHeap<Reverse<Held>>,
+}
+
+/// A `MergeOperator` forwards, on its output, the messages it receives on any of its inputs, see [MergeDescriptor].
+pub(crate) struct MergeOperator {
+    id: NodeId,
+    inputs: Vec<(PortId, InputRaw)>,
+    output: OutputRaw,
+    order: MergeOrder,
+    window: Duration,
+    state: Mutex<MergeState>,
+}
+
+impl MergeOperator {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &MergeDescriptor,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let inputs = descriptor
+            .inputs
+            .iter()
+            .map(|port| Ok((port.clone(), take_input("merge", id, &mut inputs, port)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: id.clone(),
+            inputs,
+            output: take_output("merge", id, &mut outputs, &descriptor.output)?,
+            order: descriptor.order,
+            window: Duration::from_micros(descriptor.window),
+            state: Mutex::new(MergeState::default()),
+        })
+    }
+
+    // Returns the first message received on any of the inputs, starting with the one at index `first`.
+    //
+    // NOTE: Receiving on an input is cancel safe: the messages of the inputs that were not selected are not lost.
+    async fn recv(&self, first: usize) -> Result<LinkMessage> {
+        let receptions = (0..self.inputs.len()).map(|index| {
+            let (port, input) = &self.inputs[(first + index) % self.inputs.len()];
+            Box::pin(async move { (port, input.recv().await) })
+        });
+
+        let ((port, message), _, _) = select_all(receptions).await;
+        message.context(format!(
+            "[built-in merge: {}][port: {}] Failed to receive a message",
+            self.id, port
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for MergeOperator {
+    // When ordering by timestamp, the iteration of a merge Operator either forwards the oldest message held, if its
+    // deadline has passed, or waits for a new message until that deadline.
+    async fn iteration(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        let first = state.first;
+        state.first = (first + 1) % self.inputs.len();
+
+        if self.order == MergeOrder::Arrival {
+            return self.output.forward(self.recv(first).await?).await;
+        }
+
+        let now = Instant::now();
+        let message = match state.held.peek().map(|Reverse(held)| held.deadline) {
+            Some(deadline) if deadline <= now => {
+                if let Some(Reverse(held)) = state.held.pop() {
+                    self.output.forward(held.message).await?;
+                }
+                return Ok(());
+            }
+            Some(deadline) => {
+                match async_std::future::timeout(deadline - now, self.recv(first)).await {
+                    Ok(message) => message?,
+                    // The deadline of the oldest message held has passed: it is forwarded in the next iteration.
+                    Err(_) => return Ok(()),
+                }
+            }
+            None => self.recv(first).await?,
+        };
+
+        state.arrivals += 1;
+        let arrival = state.arrivals;
+        state.held.push(Reverse(Held {
+            timestamp: *message.timestamp(),
+            arrival,
+            deadline: Instant::now() + self.window,
+            message,
+        }));
+
+        Ok(())
+    }
+}
+
+/// A `SplitOperator` routes the messages it receives to its outputs, see [SplitDescriptor].
+pub(crate) struct SplitOperator {
+    descriptor: SplitDescriptor,
+    input: InputRaw,
+    outputs: HashMap<PortId, OutputRaw>,
+}
+
+impl SplitOperator {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &SplitDescriptor,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let raw_outputs = descriptor
+            .outputs()
+            .into_iter()
+            .map(|port| {
+                let output = take_output("split", id, &mut outputs, &port)?;
+                Ok((port, output))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(Self {
+            descriptor: descriptor.clone(),
+            input: take_input("split", id, &mut inputs, &descriptor.input)?,
+            outputs: raw_outputs,
+        })
+    }
+
+    async fn forward(&self, port: &PortId, message: &LinkMessage) -> Result<()> {
+        match self.outputs.get(port) {
+            Some(output) => output.forward(message.clone()).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for SplitOperator {
+    async fn iteration(&self) -> Result<()> {
+        let message = self.input.recv().await?;
+        // NOTE: The payload is not necessarily JSON, in which case the routes can only use the attachment.
+        let value = serde_json::from_slice::<serde_json::Value>(&message.payload().try_as_bytes()?)
+            .unwrap_or_default();
+        let attachment = attachment(&message);
+
+        let mut sent: Vec<&PortId> = Vec::with_capacity(self.outputs.len());
+        for route in self.descriptor.routes.iter() {
+            if !route.when.is_satisfied_with_attachment(&value, &attachment) {
+                continue;
+            }
+
+            if !sent.contains(&&route.output) {
+                self.forward(&route.output, &message).await?;
+                sent.push(&route.output);
+            }
+
+            if self.descriptor.first_match {
+                break;
+            }
+        }
+
+        match &self.descriptor.otherwise {
+            Some(otherwise) if sent.is_empty() => self.forward(otherwise, &message).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A `TeeOperator` duplicates, on all its outputs, the messages it receives, see [TeeDescriptor].
+pub(crate) struct TeeOperator {
+    id: NodeId,
+    input: InputRaw,
+    outputs: Vec<(TeeOutputDescriptor, OutputRaw)>,
+}
+
+impl TeeOperator {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &TeeDescriptor,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let raw_outputs = descriptor
+            .outputs
+            .iter()
+            .map(|tee_output| {
+                let output = take_output("tee", id, &mut outputs, &tee_output.output)?;
+                Ok((tee_output.clone(), output))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: id.clone(),
+            input: take_input("tee", id, &mut inputs, &descriptor.input)?,
+            outputs: raw_outputs,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for TeeOperator {
+    async fn iteration(&self) -> Result<()> {
+        let message = self.input.recv().await?;
+
+        for (descriptor, output) in self.outputs.iter() {
+            if descriptor.lossy && output.pending() >= descriptor.max_pending {
+                tracing::trace!(
+                    "[built-in tee: {}][port: {}] The downstream node(s) are lagging, dropping the message",
+                    self.id,
+                    descriptor.output
+                );
+                continue;
+            }
+
+            output.forward(message.clone()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The token bucket of a [ThrottleOperator] and the message waiting for a token, if any.
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    // NOTE: The message is kept here while waiting for a token such that it is not lost if the Operator is aborted.
+    pending: Option<LinkMessage>,
+}
+
+/// A `ThrottleOperator` caps the rate at which the messages it receives are forwarded, see [ThrottleDescriptor].
+pub(crate) struct ThrottleOperator {
+    id: NodeId,
+    input: InputRaw,
+    output: OutputRaw,
+    capacity: f64,
+    // The number of tokens added to the bucket per second.
+    rate: f64,
+    policy: ThrottlePolicy,
+    bucket: Mutex<Bucket>,
+}
+
+impl ThrottleOperator {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &ThrottleDescriptor,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let capacity = f64::from(descriptor.max_messages);
+
+        Ok(Self {
+            id: id.clone(),
+            input: take_input("throttle", id, &mut inputs, &descriptor.input)?,
+            output: take_output("throttle", id, &mut outputs, &descriptor.output)?,
+            capacity,
+            rate: capacity / Duration::from_micros(descriptor.period).as_secs_f64(),
+            policy: descriptor.policy,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled: Instant::now(),
+                pending: None,
+            }),
+        })
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        bucket.tokens = (bucket.tokens
+            + now.duration_since(bucket.refilled).as_secs_f64() * self.rate)
+            .min(self.capacity);
+        bucket.refilled = now;
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for ThrottleOperator {
+    async fn iteration(&self) -> Result<()> {
+        let mut bucket = self.bucket.lock().await;
+        if bucket.pending.is_none() {
+            bucket.pending = Some(self.input.recv().await?);
+        }
+
+        self.refill(&mut bucket);
+        if bucket.tokens < 1.0 {
+            match self.policy {
+                ThrottlePolicy::Drop => {
+                    tracing::trace!(
+                        "[built-in throttle: {}] Rate limit reached, dropping the message",
+                        self.id
+                    );
+                    bucket.pending = None;
+                    return Ok(());
+                }
+                ThrottlePolicy::Delay => {
+                    let wait = (1.0 - bucket.tokens) / self.rate;
+                    async_std::task::sleep(Duration::from_secs_f64(wait)).await;
+                    self.refill(&mut bucket);
+                }
+            }
+        }
+
+        bucket.tokens -= 1.0;
+        match bucket.pending.take() {
+            Some(message) => self.output.forward(message).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use flume::{Receiver, Sender};
+    use serde_json::json;
+    use uhlc::HLC;
+    use zenoh_flow_nodes::prelude::{Inputs, LinkMessage, Node, Outputs, Payload};
+
+    use super::{MergeOperator, SplitOperator, TeeOperator, ThrottleOperator};
+
+    type Channels = (
+        Inputs,
+        Outputs,
+        Vec<Sender<LinkMessage>>,
+        Vec<Receiver<LinkMessage>>,
+    );
+
+    fn channels(hlc: &Arc<HLC>, inputs: &[&str], outputs: &[&str]) -> Channels {
+        let mut node_inputs = Inputs::default();
+        let senders = inputs
+            .iter()
+            .map(|port| {
+                let (tx, rx) = flume::unbounded();
+                node_inputs.insert((*port).into(), rx);
+                tx
+            })
+            .collect();
+
+        let mut node_outputs = Outputs::new(hlc.clone());
+        let receivers = outputs
+            .iter()
+            .map(|port| {
+                let (tx, rx) = flume::unbounded();
+                node_outputs.insert((*port).into(), tx);
+                rx
+            })
+            .collect();
+
+        (node_inputs, node_outputs, senders, receivers)
+    }
+
+    fn message(hlc: &HLC, value: serde_json::Value) -> LinkMessage {
+        LinkMessage::new(
+            Payload::from(serde_json::to_vec(&value).unwrap()),
+            hlc.new_timestamp(),
+        )
+    }
+
+    fn value(message: LinkMessage) -> serde_json::Value {
+        serde_json::from_slice(&message.payload().try_as_bytes().unwrap()).unwrap()
+    }
+
+    #[async_std::test]
+    async fn test_merge_operator_by_timestamp() {
+        let hlc = Arc::new(HLC::default());
+        let (inputs, outputs, senders, receivers) = channels(&hlc, &["a", "b"], &["out"]);
+        let descriptor = serde_yaml::from_str(
+            r#"
+inputs: [a, b]
+order: timestamp
+window: 20ms
+"#,
+        )
+        .unwrap();
+        let merge = MergeOperator::try_new(&"merge".into(), &descriptor, inputs, outputs).unwrap();
+
+        // The oldest message arrives last, on another input, but within the window.
+        let first = message(&hlc, json!(1));
+        let second = message(&hlc, json!(2));
+        senders[0].send_async(second).await.unwrap();
+        senders[1].send_async(first).await.unwrap();
+
+        merge.iteration().await.unwrap();
+        merge.iteration().await.unwrap();
+        assert!(receivers[0].is_empty());
+
+        async_std::task::sleep(Duration::from_millis(25)).await;
+        merge.iteration().await.unwrap();
+        merge.iteration().await.unwrap();
+        assert_eq!(value(receivers[0].try_recv().unwrap()), json!(1));
+        assert_eq!(value(receivers[0].try_recv().unwrap()), json!(2));
+    }
+
+    #[async_std::test]
+    async fn test_split_operator() {
+        let hlc = Arc::new(HLC::default());
+        let (inputs, outputs, senders, receivers) =
+            channels(&hlc, &["in"], &["hot", "warm", "other"]);
+        let descriptor = serde_yaml::from_str(
+            r#"
+routes:
+  - output: hot
+    when: $.temperature > 30
+  - output: warm
+    when: $.temperature > 20
+otherwise: other
+"#,
+        )
+        .unwrap();
+        let split = SplitOperator::try_new(&"split".into(), &descriptor, inputs, outputs).unwrap();
+
+        for temperature in [35, 25, 10] {
+            senders[0]
+                .send_async(message(&hlc, json!({ "temperature": temperature })))
+                .await
+                .unwrap();
+            split.iteration().await.unwrap();
+        }
+
+        let received = |receiver: &Receiver<LinkMessage>| {
+            receiver
+                .drain()
+                .map(|message| value(message)["temperature"].clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(received(&receivers[0]), vec![json!(35)]);
+        assert_eq!(received(&receivers[1]), vec![json!(35), json!(25)]);
+        assert_eq!(received(&receivers[2]), vec![json!(10)]);
+    }
+
+    #[async_std::test]
+    async fn test_tee_operator() {
+        let hlc = Arc::new(HLC::default());
+        let (inputs, outputs, senders, receivers) = channels(&hlc, &["in"], &["archive", "ui"]);
+        let descriptor = serde_yaml::from_str(
+            r#"
+outputs:
+  - archive
+  - output: ui
+    lossy: true
+    max-pending: 2
+"#,
+        )
+        .unwrap();
+        let tee = TeeOperator::try_new(&"tee".into(), &descriptor, inputs, outputs).unwrap();
+
+        for index in 0..3 {
+            senders[0]
+                .send_async(message(&hlc, json!(index)))
+                .await
+                .unwrap();
+            tee.iteration().await.unwrap();
+        }
+
+        assert_eq!(receivers[0].len(), 3);
+        assert_eq!(receivers[1].len(), 2);
+    }
+
+    #[async_std::test]
+    async fn test_throttle_operator() {
+        let hlc = Arc::new(HLC::default());
+        let (inputs, outputs, senders, receivers) = channels(&hlc, &["in"], &["out"]);
+        let descriptor = serde_yaml::from_str(
+            r#"
+max-messages: 2
+period: 1h
+"#,
+        )
+        .unwrap();
+        let throttle =
+            ThrottleOperator::try_new(&"throttle".into(), &descriptor, inputs, outputs).unwrap();
+
+        for index in 0..3 {
+            senders[0]
+                .send_async(message(&hlc, json!(index)))
+                .await
+                .unwrap();
+            throttle.iteration().await.unwrap();
+        }
+
+        assert_eq!(value(receivers[0].try_recv().unwrap()), json!(0));
+        assert_eq!(value(receivers[0].try_recv().unwrap()), json!(1));
+        assert!(receivers[0].is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use anyhow::{anyhow, Context as _};
+use async_std::{
+    io::{prelude::*, BufReader, Stdin},
+    sync::Mutex,
+};
+use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_descriptors::{StdinDescriptor, StdoutDescriptor};
+use zenoh_flow_nodes::prelude::{InputRaw, Inputs, Node, OutputRaw, Outputs};
+
+use super::file::trim_line_terminator;
+
+/// The state of the reading of the standard input.
+struct Reader {
+    reader: BufReader<Stdin>,
+    // NOTE: The bytes read are accumulated in the buffer such that nothing is lost if the Source is aborted in the
+    // middle of a line.
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+/// A `StdinSource` sends, on its output, each line it reads on the standard input.
+pub(crate) struct StdinSource {
+    id: NodeId,
+    output: OutputRaw,
+    reader: Mutex<Reader>,
+}
+
+impl StdinSource {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &StdinDescriptor,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let output = outputs
+            .take(descriptor.output.as_ref())
+            .context(format!(
+                r#"
+[built-in stdin source: {}] Zenoh-Flow encountered a fatal internal error.
+No Output was created for port: < {} >.
+"#,
+                id, descriptor.output
+            ))?
+            .raw();
+
+        Ok(Self {
+            id: id.clone(),
+            output,
+            reader: Mutex::new(Reader {
+                reader: BufReader::new(async_std::io::stdin()),
+                buffer: Vec::new(),
+                finished: false,
+            }),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for StdinSource {
+    async fn iteration(&self) -> Result<()> {
+        let mut reader = self.reader.lock().await;
+        if reader.finished {
+            drop(reader);
+            futures::future::pending::<()>().await;
+            return Ok(());
+        }
+
+        let Reader {
+            reader,
+            buffer,
+            finished,
+        } = &mut *reader;
+
+        if let Err(e) = reader.read_until(b'\n', buffer).await {
+            *finished = true;
+            return Err(anyhow!(e).context(format!(
+                "[built-in stdin source: {}] Failed to read the standard input, stopping",
+                self.id
+            )));
+        }
+
+        if buffer.is_empty() {
+            tracing::info!(
+                "[built-in stdin source: {}] The standard input was closed",
+                self.id
+            );
+            *finished = true;
+            return Ok(());
+        }
+
+        let mut line = std::mem::take(buffer);
+        trim_line_terminator(&mut line);
+
+        self.output.send(line, None).await
+    }
+}
+
+/// A `StdoutSink` writes, on the standard output, the messages it receives.
+pub(crate) struct StdoutSink {
+    input: InputRaw,
+    newline: bool,
+}
+
+impl StdoutSink {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &StdoutDescriptor,
+        mut inputs: Inputs,
+    ) -> Result<Self> {
+        let input = inputs
+            .take(descriptor.input.as_ref())
+            .context(format!(
+                r#"
+[built-in stdout sink: {}] Zenoh-Flow encountered a fatal internal error.
+No Input was created for port: < {} >.
+"#,
+                id, descriptor.input
+            ))?
+            .raw();
+
+        Ok(Self {
+            input,
+            newline: descriptor.newline,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for StdoutSink {
+    async fn iteration(&self) -> Result<()> {
+        let message = self.input.recv().await?;
+        let payload = message.payload().try_as_bytes()?;
+
+        // NOTE: The payload and the newline are written in a single call such that the messages of several Sinks
+        // writing on the standard output are not interleaved.
+        let mut buffer = Vec::with_capacity(payload.len() + 1);
+        buffer.extend_from_slice(&payload);
+        if self.newline {
+            buffer.push(b'\n');
+        }
+
+        let mut stdout = async_std::io::stdout();
+        stdout.write_all(&buffer).await?;
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
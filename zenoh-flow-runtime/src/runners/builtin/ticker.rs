@@ -0,0 +1,121 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use async_std::sync::Mutex;
+use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_descriptors::TickerDescriptor;
+use zenoh_flow_nodes::prelude::{Node, OutputRaw, Outputs};
+
+/// The next tick: its number and the instant at which it is due.
+struct Tick {
+    number: u64,
+    due: Instant,
+}
+
+/// A `TickerSource` sends, on its output, a message every `period`.
+pub(crate) struct TickerSource {
+    output: OutputRaw,
+    period: Duration,
+    tick: Mutex<Tick>,
+}
+
+impl TickerSource {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        descriptor: &TickerDescriptor,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let output = outputs
+            .take(descriptor.output.as_ref())
+            .context(format!(
+                r#"
+[built-in ticker source: {}] Zenoh-Flow encountered a fatal internal error.
+No Output was created for port: < {} >.
+"#,
+                id, descriptor.output
+            ))?
+            .raw();
+
+        Ok(Self {
+            output,
+            period: Duration::from_micros(descriptor.period),
+            tick: Mutex::new(Tick {
+                number: 0,
+                due: Instant::now(),
+            }),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for TickerSource {
+    async fn iteration(&self) -> Result<()> {
+        let mut tick = self.tick.lock().await;
+        async_std::task::sleep(tick.due.saturating_duration_since(Instant::now())).await;
+
+        self.output
+            .send(tick.number.to_le_bytes().to_vec(), None)
+            .await?;
+
+        // NOTE: If the Source fell behind, the next tick is sent right away instead of sending all the ones that were
+        // missed.
+        tick.number += 1;
+        tick.due = (tick.due + self.period).max(Instant::now());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uhlc::HLC;
+    use zenoh_flow_descriptors::TickerDescriptor;
+    use zenoh_flow_nodes::prelude::{Node, Outputs};
+
+    use super::TickerSource;
+
+    #[async_std::test]
+    async fn test_ticker() {
+        let mut outputs = Outputs::new(Arc::new(HLC::default()));
+        let (out_tx, out_rx) = flume::unbounded();
+        outputs.insert("tick".into(), out_tx);
+
+        let ticker = TickerSource::try_new(
+            &"ticker".into(),
+            &TickerDescriptor {
+                period: 10_000,
+                output: "tick".into(),
+            },
+            outputs,
+        )
+        .unwrap();
+
+        let start = std::time::Instant::now();
+        for number in 0u64..3 {
+            ticker.iteration().await.unwrap();
+            let message = out_rx.try_recv().unwrap();
+            assert_eq!(
+                *message.payload().try_as_bytes().unwrap(),
+                number.to_le_bytes()
+            );
+        }
+        // The first tick is sent right away, the two others after a period each.
+        assert!(start.elapsed() >= std::time::Duration::from_millis(20));
+    }
+}
@@ -12,5 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+pub(crate) mod querier;
+pub(crate) mod queryable;
 pub(crate) mod sink;
 pub(crate) mod source;
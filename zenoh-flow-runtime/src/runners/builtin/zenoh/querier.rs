@@ -0,0 +1,351 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context as ac};
+use async_std::sync::Mutex;
+use zenoh::{prelude::r#async::*, query::Reply, Session};
+use zenoh_flow_commons::{NodeId, PortId, Result};
+use zenoh_flow_descriptors::ZenohQuerierDescriptor;
+use zenoh_flow_nodes::prelude::{Node, OutputRaw, Outputs, SampleMetadata};
+
+// The delay after which a querier that queries once issues its query again, if it failed.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// A `ZenohQuerierSource` issues, once or periodically, queries on the selectors it was given and sends each reply
+/// received on the associated output.
+pub(crate) struct ZenohQuerierSource {
+    id: NodeId,
+    session: Arc<Session>,
+    queriers: HashMap<PortId, ZenohQuerierDescriptor>,
+    outputs: HashMap<PortId, OutputRaw>,
+    // The instant at which each querier is due. A querier that queries once is removed from the schedule after the
+    // replies to its query were sent.
+    schedule: Mutex<HashMap<PortId, Instant>>,
+}
+
+impl ZenohQuerierSource {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        session: Arc<Session>,
+        queriers: &HashMap<PortId, ZenohQuerierDescriptor>,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let mut raw_outputs = HashMap::with_capacity(queriers.len());
+        let now = Instant::now();
+        let mut schedule = HashMap::with_capacity(queriers.len());
+
+        for (port, querier) in queriers.iter() {
+            raw_outputs.insert(
+                port.clone(),
+                outputs
+                    .take(port.as_ref())
+                    .with_context(|| {
+                        format!(
+                            "{id}: fatal internal error: no channel was created for selector < {} >",
+                            querier.selector
+                        )
+                    })?
+                    .raw(),
+            );
+            schedule.insert(port.clone(), now);
+        }
+
+        Ok(Self {
+            id: id.clone(),
+            session,
+            queriers: queriers.clone(),
+            outputs: raw_outputs,
+            schedule: Mutex::new(schedule),
+        })
+    }
+
+    // Issues the query of the querier associated to `port` and collects its replies.
+    async fn query(&self, port: &PortId, querier: &ZenohQuerierDescriptor) -> Result<Vec<Reply>> {
+        let mut get = self.session.get(querier.selector.as_ref());
+        if let Some(timeout) = querier.timeout {
+            get = get.timeout(Duration::from_micros(timeout));
+        }
+
+        let replies = get.res().await.map_err(|e| {
+            anyhow!(
+                r#"[built-in zenoh querier: {}][port: {}] Failed to query < {} >
+Caused by:
+{:?}"#,
+                self.id,
+                port,
+                querier.selector,
+                e
+            )
+        })?;
+
+        let mut received = Vec::new();
+        while let Ok(reply) = replies.recv_async().await {
+            received.push(reply);
+        }
+
+        Ok(received)
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for ZenohQuerierSource {
+    // The iteration of a Zenoh querier Source waits until the next querier is due, issues its query and sends, one by
+    // one, the replies received on the associated output.
+    //
+    // A querier that queries once is removed from the schedule only once all the replies to its query were sent such
+    // that, if the Source is aborted before, the query is issued again when the Source is resumed. If its query failed,
+    // it is issued again after `RETRY_DELAY`.
+    //
+    // If the querier was configured to, the timestamp of a reply is used as the timestamp of the message.
+    async fn iteration(&self) -> Result<()> {
+        let mut schedule = self.schedule.lock().await;
+
+        let Some((port, due)) = schedule
+            .iter()
+            .min_by_key(|(_, due)| **due)
+            .map(|(port, due)| (port.clone(), *due))
+        else {
+            // All the queriers query once and they already did: there is nothing left to do.
+            drop(schedule);
+            return futures::future::pending().await;
+        };
+
+        async_std::task::sleep(due.saturating_duration_since(Instant::now())).await;
+
+        let querier = self.queriers.get(&port).ok_or_else(|| {
+            anyhow!(
+                "{}: internal error, unable to find querier < {} >",
+                self.id,
+                port
+            )
+        })?;
+        let replies = self.query(&port, querier).await;
+
+        match querier.period {
+            // NOTE: If the query took longer than the period, the next one is issued right away instead of issuing all
+            // the ones that were missed.
+            Some(period) => {
+                let next = due + Duration::from_micros(period);
+                schedule.insert(port.clone(), next.max(Instant::now()));
+            }
+            None if replies.is_err() => {
+                schedule.insert(port.clone(), Instant::now() + RETRY_DELAY);
+            }
+            None => {}
+        }
+        drop(schedule);
+
+        let output = self.outputs.get(&port).ok_or_else(|| {
+            anyhow!(
+                "{}: internal error, unable to find output < {} >",
+                self.id,
+                port
+            )
+        })?;
+
+        for reply in replies? {
+            match reply.sample {
+                Ok(sample) => {
+                    let timestamp = if querier.use_sample_timestamp {
+                        if sample.timestamp.is_none() {
+                            tracing::trace!(
+                                "reply received on {} has no timestamp, generating one instead",
+                                sample.key_expr
+                            );
+                        }
+                        sample
+                            .timestamp
+                            .map(|timestamp| timestamp.get_time().as_u64())
+                    } else {
+                        None
+                    };
+
+                    let metadata = SampleMetadata {
+                        key_expr: sample.key_expr.as_str().into(),
+                        encoding: sample.encoding.to_string().into(),
+                    };
+                    output
+                        .send_with_metadata(
+                            sample.payload.contiguous().to_vec(),
+                            timestamp,
+                            metadata,
+                        )
+                        .await?;
+                }
+                Err(value) => tracing::warn!(
+                    "[built-in zenoh querier: {}][port: {}] Received an error reply to the query on < {} >: {}",
+                    self.id,
+                    port,
+                    querier.selector,
+                    value
+                ),
+            }
+        }
+
+        if querier.period.is_none() {
+            self.schedule.lock().await.remove(&port);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    use uhlc::HLC;
+    use zenoh::{
+        prelude::r#async::*,
+        time::{Timestamp, TimestampId, NTP64},
+    };
+    use zenoh_flow_descriptors::ZenohQuerierDescriptor;
+    use zenoh_flow_nodes::prelude::{LinkMessage, Node, Outputs};
+
+    use super::ZenohQuerierSource;
+
+    fn querier_source(
+        session: Arc<Session>,
+        selector: &str,
+        use_sample_timestamp: bool,
+        tx: flume::Sender<LinkMessage>,
+    ) -> ZenohQuerierSource {
+        let mut outputs = Outputs::new(Arc::new(HLC::default()));
+        outputs.insert("out".into(), tx);
+        let descriptors = HashMap::from([(
+            "out".into(),
+            ZenohQuerierDescriptor {
+                selector: selector.into(),
+                period: None,
+                timeout: None,
+                use_sample_timestamp,
+            },
+        )]);
+
+        ZenohQuerierSource::try_new(&"querier".into(), session, &descriptors, outputs).unwrap()
+    }
+
+    // The timestamp of the replies of the queryable declared by `session_with_queryable`.
+    const REPLY_TIME: u64 = 42_000;
+
+    // Opens a Zenoh session and declares, on a unique key expression, a queryable that replies `42` to every query,
+    // with a timestamp whose time is `REPLY_TIME`.
+    async fn session_with_queryable() -> (Arc<Session>, OwnedKeyExpr) {
+        let session = zenoh::open(zenoh::config::peer())
+            .res()
+            .await
+            .unwrap()
+            .into_arc();
+        let key_expr =
+            OwnedKeyExpr::autocanonize(format!("zenoh-flow/test/{}", uuid::Uuid::new_v4()))
+                .unwrap();
+
+        let queryable = session.declare_queryable(&key_expr).res().await.unwrap();
+        let reply_key_expr = key_expr.clone();
+        async_std::task::spawn(async move {
+            while let Ok(query) = queryable.recv_async().await {
+                query
+                    .reply(Ok(Sample::new(reply_key_expr.clone(), vec![42u8])
+                        .with_timestamp(Timestamp::new(
+                            NTP64(REPLY_TIME),
+                            TimestampId::try_from([1u8].as_slice()).unwrap(),
+                        ))))
+                    .res()
+                    .await
+                    .unwrap();
+            }
+        });
+
+        (session, key_expr)
+    }
+
+    #[async_std::test]
+    async fn test_querier_once() {
+        let (session, key_expr) = session_with_queryable().await;
+
+        let (tx, rx) = flume::unbounded();
+        let source = querier_source(session, key_expr.as_str(), false, tx);
+        source.iteration().await.unwrap();
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(*message.payload().try_as_bytes().unwrap(), vec![42u8]);
+        assert_ne!(message.timestamp().get_time().as_u64(), REPLY_TIME);
+        assert!(source.schedule.lock().await.is_empty());
+
+        // The querier queried once: the next iteration never completes.
+        assert!(
+            async_std::future::timeout(Duration::from_millis(100), source.iteration())
+                .await
+                .is_err()
+        );
+    }
+
+    #[async_std::test]
+    async fn test_querier_sample_timestamp() {
+        let (session, key_expr) = session_with_queryable().await;
+
+        let (tx, rx) = flume::unbounded();
+        let source = querier_source(session, key_expr.as_str(), true, tx);
+        source.iteration().await.unwrap();
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.timestamp().get_time().as_u64(), REPLY_TIME);
+    }
+
+    #[async_std::test]
+    async fn test_querier_once_aborted() {
+        let (session, key_expr) = session_with_queryable().await;
+
+        // Nobody receives on a rendezvous channel: sending the reply blocks until the Source is aborted.
+        let (tx, rx) = flume::bounded(0);
+        let source = querier_source(session, key_expr.as_str(), false, tx);
+        assert!(
+            async_std::future::timeout(Duration::from_millis(500), source.iteration())
+                .await
+                .is_err()
+        );
+        assert!(source.schedule.lock().await.contains_key(&"out".into()));
+
+        // Once resumed, the query is issued again and its reply is delivered.
+        let (iteration, message) = futures::join!(source.iteration(), rx.recv_async());
+        iteration.unwrap();
+        assert_eq!(
+            *message.unwrap().payload().try_as_bytes().unwrap(),
+            vec![42u8]
+        );
+        assert!(source.schedule.lock().await.is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_querier_once_retried() {
+        let session = zenoh::open(zenoh::config::peer())
+            .res()
+            .await
+            .unwrap()
+            .into_arc();
+
+        // An invalid selector makes the query fail: the querier must stay in the schedule.
+        let (tx, rx) = flume::unbounded();
+        let source = querier_source(session, "zenoh-flow//test", false, tx);
+        assert!(source.iteration().await.is_err());
+        assert!(rx.is_empty());
+        assert!(source.schedule.lock().await.contains_key(&"out".into()));
+    }
+}
@@ -0,0 +1,304 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context};
+use async_std::sync::Mutex;
+use futures::{future::select_all, Future};
+use zenoh::{
+    prelude::r#async::*,
+    queryable::{Query, Queryable},
+    time::{TimestampId, NTP64},
+};
+use zenoh_flow_commons::{NodeId, PortId, Result};
+use zenoh_flow_descriptors::ZenohQueryableDescriptor;
+use zenoh_flow_nodes::prelude::{InputRaw, Inputs, LinkMessage, Node, Timestamp};
+
+/// Internal type of pending futures for the ZenohQueryableSink
+type ZFInputFut = Pin<Box<dyn Future<Output = (PortId, Result<LinkMessage>)> + Send + Sync>>;
+
+fn wait_flow_input(id: PortId, input: &InputRaw) -> ZFInputFut {
+    let input = input.clone();
+    Box::pin(async move { (id, input.recv().await) })
+}
+
+/// The last messages received on an input, the oldest first, shared with the callback of the associated queryable.
+type Cache = Arc<std::sync::Mutex<VecDeque<(Arc<Vec<u8>>, Timestamp)>>>;
+
+/// A `ZenohQueryableSink` keeps, for each of its inputs, the last messages it received and answers the queries made on
+/// the associated key expression with them.
+pub(crate) struct ZenohQueryableSink {
+    id: NodeId,
+    session: Arc<Session>,
+    descriptors: HashMap<PortId, ZenohQueryableDescriptor>,
+    inputs: HashMap<PortId, InputRaw>,
+    caches: HashMap<PortId, Cache>,
+    queryables: Mutex<HashMap<PortId, Queryable<'static, ()>>>,
+    futs: Mutex<Vec<ZFInputFut>>,
+}
+
+impl ZenohQueryableSink {
+    pub(crate) fn try_new(
+        id: &NodeId,
+        session: Arc<Session>,
+        descriptors: &HashMap<PortId, ZenohQueryableDescriptor>,
+        mut inputs: Inputs,
+    ) -> Result<Self> {
+        let mut raw_inputs = HashMap::with_capacity(descriptors.len());
+        let mut caches = HashMap::with_capacity(descriptors.len());
+
+        for (port, queryable) in descriptors.iter() {
+            raw_inputs.insert(
+                port.clone(),
+                inputs
+                    .take(port.as_ref())
+                    .context(format!(
+                        r#"
+[built-in zenoh queryable: {}][port: {}] Zenoh-Flow encountered a fatal internal error.
+No Input was created for port: < {1} > (key expression: {}).
+"#,
+                        id, port, queryable.key_expr,
+                    ))?
+                    .raw(),
+            );
+            caches.insert(
+                port.clone(),
+                Arc::new(std::sync::Mutex::new(VecDeque::with_capacity(
+                    queryable.history,
+                ))),
+            );
+        }
+
+        Ok(Self {
+            id: id.clone(),
+            session,
+            descriptors: descriptors.clone(),
+            inputs: raw_inputs,
+            caches,
+            queryables: Mutex::new(HashMap::with_capacity(descriptors.len())),
+            futs: Mutex::new(Vec::with_capacity(descriptors.len())),
+        })
+    }
+}
+
+// Answers the `query` with the content of the `cache`, the oldest message first.
+//
+// NOTE: Each message is a distinct reply, on the same key expression and with its own timestamp. Unless the query is
+// made with `ConsolidationMode::None`, Zenoh only delivers the most recent one to the querier.
+fn reply(id: &NodeId, key_expr: &OwnedKeyExpr, cache: &Cache, query: Query) {
+    // NOTE: The callback of a queryable is synchronous.
+    use zenoh::prelude::sync::SyncResolve;
+
+    let cached = cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+
+    for (payload, timestamp) in cached {
+        let mut sample = Sample::new(key_expr.clone(), payload.as_ref().clone());
+        match TimestampId::try_from(timestamp.get_id().to_le_bytes().as_slice()) {
+            Ok(timestamp_id) => {
+                sample = sample.with_timestamp(zenoh::time::Timestamp::new(
+                    NTP64(timestamp.get_time().as_u64()),
+                    timestamp_id,
+                ))
+            }
+            Err(e) => tracing::warn!(
+                "[built-in zenoh queryable: {}] Failed to convert the timestamp < {} >: {:?}",
+                id,
+                timestamp,
+                e
+            ),
+        }
+
+        if let Err(e) = query.reply(Ok(sample)).res_sync() {
+            tracing::error!(
+                "[built-in zenoh queryable: {}] Failed to reply to the query on < {} >: {:?}",
+                id,
+                key_expr,
+                e
+            );
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for ZenohQueryableSink {
+    // When we resume an aborted Zenoh queryable Sink, we have to re-declare the queryables and, possibly, recreate the
+    // futures awaiting messages.
+    async fn on_resume(&self) -> Result<()> {
+        let mut queryables = self.queryables.lock().await;
+        for (port, ZenohQueryableDescriptor { key_expr, .. }) in self.descriptors.iter() {
+            let id = self.id.clone();
+            let cache = self.caches[port].clone();
+            let reply_key_expr = key_expr.clone();
+
+            let queryable = self
+                .session
+                .declare_queryable(key_expr.clone())
+                .callback(move |query| reply(&id, &reply_key_expr, &cache, query))
+                .res()
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        r#"
+[built-in zenoh queryable: {}][port: {}] Zenoh-Flow encountered a fatal internal error.
+Zenoh failed to declare a queryable on < {} >.
+Caused by:
+
+{:?}"#,
+                        self.id,
+                        port,
+                        key_expr,
+                        e
+                    )
+                })?;
+
+            queryables.insert(port.clone(), queryable);
+        }
+
+        let mut futs = self.futs.lock().await;
+        if futs.is_empty() {
+            self.inputs
+                .iter()
+                .for_each(|(id, input)| futs.push(wait_flow_input(id.clone(), input)));
+        }
+
+        Ok(())
+    }
+
+    // When we abort a Zenoh queryable Sink we drop the queryables: queries made while the data flow is not running are
+    // not answered. The messages received are kept.
+    async fn on_abort(&self) {
+        self.queryables.lock().await.clear();
+    }
+
+    // The iteration of a Zenoh queryable Sink waits for a message on any of its inputs and adds it to the associated
+    // cache, evicting the oldest message if the cache is full.
+    async fn iteration(&self) -> Result<()> {
+        let mut futs = self.futs.lock().await;
+        let inputs = std::mem::take(&mut *futs);
+
+        let ((id, message), _index, mut remaining) = select_all(inputs).await;
+
+        match message {
+            Ok(message) => match message.payload().try_as_bytes() {
+                Ok(payload) => {
+                    let mut cache = self.caches[&id]
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    if cache.len() == self.descriptors[&id].history {
+                        cache.pop_front();
+                    }
+                    cache.push_back((payload, *message.timestamp()));
+                }
+                Err(e) => tracing::error!(
+                    "[built-in zenoh queryable: {}][port: {}] Failed to serialise the message: {e:?}",
+                    self.id,
+                    id
+                ),
+            },
+            Err(e) => tracing::error!(
+                "[built-in zenoh queryable: {}][port: {}] Channel returned an error: {e:?}",
+                self.id,
+                id
+            ),
+        }
+
+        remaining.push(wait_flow_input(id.clone(), &self.inputs[&id]));
+
+        // Set back the complete list for the next iteration
+        *futs = remaining;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use uhlc::HLC;
+    use zenoh::prelude::r#async::*;
+    use zenoh_flow_descriptors::ZenohQueryableDescriptor;
+    use zenoh_flow_nodes::prelude::{Inputs, LinkMessage, Node, Payload};
+
+    use super::ZenohQueryableSink;
+
+    #[async_std::test]
+    async fn test_queryable_history() {
+        let session = zenoh::open(zenoh::config::peer())
+            .res()
+            .await
+            .unwrap()
+            .into_arc();
+        let key_expr =
+            OwnedKeyExpr::autocanonize(format!("zenoh-flow/test/{}", uuid::Uuid::new_v4()))
+                .unwrap();
+
+        let (tx, rx) = flume::unbounded();
+        let mut inputs = Inputs::default();
+        inputs.insert("history".into(), rx);
+        let descriptors = HashMap::from([(
+            "history".into(),
+            ZenohQueryableDescriptor {
+                key_expr: key_expr.clone(),
+                history: 3,
+            },
+        )]);
+
+        let sink =
+            ZenohQueryableSink::try_new(&"queryable".into(), session.clone(), &descriptors, inputs)
+                .unwrap();
+        sink.on_resume().await.unwrap();
+
+        let hlc = Arc::new(HLC::default());
+        for i in 0u8..4 {
+            tx.send(LinkMessage::new(
+                Payload::from(vec![i]),
+                hlc.new_timestamp(),
+            ))
+            .unwrap();
+            sink.iteration().await.unwrap();
+        }
+
+        let payloads = |replies: Vec<zenoh::query::Reply>| {
+            replies
+                .into_iter()
+                .map(|reply| reply.sample.unwrap().value.payload.contiguous().to_vec())
+                .collect::<Vec<_>>()
+        };
+
+        // With the default settings, the replies are consolidated: only the most recent message is received.
+        let replies = session.get(&key_expr).res().await.unwrap();
+        assert_eq!(payloads(replies.into_iter().collect()), vec![vec![3]]);
+
+        // Without consolidation, the complete history is received, the oldest message first.
+        let replies = session
+            .get(&key_expr)
+            .consolidation(ConsolidationMode::None)
+            .res()
+            .await
+            .unwrap();
+        assert_eq!(
+            payloads(replies.into_iter().collect()),
+            vec![vec![1], vec![2], vec![3]]
+        );
+    }
+}
@@ -14,15 +14,17 @@
 
 use std::{collections::HashMap, pin::Pin, sync::Arc};
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, Context};
 use async_std::sync::Mutex;
 use futures::{future::select_all, Future};
 use zenoh::{prelude::r#async::*, publication::Publisher};
 #[cfg(feature = "shared-memory")]
 use zenoh_flow_commons::SharedMemoryConfiguration;
-use zenoh_flow_commons::{NodeId, PortId, Result};
+use zenoh_flow_commons::{NodeId, PortId, Reliability, Result};
+use zenoh_flow_descriptors::ZenohPublisherDescriptor;
 use zenoh_flow_nodes::prelude::{InputRaw, Inputs, LinkMessage, Node};
 
+use crate::runners::qos;
 #[cfg(feature = "shared-memory")]
 use crate::shared_memory::SharedMemory;
 
@@ -34,15 +36,24 @@ fn wait_flow_input(id: PortId, input: &InputRaw) -> ZFInputFut {
     Box::pin(async move { (id, input.recv().await) })
 }
 
-/// TODO
+/// A `ZenohSink` publishes, on Zenoh, the messages it receives on its inputs.
 pub(crate) struct ZenohSink<'a> {
     id: NodeId,
+    session: Arc<Session>,
     inputs: HashMap<PortId, InputRaw>,
-    publishers: HashMap<PortId, Publisher<'a>>,
-    key_exprs: HashMap<PortId, OwnedKeyExpr>,
+    publishers: HashMap<PortId, ZenohPublisher<'a>>,
     state: Arc<Mutex<State>>,
 }
 
+/// The publisher associated to an input.
+///
+/// If the key expression of the publisher is dynamic, i.e. suffixed for each message, no Zenoh publisher can be
+/// declared beforehand and the publications are made through the session.
+struct ZenohPublisher<'a> {
+    descriptor: ZenohPublisherDescriptor,
+    publisher: Option<Publisher<'a>>,
+}
+
 /// Structure grouping the fields that need interior mutability.
 struct State {
     pub(crate) futs: Vec<ZFInputFut>,
@@ -52,25 +63,39 @@ struct State {
 }
 
 impl<'a> ZenohSink<'a> {
-    pub(crate) fn get(&self, port: &PortId) -> (&OwnedKeyExpr, &InputRaw, &Publisher<'a>) {
-        let key_expr = self.key_exprs.get(port).unwrap();
-        let input_raw = self.inputs.get(port).unwrap();
+    fn get(&self, port: &PortId) -> (&ZenohPublisher<'a>, &InputRaw) {
         let publisher = self.publishers.get(port).unwrap();
+        let input_raw = self.inputs.get(port).unwrap();
 
-        (key_expr, input_raw, publisher)
+        (publisher, input_raw)
     }
 
     pub(crate) async fn try_new(
         id: NodeId,
         session: Arc<Session>,
-        key_exprs: &HashMap<PortId, OwnedKeyExpr>,
+        publishers_descriptors: &HashMap<PortId, ZenohPublisherDescriptor>,
         #[cfg(feature = "shared-memory")] shm_configuration: &SharedMemoryConfiguration,
         mut inputs: Inputs,
     ) -> Result<ZenohSink<'a>> {
-        let mut raw_inputs = HashMap::with_capacity(key_exprs.len());
-        let mut publishers = HashMap::with_capacity(key_exprs.len());
+        let mut raw_inputs = HashMap::with_capacity(publishers_descriptors.len());
+        let mut publishers = HashMap::with_capacity(publishers_descriptors.len());
+
+        for (port, descriptor) in publishers_descriptors.clone().into_iter() {
+            let ZenohPublisherDescriptor {
+                key_expr,
+                qos,
+                dynamic_suffix,
+                ..
+            } = &descriptor;
+
+            if qos.reliability != Reliability::default() {
+                tracing::warn!(
+                    "[built-in zenoh sink: {}][port: {}] The reliability is set by the subscribers, ignoring it",
+                    id,
+                    port
+                );
+            }
 
-        for (port, key_expr) in key_exprs.clone().into_iter() {
             raw_inputs.insert(
                 port.clone(),
                 inputs
@@ -85,26 +110,39 @@ No Input was created for port: < {1} > (key expression: {}).
                     .raw(),
             );
 
-            publishers.insert(
-                port.clone(),
-                session
-                    .declare_publisher(key_expr.clone())
-                    .res()
-                    .await
-                    .map_err(|e| {
-                        anyhow!(
-                            r#"
+            let publisher = if *dynamic_suffix {
+                None
+            } else {
+                Some(
+                    session
+                        .declare_publisher(key_expr.clone())
+                        .priority(qos::priority(qos))
+                        .congestion_control(qos::congestion_control(qos))
+                        .res()
+                        .await
+                        .map_err(|e| {
+                            anyhow!(
+                                r#"
 [built-in zenoh sink: {}][port: {}] Zenoh-Flow encountered a fatal internal error.
 Zenoh failed to declare a publisher on < {} >.
 Caused by:
 
 {:?}"#,
-                            id,
-                            port,
-                            key_expr,
-                            e
-                        )
-                    })?,
+                                id,
+                                port,
+                                key_expr,
+                                e
+                            )
+                        })?,
+                )
+            };
+
+            publishers.insert(
+                port,
+                ZenohPublisher {
+                    descriptor,
+                    publisher,
+                },
             );
         }
 
@@ -118,9 +156,9 @@ Caused by:
 
         Ok(Self {
             id,
+            session,
             inputs: raw_inputs,
             publishers,
-            key_exprs: key_exprs.clone(),
             state: Arc::new(Mutex::new(State {
                 #[cfg(feature = "shared-memory")]
                 shm,
@@ -131,6 +169,72 @@ Caused by:
     }
 }
 
+impl<'a> ZenohPublisher<'a> {
+    /// Returns the key expression on which the `message` should be published.
+    ///
+    /// # Errors
+    ///
+    /// If the key expression is dynamic, an error is returned if the message has no key suffix or if the suffixed key
+    /// expression is not valid.
+    fn key_expr(&self, message: &LinkMessage) -> Result<KeyExpr<'static>> {
+        let key_expr = &self.descriptor.key_expr;
+        if !self.descriptor.dynamic_suffix {
+            return Ok(KeyExpr::from(key_expr.clone()));
+        }
+
+        let Some(suffix) = message.key_suffix() else {
+            bail!(
+                "The message has no key suffix, it cannot be published on < {key_expr}/<suffix> >"
+            )
+        };
+
+        KeyExpr::autocanonize(format!("{key_expr}/{suffix}")).map_err(|e| {
+            anyhow!("The key suffix < {suffix} > does not form a valid key expression with < {key_expr} >:\n{e:?}")
+        })
+    }
+
+    /// Returns the encoding of the publication of the `message`: the encoding of the publisher, if any, otherwise the
+    /// encoding of the Zenoh sample the message was created from, if any.
+    fn encoding(&self, message: &LinkMessage) -> Option<Encoding> {
+        self.descriptor
+            .encoding
+            .as_deref()
+            .or_else(|| {
+                message
+                    .sample_metadata()
+                    .map(|metadata| metadata.encoding.as_ref())
+            })
+            .map(|encoding| Encoding::from(encoding.to_string()))
+    }
+
+    /// Publishes the `payload` of the `message`.
+    async fn put(
+        &self,
+        session: &Session,
+        message: &LinkMessage,
+        payload: impl Into<Value>,
+    ) -> Result<()> {
+        let mut value = payload.into();
+        if let Some(encoding) = self.encoding(message) {
+            value = value.encoding(encoding);
+        }
+
+        match &self.publisher {
+            Some(publisher) => publisher.put(value).res().await,
+            None => {
+                let qos = &self.descriptor.qos;
+                session
+                    .put(self.key_expr(message)?, value)
+                    .priority(qos::priority(qos))
+                    .congestion_control(qos::congestion_control(qos))
+                    .res()
+                    .await
+            }
+        }
+        .map_err(|e| anyhow!("{:?}", e))
+    }
+}
+
 #[async_trait::async_trait]
 impl<'a> Node for ZenohSink<'a> {
     // If the node is aborted / paused while waiting for inputs, the execution will be interrupted while
@@ -154,7 +258,8 @@ impl<'a> Node for ZenohSink<'a> {
 
         let ((id, message), _index, mut remaining) = select_all(inputs).await;
 
-        let (key_expr, input, publisher) = self.get(&id);
+        let (publisher, input) = self.get(&id);
+        let key_expr = &publisher.descriptor.key_expr;
 
         match message {
             Ok(data) => {
@@ -171,45 +276,59 @@ impl<'a> Node for ZenohSink<'a> {
                 //   memory),
                 // - the memory is full (is there a slow subscriber? some congestion on the network?).
                 #[cfg(feature = "shared-memory")]
-                {
-                    if let Err(e) = state
-                        .shm
-                        .try_send_payload(key_expr, data, &mut payload_buffer)
-                        .await
-                    {
-                        tracing::warn!(
-                            r#"
+                let result = match publisher.key_expr(&data) {
+                    Ok(publication_key_expr) => {
+                        let encoding = publisher.encoding(&data).unwrap_or_default();
+                        match state
+                            .shm
+                            .try_send_payload(
+                                &publication_key_expr,
+                                encoding,
+                                &data,
+                                &mut payload_buffer,
+                                qos::priority(&publisher.descriptor.qos),
+                                qos::congestion_control(&publisher.descriptor.qos),
+                            )
+                            .await
+                        {
+                            Ok(()) => Ok(()),
+                            Err(e) => {
+                                tracing::warn!(
+                                    r#"
 [built-in zenoh sink: {}][port: {}] Failed to send the data via Zenoh's shared memory.
 
 Caused by:
 {:?}
 "#,
-                            self.id,
-                            key_expr,
-                            e
-                        );
-                        tracing::warn!(
-                        "[built-in zenoh sink: {}][port: {}] Attempting to send via a non-shared memory channel.",
-                        self.id,
-                        key_expr
-                    );
+                                    self.id,
+                                    key_expr,
+                                    e
+                                );
+                                tracing::warn!(
+                                    "[built-in zenoh sink: {}][port: {}] Attempting to send via a non-shared memory channel.",
+                                    self.id,
+                                    key_expr
+                                );
 
-                        publisher
-                            .put(payload_buffer)
-                            .res()
-                            .await
-                            .map_err(|e| anyhow!("{:?}", e))?
+                                publisher.put(&self.session, &data, payload_buffer).await
+                            }
+                        }
                     }
-                }
+                    Err(e) => Err(e),
+                };
 
                 #[cfg(not(feature = "shared-memory"))]
-                {
-                    data.payload().try_as_bytes_into(&mut payload_buffer)?;
-                    publisher
-                        .put(payload_buffer)
-                        .res()
-                        .await
-                        .map_err(|e| anyhow!("{:?}", e))?
+                let result = match data.payload().try_as_bytes_into(&mut payload_buffer) {
+                    Ok(()) => publisher.put(&self.session, &data, payload_buffer).await,
+                    Err(e) => Err(e),
+                };
+
+                if let Err(e) = result {
+                    tracing::error!(
+                        "[built-in zenoh sink: {}][port: {}] Failed to publish the message: {e:?}",
+                        self.id,
+                        key_expr
+                    );
                 }
             }
             Err(e) => tracing::error!(
@@ -19,7 +19,8 @@ use async_std::sync::Mutex;
 use futures::{future::select_all, Future};
 use zenoh::{prelude::r#async::*, sample::Sample, subscriber::FlumeSubscriber, Session};
 use zenoh_flow_commons::{NodeId, PortId, Result};
-use zenoh_flow_nodes::prelude::{Node, OutputRaw, Outputs};
+use zenoh_flow_descriptors::ZenohSubscriberDescriptor;
+use zenoh_flow_nodes::prelude::{Node, OutputRaw, Outputs, SampleMetadata};
 
 /// Internal type of pending futures for the ZenohSource
 pub(crate) type ZSubFut = Pin<Box<dyn Future<Output = (PortId, Result<Sample>)> + Send + Sync>>;
@@ -33,7 +34,7 @@ pub(crate) struct ZenohSource<'a> {
     id: NodeId,
     session: Arc<Session>,
     outputs: HashMap<PortId, OutputRaw>,
-    key_exprs: HashMap<PortId, OwnedKeyExpr>,
+    subscribers_descriptors: HashMap<PortId, ZenohSubscriberDescriptor>,
     subscribers: Mutex<HashMap<PortId, FlumeSubscriber<'a>>>,
     futs: Arc<Mutex<Vec<ZSubFut>>>,
 }
@@ -42,12 +43,12 @@ impl<'a> ZenohSource<'a> {
     pub(crate) async fn try_new(
         id: &NodeId,
         session: Arc<Session>,
-        key_exprs: &HashMap<PortId, OwnedKeyExpr>,
+        subscribers_descriptors: &HashMap<PortId, ZenohSubscriberDescriptor>,
         mut outputs: Outputs,
     ) -> Result<ZenohSource<'a>> {
-        let mut raw_outputs = HashMap::with_capacity(key_exprs.len());
+        let mut raw_outputs = HashMap::with_capacity(subscribers_descriptors.len());
 
-        for (port, key_expr) in key_exprs.iter() {
+        for (port, ZenohSubscriberDescriptor { key_expr, .. }) in subscribers_descriptors.iter() {
             raw_outputs.insert(
                 port.clone(),
                 outputs
@@ -63,9 +64,11 @@ impl<'a> ZenohSource<'a> {
             id: id.clone(),
             session,
             outputs: raw_outputs,
-            key_exprs: key_exprs.clone(),
-            subscribers: Mutex::new(HashMap::with_capacity(key_exprs.len())),
-            futs: Arc::new(Mutex::new(Vec::with_capacity(key_exprs.len()))),
+            subscribers_descriptors: subscribers_descriptors.clone(),
+            subscribers: Mutex::new(HashMap::with_capacity(subscribers_descriptors.len())),
+            futs: Arc::new(Mutex::new(Vec::with_capacity(
+                subscribers_descriptors.len(),
+            ))),
         };
 
         Ok(zenoh_source)
@@ -81,7 +84,9 @@ impl<'a> Node for ZenohSource<'a> {
         let futures_were_empty = futures.is_empty();
 
         let mut subscribers = self.subscribers.lock().await;
-        for (port, key_expr) in self.key_exprs.iter() {
+        for (port, ZenohSubscriberDescriptor { key_expr, .. }) in
+            self.subscribers_descriptors.iter()
+        {
             let subscriber = self
                 .session
                 .declare_subscriber(key_expr)
@@ -121,7 +126,9 @@ Caused by:
     }
 
     // The iteration of a Zenoh Source polls, concurrently, the subscribers and forwards the first publication received
-    // on the associated port.
+    // on the associated port, along with the key expression and the encoding of the sample.
+    //
+    // If the subscriber was configured to, the timestamp of the sample is used as the timestamp of the message.
     async fn iteration(&self) -> Result<()> {
         let mut subscribers_futures = self.futs.lock().await;
         let subs = std::mem::take(&mut (*subscribers_futures));
@@ -131,14 +138,36 @@ Caused by:
         match result {
             Ok(sample) => {
                 let data = sample.payload.contiguous().to_vec();
-                let ke = sample.key_expr;
+                let ke = &sample.key_expr;
                 tracing::trace!("received subscription on {ke}");
                 let output = self.outputs.get(&id).ok_or(anyhow!(
                     "{}: internal error, unable to find output < {} >",
                     self.id,
                     id
                 ))?;
-                output.send(data, None).await?;
+
+                let use_sample_timestamp = self
+                    .subscribers_descriptors
+                    .get(&id)
+                    .is_some_and(|subscriber| subscriber.use_sample_timestamp);
+                let timestamp = if use_sample_timestamp {
+                    if sample.timestamp.is_none() {
+                        tracing::trace!(
+                            "sample received on {ke} has no timestamp, generating one instead"
+                        );
+                    }
+                    sample
+                        .timestamp
+                        .map(|timestamp| timestamp.get_time().as_u64())
+                } else {
+                    None
+                };
+
+                let metadata = SampleMetadata {
+                    key_expr: ke.as_str().into(),
+                    encoding: sample.encoding.to_string().into(),
+                };
+                output.send_with_metadata(data, timestamp, metadata).await?;
             }
             Err(e) => tracing::error!("subscriber for output {id} failed with: {e:?}"),
         }
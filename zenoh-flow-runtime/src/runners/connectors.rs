@@ -12,17 +12,23 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, Context};
 use async_std::sync::Mutex;
 use zenoh::{prelude::r#async::*, subscriber::FlumeSubscriber};
 #[cfg(feature = "shared-memory")]
 use zenoh_flow_commons::SharedMemoryConfiguration;
-use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_commons::{Compression, NodeId, Result};
 use zenoh_flow_nodes::prelude::{InputRaw, Inputs, LinkMessage, Node, OutputRaw, Outputs};
 use zenoh_flow_records::{ReceiverRecord, SenderRecord};
 
+use super::{
+    qos,
+    sequence::{ConnectorCounters, SequenceTracker},
+};
+use crate::wire::{self, Batch};
+
 #[cfg(feature = "shared-memory")]
 use crate::shared_memory::SharedMemory;
 
@@ -30,6 +36,12 @@ pub(crate) struct ZenohConnectorSender {
     id: NodeId,
     input: InputRaw,
     key_expr: OwnedKeyExpr,
+    priority: Priority,
+    congestion_control: CongestionControl,
+    compression: Compression,
+    // A random number drawn when the sender was created: the receiver relies on it to know that the numbering of the
+    // messages started over.
+    epoch: u64,
     session: Arc<Session>,
     state: Arc<Mutex<State>>,
 }
@@ -37,6 +49,9 @@ pub(crate) struct ZenohConnectorSender {
 struct State {
     pub(crate) payload_buffer: Vec<u8>,
     pub(crate) message_buffer: Vec<u8>,
+    pub(crate) batch: Option<Batch>,
+    // The sequence number of the next message sent.
+    pub(crate) sequence: u64,
     #[cfg(feature = "shared-memory")]
     pub(crate) shm: SharedMemory,
 }
@@ -57,114 +72,187 @@ impl ZenohConnectorSender {
         Ok(Self {
             input,
             key_expr: record.resource().clone(),
+            priority: qos::priority(record.qos()),
+            congestion_control: qos::congestion_control(record.qos()),
+            compression: record.compression(),
+            epoch: uuid::Uuid::new_v4().as_u64_pair().0,
             state: Arc::new(Mutex::new(State {
                 payload_buffer: Vec::new(),
                 message_buffer: Vec::new(),
+                batch: record.batching().copied().map(Batch::new),
+                sequence: 0,
                 #[cfg(feature = "shared-memory")]
-                shm: SharedMemory::new(&record.id, session.clone(), shm_config),
+                shm: SharedMemory::new(&record.id(), session.clone(), shm_config),
             })),
             id: record.id(),
             session,
         })
     }
+
+    async fn publish(&self, buffer: Vec<u8>) -> Result<()> {
+        self.session
+            .put(&self.key_expr, buffer)
+            .priority(self.priority)
+            .congestion_control(self.congestion_control)
+            .res()
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    r#"
+[connector sender (zenoh): {}][key expr: {}] Failed to send the message via a Zenoh publication.
+
+Caused by:
+{:?}
+"#,
+                    self.id,
+                    self.key_expr,
+                    e
+                )
+            })
+    }
+
+    async fn publish_batch(&self, batch: &mut Batch, sequence: &mut u64) -> Result<()> {
+        let mut buffer = Vec::new();
+        let len = batch.len();
+        batch.encode_into(self.compression, self.epoch, *sequence, &mut buffer)?;
+        *sequence += len;
+        self.publish(buffer).await
+    }
+
+    // When batching, a message is awaited only until the deadline of the batch (if it is not empty): once it is
+    // reached, the batch is published even though no message was received.
+    //
+    // NOTE: A batch is always published, it never goes through the shared memory.
+    async fn iteration_batched(
+        &self,
+        batch: &mut Batch,
+        sequence: &mut u64,
+        message_buffer: &mut Vec<u8>,
+        payload_buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        let received = match batch.deadline() {
+            Some(deadline) => async_std::future::timeout(
+                deadline.saturating_duration_since(Instant::now()),
+                self.input.recv(),
+            )
+            .await
+            .ok(),
+            None => Some(self.input.recv().await),
+        };
+
+        if let Some(message) = received {
+            let message = message.map_err(|e| self.log_channel_error(e))?;
+            batch.push(&message, message_buffer, payload_buffer)?;
+        }
+
+        if batch.is_ready() {
+            self.publish_batch(batch, sequence).await?;
+        }
+
+        Ok(())
+    }
+
+    fn log_channel_error(&self, e: anyhow::Error) -> anyhow::Error {
+        tracing::error!(
+            r#"
+[connector sender (zenoh): {}][key expr: {}] Internal channel returned the following error:
+{:?}
+"#,
+            self.id,
+            self.key_expr,
+            e
+        );
+        e
+    }
 }
 
 #[async_trait::async_trait]
 impl Node for ZenohConnectorSender {
     async fn iteration(&self) -> Result<()> {
-        match self.input.recv().await {
-            Ok(message) => {
-                let mut state = self.state.lock().await;
+        let mut state = self.state.lock().await;
+        let State {
+            batch,
+            sequence,
+            message_buffer,
+            payload_buffer,
+            ..
+        } = &mut *state;
+        if let Some(batch) = batch {
+            return self
+                .iteration_batched(batch, sequence, message_buffer, payload_buffer)
+                .await;
+        }
+
+        let message = self
+            .input
+            .recv()
+            .await
+            .map_err(|e| self.log_channel_error(e))?;
 
-                let mut message_buffer = std::mem::take(&mut state.message_buffer);
-                let mut payload_buffer = std::mem::take(&mut state.payload_buffer);
+        // NOTE: Whether it goes through the shared memory or not, a message is sent in an envelope: the receiver
+        // relies on it to decompress the message and to track its sequence number and epoch.
+        let mut buffer = Vec::with_capacity(message_buffer.capacity());
+        wire::encode_into(
+            &message,
+            self.compression,
+            self.epoch,
+            *sequence,
+            &mut buffer,
+            message_buffer,
+            payload_buffer,
+        )?;
+        *sequence += 1;
 
-                #[cfg(feature = "shared-memory")]
-                {
-                    if let Err(e) = state
-                        .shm
-                        .try_send_message(
-                            &self.key_expr,
-                            message,
-                            &mut message_buffer,
-                            &mut payload_buffer,
-                        )
-                        .await
-                    {
-                        tracing::warn!(
-                            r#"
+        #[cfg(feature = "shared-memory")]
+        {
+            match state
+                .shm
+                .try_send_envelope(
+                    &self.key_expr,
+                    &buffer,
+                    self.priority,
+                    self.congestion_control,
+                )
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    tracing::warn!(
+                        r#"
 [connector sender (zenoh): {}][key expr: {}] Failed to send the message via Zenoh's shared memory.
 
 Caused by:
 {:?}
 "#,
-                            self.id,
-                            self.key_expr,
-                            e
-                        );
-                        tracing::warn!(
+                        self.id,
+                        self.key_expr,
+                        e
+                    );
+                    tracing::warn!(
                         "[connector sender (zenoh): {}][key expr: {}] Attempting to send via a non-shared memory channel.",
                         self.id,
                         self.key_expr
                     );
-
-                        self.session
-                            .put(&self.key_expr, message_buffer)
-                            .res()
-                            .await
-                            .map_err(|e| {
-                                anyhow!(
-                                    r#"
-[connector sender (zenoh): {}][key expr: {}] Failed to send the message via a Zenoh publication.
-
-Caused by:
-{:?}
-"#,
-                                    self.id,
-                                    self.key_expr,
-                                    e
-                                )
-                            })?;
-                    }
                 }
-
-                #[cfg(not(feature = "shared-memory"))]
-                {
-                    message.serialize_bincode_into(&mut message_buffer, &mut payload_buffer)?;
-
-                    self.session
-                        .put(&self.key_expr, message_buffer)
-                        .res()
-                        .await
-                        .map_err(|e| {
-                            anyhow!(
-                                r#"
-[connector sender (zenoh): {}][key expr: {}] Failed to send the message via a Zenoh publication.
-
-Caused by:
-{:?}
-"#,
-                                self.id,
-                                self.key_expr,
-                                e
-                            )
-                        })?;
-                }
-
-                Ok(())
             }
+        }
 
-            Err(e) => {
+        self.publish(buffer).await
+    }
+
+    async fn on_abort(&self) {
+        let mut state = self.state.lock().await;
+        let State {
+            batch, sequence, ..
+        } = &mut *state;
+        if let Some(batch) = batch.as_mut().filter(|batch| !batch.is_empty()) {
+            if let Err(e) = self.publish_batch(batch, sequence).await {
                 tracing::error!(
-                    r#"
-[connector sender (zenoh): {}][key expr: {}] Internal channel returned the following error:
-{:?}
-"#,
+                    "[connector sender (zenoh): {}][key expr: {}] Failed to publish the last batch: {:?}",
                     self.id,
                     self.key_expr,
                     e
                 );
-                Err(e)
             }
         }
     }
@@ -175,6 +263,8 @@ pub(crate) struct ZenohConnectorReceiver {
     pub(crate) key_expr: OwnedKeyExpr,
     pub(crate) output_raw: OutputRaw,
     pub(crate) subscriber: FlumeSubscriber<'static>,
+    counters: Arc<ConnectorCounters>,
+    tracker: Mutex<SequenceTracker<LinkMessage>>,
 }
 
 impl ZenohConnectorReceiver {
@@ -192,6 +282,7 @@ impl ZenohConnectorReceiver {
 
         let subscriber = session
             .declare_subscriber(ke)
+            .reliability(qos::reliability(record.qos()))
             .res()
             .await
             // TODO@J-Loudet
@@ -203,13 +294,25 @@ impl ZenohConnectorReceiver {
             .ok_or_else(|| anyhow!(""))?
             .raw();
 
+        let counters = Arc::new(ConnectorCounters::default());
+
         Ok(Self {
             id: record.id(),
             key_expr: record.resource().clone(),
             output_raw,
             subscriber,
+            tracker: Mutex::new(SequenceTracker::new(
+                counters.clone(),
+                record.reorder_buffer(),
+            )),
+            counters,
         })
     }
+
+    /// Returns the counters of the messages received by this connector.
+    pub(crate) fn counters(&self) -> Arc<ConnectorCounters> {
+        self.counters.clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -217,9 +320,34 @@ impl Node for ZenohConnectorReceiver {
     async fn iteration(&self) -> Result<()> {
         match self.subscriber.recv_async().await {
             Ok(message) => {
-                let de: LinkMessage = bincode::deserialize(&message.value.payload.contiguous())?;
+                let decoded =
+                    wire::decode(&message.value.payload.contiguous()).context(format!(
+                        "[connector receiver (zenoh): {}][key expr: {}] Failed to decode a message",
+                        self.id, self.key_expr
+                    ))?;
+
+                // NOTE: Publications without sequence number come from a runtime predating them, they are forwarded
+                // as they arrive.
+                let messages = match decoded.sequence {
+                    Some(first) => {
+                        let mut tracker = self.tracker.lock().await;
+                        decoded
+                            .messages
+                            .into_iter()
+                            .zip(first..=u64::MAX)
+                            .flat_map(|(message, sequence)| {
+                                tracker.push(decoded.epoch, sequence, message)
+                            })
+                            .collect()
+                    }
+                    None => decoded.messages,
+                };
+
+                for message in messages {
+                    self.output_raw.forward(message).await?;
+                }
 
-                self.output_raw.forward(de).await
+                Ok(())
             }
 
             Err(e) => {
@@ -0,0 +1,258 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::{anyhow, bail, Context};
+use async_std::process::{Child, Command, Stdio};
+use async_trait::async_trait;
+use futures::future::{select, select_all, Either};
+use uhlc::HLC;
+use zenoh_flow_commons::{NodeId, PortId, Result};
+use zenoh_flow_nodes::prelude::{InputRaw, Inputs, LinkMessage, Node, OutputRaw, Outputs};
+
+use crate::isolation::{read_frame, write_frame, Frame, NodeSpec};
+
+/// An `IsolatedNode` is the counterpart, in the Zenoh-Flow runtime, of a node running in a child helper process.
+///
+/// It forwards the messages received on the inputs of the node to the child process and, conversely, the messages the
+/// node produces to its outputs.
+///
+/// If the child process exits, the next call to `iteration` returns an error describing the exit status and all
+/// subsequent calls never complete: the node is considered as failed.
+pub(crate) struct IsolatedNode {
+    id: NodeId,
+    inputs: Vec<InputRaw>,
+    frames: flume::Sender<Frame>,
+    exit: flume::Receiver<String>,
+    failed: AtomicBool,
+    // Dropping this channel kills the child process.
+    _kill: flume::Sender<()>,
+}
+
+impl IsolatedNode {
+    /// Spawns the helper process, located at `helper`, and asks it to load the node described by the [NodeSpec].
+    ///
+    /// The `hlc` of the runtime is updated with the timestamp of every message the node produces.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// - the helper process could not be spawned,
+    /// - the helper process failed to load the node,
+    /// - the communication with the helper process failed.
+    pub(crate) async fn try_new(
+        helper: &Path,
+        spec: NodeSpec,
+        hlc: Arc<HLC>,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let id = spec.node_id.clone();
+
+        let mut child = Command::new(helper)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .context(format!(
+                "[{}] Failed to spawn the helper process:\n{}",
+                id,
+                helper.display()
+            ))?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("[{}] Failed to access the standard input of the helper", id))?;
+        let mut stdout = child.stdout.take().ok_or_else(|| {
+            anyhow!(
+                "[{}] Failed to access the standard output of the helper",
+                id
+            )
+        })?;
+
+        write_frame(&mut stdin, &Frame::Spec(spec)).await?;
+        match read_frame(&mut stdout).await? {
+            Some(Frame::Ready) => {}
+            Some(Frame::Error(e)) => bail!("[{}] The helper failed to load the node:\n{}", id, e),
+            frame => bail!("[{}] Unexpected answer from the helper: {:?}", id, frame),
+        }
+
+        let input_ids = inputs.keys().cloned().collect::<Vec<_>>();
+        let inputs = input_ids
+            .into_iter()
+            .filter_map(|port| inputs.take(&*port).map(|input| input.raw()))
+            .collect::<Vec<_>>();
+
+        let output_ids = outputs.keys().cloned().collect::<Vec<_>>();
+        let outputs = output_ids
+            .into_iter()
+            .filter_map(|port| outputs.take(&*port).map(|output| (port, output.raw())))
+            .collect::<HashMap<_, _>>();
+
+        let (frames_tx, frames_rx) = flume::unbounded::<Frame>();
+        let node_id = id.clone();
+        async_std::task::spawn(async move {
+            while let Ok(frame) = frames_rx.recv_async().await {
+                if let Err(e) = write_frame(&mut stdin, &frame).await {
+                    tracing::error!("[{}] Failed to write to the helper: {:?}", node_id, e);
+                    return;
+                }
+            }
+        });
+
+        let node_id = id.clone();
+        async_std::task::spawn(async move {
+            if let Err(e) = forward_outputs(&mut stdout, &hlc, outputs).await {
+                tracing::error!("[{}] {:?}", node_id, e);
+            }
+        });
+
+        let (kill_tx, kill_rx) = flume::bounded::<()>(1);
+        let (exit_tx, exit_rx) = flume::bounded::<String>(1);
+        async_std::task::spawn(monitor(child, kill_rx, exit_tx));
+
+        Ok(Self {
+            id,
+            inputs,
+            frames: frames_tx,
+            exit: exit_rx,
+            failed: AtomicBool::new(false),
+            _kill: kill_tx,
+        })
+    }
+
+    /// Sends the frame to the helper process.
+    fn send(&self, frame: Frame) -> Result<()> {
+        self.frames.send(frame).map_err(|_| {
+            anyhow!(
+                "[{}] The connection to the helper process is closed",
+                self.id
+            )
+        })
+    }
+
+    /// Marks the node as failed, returning the error describing the exit of the helper.
+    fn fail(&self, reason: String) -> anyhow::Error {
+        self.failed.store(true, Ordering::Relaxed);
+        anyhow!("[{}] Isolated node failed: {}", self.id, reason)
+    }
+}
+
+#[async_trait]
+impl Node for IsolatedNode {
+    async fn on_resume(&self) -> Result<()> {
+        self.send(Frame::Start)
+    }
+
+    async fn on_abort(&self) {
+        if let Err(e) = self.send(Frame::Abort) {
+            tracing::error!("{:?}", e);
+        }
+    }
+
+    async fn iteration(&self) -> Result<()> {
+        if self.failed.load(Ordering::Relaxed) {
+            futures::future::pending::<()>().await;
+        }
+
+        let exit = self.exit.recv_async();
+        if self.inputs.is_empty() {
+            let reason = exit
+                .await
+                .unwrap_or_else(|_| "the helper process is gone".to_string());
+            return Err(self.fail(reason));
+        }
+
+        let recv = select_all(self.inputs.iter().map(|input| Box::pin(input.recv())));
+        match select(exit, recv).await {
+            Either::Left((reason, _)) => {
+                Err(self.fail(reason.unwrap_or_else(|_| "the helper process is gone".to_string())))
+            }
+            Either::Right(((message, index, _), _)) => {
+                let message = message?;
+                let mut message_buffer = Vec::new();
+                message.serialize_bincode_into(&mut message_buffer, &mut Vec::new())?;
+                self.send(Frame::Message {
+                    port: self.inputs[index].port_id().clone(),
+                    message: message_buffer,
+                })
+            }
+        }
+    }
+}
+
+/// Reads the frames sent by the helper process and forwards the messages on the corresponding outputs, after having
+/// updated the HLC of the runtime with their timestamp.
+async fn forward_outputs(
+    stdout: &mut async_std::process::ChildStdout,
+    hlc: &HLC,
+    outputs: HashMap<PortId, OutputRaw>,
+) -> Result<()> {
+    while let Some(frame) = read_frame(stdout).await? {
+        match frame {
+            Frame::Message { port, message } => {
+                let output = outputs.get(&port).ok_or_else(|| {
+                    anyhow!(
+                        "The helper sent a message on an unknown output < {} >",
+                        port
+                    )
+                })?;
+                let message = bincode::deserialize::<LinkMessage>(&message)
+                    .context("Failed to deserialise message")?;
+                if let Err(e) = hlc.update_with_timestamp(message.timestamp()) {
+                    tracing::warn!("Failed to update the HLC: {}", e);
+                }
+                output.forward(message).await?;
+            }
+            frame => bail!("Unexpected frame from the helper: {:?}", frame),
+        }
+    }
+
+    Ok(())
+}
+
+/// Waits for the child process to exit, reporting its exit status, or for the kill signal.
+async fn monitor(mut child: Child, kill: flume::Receiver<()>, exit: flume::Sender<String>) {
+    let status = {
+        let status = child.status();
+        futures::pin_mut!(status);
+        match select(status, kill.recv_async()).await {
+            Either::Left((status, _)) => Some(status),
+            Either::Right(_) => None,
+        }
+    };
+
+    let reason = match status {
+        Some(Ok(status)) => format!("the helper process exited ({status})"),
+        Some(Err(e)) => format!("failed to wait for the helper process: {e:?}"),
+        None => {
+            if let Err(e) = child.kill() {
+                tracing::error!("Failed to kill the helper process: {:?}", e);
+            }
+            return;
+        }
+    };
+
+    let _ = exit.send_async(reason).await;
+}
@@ -13,19 +13,35 @@
 //
 
 pub(crate) mod builtin;
+pub(crate) mod isolated;
 
 #[cfg(feature = "zenoh")]
 pub(crate) mod connectors;
+#[cfg(feature = "zenoh")]
+pub(crate) mod qos;
+#[cfg(feature = "zenoh")]
+pub(crate) mod sequence;
+
+#[cfg(feature = "wasm")]
+pub(crate) mod wasm;
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use async_std::task::JoinHandle;
 use libloading::Library;
 use tracing::Instrument;
-use zenoh_flow_commons::{NodeId, Result};
+use zenoh_flow_commons::{InstanceId, NodeId, Result};
 use zenoh_flow_nodes::prelude::Node;
 
+use crate::events::{EventsHub, InstanceEventKind};
+
+/// The minimum duration between two reports of the errors of the same node.
+const ERROR_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
 /// A `Runner` takes care of running a `Node`.
 ///
 /// Each Runner runs in a separate task.
@@ -39,6 +55,8 @@ pub(crate) struct Runner {
     // The `Option` exists because only user-implemented nodes have a `Library`. For example, built-in Zenoh Source /
     // Sink and the connectors have no `Library`.
     _library: Option<Arc<Library>>,
+    // Where the errors returned by `iteration` are reported, in addition to being logged.
+    events: Option<(EventsHub, InstanceId)>,
 }
 
 impl Runner {
@@ -55,9 +73,18 @@ impl Runner {
             node,
             handle: None,
             _library: library,
+            events: None,
         }
     }
 
+    /// Reports the errors returned by the `iteration` of the [Node] as [NodeError](InstanceEventKind::NodeError)
+    /// events of the provided instance.
+    ///
+    /// This only affects the next calls to `start`.
+    pub(crate) fn report_errors(&mut self, events: EventsHub, instance_id: InstanceId) {
+        self.events = Some((events, instance_id));
+    }
+
     /// Returns `true` if the Runner is running, i.e. if the `iteration` of the [Node] it wraps is being polled in a
     /// loop.
     pub(crate) fn is_running(&self) -> bool {
@@ -77,9 +104,9 @@ impl Runner {
             .await
             .with_context(|| format!("{}: call to `on_resume` failed", self.id))?;
 
-        let id = self.id.clone();
         let node = self.node.clone();
-        let iteration_span = tracing::trace_span!("iteration", node = %id);
+        let mut reporter = ErrorReporter::new(self.id.clone(), self.events.clone());
+        let iteration_span = tracing::trace_span!("iteration", node = %self.id);
 
         self.handle = Some(async_std::task::spawn(
             async move {
@@ -90,7 +117,7 @@ impl Runner {
                     iteration = node.iteration().await;
                     tracing::trace!("duration: {}µs", instant.elapsed().as_micros());
                     if let Err(e) = iteration {
-                        tracing::error!("{:?}", e);
+                        reporter.report(e);
                     }
 
                     async_std::task::yield_now().await;
@@ -121,3 +148,99 @@ impl Runner {
         }
     }
 }
+
+/// The `ErrorReporter` logs, and publishes as [NodeError](InstanceEventKind::NodeError) events, the errors returned by
+/// the `iteration` of a node.
+///
+/// A node failing persistently (e.g. a Source whose every call fails) would otherwise flood the logs and the
+/// subscribers to the events: an error is only reported if the previous report is older than [ERROR_REPORT_INTERVAL],
+/// the others are counted and the count is attached to the next report.
+struct ErrorReporter {
+    node: NodeId,
+    events: Option<(EventsHub, InstanceId)>,
+    last_report: Option<Instant>,
+    suppressed: u64,
+}
+
+impl ErrorReporter {
+    fn new(node: NodeId, events: Option<(EventsHub, InstanceId)>) -> Self {
+        Self {
+            node,
+            events,
+            last_report: None,
+            suppressed: 0,
+        }
+    }
+
+    fn report(&mut self, error: anyhow::Error) {
+        if self
+            .last_report
+            .is_some_and(|last_report| last_report.elapsed() < ERROR_REPORT_INTERVAL)
+        {
+            self.suppressed += 1;
+            return;
+        }
+
+        self.last_report = Some(Instant::now());
+        let suppressed = std::mem::take(&mut self.suppressed);
+        if suppressed > 0 {
+            tracing::error!(
+                "{:?}\n({} errors since the previous report)",
+                error,
+                suppressed
+            );
+        } else {
+            tracing::error!("{:?}", error);
+        }
+
+        if let Some((events, instance_id)) = &self.events {
+            events.publish(
+                instance_id,
+                InstanceEventKind::NodeError {
+                    node: self.node.clone(),
+                    error: format!("{error:?}"),
+                    suppressed,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::anyhow;
+    use uhlc::HLC;
+    use zenoh_flow_commons::{InstanceId, RuntimeId};
+
+    use super::{ErrorReporter, ERROR_REPORT_INTERVAL};
+    use crate::events::{EventsHub, InstanceEventKind};
+
+    #[test]
+    fn test_error_reporter() {
+        let hub = EventsHub::new(RuntimeId::rand(), Arc::new(HLC::default()));
+        let events = hub.subscribe();
+        let instance_id = InstanceId::from(uuid::Uuid::new_v4());
+        let mut reporter = ErrorReporter::new("node".into(), Some((hub, instance_id)));
+
+        // Only the first of the errors returned in a row is reported right away.
+        for i in 0..100 {
+            reporter.report(anyhow!("error {i}"));
+        }
+        let suppressed = |kind| match kind {
+            InstanceEventKind::NodeError { suppressed, .. } => suppressed,
+            kind => panic!("Unexpected event: {kind}"),
+        };
+        assert_eq!(suppressed(events.try_recv().unwrap().kind), 0);
+        assert!(events.try_recv().is_err());
+
+        // The next error reported carries the count of the ones that were not.
+        reporter.last_report = reporter
+            .last_report
+            .map(|last_report| last_report - ERROR_REPORT_INTERVAL);
+        reporter.report(anyhow!("error 100"));
+        assert_eq!(suppressed(events.try_recv().unwrap().kind), 99);
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Conversions from the [QoS] of Zenoh-Flow to the settings of Zenoh.
+//!
+//! With the version of Zenoh in use, the priority and the congestion control are set on the publications while the
+//! reliability is set on the subscribers.
+
+use zenoh::prelude::{CongestionControl, Priority, Reliability};
+use zenoh_flow_commons::{self as commons, QoS};
+
+pub(crate) fn priority(qos: &QoS) -> Priority {
+    match qos.priority {
+        commons::Priority::RealTime => Priority::RealTime,
+        commons::Priority::InteractiveHigh => Priority::InteractiveHigh,
+        commons::Priority::InteractiveLow => Priority::InteractiveLow,
+        commons::Priority::DataHigh => Priority::DataHigh,
+        commons::Priority::Data => Priority::Data,
+        commons::Priority::DataLow => Priority::DataLow,
+        commons::Priority::Background => Priority::Background,
+    }
+}
+
+pub(crate) fn congestion_control(qos: &QoS) -> CongestionControl {
+    match qos.congestion_control {
+        commons::CongestionControl::Drop => CongestionControl::Drop,
+        commons::CongestionControl::Block => CongestionControl::Block,
+    }
+}
+
+pub(crate) fn reliability(qos: &QoS) -> Reliability {
+    match qos.reliability {
+        commons::Reliability::BestEffort => Reliability::BestEffort,
+        commons::Reliability::Reliable => Reliability::Reliable,
+    }
+}
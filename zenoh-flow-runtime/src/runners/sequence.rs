@@ -0,0 +1,339 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of lost sequence numbers remembered, to recognise a message that arrives after it was declared
+/// lost.
+const MAX_MISSING: usize = 1024;
+
+/// The maximum number of previous epochs of the sender remembered, to recognise a message sent before it restarted.
+const MAX_RETIRED_EPOCHS: usize = 16;
+
+/// The `ConnectorMetrics` count, on the receiving end of a link between two runtimes, the messages that were received,
+/// lost, duplicated or reordered.
+///
+/// They are obtained through the [Runtime](crate::Runtime::try_get_connector_metrics()).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectorMetrics {
+    /// The number of messages received, including the duplicated ones.
+    pub received: u64,
+    /// The number of messages that were never received (so far).
+    pub lost: u64,
+    /// The number of messages received more than once. The copies are discarded.
+    pub duplicated: u64,
+    /// The number of messages received after a message that was sent after them.
+    pub reordered: u64,
+}
+
+/// The counters behind the [ConnectorMetrics], updated by a connector and read by the runtime.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectorCounters {
+    received: AtomicU64,
+    lost: AtomicU64,
+    duplicated: AtomicU64,
+    reordered: AtomicU64,
+}
+
+impl ConnectorCounters {
+    pub(crate) fn snapshot(&self) -> ConnectorMetrics {
+        ConnectorMetrics {
+            received: self.received.load(Relaxed),
+            lost: self.lost.load(Relaxed),
+            duplicated: self.duplicated.load(Relaxed),
+            reordered: self.reordered.load(Relaxed),
+        }
+    }
+}
+
+/// A `SequenceTracker` puts back in order the messages of a link, given their sequence number, and updates the
+/// counters of the link.
+///
+/// A message that arrives ahead of the next expected one is held in the reorder buffer, if it has a capacity, until the
+/// missing messages arrive or the buffer is full; the missing messages are then declared lost. Without reorder buffer,
+/// the messages are delivered as they arrive and a message arriving after it was declared lost is still delivered.
+///
+/// The numbering of the messages starts over with each epoch of the sender: a message of a new epoch indicates that the
+/// sender restarted, the messages still held are then delivered and the tracking starts over. A message of a previous
+/// epoch, sent before the restart, is delivered as it arrives.
+///
+/// NOTE: The epochs are random, they are not ordered. The previous epochs are thus remembered, up to
+///       [MAX_RETIRED_EPOCHS], such that a late message does not make the tracker go back to its epoch.
+///
+/// NOTE: The sequence numbers are read from the network. Once a message numbered `u64::MAX` is delivered, no message
+///       is expected any longer: like for the very first message received, the numbering is picked up where it is.
+pub(crate) struct SequenceTracker<T> {
+    counters: Arc<ConnectorCounters>,
+    capacity: usize,
+    epoch: Option<u64>,
+    retired_epochs: VecDeque<Option<u64>>,
+    expected: Option<u64>,
+    buffer: BTreeMap<u64, T>,
+    missing: BTreeSet<u64>,
+}
+
+impl<T> SequenceTracker<T> {
+    pub(crate) fn new(counters: Arc<ConnectorCounters>, capacity: usize) -> Self {
+        Self {
+            counters,
+            capacity,
+            epoch: None,
+            retired_epochs: VecDeque::default(),
+            expected: None,
+            buffer: BTreeMap::default(),
+            missing: BTreeSet::default(),
+        }
+    }
+
+    /// Tracks the `item` numbered `sequence` in the `epoch` of the sender, returning the items that can be delivered,
+    /// in order.
+    ///
+    /// Senders predating the epochs do not announce one (`None`), all their messages then belong to the same epoch.
+    pub(crate) fn push(&mut self, epoch: Option<u64>, sequence: u64, item: T) -> Vec<T> {
+        self.counters.received.fetch_add(1, Relaxed);
+
+        let mut ready = Vec::new();
+        if epoch != self.epoch {
+            if self.retired_epochs.contains(&epoch) {
+                tracing::debug!("Received a message sent before the sender restarted");
+                ready.push(item);
+                return ready;
+            }
+
+            // NOTE: If messages were already tracked, the numbering started over from 0. Otherwise, like for the very
+            // first message received, the numbering is picked up where it is.
+            if self.expected.is_some() {
+                tracing::debug!("The epoch of the sender changed, it was restarted");
+                self.expected = Some(0);
+                self.retired_epochs.push_back(self.epoch);
+                if self.retired_epochs.len() > MAX_RETIRED_EPOCHS {
+                    self.retired_epochs.pop_front();
+                }
+            }
+            ready.extend(std::mem::take(&mut self.buffer).into_values());
+            self.missing.clear();
+            self.epoch = epoch;
+        }
+
+        let expected = match self.expected {
+            Some(expected) => expected,
+            None => {
+                self.expected = sequence.checked_add(1);
+                ready.push(item);
+                return ready;
+            }
+        };
+
+        match sequence.cmp(&expected) {
+            Ordering::Equal => {
+                if !self.buffer.is_empty() {
+                    self.counters.reordered.fetch_add(1, Relaxed);
+                }
+                ready.push(item);
+                self.expected = expected.checked_add(1);
+            }
+            Ordering::Greater => {
+                if self.buffer.contains_key(&sequence) {
+                    self.counters.duplicated.fetch_add(1, Relaxed);
+                    return ready;
+                }
+
+                if self.capacity == 0 {
+                    self.declare_lost(expected, sequence);
+                    ready.push(item);
+                    self.expected = sequence.checked_add(1);
+                    return ready;
+                }
+
+                self.buffer.insert(sequence, item);
+                if self.buffer.len() <= self.capacity {
+                    return ready;
+                }
+
+                // The buffer is full: stop waiting for the missing messages.
+                let first = *self
+                    .buffer
+                    .keys()
+                    .next()
+                    .expect("the buffer cannot be empty");
+                self.declare_lost(expected, first);
+                self.expected = Some(first);
+            }
+            Ordering::Less => {
+                if self.missing.remove(&sequence) {
+                    self.counters.lost.fetch_sub(1, Relaxed);
+                    self.counters.reordered.fetch_add(1, Relaxed);
+                    ready.push(item);
+                } else {
+                    self.counters.duplicated.fetch_add(1, Relaxed);
+                }
+                return ready;
+            }
+        }
+
+        while let Some(expected) = self.expected {
+            match self.buffer.remove(&expected) {
+                Some(item) => {
+                    ready.push(item);
+                    self.expected = expected.checked_add(1);
+                }
+                None => break,
+            }
+        }
+
+        ready
+    }
+
+    fn declare_lost(&mut self, from: u64, to: u64) {
+        self.counters.lost.fetch_add(to - from, Relaxed);
+        // NOTE: There is no point in remembering more sequence numbers than `MAX_MISSING`.
+        for sequence in from.max(to.saturating_sub(MAX_MISSING as u64))..to {
+            self.missing.insert(sequence);
+        }
+        while self.missing.len() > MAX_MISSING {
+            self.missing.pop_first();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{ConnectorCounters, ConnectorMetrics, SequenceTracker};
+
+    #[test]
+    fn test_without_reorder_buffer() {
+        let counters = Arc::new(ConnectorCounters::default());
+        let mut tracker = SequenceTracker::new(counters.clone(), 0);
+
+        let mut delivered = Vec::new();
+        for sequence in [0, 1, 3, 4, 4, 2, 1, 6] {
+            delivered.extend(tracker.push(Some(1), sequence, sequence));
+        }
+
+        assert_eq!(delivered, vec![0, 1, 3, 4, 2, 6]);
+        assert_eq!(
+            counters.snapshot(),
+            ConnectorMetrics {
+                received: 8,
+                lost: 1,
+                duplicated: 2,
+                reordered: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_reorder_buffer() {
+        let counters = Arc::new(ConnectorCounters::default());
+        let mut tracker = SequenceTracker::new(counters.clone(), 2);
+
+        let mut delivered = Vec::new();
+        // 2 is reordered, 5 is lost once the buffer overflows.
+        for sequence in [0, 1, 3, 2, 4, 6, 7, 8, 9] {
+            delivered.extend(tracker.push(Some(1), sequence, sequence));
+        }
+
+        assert_eq!(delivered, vec![0, 1, 2, 3, 4, 6, 7, 8, 9]);
+        assert_eq!(
+            counters.snapshot(),
+            ConnectorMetrics {
+                received: 9,
+                lost: 1,
+                duplicated: 0,
+                reordered: 1,
+            }
+        );
+
+        // Within the same epoch, a sequence number of 0 is a duplicate.
+        assert!(tracker.push(Some(1), 0, 0).is_empty());
+
+        // The sender restarted: 11 is held until 10 arrives, a message sent before the restart is delivered as is.
+        assert!(tracker.push(Some(2), 1, 11).is_empty());
+        assert_eq!(tracker.push(Some(1), 5, 5), vec![5]);
+        assert_eq!(tracker.push(Some(2), 0, 10), vec![10, 11]);
+
+        // It restarted again before the messages of its previous epoch were delivered: they are not held any longer.
+        assert!(tracker.push(Some(2), 3, 13).is_empty());
+        assert_eq!(tracker.push(Some(3), 0, 20), vec![13, 20]);
+    }
+
+    #[test]
+    fn test_unordered_epochs() {
+        let counters = Arc::new(ConnectorCounters::default());
+        let mut tracker = SequenceTracker::new(counters.clone(), 2);
+
+        for sequence in 0..3 {
+            assert_eq!(tracker.push(Some(42), sequence, sequence), vec![sequence]);
+        }
+
+        // The sender restarted with a lesser epoch: it is adopted and the tracking keeps going.
+        assert_eq!(tracker.push(Some(7), 0, 10), vec![10]);
+        assert!(tracker.push(Some(7), 2, 12).is_empty());
+        // A late message of the previous epoch is delivered as is, the tracker stays on the new epoch.
+        assert_eq!(tracker.push(Some(42), 3, 3), vec![3]);
+        assert_eq!(tracker.push(Some(7), 1, 11), vec![11, 12]);
+        assert!(tracker.push(Some(7), 1, 11).is_empty());
+
+        assert_eq!(
+            counters.snapshot(),
+            ConnectorMetrics {
+                received: 8,
+                lost: 0,
+                duplicated: 1,
+                reordered: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_sequence_overflow() {
+        let counters = Arc::new(ConnectorCounters::default());
+        let mut tracker = SequenceTracker::new(counters.clone(), 2);
+
+        // The last sequence numbers are delivered, including when they are reordered.
+        assert_eq!(tracker.push(Some(1), u64::MAX - 2, 0), vec![0]);
+        assert!(tracker.push(Some(1), u64::MAX, 2).is_empty());
+        assert_eq!(tracker.push(Some(1), u64::MAX - 1, 1), vec![1, 2]);
+        // The numbering is then picked up where it is.
+        assert_eq!(tracker.push(Some(1), 5, 5), vec![5]);
+        assert_eq!(tracker.push(Some(1), 6, 6), vec![6]);
+
+        // Same when the very first message received is numbered `u64::MAX`.
+        let mut tracker = SequenceTracker::new(counters.clone(), 0);
+        assert_eq!(tracker.push(Some(1), u64::MAX, 0), vec![0]);
+        assert_eq!(tracker.push(Some(1), 0, 1), vec![1]);
+        assert_eq!(tracker.push(Some(1), 2, 2), vec![2]);
+
+        assert_eq!(
+            counters.snapshot(),
+            ConnectorMetrics {
+                received: 8,
+                lost: 1,
+                duplicated: 0,
+                reordered: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Support for nodes compiled to WebAssembly.
+//!
+//! A node whose `library` points to a file with the `.wasm` extension is not loaded as a shared library: its module is
+//! compiled and instantiated in a sandbox, without access to the file system, the network or any other resource than
+//! the ones explicitly provided by the host interface described below.
+//!
+//! # Host interface
+//!
+//! All the functions provided by the Zenoh-Flow runtime are imported from the `zenoh_flow` module. Strings (i.e. port
+//! identifiers and log messages) are UTF-8 encoded and passed as a pointer and a length in the linear memory of the
+//! module.
+//!
+//! - `recv(port_ptr: i32, port_len: i32) -> i64`: waits for a message on the input `port`. The payload is copied in a
+//!   buffer obtained through `zf_alloc`; the returned value contains the address of that buffer in its upper 32 bits
+//!   and its length in its lower 32 bits.
+//! - `send(port_ptr: i32, port_len: i32, data_ptr: i32, data_len: i32)`: sends the `data_len` bytes located at
+//!   `data_ptr` on the output `port`.
+//! - `log(level: i32, msg_ptr: i32, msg_len: i32)`: logs the message through the runtime. The level is: 0 → trace,
+//!   1 → debug, 2 → info, 3 → warn, any other value → error.
+//!
+//! Calling `recv` (resp. `send`) with a port that is not an input (resp. output) of the node traps.
+//!
+//! The module, in turn, must export:
+//!
+//! - `memory`: its linear memory,
+//! - `zf_alloc(len: i32) -> i32`: returns the address of a buffer, owned by the module, of at least `len` bytes in
+//!   which the runtime will write,
+//! - `zf_iteration() -> i32`: the equivalent of [Node::iteration], any value different than 0 indicating a failure,
+//! - `zf_new(config_ptr: i32, config_len: i32) -> i32` *(optional)*: called once, after the instantiation, with the
+//!   JSON representation of the `Configuration` of the node. Any value different than 0 indicates a failure.
+//!
+//! # Limits
+//!
+//! A module cannot monopolise the resources of the runtime:
+//!
+//! - each call to `zf_new` or `zf_iteration` can execute at most [FUEL_PER_CALL] instructions (the time spent waiting
+//!   in `recv` or `send` is not accounted for), after which it traps. While it executes, the module regularly yields
+//!   to the executor such that the other nodes are not starved.
+//! - its linear memory cannot grow beyond [MAX_MEMORY_SIZE] bytes: a `memory.grow` past that limit returns -1.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{anyhow, bail, Context};
+use async_std::sync::Mutex;
+use async_trait::async_trait;
+use url::Url;
+use wasmtime::{
+    Caller, Config, Engine, Extern, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+use zenoh_flow_commons::{Configuration, NodeId, PortId, Result};
+use zenoh_flow_nodes::prelude::{InputRaw, Inputs, Node, OutputRaw, Outputs};
+
+/// The name of the module from which the host functions are imported.
+const HOST_MODULE: &str = "zenoh_flow";
+
+/// The maximum number of instructions, or units of fuel, a call to `zf_new` or `zf_iteration` can execute.
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// The number of units of fuel consumed by a module between two yields to the executor.
+const FUEL_YIELD_INTERVAL: u64 = 100_000;
+
+/// The maximum size, in bytes, of the linear memory of a module.
+const MAX_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Returns `true` if the provided [Url] points to a WebAssembly module.
+pub(crate) fn is_wasm_module(url: &Url) -> bool {
+    url.scheme() == "file"
+        && Path::new(url.path())
+            .extension()
+            .is_some_and(|extension| extension == "wasm")
+}
+
+/// Creates the [Engine] used to compile and run all the WebAssembly nodes of a Zenoh-Flow runtime.
+///
+/// # Errors
+///
+/// This function will return an error if the configuration of the engine is not supported on the host.
+pub(crate) fn new_engine() -> Result<Engine> {
+    let mut config = Config::new();
+    // NOTE: Calls to `recv` and `send` are asynchronous in Zenoh-Flow, enabling the async support allows suspending
+    // the execution of the module while they are pending instead of blocking the executor thread.
+    config.async_support(true);
+    // NOTE: The fuel bounds the number of instructions executed by a call and allows yielding to the executor while a
+    // module is computing, see the `Limits` section of the module documentation.
+    config.consume_fuel(true);
+    Engine::new(&config).map_err(|e| anyhow!("Failed to create the WebAssembly engine:\n{e:?}"))
+}
+
+/// The state accessible to the host functions.
+struct HostState {
+    node_id: NodeId,
+    limits: StoreLimits,
+    inputs: HashMap<PortId, InputRaw>,
+    outputs: HashMap<PortId, OutputRaw>,
+}
+
+/// A `WasmNode` executes a node compiled to WebAssembly.
+pub(crate) struct WasmNode {
+    id: NodeId,
+    // The fuel available to each call to `zf_iteration`.
+    fuel: u64,
+    state: Mutex<(Store<HostState>, TypedFunc<(), i32>)>,
+}
+
+impl WasmNode {
+    /// Attempts to compile and instantiate the WebAssembly module located at `path`.
+    ///
+    /// See [try_new_from_bytes](WasmNode::try_new_from_bytes) for the possible errors.
+    pub(crate) async fn try_new(
+        engine: &Engine,
+        id: NodeId,
+        path: &Path,
+        configuration: &Configuration,
+        inputs: Inputs,
+        outputs: Outputs,
+    ) -> Result<Self> {
+        let bytes = async_std::fs::read(path).await.context(format!(
+            "Failed to read WebAssembly module:\n{}",
+            path.display()
+        ))?;
+
+        Self::try_new_from_bytes(engine, id, &bytes, configuration, inputs, outputs).await
+    }
+
+    /// Attempts to compile and instantiate the provided WebAssembly module.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// - the module could not be compiled,
+    /// - the module imports a function that is not part of the host interface,
+    /// - the module does not export the mandatory `memory`, `zf_alloc` or `zf_iteration`,
+    /// - the call to `zf_new` failed or returned a value different than 0.
+    pub(crate) async fn try_new_from_bytes(
+        engine: &Engine,
+        id: NodeId,
+        bytes: &[u8],
+        configuration: &Configuration,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        let module = Module::new(engine, bytes)
+            .map_err(|e| anyhow!("[{}] Failed to compile WebAssembly module:\n{e:?}", id))?;
+
+        let input_ids = inputs.keys().cloned().collect::<Vec<_>>();
+        let output_ids = outputs.keys().cloned().collect::<Vec<_>>();
+        let host_state = HostState {
+            node_id: id.clone(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MAX_MEMORY_SIZE)
+                .build(),
+            inputs: input_ids
+                .into_iter()
+                .filter_map(|port| inputs.take(&*port).map(|input| (port, input.raw())))
+                .collect(),
+            outputs: output_ids
+                .into_iter()
+                .filter_map(|port| outputs.take(&*port).map(|output| (port, output.raw())))
+                .collect(),
+        };
+
+        let mut store = Store::new(engine, host_state);
+        store.limiter(|host_state| &mut host_state.limits);
+        store
+            .fuel_async_yield_interval(Some(FUEL_YIELD_INTERVAL))
+            .map_err(|e| anyhow!("[{}] Failed to configure the fuel:\n{e:?}", id))?;
+        set_fuel(&mut store, FUEL_PER_CALL)?;
+        let linker = new_linker(engine)?;
+        let instance = linker
+            .instantiate_async(&mut store, &module)
+            .await
+            .map_err(|e| anyhow!("[{}] Failed to instantiate WebAssembly module:\n{e:?}", id))?;
+
+        if instance.get_memory(&mut store, "memory").is_none() {
+            bail!(
+                "[{}] The WebAssembly module does not export its `memory`",
+                id
+            );
+        }
+
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "zf_alloc")
+            .map_err(|e| anyhow!("[{}] Missing export `zf_alloc`:\n{e:?}", id))?;
+
+        let iteration = instance
+            .get_typed_func::<(), i32>(&mut store, "zf_iteration")
+            .map_err(|e| anyhow!("[{}] Missing export `zf_iteration`:\n{e:?}", id))?;
+
+        if let Ok(new) = instance.get_typed_func::<(i32, i32), i32>(&mut store, "zf_new") {
+            let configuration = configuration.to_string();
+            let len = i32::try_from(configuration.len())
+                .context(format!("[{}] Configuration is too large", id))?;
+            let ptr = alloc
+                .call_async(&mut store, len)
+                .await
+                .map_err(|e| anyhow!("[{}] Call to `zf_alloc` failed:\n{e:?}", id))?;
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .expect("Presence of the memory was checked above");
+            memory
+                .write(&mut store, ptr as usize, configuration.as_bytes())
+                .map_err(|e| anyhow!("[{}] Failed to write the configuration:\n{e:?}", id))?;
+
+            set_fuel(&mut store, FUEL_PER_CALL)?;
+            let code = new
+                .call_async(&mut store, (ptr, len))
+                .await
+                .map_err(|e| anyhow!("[{}] Call to `zf_new` failed:\n{e:?}", id))?;
+            if code != 0 {
+                bail!("[{}] Call to `zf_new` returned: {}", id, code);
+            }
+        }
+
+        Ok(Self {
+            id,
+            fuel: FUEL_PER_CALL,
+            state: Mutex::new((store, iteration)),
+        })
+    }
+}
+
+#[async_trait]
+impl Node for WasmNode {
+    async fn iteration(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        let (store, iteration) = &mut *state;
+        set_fuel(store, self.fuel)?;
+
+        let code = iteration
+            .call_async(store, ())
+            .await
+            .map_err(|e| anyhow!("[{}] Call to `zf_iteration` failed:\n{e:?}", self.id))?;
+        if code != 0 {
+            bail!("[{}] Call to `zf_iteration` returned: {}", self.id, code);
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets the fuel available to the next call made in the `store`.
+fn set_fuel(store: &mut Store<HostState>, fuel: u64) -> Result<()> {
+    store
+        .set_fuel(fuel)
+        .map_err(|e| anyhow!("Failed to set the fuel of the WebAssembly module:\n{e:?}"))
+}
+
+/// Returns the linear memory exported by the module.
+fn get_memory(caller: &mut Caller<'_, HostState>) -> anyhow::Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(memory)) => Ok(memory),
+        _ => bail!("The WebAssembly module does not export its `memory`"),
+    }
+}
+
+/// Copies `len` bytes located at `ptr` in the linear memory of the module.
+fn read_bytes(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> anyhow::Result<Vec<u8>> {
+    let memory = get_memory(caller)?;
+    let mut buffer = vec![0; usize::try_from(len).context("Negative length")?];
+    memory
+        .read(caller, ptr as usize, &mut buffer)
+        .map_err(|e| anyhow!("Out of bounds memory access:\n{e:?}"))?;
+    Ok(buffer)
+}
+
+/// Reads the UTF-8 encoded string of `len` bytes located at `ptr` in the linear memory of the module.
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> anyhow::Result<String> {
+    String::from_utf8(read_bytes(caller, ptr, len)?).context("String is not valid UTF-8")
+}
+
+/// Creates the [Linker] exposing the host interface.
+fn new_linker(engine: &Engine) -> Result<Linker<HostState>> {
+    let mut linker = Linker::new(engine);
+
+    linker
+        .func_wrap2_async(
+            HOST_MODULE,
+            "recv",
+            |mut caller: Caller<'_, HostState>, port_ptr: i32, port_len: i32| {
+                Box::new(async move {
+                    let port = read_string(&mut caller, port_ptr, port_len)?;
+                    let input = caller
+                        .data()
+                        .inputs
+                        .get(&PortId::from(port.as_str()))
+                        .cloned()
+                        .ok_or_else(|| anyhow!("No input named < {} >", port))?;
+
+                    let message = input.recv().await?;
+                    let payload = message.payload().try_as_bytes()?;
+                    let len = i32::try_from(payload.len()).context("Payload is too large")?;
+
+                    let alloc = match caller.get_export("zf_alloc") {
+                        Some(Extern::Func(alloc)) => alloc.typed::<i32, i32>(&caller)?,
+                        _ => bail!("The WebAssembly module does not export `zf_alloc`"),
+                    };
+                    let ptr = alloc.call_async(&mut caller, len).await?;
+                    get_memory(&mut caller)?
+                        .write(&mut caller, ptr as usize, &payload)
+                        .map_err(|e| anyhow!("Out of bounds memory access:\n{e:?}"))?;
+
+                    Ok(((ptr as u32 as i64) << 32) | (len as u32 as i64))
+                })
+            },
+        )
+        .map_err(|e| anyhow!("Failed to define `recv`:\n{e:?}"))?;
+
+    linker
+        .func_wrap4_async(
+            HOST_MODULE,
+            "send",
+            |mut caller: Caller<'_, HostState>,
+             port_ptr: i32,
+             port_len: i32,
+             data_ptr: i32,
+             data_len: i32| {
+                Box::new(async move {
+                    let port = read_string(&mut caller, port_ptr, port_len)?;
+                    let data = read_bytes(&mut caller, data_ptr, data_len)?;
+                    let output = caller
+                        .data()
+                        .outputs
+                        .get(&PortId::from(port.as_str()))
+                        .cloned()
+                        .ok_or_else(|| anyhow!("No output named < {} >", port))?;
+
+                    output.send(data, None).await
+                })
+            },
+        )
+        .map_err(|e| anyhow!("Failed to define `send`:\n{e:?}"))?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "log",
+            |mut caller: Caller<'_, HostState>, level: i32, msg_ptr: i32, msg_len: i32| {
+                let message = read_string(&mut caller, msg_ptr, msg_len)?;
+                let node_id = &caller.data().node_id;
+                match level {
+                    0 => tracing::trace!("[{}] {}", node_id, message),
+                    1 => tracing::debug!("[{}] {}", node_id, message),
+                    2 => tracing::info!("[{}] {}", node_id, message),
+                    3 => tracing::warn!("[{}] {}", node_id, message),
+                    _ => tracing::error!("[{}] {}", node_id, message),
+                }
+                Ok(())
+            },
+        )
+        .map_err(|e| anyhow!("Failed to define `log`:\n{e:?}"))?;
+
+    Ok(linker)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uhlc::HLC;
+    use zenoh_flow_commons::Configuration;
+    use zenoh_flow_nodes::prelude::{Inputs, LinkMessage, Node, Outputs, Payload};
+
+    use super::{new_engine, WasmNode, MAX_MEMORY_SIZE};
+
+    // Forwards, on its output `out`, every message received on its input `in` and checks that its configuration is
+    // `{"answer":42}`.
+    const FORWARD: &str = r#"
+(module
+  (import "zenoh_flow" "recv" (func $recv (param i32 i32) (result i64)))
+  (import "zenoh_flow" "send" (func $send (param i32 i32 i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "in")
+  (data (i32.const 8) "out")
+  (data (i32.const 16) "{\"answer\":42}")
+
+  (func (export "zf_alloc") (param i32) (result i32)
+    (i32.const 1024))
+
+  (func (export "zf_new") (param $ptr i32) (param $len i32) (result i32)
+    (if (i32.ne (local.get $len) (i32.const 13))
+      (then (return (i32.const 1))))
+    (if (i64.ne (i64.load (local.get $ptr)) (i64.load (i32.const 16)))
+      (then (return (i32.const 2))))
+    (i32.const 0))
+
+  (func (export "zf_iteration") (result i32)
+    (local $msg i64)
+    (local.set $msg (call $recv (i32.const 0) (i32.const 2)))
+    (call $send
+      (i32.const 8) (i32.const 3)
+      (i32.wrap_i64 (i64.shr_u (local.get $msg) (i64.const 32)))
+      (i32.wrap_i64 (local.get $msg)))
+    (i32.const 0)))
+"#;
+
+    // Loops forever on its first iteration and counts up to 50,000 on the following ones.
+    const LOOP_ONCE: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $calls (mut i32) (i32.const 0))
+  (func (export "zf_alloc") (param i32) (result i32)
+    (i32.const 0))
+  (func (export "zf_iteration") (result i32)
+    (local $i i32)
+    (global.set $calls (i32.add (global.get $calls) (i32.const 1)))
+    (if (i32.eq (global.get $calls) (i32.const 1))
+      (then
+        (loop $forever
+          (br $forever))))
+    (loop $count
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br_if $count (i32.lt_u (local.get $i) (i32.const 50000))))
+    (i32.const 0)))
+"#;
+
+    // Succeeds if its memory can grow by one page but not beyond the limit (1024 pages of 64KiB).
+    const GROW: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "zf_alloc") (param i32) (result i32)
+    (i32.const 0))
+  (func (export "zf_iteration") (result i32)
+    (if (i32.eq (memory.grow (i32.const 1)) (i32.const -1))
+      (then (return (i32.const 1))))
+    (if (i32.ne (memory.grow (i32.const 1024)) (i32.const -1))
+      (then (return (i32.const 2))))
+    (i32.const 0)))
+"#;
+
+    async fn try_new_node(module: &str) -> WasmNode {
+        let module = wat::parse_str(module).unwrap();
+        WasmNode::try_new_from_bytes(
+            &new_engine().unwrap(),
+            "wasm-limits".into(),
+            &module,
+            &Configuration::default(),
+            Inputs::default(),
+            Outputs::new(Arc::new(HLC::default())),
+        )
+        .await
+        .expect("Failed to instantiate WebAssembly node")
+    }
+
+    #[async_std::test]
+    async fn test_wasm_fuel() {
+        let mut node = try_new_node(LOOP_ONCE).await;
+        node.fuel = 1_000_000;
+
+        let error = node.iteration().await.unwrap_err();
+        assert!(format!("{error:?}").contains("fuel"));
+        // The first call consumed all its fuel: the second one only completes if it is given a new budget.
+        node.iteration()
+            .await
+            .expect("The budget was not renewed after a fuel trap");
+
+        // Counting up to 50,000 does not fit in a smaller budget.
+        node.fuel = 10_000;
+        let error = node.iteration().await.unwrap_err();
+        assert!(format!("{error:?}").contains("fuel"));
+    }
+
+    #[async_std::test]
+    async fn test_wasm_memory_limit() {
+        assert_eq!(MAX_MEMORY_SIZE, 1024 * 64 * 1024);
+        let node = try_new_node(GROW).await;
+        node.iteration().await.expect("Iteration failed");
+    }
+
+    #[async_std::test]
+    async fn test_wasm_forward() {
+        let hlc = Arc::new(HLC::default());
+        let engine = new_engine().unwrap();
+        let module = wat::parse_str(FORWARD).unwrap();
+
+        let (tx_in, rx_in) = flume::unbounded();
+        let mut inputs = Inputs::default();
+        inputs.insert("in".into(), rx_in);
+
+        let (tx_out, rx_out) = flume::unbounded();
+        let mut outputs = Outputs::new(hlc.clone());
+        outputs.insert("out".into(), tx_out);
+
+        let configuration = Configuration::from(serde_json::json!({ "answer": 42 }));
+        let node = WasmNode::try_new_from_bytes(
+            &engine,
+            "wasm-forward".into(),
+            &module,
+            &configuration,
+            inputs,
+            outputs,
+        )
+        .await
+        .expect("Failed to instantiate WebAssembly node");
+
+        tx_in
+            .send(LinkMessage::new(
+                Payload::from(b"zenoh-flow".to_vec()),
+                hlc.new_timestamp(),
+            ))
+            .unwrap();
+
+        node.iteration().await.expect("Iteration failed");
+
+        let message = rx_out.try_recv().expect("No message was forwarded");
+        assert_eq!(
+            message.payload().try_as_bytes().unwrap().as_slice(),
+            b"zenoh-flow"
+        );
+
+        assert!(WasmNode::try_new_from_bytes(
+            &engine,
+            "wasm-forward".into(),
+            &module,
+            &Configuration::default(),
+            Inputs::default(),
+            Outputs::new(hlc),
+        )
+        .await
+        .is_err());
+    }
+}
@@ -21,8 +21,12 @@ use zenoh::prelude::r#async::*;
 #[cfg(feature = "shared-memory")]
 use zenoh_flow_commons::SharedMemoryConfiguration;
 use zenoh_flow_commons::{Result, RuntimeId};
+use zenoh_flow_nodes::{OperatorFn, SinkFn, SourceFn};
 
-use crate::{loader::Loader, Extensions, Runtime};
+#[cfg(feature = "zenoh")]
+use super::RuntimeLossPolicy;
+use super::{persistence::StateStore, RecoveryPolicy};
+use crate::{events::EventsHub, loader::Loader, Extensions, Runtime};
 
 /// Builder structure to help create a [Runtime].
 ///
@@ -35,9 +39,14 @@ pub struct RuntimeBuilder {
     runtime_id: Option<RuntimeId>,
     #[cfg(feature = "zenoh")]
     session: Option<Arc<Session>>,
+    #[cfg(feature = "zenoh")]
+    runtime_loss_policy: RuntimeLossPolicy,
     #[cfg(feature = "shared-memory")]
     shared_memory: Option<SharedMemoryConfiguration>,
     loader: Loader,
+    isolation_helper: Option<PathBuf>,
+    state_directory: Option<PathBuf>,
+    recovery_policy: RecoveryPolicy,
 }
 
 impl RuntimeBuilder {
@@ -49,7 +58,14 @@ impl RuntimeBuilder {
             runtime_id: None,
             #[cfg(feature = "zenoh")]
             session: None,
+            #[cfg(feature = "zenoh")]
+            runtime_loss_policy: RuntimeLossPolicy::default(),
+            #[cfg(feature = "shared-memory")]
+            shared_memory: None,
             loader: Loader::default(),
+            isolation_helper: None,
+            state_directory: None,
+            recovery_policy: RecoveryPolicy::default(),
         }
     }
 
@@ -86,6 +102,9 @@ impl RuntimeBuilder {
         Ok(self)
     }
 
+    /// Sets how the links between runtimes, and the built-in Zenoh Sinks, use Zenoh's shared memory.
+    ///
+    /// If it is not set, the [default](SharedMemoryConfiguration::default()) configuration is used.
     #[cfg(feature = "shared-memory")]
     pub fn shared_memory(mut self, shm: SharedMemoryConfiguration) -> Self {
         self.shared_memory = Some(shm);
@@ -118,6 +137,25 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Sets what the Runtime does with a running data flow instance when another runtime involved in it is lost.
+    /// Defaults to [KeepRunning](RuntimeLossPolicy::KeepRunning).
+    ///
+    /// The runtimes involved in an instance watch each other through Zenoh's liveliness. A runtime deleting the
+    /// instance is not considered as lost.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zenoh_flow_runtime::{Runtime, RuntimeLossPolicy};
+    ///
+    /// let builder = Runtime::builder("demo").runtime_loss_policy(RuntimeLossPolicy::Abort);
+    /// ```
+    #[cfg(feature = "zenoh")]
+    pub fn runtime_loss_policy(mut self, policy: RuntimeLossPolicy) -> Self {
+        self.runtime_loss_policy = policy;
+        self
+    }
+
     /// Forces the hybrid logical clock the Runtime should use.
     ///
     /// # Example
@@ -210,12 +248,142 @@ impl RuntimeBuilder {
         Ok(self)
     }
 
+    /// Forces the location of the helper executable in which the nodes declared with `isolation: process` are loaded.
+    ///
+    /// By default, the helper is looked for in the directory of the current executable and then in the `PATH`. See
+    /// the [isolation](crate::isolation) module for more details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zenoh_flow_runtime::Runtime;
+    ///
+    /// let builder = Runtime::builder("demo").isolation_helper("/usr/local/bin/zenoh-flow-node-host");
+    /// ```
+    pub fn isolation_helper(mut self, helper: impl Into<PathBuf>) -> Self {
+        self.isolation_helper = Some(helper.into());
+        self
+    }
+
+    /// Persists the record and the state of every data flow instance in the provided directory.
+    ///
+    /// When the Runtime is built, the instances found in this directory are recovered according to the
+    /// [RecoveryPolicy] (see [recovery_policy](RuntimeBuilder::recovery_policy())). The directory is created if it
+    /// does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zenoh_flow_runtime::Runtime;
+    ///
+    /// let builder = Runtime::builder("demo").state_directory("/var/lib/zenoh-flow");
+    /// ```
+    pub fn state_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.state_directory = Some(directory.into());
+        self
+    }
+
+    /// Sets what the Runtime does with the persisted instances when it is built. Defaults to
+    /// [Restart](RecoveryPolicy::Restart).
+    ///
+    /// This policy has no effect if no [state directory](RuntimeBuilder::state_directory()) was provided.
+    pub fn recovery_policy(mut self, policy: RecoveryPolicy) -> Self {
+        self.recovery_policy = policy;
+        self
+    }
+
+    /// Requires every library to come with a `sha256` checksum in its descriptor. Defaults to `false`.
+    ///
+    /// The SHA-256 digest of a library is always verified when a checksum is provided, this setting only forbids
+    /// omitting it. Nodes registered statically or built-in Zenoh nodes are not concerned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use zenoh_flow_runtime::Runtime;
+    ///
+    /// let builder = Runtime::builder("demo").require_library_checksums(true);
+    /// ```
+    pub fn require_library_checksums(mut self, require: bool) -> Self {
+        self.loader.require_checksums = require;
+        self
+    }
+
+    /// Registers, under the provided `name`, the constructor of a Source that is compiled with the Runtime.
+    ///
+    /// Such Source can then be referenced in a descriptor through the `builtin://` (or `static://`) scheme, followed
+    /// by its name. No shared library is loaded for it.
+    ///
+    /// If a Source was already registered under the same name, it is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use async_trait::async_trait;
+    /// use zenoh_flow_nodes::prelude::*;
+    /// use zenoh_flow_runtime::Runtime;
+    ///
+    /// struct MySource;
+    ///
+    /// #[async_trait]
+    /// impl Node for MySource {
+    ///     async fn iteration(&self) -> Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// // The Source is then referenced in a descriptor with: `library: builtin://my-source`
+    /// let builder = Runtime::builder("demo").register_source("my-source", |_, _, _| {
+    ///     Box::pin(async { Ok(Arc::new(MySource) as Arc<dyn Node>) })
+    /// });
+    /// ```
+    pub fn register_source(mut self, name: impl Into<Arc<str>>, constructor: SourceFn) -> Self {
+        self.loader
+            .static_nodes
+            .sources
+            .insert(name.into(), constructor);
+        self
+    }
+
+    /// Registers, under the provided `name`, the constructor of an Operator that is compiled with the Runtime.
+    ///
+    /// Such Operator can then be referenced in a descriptor through the `builtin://` (or `static://`) scheme, followed
+    /// by its name. No shared library is loaded for it.
+    ///
+    /// If an Operator was already registered under the same name, it is replaced.
+    pub fn register_operator(mut self, name: impl Into<Arc<str>>, constructor: OperatorFn) -> Self {
+        self.loader
+            .static_nodes
+            .operators
+            .insert(name.into(), constructor);
+        self
+    }
+
+    /// Registers, under the provided `name`, the constructor of a Sink that is compiled with the Runtime.
+    ///
+    /// Such Sink can then be referenced in a descriptor through the `builtin://` (or `static://`) scheme, followed by
+    /// its name. No shared library is loaded for it.
+    ///
+    /// If a Sink was already registered under the same name, it is replaced.
+    pub fn register_sink(mut self, name: impl Into<Arc<str>>, constructor: SinkFn) -> Self {
+        self.loader
+            .static_nodes
+            .sinks
+            .insert(name.into(), constructor);
+        self
+    }
+
     /// Attempts to build the [Runtime].
     ///
     /// # Errors
     ///
-    /// This method can fail if the `zenoh` feature is enabled (it is by default), no [Session] was provided to the
-    /// builder and the creation of a Session failed.
+    /// This method can fail if:
+    /// - the `zenoh` feature is enabled (it is by default), no [Session] was provided to the builder and the creation
+    ///   of a Session failed,
+    /// - the `wasm` feature is enabled and the WebAssembly engine could not be created,
+    /// - a state directory was provided and it could not be created or read.
     ///
     /// # Example
     ///
@@ -253,19 +421,39 @@ impl RuntimeBuilder {
         #[cfg(not(feature = "zenoh"))]
         let runtime_id = self.runtime_id.unwrap_or_else(RuntimeId::rand);
         #[cfg(feature = "zenoh")]
-        let runtime_id = session.zid().into();
+        let runtime_id: RuntimeId = session.zid().into();
+
+        let state_store = self.state_directory.map(StateStore::try_new).transpose()?;
 
-        Ok(Runtime {
+        let hlc = self
+            .hlc
+            .map(Arc::new)
+            .unwrap_or_else(|| Arc::new(HLC::default()));
+
+        let runtime = Runtime {
             name: self.name,
+            events: EventsHub::new(runtime_id.clone(), hlc.clone()),
             runtime_id,
-            hlc: self
-                .hlc
-                .map(Arc::new)
-                .unwrap_or_else(|| Arc::new(HLC::default())),
+            hlc,
             #[cfg(feature = "zenoh")]
             session,
+            #[cfg(feature = "zenoh")]
+            runtime_loss_policy: self.runtime_loss_policy,
+            #[cfg(feature = "shared-memory")]
+            shared_memory: self.shared_memory.unwrap_or_default(),
             loader: Mutex::new(self.loader),
+            #[cfg(feature = "wasm")]
+            wasm_engine: crate::runners::wasm::new_engine()?,
+            isolation_helper: self
+                .isolation_helper
+                .unwrap_or_else(crate::isolation::default_helper_path),
+            state_store,
+            recovery_policy: self.recovery_policy,
             flows: RwLock::new(HashMap::new()),
-        })
+        };
+
+        runtime.try_recover_instances().await?;
+
+        Ok(runtime)
     }
 }
@@ -0,0 +1,614 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// This file centralizes the detection, through Zenoh's liveliness, of the loss of a runtime involved in a data flow
+// instance.
+//
+// For each instance spanning several runtimes, every runtime declares a liveliness token on:
+//
+//     zenoh-flow/<runtime id>/liveliness/<instance id>
+//
+// and subscribes to the tokens of the other runtimes of the instance. When a token disappears (the runtime crashed or
+// lost its connectivity), the reaction configured through the `RuntimeLossPolicy` is applied.
+//
+// A runtime deleting the instance first declares, for `DELETION_ANNOUNCEMENT`, a second token on:
+//
+//     zenoh-flow/<runtime id>/deleted/<instance id>
+//
+// The other runtimes look for that token when the first one disappears: if it exists, the instance is being deleted
+// and there is nothing to react to.
+
+use std::{
+    str::FromStr,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use async_std::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use uhlc::HLC;
+use zenoh::{
+    liveliness::LivelinessToken, prelude::r#async::*, sample::Sample, subscriber::Subscriber,
+};
+use zenoh_flow_commons::{InstanceId, Result, RuntimeId};
+
+use super::{persistence::StateStore, Runtime};
+use crate::{
+    events::{EventsHub, InstanceEventKind},
+    instance::DataFlowInstance,
+    InstanceState,
+};
+
+/// What a [Runtime] does with a running data flow instance when another runtime involved in it is lost.
+///
+/// Whatever the policy, an event [RuntimeLost](InstanceEventKind::RuntimeLost) is emitted.
+///
+/// # Example
+///
+/// In the configuration of a Zenoh-Flow daemon:
+///
+/// ```yaml
+/// runtime_loss_policy: abort
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeLossPolicy {
+    /// The nodes keep running and the instance is put in the [Degraded](InstanceState::Degraded) state, until all the
+    /// lost runtimes are back.
+    #[default]
+    KeepRunning,
+    /// The nodes are aborted and the instance is put in the [Aborted](InstanceState::Aborted) state.
+    Abort,
+    /// The nodes are aborted and the instance is put in the [Failed](InstanceState::Failed) state.
+    Fail,
+}
+
+/// The liveliness token of this runtime for an instance and the subscriber to the tokens of the other runtimes.
+///
+/// Both are undeclared when this structure is dropped, i.e. when the instance is deleted.
+pub(crate) struct InstanceLiveliness {
+    _token: LivelinessToken<'static>,
+    _subscriber: Subscriber<'static, ()>,
+}
+
+// Everything the reaction to a change of liveliness needs, without borrowing the runtime.
+#[derive(Clone)]
+struct LivelinessContext {
+    runtime_id: RuntimeId,
+    hlc: Arc<HLC>,
+    events: EventsHub,
+    state_store: Option<StateStore>,
+    policy: RuntimeLossPolicy,
+}
+
+// How long a runtime announces that it deleted an instance.
+const DELETION_ANNOUNCEMENT: Duration = Duration::from_secs(10);
+
+fn liveliness_key_expr(runtime_id: &str, instance_id: &InstanceId) -> Result<OwnedKeyExpr> {
+    OwnedKeyExpr::autocanonize(format!("zenoh-flow/{runtime_id}/liveliness/{instance_id}"))
+        .map_err(|e| anyhow::anyhow!("Failed to build the liveliness key expression:\n{e:?}"))
+}
+
+fn deletion_key_expr(runtime_id: &RuntimeId, instance_id: &InstanceId) -> Result<OwnedKeyExpr> {
+    OwnedKeyExpr::autocanonize(format!("zenoh-flow/{runtime_id}/deleted/{instance_id}"))
+        .map_err(|e| anyhow::anyhow!("Failed to build the deletion key expression:\n{e:?}"))
+}
+
+// Returns `true` if the runtime announced that it deleted the instance.
+async fn is_deleted(session: &Session, runtime_id: &RuntimeId, instance_id: &InstanceId) -> bool {
+    let replies = match deletion_key_expr(runtime_id, instance_id) {
+        Ok(key_expr) => session.liveliness().get(key_expr).res().await,
+        Err(e) => {
+            tracing::error!("{:?}", e);
+            return false;
+        }
+    };
+
+    match replies {
+        Ok(replies) => matches!(replies.recv_async().await, Ok(reply) if reply.sample.is_ok()),
+        Err(e) => {
+            tracing::error!(
+                "Failed to query the deletion token of < {} >: {:?}",
+                runtime_id,
+                e
+            );
+            false
+        }
+    }
+}
+
+impl Runtime {
+    /// Declares the liveliness token of this runtime for the instance and subscribes to the tokens of the other
+    /// runtimes involved in it.
+    ///
+    /// Nothing is declared, and `None` is returned, if the instance does not involve other runtimes.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the token or the subscriber could not be declared.
+    pub(crate) async fn try_declare_liveliness(
+        &self,
+        instance: &Arc<RwLock<DataFlowInstance>>,
+        instance_guard: &DataFlowInstance,
+    ) -> Result<Option<InstanceLiveliness>> {
+        let peers = instance_guard
+            .mapping()
+            .keys()
+            .filter(|&runtime_id| runtime_id != &self.runtime_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        if peers.is_empty() {
+            return Ok(None);
+        }
+
+        let instance_id = instance_guard.instance_id();
+        let token = self
+            .session
+            .liveliness()
+            .declare_token(liveliness_key_expr(
+                &self.runtime_id.to_string(),
+                instance_id,
+            )?)
+            .res()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to declare the liveliness token:\n{e:?}"))?;
+
+        let context = LivelinessContext {
+            runtime_id: self.runtime_id.clone(),
+            hlc: self.hlc.clone(),
+            events: self.events.clone(),
+            state_store: self.state_store.clone(),
+            policy: self.runtime_loss_policy,
+        };
+        let instance = Arc::downgrade(instance);
+        let (session, watched_instance_id) = (self.session.clone(), instance_id.clone());
+        let subscriber = self
+            .session
+            .liveliness()
+            .declare_subscriber(liveliness_key_expr("*", instance_id)?)
+            .callback(move |sample: Sample| {
+                let Some(runtime_id) = sample
+                    .key_expr
+                    .as_str()
+                    .split('/')
+                    .nth(1)
+                    .and_then(|chunk| RuntimeId::from_str(chunk).ok())
+                else {
+                    return;
+                };
+                if !peers.contains(&runtime_id) {
+                    return;
+                }
+
+                let is_alive = sample.kind == SampleKind::Put;
+                let (instance, context) = (instance.clone(), context.clone());
+                let (session, instance_id) = (session.clone(), watched_instance_id.clone());
+                async_std::task::spawn(async move {
+                    if !is_alive && is_deleted(&session, &runtime_id, &instance_id).await {
+                        tracing::debug!(
+                            "[{}] Runtime < {} > deleted the instance",
+                            instance_id,
+                            runtime_id
+                        );
+                        return;
+                    }
+
+                    on_liveliness_change(&instance, runtime_id, is_alive, &context).await
+                });
+            })
+            .res()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to declare the liveliness subscriber:\n{e:?}"))?;
+
+        Ok(Some(InstanceLiveliness {
+            _token: token,
+            _subscriber: subscriber,
+        }))
+    }
+
+    /// Announces, to the other runtimes involved in the instance, that this runtime is deleting it, such that they do
+    /// not mistake the disappearance of its liveliness token for a loss.
+    ///
+    /// This method must be called before the liveliness token of the instance is undeclared, i.e. before the instance
+    /// is dropped. A failure to announce the deletion is logged.
+    pub(crate) async fn announce_deletion(&self, instance: &DataFlowInstance) {
+        if instance.liveliness.is_none() {
+            return;
+        }
+
+        let token = match deletion_key_expr(&self.runtime_id, instance.instance_id()) {
+            Ok(key_expr) => self
+                .session
+                .liveliness()
+                .declare_token(key_expr)
+                .res()
+                .await
+                .map_err(|e| anyhow::anyhow!("{e:?}")),
+            Err(e) => Err(e),
+        };
+
+        match token {
+            Ok(token) => {
+                async_std::task::spawn(async move {
+                    async_std::task::sleep(DELETION_ANNOUNCEMENT).await;
+                    drop(token);
+                });
+            }
+            Err(e) => tracing::error!(
+                "[{}] Failed to announce the deletion of the instance: {:?}",
+                instance.instance_id(),
+                e
+            ),
+        }
+    }
+}
+
+async fn on_liveliness_change(
+    instance: &Weak<RwLock<DataFlowInstance>>,
+    runtime_id: RuntimeId,
+    is_alive: bool,
+    context: &LivelinessContext,
+) {
+    // NOTE: The instance was deleted in the meantime, there is nothing to do.
+    let Some(instance) = instance.upgrade() else {
+        return;
+    };
+    let mut instance_guard = instance.write().await;
+
+    let changed = if is_alive {
+        on_runtime_back(&mut instance_guard, runtime_id, context)
+    } else {
+        on_runtime_lost(&mut instance_guard, runtime_id, context).await
+    };
+
+    if changed {
+        if let Some(store) = &context.state_store {
+            if let Err(e) = store
+                .save(&instance_guard.record, &instance_guard.state)
+                .await
+            {
+                tracing::error!(
+                    "Failed to persist the state of instance < {} >: {:?}",
+                    instance_guard.instance_id(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Applies the [RuntimeLossPolicy] to the instance, if it is running, returning `true` if its state changed.
+async fn on_runtime_lost(
+    instance: &mut DataFlowInstance,
+    runtime_id: RuntimeId,
+    context: &LivelinessContext,
+) -> bool {
+    let instance_id = instance.instance_id().clone();
+    match &mut instance.state {
+        InstanceState::Running(_) => {}
+        InstanceState::Degraded((_, lost)) => {
+            if lost.contains(&runtime_id) {
+                return false;
+            }
+            lost.push(runtime_id.clone());
+        }
+        _ => {
+            tracing::debug!(
+                "[{}] Runtime < {} > was lost, the instance is not running: nothing to do",
+                instance_id,
+                runtime_id
+            );
+            return false;
+        }
+    }
+
+    tracing::warn!(
+        "[{}] Runtime < {} > was lost, applying policy: {:?}",
+        instance_id,
+        runtime_id,
+        context.policy
+    );
+    context.events.publish(
+        &instance_id,
+        InstanceEventKind::RuntimeLost {
+            runtime: runtime_id.clone(),
+        },
+    );
+
+    match context.policy {
+        RuntimeLossPolicy::KeepRunning => {
+            if matches!(instance.state, InstanceState::Running(_)) {
+                instance.state =
+                    InstanceState::Degraded((context.hlc.new_timestamp(), vec![runtime_id]));
+            }
+        }
+        RuntimeLossPolicy::Abort => {
+            instance.abort(&context.hlc).await;
+            context
+                .events
+                .publish(&instance_id, InstanceEventKind::Aborted);
+        }
+        RuntimeLossPolicy::Fail => {
+            instance.abort(&context.hlc).await;
+            let reason = format!(
+                "Runtime < {} > was lost (detected by < {} >)",
+                runtime_id, context.runtime_id
+            );
+            instance.state = InstanceState::Failed((context.hlc.new_timestamp(), reason.clone()));
+            context
+                .events
+                .publish(&instance_id, InstanceEventKind::Failed { reason });
+        }
+    }
+
+    true
+}
+
+/// Puts the instance back in the `Running` state once all the lost runtimes are back, returning `true` if its state
+/// changed.
+fn on_runtime_back(
+    instance: &mut DataFlowInstance,
+    runtime_id: RuntimeId,
+    context: &LivelinessContext,
+) -> bool {
+    let InstanceState::Degraded((_, lost)) = &mut instance.state else {
+        return false;
+    };
+
+    let len = lost.len();
+    lost.retain(|lost_runtime_id| lost_runtime_id != &runtime_id);
+    if lost.len() == len {
+        return false;
+    }
+    let all_back = lost.is_empty();
+
+    tracing::info!(
+        "[{}] Runtime < {} > is back",
+        instance.instance_id(),
+        runtime_id
+    );
+    context.events.publish(
+        instance.instance_id(),
+        InstanceEventKind::RuntimeRecovered {
+            runtime: runtime_id,
+        },
+    );
+    if all_back {
+        instance.state = InstanceState::Running(context.hlc.new_timestamp());
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use uhlc::HLC;
+    use zenoh::prelude::r#async::*;
+    use zenoh_flow_commons::RuntimeId;
+    use zenoh_flow_descriptors::FlattenedDataFlowDescriptor;
+    use zenoh_flow_records::DataFlowRecord;
+
+    use super::{on_runtime_back, on_runtime_lost, LivelinessContext, RuntimeLossPolicy};
+    use crate::{
+        events::EventsHub, instance::DataFlowInstance, InstanceEventKind, InstanceState, Runtime,
+    };
+
+    #[async_std::test]
+    async fn test_runtime_loss() {
+        let flow = r#"
+name: test-flow
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+sinks:
+  - id: sink-1
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-1
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: sink-1
+      input: in-1
+"#;
+        let descriptor = serde_yaml::from_str::<FlattenedDataFlowDescriptor>(flow).unwrap();
+        let record = DataFlowRecord::try_new(&descriptor, &RuntimeId::rand()).unwrap();
+        let runtime_id = RuntimeId::rand();
+        let hlc = Arc::new(HLC::default());
+        let mut context = LivelinessContext {
+            runtime_id: runtime_id.clone(),
+            hlc: hlc.clone(),
+            events: EventsHub::new(runtime_id, hlc.clone()),
+            state_store: None,
+            policy: RuntimeLossPolicy::KeepRunning,
+        };
+        let events = context.events.subscribe();
+        let (lost_a, lost_b) = (RuntimeId::rand(), RuntimeId::rand());
+
+        let mut instance = DataFlowInstance::new(record, &hlc);
+        // An instance that is not running is left untouched.
+        assert!(!on_runtime_lost(&mut instance, lost_a.clone(), &context).await);
+        assert!(matches!(instance.state, InstanceState::Creating(_)));
+
+        instance.state = InstanceState::Running(hlc.new_timestamp());
+        assert!(on_runtime_lost(&mut instance, lost_a.clone(), &context).await);
+        assert!(on_runtime_lost(&mut instance, lost_b.clone(), &context).await);
+        assert!(!on_runtime_lost(&mut instance, lost_b.clone(), &context).await);
+        assert!(
+            matches!(&instance.state, InstanceState::Degraded((_, lost)) if lost == &vec![lost_a.clone(), lost_b.clone()])
+        );
+
+        // Starting a degraded instance does not hide the loss.
+        instance.start(&hlc).await.unwrap();
+        assert!(matches!(instance.state, InstanceState::Degraded(_)));
+
+        // The instance is running again once all the lost runtimes are back.
+        assert!(on_runtime_back(&mut instance, lost_a.clone(), &context));
+        assert!(matches!(instance.state, InstanceState::Degraded(_)));
+        assert!(on_runtime_back(&mut instance, lost_b.clone(), &context));
+        assert!(matches!(instance.state, InstanceState::Running(_)));
+        assert!(!on_runtime_back(&mut instance, lost_b.clone(), &context));
+
+        assert_eq!(
+            events
+                .try_iter()
+                .map(|event| event.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                InstanceEventKind::RuntimeLost {
+                    runtime: lost_a.clone()
+                },
+                InstanceEventKind::RuntimeLost {
+                    runtime: lost_b.clone()
+                },
+                InstanceEventKind::RuntimeRecovered {
+                    runtime: lost_a.clone()
+                },
+                InstanceEventKind::RuntimeRecovered { runtime: lost_b },
+            ]
+        );
+
+        context.policy = RuntimeLossPolicy::Fail;
+        assert!(on_runtime_lost(&mut instance, lost_a.clone(), &context).await);
+        assert!(matches!(instance.state, InstanceState::Failed(_)));
+
+        context.policy = RuntimeLossPolicy::Abort;
+        instance.state = InstanceState::Running(hlc.new_timestamp());
+        assert!(on_runtime_lost(&mut instance, lost_a, &context).await);
+        assert!(matches!(instance.state, InstanceState::Aborted(_)));
+    }
+
+    // Builds a runtime over a Zenoh session that does not scout, either listening on or connecting to `endpoint`.
+    async fn runtime(
+        name: &str,
+        endpoint: &str,
+        listen: bool,
+        policy: RuntimeLossPolicy,
+    ) -> Runtime {
+        let mut config = zenoh::config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let endpoints = vec![endpoint.parse().unwrap()];
+        if listen {
+            config.listen.endpoints = endpoints;
+        } else {
+            config.connect.endpoints = endpoints;
+        }
+        let session = zenoh::open(config).res().await.unwrap().into_arc();
+
+        Runtime::builder(name)
+            .session(session)
+            .runtime_loss_policy(policy)
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[async_std::test]
+    async fn test_deletion_is_not_a_loss() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let endpoint = format!("tcp/127.0.0.1:{port}");
+        let runtime_a = runtime("runtime-a", &endpoint, true, RuntimeLossPolicy::Fail).await;
+        let runtime_b = runtime("runtime-b", &endpoint, false, RuntimeLossPolicy::Fail).await;
+
+        let path = std::env::temp_dir().join(format!("zf-liveliness-{}.log", uuid::Uuid::new_v4()));
+        let flow = format!(
+            r#"
+name: test-flow
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    outputs:
+      - out
+sinks:
+  - id: file
+    file:
+      path: {}
+      input: in
+    inputs:
+      - in
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: file
+      input: in
+mapping:
+  {}:
+    - ticker
+  {}:
+    - file
+"#,
+            path.display(),
+            runtime_a.id(),
+            runtime_b.id()
+        );
+        let descriptor = serde_yaml::from_str::<FlattenedDataFlowDescriptor>(&flow).unwrap();
+        let deploy = || async {
+            let record = DataFlowRecord::try_new(&descriptor, runtime_a.id()).unwrap();
+            let instance_id = record.instance_id().clone();
+            for runtime in [&runtime_a, &runtime_b] {
+                runtime.try_load_data_flow(record.clone()).await.unwrap();
+                runtime.try_start_instance(&instance_id).await.unwrap();
+            }
+            // Leave time for the runtimes to see each other's liveliness token.
+            async_std::task::sleep(Duration::from_secs(1)).await;
+            instance_id
+        };
+        let lost = |events: &flume::Receiver<crate::InstanceEvent>| {
+            events.try_iter().any(|event| {
+                matches!(
+                    event.kind,
+                    InstanceEventKind::RuntimeLost { .. } | InstanceEventKind::Failed { .. }
+                )
+            })
+        };
+        let events = runtime_b.events.subscribe();
+
+        // An orderly deletion, on one runtime, is not mistaken for the loss of that runtime.
+        let deleted = deploy().await;
+        runtime_a.try_delete_instance(&deleted).await.unwrap();
+        async_std::task::sleep(Duration::from_secs(1)).await;
+        assert!(!lost(&events));
+        assert!(matches!(
+            runtime_b.get_instance_status(&deleted).await.unwrap().state,
+            InstanceState::Running(_)
+        ));
+        runtime_b.try_delete_instance(&deleted).await.unwrap();
+
+        // Whereas a runtime that disappears without deleting the instance is lost.
+        let stopped = deploy().await;
+        runtime_a.stop().await;
+        async_std::task::sleep(Duration::from_secs(1)).await;
+        assert!(lost(&events));
+        assert!(matches!(
+            runtime_b.get_instance_status(&stopped).await.unwrap().state,
+            InstanceState::Failed(_)
+        ));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -20,29 +20,57 @@
 // - Creating all the channels connecting all the nodes -> the channels are then passed down the constructor of all
 //   the nodes.
 // - For each type of node and each node:
-//   - load its library,
+//   - load its library (or, with the "wasm" feature, compile its WebAssembly module; or, if the node is isolated,
+//     spawn the helper process that will load it),
 //   - call its constructor with the correct parameters (i.e. only Inputs for a Sink, only Outputs for a Source).
 
+#[cfg(feature = "wasm")]
+use std::path::Path;
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-use anyhow::{bail, Context as _};
+use anyhow::{anyhow, bail, Context as _};
 use async_std::sync::RwLock;
 use libloading::Library;
 use url::Url;
-use zenoh_flow_commons::{NodeId, Result};
-use zenoh_flow_descriptors::{SinkVariant, SourceVariant};
+use zenoh_flow_commons::{Configuration, NodeId, Result};
+use zenoh_flow_descriptors::{Isolation, OperatorVariant, SinkVariant, SourceVariant};
 use zenoh_flow_nodes::{
-    prelude::{Context, Inputs, Outputs},
+    prelude::{Context, Inputs, LinkMessage, Outputs},
     OperatorFn, SinkFn, SourceFn,
 };
 use zenoh_flow_records::DataFlowRecord;
 
 use super::Runtime;
 #[cfg(feature = "zenoh")]
-use crate::runners::builtin::zenoh::sink::ZenohSink;
+use crate::runners::builtin::zenoh::{querier::ZenohQuerierSource, source::ZenohSource};
 #[cfg(feature = "zenoh")]
-use crate::runners::builtin::zenoh::source::ZenohSource;
-use crate::{instance::DataFlowInstance, loader::NodeSymbol, runners::Runner, InstanceState};
+use crate::runners::builtin::zenoh::{queryable::ZenohQueryableSink, sink::ZenohSink};
+#[cfg(feature = "zenoh")]
+use crate::runners::sequence::ConnectorCounters;
+#[cfg(feature = "wasm")]
+use crate::runners::wasm::{is_wasm_module, WasmNode};
+#[cfg(feature = "opentelemetry")]
+use crate::telemetry::OpenTelemetryTracer;
+use crate::{
+    events::InstanceEventKind,
+    instance::DataFlowInstance,
+    isolation::NodeSpec,
+    loader::{NodeSymbol, StaticConstructor},
+    recording::{spawn_recorder, try_create_recording},
+    runners::{
+        builtin::{
+            expression::ExpressionOperator,
+            file::{FileSink, FileSource},
+            replay::ReplaySource,
+            routing::{MergeOperator, SplitOperator, TeeOperator, ThrottleOperator},
+            stdio::{StdinSource, StdoutSink},
+            ticker::TickerSource,
+        },
+        isolated::IsolatedNode,
+        Runner,
+    },
+    InstanceState,
+};
 
 pub(crate) type Channels = HashMap<NodeId, (Inputs, Outputs)>;
 
@@ -61,7 +89,9 @@ impl Runtime {
     /// - the runtime failed to load: an operator, a source, a sink,
     /// - the runtime encountered an internal error:
     ///   - a channel was not created for a node,
-    ///   - a Zenoh built-in source failed to declare its subscriber.
+    ///   - a Zenoh built-in source failed to declare its subscriber,
+    ///   - the liveliness token of this runtime, or the subscriber to the tokens of the other runtimes involved in the
+    ///     data flow, could not be declared.
     pub async fn try_load_data_flow(&self, data_flow: DataFlowRecord) -> Result<()> {
         // -----------------------------------
         // The following code tries to do two things:
@@ -101,7 +131,16 @@ impl Runtime {
         }
         // -----------------------------------
 
+        self.events.publish(
+            instance_guard.instance_id(),
+            InstanceEventKind::Created {
+                name: instance_guard.name().clone(),
+            },
+        );
+
         let mut runners = HashMap::<NodeId, Runner>::default();
+        #[cfg(feature = "zenoh")]
+        let mut connector_counters = HashMap::default();
         let data_flow = &instance_guard.record;
 
         // NOTE: By wrapping all the calls in a named block we avoid having to separately call `map_err` and set the
@@ -135,12 +174,13 @@ impl Runtime {
 
             #[cfg(feature = "zenoh")]
             {
-                runners.extend(
+                let (receivers, counters) =
                     match self.try_load_receivers(data_flow, &mut channels).await {
                         Ok(receivers) => receivers,
                         Err(e) => break 'load Err(e),
-                    },
-                );
+                    };
+                runners.extend(receivers);
+                connector_counters = counters;
                 runners.extend(match self.try_load_senders(data_flow, &mut channels) {
                     Ok(senders) => senders,
                     Err(e) => break 'load Err(e),
@@ -150,14 +190,40 @@ impl Runtime {
             Ok(())
         };
 
+        #[cfg(feature = "zenoh")]
+        let load_result = match load_result {
+            Ok(()) => self
+                .try_declare_liveliness(&instance, &instance_guard)
+                .await
+                .map(|liveliness| instance_guard.liveliness = liveliness),
+            Err(e) => Err(e),
+        };
+
         if let Err(e) = load_result {
             instance_guard.state =
                 InstanceState::Failed((self.hlc.new_timestamp(), format!("{e:?}")));
+            self.persist(&instance_guard).await;
+            self.events.publish(
+                instance_guard.instance_id(),
+                InstanceEventKind::Failed {
+                    reason: format!("{e:?}"),
+                },
+            );
             return Err(e);
         }
 
+        for runner in runners.values_mut() {
+            runner.report_errors(self.events.clone(), instance_guard.instance_id().clone());
+        }
         instance_guard.runners = runners;
+        #[cfg(feature = "zenoh")]
+        {
+            instance_guard.connector_counters = connector_counters;
+        }
         instance_guard.state = InstanceState::Loaded(self.hlc.new_timestamp());
+        self.persist(&instance_guard).await;
+        self.events
+            .publish(instance_guard.instance_id(), InstanceEventKind::Loaded);
 
         Ok(())
     }
@@ -215,7 +281,49 @@ The problematic link is:
                 }
             }
 
-            let (tx, rx) = flume::unbounded();
+            // NOTE: A feedback link can bound the number of messages it holds. When the link is recorded, both channels
+            // are bounded such that the node sending on the link still waits for the node receiving on it.
+            let capacity = link
+                .feedback
+                .as_ref()
+                .and_then(|feedback| feedback.capacity);
+            let channel = || match capacity {
+                Some(capacity) => flume::bounded(capacity),
+                None => flume::unbounded(),
+            };
+
+            let (tx, rx) = match &link.recording {
+                Some(path) => {
+                    let file = try_create_recording(path)
+                        .context(format!("Failed to record link:\n{}", link))?;
+                    let (tap_tx, tap_rx) = channel();
+                    let (tx, rx) = channel();
+                    spawn_recorder(file, tap_rx, tx);
+                    (tap_tx, rx)
+                }
+                None => channel(),
+            };
+
+            // NOTE: The initial message of a feedback link is sent before any node is started such that it is the
+            // first message received by the node closing the loop.
+            if let Some(initial) = link
+                .feedback
+                .as_ref()
+                .and_then(|feedback| feedback.initial.as_ref())
+            {
+                let payload = serde_json::to_vec(initial).context(format!(
+                    "Failed to serialize the initial message of the feedback link:\n{}",
+                    link
+                ))?;
+                tx.try_send(LinkMessage::new_serialized(payload, self.hlc.new_timestamp()))
+                    .map_err(|e| {
+                        anyhow!(
+                            "Failed to send the initial message of the feedback link:\n{}\n\nCaused by:\n{:?}",
+                            link,
+                            e
+                        )
+                    })?;
+            }
             let (_, outputs) = channels
                 .entry(link.from.node.clone())
                 .or_insert_with(|| (Inputs::default(), Outputs::new(self.hlc.clone())));
@@ -227,6 +335,14 @@ The problematic link is:
             inputs.insert(link.to.input.clone(), rx);
         }
 
+        // NOTE: The connectors are not traced, the messages they carry keep the context set by the node that sent them.
+        #[cfg(feature = "opentelemetry")]
+        for (node_id, (inputs, outputs)) in channels.iter_mut() {
+            let tracer = OpenTelemetryTracer::new(record.instance_id().clone(), node_id.clone());
+            inputs.set_tracer(tracer.clone());
+            outputs.set_tracer(tracer);
+        }
+
         Ok(channels)
     }
 
@@ -239,6 +355,8 @@ The problematic link is:
     ///
     /// This method can fail for the following reasons:
     /// - a channel was not created for one of the Operators managed by this runtime,
+    /// - one of the Operators is isolated in a process but it is not implemented by a shared library,
+    /// - the call to create a built-in Operator failed,
     /// - the call to `try_load_constructor` failed,
     /// - the call to the actual constructor failed.
     async fn try_load_operators(
@@ -267,8 +385,108 @@ The channels for the Inputs and Outputs of Operator < {} > were not created.
                 &operator_id
             ))?;
 
+            operator
+                .isolation
+                .check(match &operator.operator {
+                    OperatorVariant::Library(library) => Some(library),
+                    _ => None,
+                })
+                .context(format!("[{}] Failed to load the Operator", operator_id))?;
+
+            let library = match &operator.operator {
+                OperatorVariant::Library(library) => library,
+                OperatorVariant::Expression(expression) => {
+                    let expression_operator =
+                        ExpressionOperator::try_new(operator_id, expression, inputs, outputs)?;
+                    runners.insert(
+                        operator_id.clone(),
+                        Runner::new(operator_id.clone(), Arc::new(expression_operator), None),
+                    );
+                    continue;
+                }
+                OperatorVariant::Merge(merge) => {
+                    let merge_operator =
+                        MergeOperator::try_new(operator_id, merge, inputs, outputs)?;
+                    runners.insert(
+                        operator_id.clone(),
+                        Runner::new(operator_id.clone(), Arc::new(merge_operator), None),
+                    );
+                    continue;
+                }
+                OperatorVariant::Split(split) => {
+                    let split_operator =
+                        SplitOperator::try_new(operator_id, split, inputs, outputs)?;
+                    runners.insert(
+                        operator_id.clone(),
+                        Runner::new(operator_id.clone(), Arc::new(split_operator), None),
+                    );
+                    continue;
+                }
+                OperatorVariant::Tee(tee) => {
+                    let tee_operator = TeeOperator::try_new(operator_id, tee, inputs, outputs)?;
+                    runners.insert(
+                        operator_id.clone(),
+                        Runner::new(operator_id.clone(), Arc::new(tee_operator), None),
+                    );
+                    continue;
+                }
+                OperatorVariant::Throttle(throttle) => {
+                    let throttle_operator =
+                        ThrottleOperator::try_new(operator_id, throttle, inputs, outputs)?;
+                    runners.insert(
+                        operator_id.clone(),
+                        Runner::new(operator_id.clone(), Arc::new(throttle_operator), None),
+                    );
+                    continue;
+                }
+            };
+
+            if operator.isolation == Isolation::Process {
+                let runner = self
+                    .try_load_isolated_node(
+                        record,
+                        operator_id,
+                        library,
+                        operator.sha256.as_deref(),
+                        NodeSymbol::Operator,
+                        &operator.configuration,
+                        inputs,
+                        outputs,
+                    )
+                    .await?;
+                runners.insert(operator_id.clone(), runner);
+                continue;
+            }
+
+            #[cfg(feature = "wasm")]
+            if is_wasm_module(library) {
+                let path = Path::new(library.path());
+                self.loader
+                    .lock()
+                    .await
+                    .verify_checksum(path, operator.sha256.as_deref())?;
+                let wasm_node = WasmNode::try_new(
+                    &self.wasm_engine,
+                    operator_id.clone(),
+                    path,
+                    &operator.configuration,
+                    inputs,
+                    outputs,
+                )
+                .await?;
+                runners.insert(
+                    operator_id.clone(),
+                    Runner::new(operator_id.clone(), Arc::new(wasm_node), None),
+                );
+                continue;
+            }
+
             let (constructor, path, library) = self
-                .try_load_constructor::<OperatorFn>(&operator.library, &NodeSymbol::Operator)
+                .try_load_constructor::<OperatorFn>(
+                    library,
+                    operator.sha256.as_deref(),
+                    &NodeSymbol::Operator,
+                )
                 .await?;
 
             let context = Context::new(
@@ -288,7 +506,7 @@ The channels for the Inputs and Outputs of Operator < {} > were not created.
             .await?;
             runners.insert(
                 operator_id.clone(),
-                Runner::new(operator_id.clone(), operator_node, Some(library)),
+                Runner::new(operator_id.clone(), operator_node, library),
             );
         }
 
@@ -304,6 +522,7 @@ The channels for the Inputs and Outputs of Operator < {} > were not created.
     ///
     /// This method can fail for the following reasons:
     /// - a channel was not created for one of the Sources managed by this runtime,
+    /// - one of the Sources is isolated in a process but it is not implemented by a shared library,
     /// - the call to create a Zenoh built-in Source failed,
     /// - the call to `try_load_constructor` failed,
     /// - the call to the actual constructor failed.
@@ -333,10 +552,53 @@ The channels for the Outputs of Source < {} > were not created.
                 &source_id
             ))?;
 
+            source
+                .isolation
+                .check(match &source.source {
+                    SourceVariant::Library(library) => Some(library),
+                    _ => None,
+                })
+                .context(format!("[{}] Failed to load the Source", source_id))?;
+
             let runner = match &source.source {
+                SourceVariant::Library(uri) if source.isolation == Isolation::Process => {
+                    self.try_load_isolated_node(
+                        record,
+                        source_id,
+                        uri,
+                        source.sha256.as_deref(),
+                        NodeSymbol::Source,
+                        &source.configuration,
+                        Inputs::default(),
+                        outputs,
+                    )
+                    .await?
+                }
+                #[cfg(feature = "wasm")]
+                SourceVariant::Library(uri) if is_wasm_module(uri) => {
+                    let path = Path::new(uri.path());
+                    self.loader
+                        .lock()
+                        .await
+                        .verify_checksum(path, source.sha256.as_deref())?;
+                    let wasm_node = WasmNode::try_new(
+                        &self.wasm_engine,
+                        source_id.clone(),
+                        path,
+                        &source.configuration,
+                        Inputs::default(),
+                        outputs,
+                    )
+                    .await?;
+                    Runner::new(source.id.clone(), Arc::new(wasm_node), None)
+                }
                 SourceVariant::Library(uri) => {
                     let (constructor, path, library) = self
-                        .try_load_constructor::<SourceFn>(uri, &NodeSymbol::Source)
+                        .try_load_constructor::<SourceFn>(
+                            uri,
+                            source.sha256.as_deref(),
+                            &NodeSymbol::Source,
+                        )
                         .await?;
 
                     let context = Context::new(
@@ -351,10 +613,26 @@ The channels for the Outputs of Source < {} > were not created.
                         (constructor)(context.clone(), source.configuration.clone(), outputs)
                             .await?;
 
-                    Runner::new(source.id.clone(), source_node, Some(library))
+                    Runner::new(source.id.clone(), source_node, library)
+                }
+                SourceVariant::File(file) => {
+                    let file_source = FileSource::try_new(&source.id, file, outputs).await?;
+                    Runner::new(source.id.clone(), Arc::new(file_source), None)
+                }
+                SourceVariant::Stdin(stdin) => {
+                    let stdin_source = StdinSource::try_new(&source.id, stdin, outputs)?;
+                    Runner::new(source.id.clone(), Arc::new(stdin_source), None)
+                }
+                SourceVariant::Ticker(ticker) => {
+                    let ticker_source = TickerSource::try_new(&source.id, ticker, outputs)?;
+                    Runner::new(source.id.clone(), Arc::new(ticker_source), None)
+                }
+                SourceVariant::Replay(replay) => {
+                    let replay_source = ReplaySource::try_new(&source.id, replay, outputs).await?;
+                    Runner::new(source.id.clone(), Arc::new(replay_source), None)
                 }
                 #[cfg(not(feature = "zenoh"))]
-                SourceVariant::Zenoh(_) => {
+                SourceVariant::Zenoh(_) | SourceVariant::ZenohQueriers(_) => {
                     bail!(
                         r#"
 The Zenoh-Flow runtime was compiled without the feature "zenoh" but includes a built-in Zenoh Source.
@@ -363,10 +641,24 @@ Maybe change the features in the Cargo.toml?
                     )
                 }
                 #[cfg(feature = "zenoh")]
-                SourceVariant::Zenoh(key_exprs) => {
-                    let dyn_source =
-                        ZenohSource::try_new(&source.id, self.session.clone(), key_exprs, outputs)
-                            .await?;
+                SourceVariant::Zenoh(subscribers) => {
+                    let dyn_source = ZenohSource::try_new(
+                        &source.id,
+                        self.session.clone(),
+                        subscribers,
+                        outputs,
+                    )
+                    .await?;
+                    Runner::new(source.id.clone(), Arc::new(dyn_source), None)
+                }
+                #[cfg(feature = "zenoh")]
+                SourceVariant::ZenohQueriers(queriers) => {
+                    let dyn_source = ZenohQuerierSource::try_new(
+                        &source.id,
+                        self.session.clone(),
+                        queriers,
+                        outputs,
+                    )?;
                     Runner::new(source.id.clone(), Arc::new(dyn_source), None)
                 }
             };
@@ -386,6 +678,7 @@ Maybe change the features in the Cargo.toml?
     ///
     /// This method can fail for the following reasons:
     /// - a channel was not created for one of the Sinks managed by this runtime,
+    /// - one of the Sinks is isolated in a process but it is not implemented by a shared library,
     /// - the call to create a Zenoh built-in Sink failed,
     /// - the call to `try_load_constructor` failed,
     /// - the call to the actual constructor failed.
@@ -415,10 +708,52 @@ The channels for the Inputs of Sink < {} > were not created.
                 &sink_id
             ))?;
 
+            sink.isolation
+                .check(match &sink.sink {
+                    SinkVariant::Library(library) => Some(library),
+                    _ => None,
+                })
+                .context(format!("[{}] Failed to load the Sink", sink_id))?;
+
             let runner = match &sink.sink {
+                SinkVariant::Library(uri) if sink.isolation == Isolation::Process => {
+                    self.try_load_isolated_node(
+                        record,
+                        sink_id,
+                        uri,
+                        sink.sha256.as_deref(),
+                        NodeSymbol::Sink,
+                        &sink.configuration,
+                        inputs,
+                        Outputs::new(self.hlc.clone()),
+                    )
+                    .await?
+                }
+                #[cfg(feature = "wasm")]
+                SinkVariant::Library(uri) if is_wasm_module(uri) => {
+                    let path = Path::new(uri.path());
+                    self.loader
+                        .lock()
+                        .await
+                        .verify_checksum(path, sink.sha256.as_deref())?;
+                    let wasm_node = WasmNode::try_new(
+                        &self.wasm_engine,
+                        sink_id.clone(),
+                        path,
+                        &sink.configuration,
+                        inputs,
+                        Outputs::new(self.hlc.clone()),
+                    )
+                    .await?;
+                    Runner::new(sink.id.clone(), Arc::new(wasm_node), None)
+                }
                 SinkVariant::Library(uri) => {
                     let (constructor, library_path, library) = self
-                        .try_load_constructor::<SinkFn>(uri, &NodeSymbol::Sink)
+                        .try_load_constructor::<SinkFn>(
+                            uri,
+                            sink.sha256.as_deref(),
+                            &NodeSymbol::Sink,
+                        )
                         .await?;
 
                     let context = Context::new(
@@ -432,10 +767,18 @@ The channels for the Inputs of Sink < {} > were not created.
                     let sink_node =
                         (constructor)(context.clone(), sink.configuration.clone(), inputs).await?;
 
-                    Runner::new(sink.id.clone(), sink_node, Some(library))
+                    Runner::new(sink.id.clone(), sink_node, library)
+                }
+                SinkVariant::File(file) => {
+                    let file_sink = FileSink::try_new(sink_id, file, inputs).await?;
+                    Runner::new(sink_id.clone(), Arc::new(file_sink), None)
+                }
+                SinkVariant::Stdout(stdout) => {
+                    let stdout_sink = StdoutSink::try_new(sink_id, stdout, inputs)?;
+                    Runner::new(sink_id.clone(), Arc::new(stdout_sink), None)
                 }
                 #[cfg(not(feature = "zenoh"))]
-                SinkVariant::Zenoh(_) => {
+                SinkVariant::Zenoh(_) | SinkVariant::ZenohQueryables(_) => {
                     bail!(
                         r#"
 The Zenoh-Flow runtime was compiled without the feature "zenoh" but includes a built-in Zenoh Sink.
@@ -455,6 +798,17 @@ Maybe change the features in the Cargo.toml?
                     )
                     .await?;
 
+                    Runner::new(sink_id.clone(), Arc::new(zenoh_sink), None)
+                }
+                #[cfg(feature = "zenoh")]
+                SinkVariant::ZenohQueryables(queryables) => {
+                    let zenoh_sink = ZenohQueryableSink::try_new(
+                        sink_id,
+                        self.session.clone(),
+                        queryables,
+                        inputs,
+                    )?;
+
                     Runner::new(sink_id.clone(), Arc::new(zenoh_sink), None)
                 }
             };
@@ -480,13 +834,17 @@ Maybe change the features in the Cargo.toml?
         &self,
         record: &DataFlowRecord,
         channels: &mut Channels,
-    ) -> Result<HashMap<NodeId, Runner>> {
+    ) -> Result<(
+        HashMap<NodeId, Runner>,
+        HashMap<NodeId, Arc<ConnectorCounters>>,
+    )> {
         use crate::runners::connectors::ZenohConnectorReceiver;
 
         let mut runners = HashMap::new();
+        let mut counters = HashMap::new();
         let assigned_nodes = match record.mapping().get(&self.runtime_id) {
             Some(nodes) => nodes,
-            None => return Ok(HashMap::default()),
+            None => return Ok((HashMap::default(), HashMap::default())),
         };
 
         for (receiver_id, receiver) in record
@@ -506,13 +864,14 @@ The channels for the Outputs of Connector Receiver < {} > were not created.
                 ZenohConnectorReceiver::try_new(self.session.clone(), receiver.clone(), outputs)
                     .await?;
 
+            counters.insert(receiver_id.clone(), runner.counters());
             runners.insert(
                 receiver_id.clone(),
                 Runner::new(receiver_id.clone(), Arc::new(runner), None),
             );
         }
 
-        Ok(runners)
+        Ok((runners, counters))
     }
 
     /// Attempts to load the Zenoh Senders from the provided [DataFlowRecord], returning a list of [Runners].
@@ -569,6 +928,58 @@ The channels for the Inputs of Connector Sender < {} > were not created.
         Ok(runners)
     }
 
+    /// Attempts to load, in a child helper process, the node whose implementation is located at [Url].
+    ///
+    /// # Errors
+    ///
+    /// This method can fail for the following reasons:
+    /// - the Url could not be resolved to a shared library or its digest did not match (see `try_resolve_library`),
+    /// - the helper process could not be spawned or failed to load the node.
+    #[allow(clippy::too_many_arguments)]
+    async fn try_load_isolated_node(
+        &self,
+        record: &DataFlowRecord,
+        node_id: &NodeId,
+        url: &Url,
+        sha256: Option<&str>,
+        node_symbol: NodeSymbol,
+        configuration: &Configuration,
+        inputs: Inputs,
+        outputs: Outputs,
+    ) -> Result<Runner> {
+        let (library_path, rust_library_path, digest) = self
+            .loader
+            .lock()
+            .await
+            .try_resolve_library(url, sha256, &node_symbol)?;
+
+        let spec = NodeSpec {
+            node_symbol,
+            flow_name: record.name().to_string(),
+            instance_id: record.instance_id().clone(),
+            runtime_id: self.runtime_id.clone(),
+            timestamp: self.hlc.new_timestamp(),
+            node_id: node_id.clone(),
+            library_path,
+            rust_library_path,
+            sha256: digest,
+            configuration: configuration.to_string(),
+            inputs: inputs.keys().cloned().collect(),
+            outputs: outputs.keys().cloned().collect(),
+        };
+
+        let isolated_node = IsolatedNode::try_new(
+            &self.isolation_helper,
+            spec,
+            self.hlc.clone(),
+            inputs,
+            outputs,
+        )
+        .await?;
+
+        Ok(Runner::new(node_id.clone(), Arc::new(isolated_node), None))
+    }
+
     /// Attempts to load the constructor of the node implementation located at [Url].
     ///
     /// This method is a convenience wrapper that automates locking and releasing the lock over the internal Loader
@@ -577,12 +988,180 @@ The channels for the Inputs of Connector Sender < {} > were not created.
     /// # Errors
     ///
     /// This method can fail if the Loader failed to load the constructor.
-    async fn try_load_constructor<C>(
+    async fn try_load_constructor<C: StaticConstructor>(
         &self,
         url: &Url,
+        sha256: Option<&str>,
         node_symbol: &NodeSymbol,
-    ) -> Result<(C, Arc<PathBuf>, Arc<Library>)> {
+    ) -> Result<(C, Arc<PathBuf>, Option<Arc<Library>>)> {
         let mut loader_write_guard = self.loader.lock().await;
-        loader_write_guard.try_load_constructor::<C>(url, node_symbol)
+        loader_write_guard.try_load_constructor::<C>(url, sha256, node_symbol)
+    }
+}
+
+#[cfg(all(test, feature = "zenoh"))]
+mod tests {
+    use zenoh_flow_commons::RuntimeId;
+    use zenoh_flow_descriptors::FlattenedDataFlowDescriptor;
+    use zenoh_flow_records::DataFlowRecord;
+
+    use crate::Runtime;
+
+    // The `operator-b` node, on `runtime_b`, closes the loop by feeding back its output to `operator-a`, on
+    // `runtime_a`.
+    fn looped_flow(runtime_a: &RuntimeId, runtime_b: &RuntimeId) -> FlattenedDataFlowDescriptor {
+        let flow = format!(
+            r#"
+name: test-loop
+sources:
+  - id: source
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out
+operators:
+  - id: operator-a
+    library: "file:///home/zenoh-flow/liboperator.so"
+    inputs:
+      - in
+      - feedback
+    outputs:
+      - out
+  - id: operator-b
+    library: "file:///home/zenoh-flow/liboperator.so"
+    inputs:
+      - in
+    outputs:
+      - out
+      - feedback
+sinks:
+  - id: sink
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in
+links:
+  - from:
+      node: source
+      output: out
+    to:
+      node: operator-a
+      input: in
+  - from:
+      node: operator-a
+      output: out
+    to:
+      node: operator-b
+      input: in
+  - from:
+      node: operator-b
+      output: feedback
+    to:
+      node: operator-a
+      input: feedback
+    feedback:
+      initial: 42
+      capacity: 2
+  - from:
+      node: operator-b
+      output: out
+    to:
+      node: sink
+      input: in
+mapping:
+  {runtime_a}:
+    - source
+    - operator-a
+  {runtime_b}:
+    - operator-b
+    - sink
+"#
+        );
+
+        serde_yaml::from_str::<FlattenedDataFlowDescriptor>(&flow).unwrap()
+    }
+
+    #[async_std::test]
+    async fn test_feedback_channels() {
+        let (id_a, id_b) = (RuntimeId::rand(), RuntimeId::rand());
+        let runtime_a = Runtime::builder("runtime-a")
+            .runtime_id(id_a.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let runtime_b = Runtime::builder("runtime-b")
+            .runtime_id(id_b.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        // All the nodes on the same runtime: the initial message is the first one received by `operator-a` and the
+        // channel holds at most `capacity` messages.
+        let record = DataFlowRecord::try_new(&looped_flow(&id_a, &id_a), &id_a).unwrap();
+        let channels = runtime_a.create_channels(&record).unwrap();
+        let (inputs, _) = channels.get(&"operator-a".into()).unwrap();
+        let feedback = inputs.get(&"feedback".into()).unwrap();
+        assert_eq!(feedback.capacity(), Some(2));
+        assert_eq!(feedback.len(), 1);
+        let initial = feedback.try_recv().unwrap();
+        assert_eq!(initial.payload().try_as_bytes().unwrap().as_ref(), b"42");
+        let (_, outputs) = channels.get(&"operator-b".into()).unwrap();
+        let senders = outputs.get(&"feedback".into()).unwrap();
+        assert_eq!(senders.len(), 1);
+        assert_eq!(senders[0].capacity(), Some(2));
+
+        // The feedback link crosses runtimes: only the runtime of `operator-a` sends the initial message.
+        let record = DataFlowRecord::try_new(&looped_flow(&id_a, &id_b), &id_a).unwrap();
+        let pending = |runtime: &Runtime| -> usize {
+            runtime
+                .create_channels(&record)
+                .unwrap()
+                .values()
+                .flat_map(|(inputs, _)| inputs.values())
+                .map(|receiver| receiver.len())
+                .sum()
+        };
+        assert_eq!(pending(&runtime_a), 1);
+        assert_eq!(pending(&runtime_b), 0);
+    }
+
+    #[async_std::test]
+    async fn test_isolated_builtin_rejected() {
+        let flow = r#"
+name: test-isolation
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    isolation: process
+    outputs:
+      - out
+sinks:
+  - id: sink
+    stdout:
+      input: in
+    inputs:
+      - in
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: sink
+      input: in
+"#;
+
+        let runtime = Runtime::builder("runtime-isolation").build().await.unwrap();
+        let record = DataFlowRecord::try_new(
+            &serde_yaml::from_str::<FlattenedDataFlowDescriptor>(flow).unwrap(),
+            runtime.id(),
+        )
+        .unwrap();
+        let error = runtime.try_load_data_flow(record).await.unwrap_err();
+        assert!(
+            format!("{error:?}").contains("A built-in node cannot be isolated in a process"),
+            "{error:?}"
+        );
     }
 }
@@ -15,26 +15,44 @@
 mod builder;
 pub use self::builder::RuntimeBuilder;
 
+#[cfg(feature = "zenoh")]
+mod liveliness;
+#[cfg(feature = "zenoh")]
+pub(crate) use self::liveliness::InstanceLiveliness;
+#[cfg(feature = "zenoh")]
+pub use self::liveliness::RuntimeLossPolicy;
+
 mod load;
 
+mod persistence;
+pub use self::persistence::RecoveryPolicy;
+use self::persistence::StateStore;
+
 use std::{
     collections::HashMap,
     fmt::{Debug, Display},
+    path::PathBuf,
     sync::Arc,
 };
 
 use anyhow::{anyhow, bail};
 use async_std::sync::{Mutex, RwLock};
+use futures::Stream;
 use thiserror::Error;
 use uhlc::HLC;
 #[cfg(feature = "zenoh")]
 use zenoh::Session;
+#[cfg(feature = "zenoh")]
+use zenoh_flow_commons::NodeId;
 #[cfg(feature = "shared-memory")]
 use zenoh_flow_commons::SharedMemoryConfiguration;
 use zenoh_flow_commons::{InstanceId, Result, RuntimeId};
 use zenoh_flow_records::DataFlowRecord;
 
+#[cfg(feature = "zenoh")]
+use crate::runners::sequence::ConnectorMetrics;
 use crate::{
+    events::{EventsHub, InstanceEvent, InstanceEventKind},
     instance::{DataFlowInstance, InstanceStatus},
     loader::Loader,
     InstanceState,
@@ -54,9 +72,17 @@ pub struct Runtime {
     pub(crate) hlc: Arc<HLC>,
     #[cfg(feature = "zenoh")]
     pub(crate) session: Arc<Session>,
+    #[cfg(feature = "zenoh")]
+    pub(crate) runtime_loss_policy: RuntimeLossPolicy,
     #[cfg(feature = "shared-memory")]
     pub(crate) shared_memory: SharedMemoryConfiguration,
     pub(crate) loader: Mutex<Loader>,
+    #[cfg(feature = "wasm")]
+    pub(crate) wasm_engine: wasmtime::Engine,
+    pub(crate) isolation_helper: PathBuf,
+    pub(crate) state_store: Option<StateStore>,
+    pub(crate) recovery_policy: RecoveryPolicy,
+    pub(crate) events: EventsHub,
     flows: RwLock<HashMap<InstanceId, Arc<RwLock<DataFlowInstance>>>>,
 }
 
@@ -117,6 +143,12 @@ impl Runtime {
         self.hlc.clone()
     }
 
+    /// Returns `true` if this runtime was given a [state directory](RuntimeBuilder::state_directory()), i.e. if the
+    /// data flow instances it manages survive a restart.
+    pub fn persists_instances(&self) -> bool {
+        self.state_store.is_some()
+    }
+
     /// Returns the [InstanceState] of the [DataFlowInstance]\(s\) managed by this Zenoh-Flow runtime.
     pub async fn instances_state(&self) -> HashMap<InstanceId, (Arc<str>, InstanceState)> {
         let flows = self.flows.read().await;
@@ -138,6 +170,93 @@ impl Runtime {
         self.session.clone()
     }
 
+    /// Returns a stream of the [events](InstanceEvent) concerning the data flow instances managed by this runtime.
+    ///
+    /// Only the events emitted after this call are received. A subscriber that does not consume its events fast
+    /// enough will miss some of them: the runtime is never slowed down by its subscribers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use zenoh_flow_runtime::Runtime;
+    /// # async_std::task::block_on(async {
+    /// let runtime = Runtime::builder("demo")
+    ///     .build()
+    ///     .await
+    ///     .expect("Failed to build the Runtime");
+    ///
+    /// let mut events = runtime.subscribe_events();
+    /// while let Some(event) = events.next().await {
+    ///     println!("{event}");
+    /// }
+    /// # });
+    /// ```
+    pub fn subscribe_events(&self) -> impl Stream<Item = InstanceEvent> + Send + Unpin + 'static {
+        self.events.subscribe().into_stream()
+    }
+
+    /// Persists the record and the state of the instance, if this runtime was given a state directory.
+    ///
+    /// A failure to persist is logged but does not interrupt the operation that changed the state.
+    pub(crate) async fn persist(&self, instance: &DataFlowInstance) {
+        if let Some(store) = &self.state_store {
+            if let Err(e) = store.save(&instance.record, &instance.state).await {
+                tracing::error!(
+                    "Failed to persist the state of instance < {} >: {:?}",
+                    instance.instance_id(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Reloads the instances persisted in the state directory, applying the [RecoveryPolicy] of this runtime.
+    ///
+    /// Note that the nodes of a persisted instance are assigned to runtimes through their identifier: a runtime
+    /// should keep the same identifier across restarts (e.g. by fixing the `id` of its Zenoh session) for the
+    /// recovered instances to be of any use.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the state directory could not be read. The instances that fail to be
+    /// loaded or started are logged and left in the corresponding state.
+    pub(crate) async fn try_recover_instances(&self) -> Result<()> {
+        let store = match &self.state_store {
+            Some(store) => store,
+            None => return Ok(()),
+        };
+
+        for persisted in store.load_all().await? {
+            let instance_id = persisted.record.instance_id().clone();
+
+            if self.recovery_policy == RecoveryPolicy::Discard {
+                store.remove(&instance_id).await?;
+                tracing::info!("Discarded persisted instance < {} >", instance_id);
+                continue;
+            }
+
+            let was_running = matches!(
+                persisted.state,
+                InstanceState::Running(_) | InstanceState::Degraded(_)
+            );
+            if let Err(e) = self.try_load_data_flow(persisted.record).await {
+                tracing::error!("Failed to recover instance < {} >: {:?}", instance_id, e);
+                continue;
+            }
+
+            if was_running && self.recovery_policy == RecoveryPolicy::Restart {
+                if let Err(e) = self.try_start_instance(&instance_id).await {
+                    tracing::error!("Failed to restart instance < {} >: {:?}", instance_id, e);
+                }
+            }
+
+            tracing::info!("Recovered instance < {} >", instance_id);
+        }
+
+        Ok(())
+    }
+
     /// Returns the [DataFlowRecord] associated with the provided instance.
     ///
     /// # Errors
@@ -162,6 +281,26 @@ impl Runtime {
         Err(DataFlowErr::NotFound)
     }
 
+    /// Returns, for each connector of the provided data flow instance receiving messages from another runtime, the
+    /// number of messages it received, and how many of them were lost, duplicated or reordered.
+    ///
+    /// The keys are the identifiers of the connectors, named after the node (and output) sending the messages.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// - this runtime does not manage a data flow with the provided id,
+    /// - the data flow is in an failed state.
+    #[cfg(feature = "zenoh")]
+    pub async fn try_get_connector_metrics(
+        &self,
+        id: &InstanceId,
+    ) -> std::result::Result<HashMap<NodeId, ConnectorMetrics>, DataFlowErr> {
+        let instance = self.try_get_instance(id).await?;
+        let metrics = instance.read().await.connector_metrics();
+        Ok(metrics)
+    }
+
     /// Returns the [status](InstanceStatus) of the provided data flow instance or [None] if this runtime does not
     /// manage this instance.
     pub async fn get_instance_status(&self, id: &InstanceId) -> Option<InstanceStatus> {
@@ -198,7 +337,8 @@ impl Runtime {
 
     /// Attempts to (re-)start the [DataFlowInstance] identified by the provided `id`.
     ///
-    /// Note that this method is idempotent: calling it on an already running data flow will do nothing.
+    /// Note that this method is idempotent: calling it on an already running data flow will do nothing. The same goes
+    /// for a degraded data flow, which stays degraded until the runtimes it lost are back.
     ///
     /// # Errors
     ///
@@ -213,7 +353,16 @@ impl Runtime {
         let instance = self.try_get_instance(id).await?;
         let mut instance_guard = instance.write().await;
 
+        if matches!(
+            instance_guard.state,
+            InstanceState::Running(_) | InstanceState::Degraded(_)
+        ) {
+            return Ok(());
+        }
+
         instance_guard.start(&self.hlc).await?;
+        self.persist(&instance_guard).await;
+        self.events.publish(id, InstanceEventKind::Started);
 
         tracing::info!("started");
 
@@ -233,13 +382,18 @@ impl Runtime {
     pub async fn try_abort_instance(&self, id: &InstanceId) -> Result<()> {
         let instance = self.try_get_instance(id).await?;
 
-        if !matches!(instance.read().await.state(), &InstanceState::Running(_)) {
+        if !matches!(
+            instance.read().await.state(),
+            &InstanceState::Running(_) | &InstanceState::Degraded(_)
+        ) {
             return Ok(());
         }
 
         let mut instance_guard = instance.write().await;
 
         instance_guard.abort(&self.hlc).await;
+        self.persist(&instance_guard).await;
+        self.events.publish(id, InstanceEventKind::Aborted);
 
         tracing::info!("aborted");
 
@@ -280,12 +434,52 @@ impl Runtime {
             }
         };
 
+        #[cfg(feature = "zenoh")]
+        self.announce_deletion(&instance).await;
         instance.abort(&self.hlc).await;
 
+        if let Some(store) = &self.state_store {
+            if let Err(e) = store.remove(id).await {
+                tracing::error!("Failed to remove the persisted state: {:?}", e);
+            }
+        }
+
+        self.events.publish(id, InstanceEventKind::Deleted);
+
         drop(instance); // Forcefully drop the instance so we can check if we can free up some Libraries.
         self.loader.lock().await.remove_unused_libraries();
         tracing::info!("deleted");
 
         Ok(())
     }
+
+    /// Stops this runtime: the nodes of all the [DataFlowInstance]\(s\) it manages are aborted and the instances are
+    /// dropped.
+    ///
+    /// Contrary to [try_delete_instance](Runtime::try_delete_instance()), what was persisted in the state directory is
+    /// kept, along with the state of each instance *before* it was stopped, such that a runtime later built over the
+    /// same directory recovers them following its [RecoveryPolicy]. Other runtimes are not contacted.
+    pub async fn stop(&self) {
+        let flows = std::mem::take(&mut *self.flows.write().await);
+
+        for (instance_id, instance) in flows {
+            let mut instance_guard = instance.write().await;
+            let state = instance_guard.state.clone();
+            instance_guard.abort(&self.hlc).await;
+
+            if let Some(store) = &self.state_store {
+                if let Err(e) = store.save(&instance_guard.record, &state).await {
+                    tracing::error!(
+                        "Failed to persist the state of instance < {} >: {:?}",
+                        instance_id,
+                        e
+                    );
+                }
+            }
+
+            tracing::info!("Stopped instance < {} >", instance_id);
+        }
+
+        self.loader.lock().await.remove_unused_libraries();
+    }
 }
@@ -0,0 +1,328 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use futures::{AsyncWriteExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use zenoh_flow_commons::{InstanceId, Result};
+use zenoh_flow_records::DataFlowRecord;
+
+use crate::InstanceState;
+
+/// What a [Runtime](crate::Runtime) does, when it starts, with the data flow instances it persisted before it stopped.
+///
+/// This policy only applies if a state directory was provided to the
+/// [RuntimeBuilder](crate::RuntimeBuilder::state_directory()).
+///
+/// # Example
+///
+/// In the configuration of a Zenoh-Flow daemon:
+///
+/// ```yaml
+/// state_directory: /var/lib/zenoh-flow
+/// recovery_policy: restart
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryPolicy {
+    /// The persisted instances are deleted, the Runtime starts empty.
+    Discard,
+    /// The persisted instances are loaded but not started.
+    Load,
+    /// The persisted instances are loaded and the ones that were running are started again.
+    #[default]
+    Restart,
+}
+
+/// A data flow instance, as it is written in the state directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PersistedInstance {
+    pub(crate) record: DataFlowRecord,
+    pub(crate) state: InstanceState,
+}
+
+/// The `StateStore` writes, in its directory, one JSON file per data flow instance named after its [InstanceId].
+#[derive(Clone)]
+pub(crate) struct StateStore {
+    directory: PathBuf,
+}
+
+impl StateStore {
+    /// Creates a new `StateStore`, creating its directory if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory could not be created.
+    pub(crate) fn try_new(directory: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&directory).context(format!(
+            "Failed to create the state directory:\n{}",
+            directory.display()
+        ))?;
+
+        Ok(Self { directory })
+    }
+
+    fn path(&self, instance_id: &InstanceId) -> PathBuf {
+        self.directory.join(format!("{instance_id}.json"))
+    }
+
+    /// Writes the record and the state of an instance, replacing what was previously persisted for that instance.
+    ///
+    /// The content is first written to a temporary file, flushed to the disk, which is then renamed such that a crash
+    /// (or a power loss) cannot leave a truncated file behind. The directory is flushed as well, for the renaming to be
+    /// durable.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the instance could not be serialised or written.
+    pub(crate) async fn save(&self, record: &DataFlowRecord, state: &InstanceState) -> Result<()> {
+        let persisted = PersistedInstance {
+            record: record.clone(),
+            state: state.clone(),
+        };
+        let content =
+            serde_json::to_vec_pretty(&persisted).context("Failed to serialise instance")?;
+
+        let path = self.path(record.instance_id());
+        let tmp_path = path.with_extension("json.tmp");
+        let mut file = async_std::fs::File::create(&tmp_path)
+            .await
+            .context(format!("Failed to create:\n{}", tmp_path.display()))?;
+        file.write_all(&content)
+            .await
+            .context(format!("Failed to write:\n{}", tmp_path.display()))?;
+        file.sync_all()
+            .await
+            .context(format!("Failed to flush:\n{}", tmp_path.display()))?;
+        drop(file);
+
+        async_std::fs::rename(&tmp_path, &path)
+            .await
+            .context(format!("Failed to rename to:\n{}", path.display()))?;
+
+        self.sync_directory().await
+    }
+
+    // Flushes the entries of the state directory to the disk, such that the renaming of a file survives a power loss.
+    //
+    // NOTE: Directories cannot be opened, and thus flushed, this way on Windows.
+    #[cfg(unix)]
+    async fn sync_directory(&self) -> Result<()> {
+        let directory = async_std::fs::File::open(&self.directory)
+            .await
+            .context(format!(
+                "Failed to open the state directory:\n{}",
+                self.directory.display()
+            ))?;
+        directory.sync_all().await.context(format!(
+            "Failed to flush the state directory:\n{}",
+            self.directory.display()
+        ))
+    }
+
+    #[cfg(not(unix))]
+    async fn sync_directory(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Removes what was persisted for that instance, if anything.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the file exists but could not be removed.
+    pub(crate) async fn remove(&self, instance_id: &InstanceId) -> Result<()> {
+        let path = self.path(instance_id);
+        match async_std::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).context(format!("Failed to remove:\n{}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads all the instances persisted in the state directory.
+    ///
+    /// The files that cannot be parsed are skipped (and logged).
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the state directory could not be read.
+    pub(crate) async fn load_all(&self) -> Result<Vec<PersistedInstance>> {
+        let mut entries = async_std::fs::read_dir(&self.directory)
+            .await
+            .context(format!(
+                "Failed to read the state directory:\n{}",
+                self.directory.display()
+            ))?;
+
+        let mut instances = Vec::default();
+        while let Some(entry) = entries.next().await {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+
+            let persisted = async_std::fs::read(&path)
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|content| {
+                    serde_json::from_slice::<PersistedInstance>(&content).map_err(Into::into)
+                });
+            match persisted {
+                Ok(persisted) => instances.push(persisted),
+                Err(e) => tracing::warn!("Skipping < {} >: {:?}", path.display(), e),
+            }
+        }
+
+        Ok(instances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uhlc::HLC;
+    use zenoh_flow_commons::RuntimeId;
+    use zenoh_flow_descriptors::FlattenedDataFlowDescriptor;
+    use zenoh_flow_records::DataFlowRecord;
+
+    use super::StateStore;
+    use crate::{InstanceState, Runtime};
+
+    #[async_std::test]
+    async fn test_state_store() {
+        let flow = r#"
+name: test-flow
+sources:
+  - id: source-0
+    library: "file:///home/zenoh-flow/libsource.so"
+    outputs:
+      - out-0
+sinks:
+  - id: sink-1
+    library: "file:///home/zenoh-flow/libsink.so"
+    inputs:
+      - in-1
+links:
+  - from:
+      node: source-0
+      output: out-0
+    to:
+      node: sink-1
+      input: in-1
+"#;
+        let descriptor = serde_yaml::from_str::<FlattenedDataFlowDescriptor>(flow).unwrap();
+        let record = DataFlowRecord::try_new(&descriptor, &RuntimeId::rand()).unwrap();
+
+        let directory = std::env::temp_dir().join(format!("zf-state-{}", uuid::Uuid::new_v4()));
+        let store = StateStore::try_new(directory.clone()).unwrap();
+        assert!(store.load_all().await.unwrap().is_empty());
+
+        let hlc = HLC::default();
+        store
+            .save(&record, &InstanceState::Loaded(hlc.new_timestamp()))
+            .await
+            .unwrap();
+        store
+            .save(&record, &InstanceState::Running(hlc.new_timestamp()))
+            .await
+            .unwrap();
+        // A file that is not a persisted instance is skipped.
+        std::fs::write(directory.join("garbage.json"), b"not json").unwrap();
+
+        let instances = store.load_all().await.unwrap();
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].record, record);
+        assert!(matches!(instances[0].state, InstanceState::Running(_)));
+
+        store.remove(record.instance_id()).await.unwrap();
+        // Removing twice is not an error.
+        store.remove(record.instance_id()).await.unwrap();
+        assert!(store.load_all().await.unwrap().is_empty());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_stop_and_recover() {
+        let directory = std::env::temp_dir().join(format!("zf-state-{}", uuid::Uuid::new_v4()));
+        let flow = format!(
+            r#"
+name: test-flow
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    outputs:
+      - out
+sinks:
+  - id: file
+    file:
+      path: {}
+      input: in
+    inputs:
+      - in
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: file
+      input: in
+"#,
+            directory.join("ticks.log").display()
+        );
+        let runtime_id = RuntimeId::rand();
+        let build = || {
+            Runtime::builder("runtime-persistence")
+                .runtime_id(runtime_id.clone())
+                .unwrap()
+                .state_directory(directory.clone())
+                .build()
+        };
+
+        let runtime = build().await.unwrap();
+        assert!(runtime.persists_instances());
+        let record = DataFlowRecord::try_new(
+            &serde_yaml::from_str::<FlattenedDataFlowDescriptor>(&flow).unwrap(),
+            runtime.id(),
+        )
+        .unwrap();
+        let instance_id = record.instance_id().clone();
+        runtime.try_load_data_flow(record).await.unwrap();
+        runtime.try_start_instance(&instance_id).await.unwrap();
+
+        // Stopping the runtime drops its instances but keeps them, as they were, in the state directory.
+        runtime.stop().await;
+        assert!(runtime.instances_state().await.is_empty());
+        drop(runtime);
+
+        let runtime = build().await.unwrap();
+        let states = runtime.instances_state().await;
+        assert!(matches!(
+            states.get(&instance_id),
+            Some((_, InstanceState::Running(_)))
+        ));
+
+        // Only an explicit deletion removes what was persisted.
+        runtime.try_delete_instance(&instance_id).await.unwrap();
+        runtime.stop().await;
+        drop(runtime);
+        assert!(build().await.unwrap().instances_state().await.is_empty());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
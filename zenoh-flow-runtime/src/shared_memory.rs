@@ -20,7 +20,7 @@ use zenoh::{
     shm::{SharedMemoryBuf, SharedMemoryManager},
 };
 use zenoh_flow_commons::{NodeId, Result, SharedMemoryConfiguration};
-use zenoh_flow_nodes::prelude::{DataMessage, LinkMessage};
+use zenoh_flow_nodes::prelude::LinkMessage;
 
 pub(crate) struct SharedMemory {
     session: Arc<Session>,
@@ -45,43 +45,75 @@ impl SharedMemory {
         }
     }
 
-    /// This method tries to send the [LinkMessage] via Zenoh's shared memory.
+    /// This method tries to send the `envelope` of a message (see the `wire` module) via Zenoh's shared memory, with
+    /// the provided `priority` and `congestion_control`.
     ///
     /// # Errors
     ///
     /// This method can fail for multiple reasons:
     /// 1. Zenoh's [SharedMemoryManager] did not manage to allocate a buffer.
-    /// 2. The serialization of the message into the buffer failed.
-    /// 3. Zenoh failed to send the message via shared memory.
-    pub(crate) async fn try_send_message(
+    /// 2. Zenoh failed to send the message via shared memory.
+    pub(crate) async fn try_send_envelope(
         &mut self,
         key_expr: &str,
-        message: LinkMessage,
-        message_buffer: &mut Vec<u8>,
-        payload_buffer: &mut Vec<u8>,
+        envelope: &[u8],
+        priority: Priority,
+        congestion_control: CongestionControl,
     ) -> Result<()> {
-        message.serialize_bincode_into(message_buffer, payload_buffer)?;
-        self.try_put_buffer(key_expr, message_buffer).await
+        self.try_put_buffer(
+            key_expr,
+            Encoding::default(),
+            envelope,
+            priority,
+            congestion_control,
+        )
+        .await
     }
 
+    /// This method tries to send the payload of the `message` via Zenoh's shared memory, with the provided `encoding`,
+    /// `priority` and `congestion_control`.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail for the same reasons as [try_send_envelope](SharedMemory::try_send_envelope) or if the
+    /// payload of the message could not be serialised.
     pub(crate) async fn try_send_payload(
         &mut self,
         key_expr: &str,
-        data: DataMessage,
+        encoding: Encoding,
+        message: &LinkMessage,
         payload_buffer: &mut Vec<u8>,
+        priority: Priority,
+        congestion_control: CongestionControl,
     ) -> Result<()> {
-        data.try_as_bytes_into(payload_buffer)?;
-        self.try_put_buffer(key_expr, payload_buffer).await
+        message.payload().try_as_bytes_into(payload_buffer)?;
+        self.try_put_buffer(
+            key_expr,
+            encoding,
+            payload_buffer,
+            priority,
+            congestion_control,
+        )
+        .await
     }
 
-    async fn try_put_buffer(&mut self, key_expr: &str, buffer: &mut Vec<u8>) -> Result<()> {
+    async fn try_put_buffer(
+        &mut self,
+        key_expr: &str,
+        encoding: Encoding,
+        buffer: &[u8],
+        priority: Priority,
+        congestion_control: CongestionControl,
+    ) -> Result<()> {
         let mut shm_buffer = self.try_allocate_buffer(buffer.len()).await?;
         let slice = unsafe { shm_buffer.as_mut_slice() };
-        slice.clone_from_slice(buffer.as_mut_slice());
+        slice.clone_from_slice(buffer);
 
         self.session
             .put(key_expr, shm_buffer)
-            .congestion_control(CongestionControl::Block)
+            .encoding(encoding)
+            .priority(priority)
+            .congestion_control(congestion_control)
             .res()
             .await
             .map_err(|e| {
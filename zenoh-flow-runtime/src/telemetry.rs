@@ -0,0 +1,276 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! [OpenTelemetry](https://opentelemetry.io/) integration, enabled with the feature `opentelemetry`.
+//!
+//! When this feature is enabled, the runtime creates a span every time a node sends or receives a message:
+//! - `<node>.<output> send` when a message is sent, its context is attached to the message,
+//! - `<node>.<input> recv` when a message is received, as a child of the span whose context is attached to it.
+//!
+//! A message sent by a node is a child of the last message it received. Hence, as the context attached to a message is
+//! carried over Zenoh when the nodes are on different runtimes, a single trace shows the whole journey of a message
+//! in the data flow.
+//!
+//! The spans are created with the tracer `zenoh-flow` obtained from the [global](opentelemetry::global) tracer
+//! provider at the time a data flow instance is loaded. It is up to the application embedding the runtime to install
+//! a provider (and its exporter): without one, the contexts are still propagated but no span is recorded.
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use opentelemetry::{
+    global::{self, BoxedTracer},
+    trace::{
+        Span, SpanBuilder, SpanContext, SpanId, SpanKind, TraceContextExt, TraceFlags, TraceId,
+        TraceState, Tracer,
+    },
+    Context, KeyValue,
+};
+use zenoh_flow_commons::{InstanceId, NodeId, PortId};
+use zenoh_flow_nodes::{MessageTracer, TraceContext};
+
+/// The name of the tracer used by Zenoh-Flow.
+const TRACER_NAME: &str = "zenoh-flow";
+
+/// The `OpenTelemetryTracer` creates the spans of a single node.
+pub(crate) struct OpenTelemetryTracer {
+    instance_id: InstanceId,
+    node_id: NodeId,
+    tracer: BoxedTracer,
+    // The context of the span of the last message received.
+    current: Mutex<Option<SpanContext>>,
+}
+
+impl Debug for OpenTelemetryTracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenTelemetryTracer")
+            .field("instance_id", &self.instance_id)
+            .field("node_id", &self.node_id)
+            .finish()
+    }
+}
+
+impl OpenTelemetryTracer {
+    /// Creates the tracer of the node `node_id`, relying on the global tracer provider.
+    pub(crate) fn new(instance_id: InstanceId, node_id: NodeId) -> Arc<Self> {
+        Self::with_tracer(instance_id, node_id, global::tracer(TRACER_NAME))
+    }
+
+    /// Creates the tracer of the node `node_id`, relying on the provided `tracer`.
+    pub(crate) fn with_tracer(
+        instance_id: InstanceId,
+        node_id: NodeId,
+        tracer: BoxedTracer,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            instance_id,
+            node_id,
+            tracer,
+            current: Mutex::new(None),
+        })
+    }
+
+    /// Creates (and ends) a span of the provided `kind`, child of `parent` (if any), returning its context.
+    fn span(&self, port: &PortId, kind: SpanKind, parent: Option<SpanContext>) -> SpanContext {
+        let operation = match kind {
+            SpanKind::Producer => "send",
+            _ => "recv",
+        };
+        let builder = SpanBuilder::from_name(format!("{}.{} {}", self.node_id, port, operation))
+            .with_kind(kind)
+            .with_attributes(vec![
+                KeyValue::new("zenoh_flow.instance_id", self.instance_id.to_string()),
+                KeyValue::new("zenoh_flow.node_id", self.node_id.to_string()),
+                KeyValue::new("zenoh_flow.port_id", port.to_string()),
+            ]);
+        let parent = match parent {
+            Some(span_context) => Context::new().with_remote_span_context(span_context),
+            None => Context::new(),
+        };
+
+        let mut span = self.tracer.build_with_context(builder, &parent);
+        let span_context = span.span_context().clone();
+        span.end();
+        span_context
+    }
+}
+
+impl MessageTracer for OpenTelemetryTracer {
+    fn on_recv(&self, port: &PortId, context: Option<&TraceContext>) {
+        // NOTE: A message without context (e.g. coming from a node that was not traced) starts a new trace, the
+        // messages sent afterwards should not be attached to the trace of a previous message.
+        let current = context
+            .map(|context| self.span(port, SpanKind::Consumer, Some(to_span_context(context))));
+
+        *self
+            .current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = current;
+    }
+
+    fn on_send(&self, port: &PortId) -> Option<TraceContext> {
+        let parent = self
+            .current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+
+        from_span_context(&self.span(port, SpanKind::Producer, parent))
+    }
+}
+
+fn to_span_context(context: &TraceContext) -> SpanContext {
+    SpanContext::new(
+        TraceId::from_bytes(context.trace_id),
+        SpanId::from_bytes(context.span_id),
+        TraceFlags::new(context.trace_flags),
+        true,
+        TraceState::default(),
+    )
+}
+
+fn from_span_context(span_context: &SpanContext) -> Option<TraceContext> {
+    if !span_context.is_valid() {
+        return None;
+    }
+
+    Some(TraceContext {
+        trace_id: span_context.trace_id().to_bytes(),
+        span_id: span_context.span_id().to_bytes(),
+        trace_flags: span_context.trace_flags().to_u8(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::BoxFuture;
+    use opentelemetry::{
+        global::BoxedTracer,
+        trace::{SpanId, TracerProvider as _},
+    };
+    use opentelemetry_sdk::{
+        export::trace::{ExportResult, SpanData, SpanExporter},
+        trace::TracerProvider,
+    };
+    use uhlc::HLC;
+    use zenoh_flow_commons::InstanceId;
+    use zenoh_flow_nodes::prelude::{Inputs, LinkMessage, Outputs};
+
+    use super::OpenTelemetryTracer;
+
+    #[derive(Debug, Clone, Default)]
+    struct InMemoryExporter {
+        spans: Arc<Mutex<Vec<SpanData>>>,
+    }
+
+    impl SpanExporter for InMemoryExporter {
+        fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+            self.spans.lock().unwrap().extend(batch);
+            Box::pin(std::future::ready(Ok(())))
+        }
+    }
+
+    #[test]
+    fn test_trace_propagation() {
+        let exporter = InMemoryExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let hlc = Arc::new(HLC::default());
+        let instance_id = InstanceId::from(uuid::Uuid::new_v4());
+        let tracer = |node: &str| {
+            OpenTelemetryTracer::with_tracer(
+                instance_id.clone(),
+                node.into(),
+                BoxedTracer::new(Box::new(provider.tracer("test"))),
+            )
+        };
+
+        // source --> operator ==(Zenoh)==> sink
+        let (tx_source, rx_operator) = flume::unbounded::<LinkMessage>();
+        let (tx_operator, rx_sink) = flume::unbounded::<LinkMessage>();
+
+        let mut source_outputs = Outputs::new(hlc.clone());
+        source_outputs.insert("out".into(), tx_source);
+        source_outputs.set_tracer(tracer("source"));
+        let source_output = source_outputs.take("out").unwrap().raw();
+
+        let operator_tracer = tracer("operator");
+        let mut operator_inputs = Inputs::default();
+        operator_inputs.insert("in".into(), rx_operator);
+        operator_inputs.set_tracer(operator_tracer.clone());
+        let operator_input = operator_inputs.take("in").unwrap().raw();
+        let mut operator_outputs = Outputs::new(hlc.clone());
+        operator_outputs.insert("out".into(), tx_operator);
+        operator_outputs.set_tracer(operator_tracer);
+        let operator_output = operator_outputs.take("out").unwrap().raw();
+
+        let (tx_zenoh, rx_zenoh) = flume::unbounded::<LinkMessage>();
+        let mut sink_inputs = Inputs::default();
+        sink_inputs.insert("in".into(), rx_zenoh);
+        sink_inputs.set_tracer(tracer("sink"));
+        let sink_input = sink_inputs.take("in").unwrap().raw();
+
+        source_output.try_send(vec![0u8], None).unwrap();
+        operator_input.try_recv().unwrap().unwrap();
+        operator_output.try_send(vec![1u8], None).unwrap();
+        // What the Zenoh connectors do: serialise the message, publish it and deserialise it.
+        let (mut message_buffer, mut payload_buffer) = (Vec::new(), Vec::new());
+        rx_sink
+            .try_recv()
+            .unwrap()
+            .serialize_bincode_into(&mut message_buffer, &mut payload_buffer)
+            .unwrap();
+        tx_zenoh
+            .send(bincode::deserialize(&message_buffer).unwrap())
+            .unwrap();
+        sink_input.try_recv().unwrap().unwrap();
+
+        provider.force_flush();
+        let spans = exporter.spans.lock().unwrap().clone();
+        let span = |name: &str| {
+            spans
+                .iter()
+                .find(|span| span.name == name)
+                .unwrap_or_else(|| panic!("No span < {name} >"))
+        };
+
+        let source_send = span("source.out send");
+        let operator_recv = span("operator.in recv");
+        let operator_send = span("operator.out send");
+        let sink_recv = span("sink.in recv");
+
+        assert_eq!(source_send.parent_span_id, SpanId::INVALID);
+        assert_eq!(
+            operator_recv.parent_span_id,
+            source_send.span_context.span_id()
+        );
+        assert_eq!(
+            operator_send.parent_span_id,
+            operator_recv.span_context.span_id()
+        );
+        assert_eq!(
+            sink_recv.parent_span_id,
+            operator_send.span_context.span_id()
+        );
+        let trace_id = source_send.span_context.trace_id();
+        assert!(spans
+            .iter()
+            .all(|span| span.span_context.trace_id() == trace_id));
+    }
+}
@@ -0,0 +1,676 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The format of the messages exchanged, over Zenoh, by the connectors of a link between two runtimes.
+//!
+//! Each publication is an envelope made of a header followed by a body:
+//!
+//! | Field   | Size (bytes) | Content                                                                     |
+//! |---------|--------------|-----------------------------------------------------------------------------|
+//! | magic   | 2            | The bytes [MAGIC].                                                          |
+//! | version | 1            | The [VERSION] of the format.                                                |
+//! | flags   | 1            | The compression of the body, whether it is a batch and/or sequenced.        |
+//! | seq     | 0 or 8       | The sequence number of the (first) message, if it is sequenced.             |
+//! | epoch   | 0 or 8       | The epoch of the sender, if it is announced.                                |
+//! | body    | *            | The [bincode] serialisation of the message(s) (`LinkMessage`), compressed.  |
+//!
+//! The compression of the body is indicated by the flags: `0` for none, `1` for LZ4 (the size of the decompressed
+//! body is prepended, as a little-endian `u32`) and `2` for a Zstandard frame. It is configured per link, see the
+//! `compression` field of a link descriptor. A decompressed body cannot exceed [MAX_BODY_SIZE] bytes.
+//!
+//! If the third bit of the flags is set, the body is a batch: the messages are serialised one after the other, each
+//! preceded by its size as a little-endian `u32`. The whole batch is then compressed. Batching is also configured per
+//! link, see the `batching` field of a link descriptor.
+//!
+//! If the fourth bit of the flags is set, the header contains the sequence number of the message, as a little-endian
+//! `u64`. For a batch, it is the sequence number of its first message, the others being numbered consecutively. The
+//! sequence numbers of a link start at `0` and allow the receiver to detect lost, duplicated and reordered messages.
+//!
+//! If the fifth bit of the flags is set, the header ends with the epoch of the sender, as a little-endian `u64`: a
+//! random number drawn when it was created. The numbering of a link starts over with each epoch, a new epoch thus tells
+//! the receiver that the sender was restarted.
+//!
+//! A receiver does not need to know how the sender is configured: everything is described by the header. Publications
+//! without header, i.e. produced by a runtime predating this format, are still understood.
+//!
+//! # Versions
+//!
+//! - `1`: the messages carry their payload, timestamp and trace context.
+//! - `2`: the messages also carry the metadata of the Zenoh sample they were created from and the suffix of the key
+//!   expression on which a built-in Zenoh Sink should publish them.
+//!
+//! The envelopes of all these versions are understood by this runtime.
+
+use std::{
+    io::Read,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+use zenoh_flow_commons::{BatchingConfiguration, Compression, Result};
+use zenoh_flow_nodes::prelude::{LinkMessage, Payload, Timestamp};
+
+use crate::recording::deserialize_traced;
+
+/// The bytes every envelope starts with.
+pub const MAGIC: &[u8; 2] = b"ZF";
+
+/// The version of the format of the envelopes produced by this runtime.
+pub const VERSION: u8 = 2;
+
+/// The size of the header: magic, version and flags.
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// The bits of the flags indicating the compression of the body.
+const COMPRESSION_MASK: u8 = 0b0000_0011;
+
+/// The bit of the flags indicating that the body is a batch of messages.
+const BATCH_FLAG: u8 = 0b0000_0100;
+
+/// The bit of the flags indicating that the header ends with a sequence number.
+const SEQUENCE_FLAG: u8 = 0b0000_1000;
+
+/// The bit of the flags indicating that the header ends with the epoch of the sender.
+const EPOCH_FLAG: u8 = 0b0001_0000;
+
+/// The size of the sequence number and of the epoch.
+const U64_LEN: usize = std::mem::size_of::<u64>();
+
+/// The size of the length preceding each message of a batch.
+const LEN_SIZE: usize = std::mem::size_of::<u32>();
+
+/// The maximum capacity, in bytes, allocated upfront for the body of a batch.
+///
+/// The `max-size` of a batch is only a publication threshold: beyond this capacity, the body grows as needed.
+const BATCH_INITIAL_CAPACITY: usize = 64 * 1024;
+
+/// The compression level used for Zstandard, `0` selects its default.
+const ZSTD_LEVEL: i32 = 0;
+
+/// The maximum size, in bytes, of a decompressed body.
+///
+/// The envelopes are received from the network: without this limit, a small compressed body could make the receiver
+/// allocate an arbitrary amount of memory.
+pub const MAX_BODY_SIZE: usize = 256 * 1024 * 1024;
+
+fn compression_flags(compression: Compression) -> u8 {
+    match compression {
+        Compression::None => 0,
+        Compression::Lz4 => 1,
+        Compression::Zstd => 2,
+    }
+}
+
+/// The content of a publication.
+#[derive(Debug)]
+pub(crate) struct Decoded {
+    /// The sequence number of the first message, if the sender numbered them.
+    pub(crate) sequence: Option<u64>,
+    /// The epoch of the sender, if it announced it.
+    pub(crate) epoch: Option<u64>,
+    /// The messages, in the order they were sent.
+    pub(crate) messages: Vec<LinkMessage>,
+}
+
+/// Writes, in `buffer`, the envelope of the `message`, numbered `sequence` in the `epoch` of the sender, with its body
+/// compressed as requested.
+///
+/// The `message_buffer` and `payload_buffer` are used to serialise the message, they are cleared and reused between
+/// calls, as is the `buffer`.
+///
+/// # Errors
+///
+/// This function will return an error if the message could not be serialised or compressed.
+pub(crate) fn encode_into(
+    message: &LinkMessage,
+    compression: Compression,
+    epoch: u64,
+    sequence: u64,
+    buffer: &mut Vec<u8>,
+    message_buffer: &mut Vec<u8>,
+    payload_buffer: &mut Vec<u8>,
+) -> Result<()> {
+    message.serialize_bincode_into(message_buffer, payload_buffer)?;
+    write_envelope(0, compression, epoch, sequence, message_buffer, buffer)
+}
+
+fn write_envelope(
+    flags: u8,
+    compression: Compression,
+    epoch: u64,
+    sequence: u64,
+    body: &[u8],
+    buffer: &mut Vec<u8>,
+) -> Result<()> {
+    buffer.clear();
+    buffer.extend_from_slice(MAGIC);
+    buffer.push(VERSION);
+    buffer.push(flags | SEQUENCE_FLAG | EPOCH_FLAG | compression_flags(compression));
+    buffer.extend_from_slice(&sequence.to_le_bytes());
+    buffer.extend_from_slice(&epoch.to_le_bytes());
+
+    match compression {
+        Compression::None => buffer.extend_from_slice(body),
+        Compression::Lz4 => buffer.extend_from_slice(&lz4_flex::compress_prepend_size(body)),
+        Compression::Zstd => zstd::stream::copy_encode(body, &mut *buffer, ZSTD_LEVEL)
+            .context("Failed to compress the message with Zstandard")?,
+    }
+
+    Ok(())
+}
+
+/// A `Batch` aggregates the messages of a link until its configured size or latency bound is reached.
+pub(crate) struct Batch {
+    configuration: BatchingConfiguration,
+    body: Vec<u8>,
+    len: u64,
+    // The instant at which the oldest message of the batch was pushed.
+    started: Option<Instant>,
+}
+
+impl Batch {
+    pub(crate) fn new(configuration: BatchingConfiguration) -> Self {
+        Self {
+            configuration,
+            body: Vec::with_capacity(configuration.max_size.min(BATCH_INITIAL_CAPACITY)),
+            len: 0,
+            started: None,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.started.is_none()
+    }
+
+    /// Returns the number of messages in the batch.
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns the instant at which the batch has to be published, if it contains at least one message.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.started
+            .map(|started| started + Duration::from_micros(self.configuration.max_latency))
+    }
+
+    /// Returns `true` if the batch has reached its maximum size or if its oldest message has reached its maximum
+    /// latency.
+    pub(crate) fn is_ready(&self) -> bool {
+        self.body.len() >= self.configuration.max_size
+            || self
+                .deadline()
+                .is_some_and(|deadline| deadline <= Instant::now())
+    }
+
+    /// Appends the `message` to the batch.
+    ///
+    /// The `message_buffer` and `payload_buffer` are used to serialise the message, see [encode_into].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message could not be serialised or if its size does not fit in a
+    /// `u32`.
+    pub(crate) fn push(
+        &mut self,
+        message: &LinkMessage,
+        message_buffer: &mut Vec<u8>,
+        payload_buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        message.serialize_bincode_into(message_buffer, payload_buffer)?;
+        let len = u32::try_from(message_buffer.len())
+            .context("The message is too large to be batched")?;
+
+        self.body.extend_from_slice(&len.to_le_bytes());
+        self.body.extend_from_slice(message_buffer);
+        self.len += 1;
+        self.started.get_or_insert_with(Instant::now);
+        Ok(())
+    }
+
+    /// Writes, in `buffer`, the envelope of the messages of the batch, compressed as requested, and empties it.
+    ///
+    /// The messages are numbered consecutively, starting at `sequence`, in the `epoch` of the sender.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the batch could not be compressed.
+    pub(crate) fn encode_into(
+        &mut self,
+        compression: Compression,
+        epoch: u64,
+        sequence: u64,
+        buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        let res = write_envelope(BATCH_FLAG, compression, epoch, sequence, &self.body, buffer);
+        self.body.clear();
+        self.len = 0;
+        self.started = None;
+        res
+    }
+}
+
+/// Returns the message(s) contained in the `bytes` of a publication, in the order they were sent.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - the envelope was produced with a version of the format, or with flags, unknown to this runtime,
+/// - the body could not be decompressed or deserialised,
+/// - the sequence numbers of the messages of a batch exceed `u64::MAX`.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Decoded> {
+    if !bytes.starts_with(MAGIC) {
+        return decode_legacy(bytes).map(|message| Decoded {
+            sequence: None,
+            epoch: None,
+            messages: vec![message],
+        });
+    }
+
+    if bytes.len() < HEADER_LEN {
+        bail!(
+            "Truncated header: expected {} bytes, received {}",
+            HEADER_LEN,
+            bytes.len()
+        )
+    }
+
+    let (version, flags, mut body) = (bytes[2], bytes[3], &bytes[HEADER_LEN..]);
+    let deserialize: fn(&[u8]) -> bincode::Result<LinkMessage> = match version {
+        1 => deserialize_traced,
+        VERSION => |bytes| bincode::deserialize(bytes),
+        _ => bail!(
+            r#"
+Received a message in the version < {} > of the wire format, this runtime only supports the versions < 1 > to < {} >.
+Was the runtime sending it upgraded? If so, this runtime should be upgraded as well.
+"#,
+            version,
+            VERSION
+        ),
+    };
+
+    if flags & !(COMPRESSION_MASK | BATCH_FLAG | SEQUENCE_FLAG | EPOCH_FLAG) != 0 {
+        bail!("Received a message with unknown flags: {:#010b}", flags)
+    }
+
+    let mut sequence = None;
+    if flags & SEQUENCE_FLAG != 0 {
+        sequence =
+            Some(read_u64(&mut body).context("Truncated header: missing the sequence number")?);
+    }
+
+    let mut epoch = None;
+    if flags & EPOCH_FLAG != 0 {
+        epoch = Some(read_u64(&mut body).context("Truncated header: missing the epoch")?);
+    }
+
+    let body = match flags & COMPRESSION_MASK {
+        0 => std::borrow::Cow::Borrowed(body),
+        1 => std::borrow::Cow::Owned(decompress_lz4(body)?),
+        2 => std::borrow::Cow::Owned(decompress_zstd(body)?),
+        unknown => bail!(
+            "Received a message with an unknown compression: < {} >",
+            unknown
+        ),
+    };
+
+    if flags & BATCH_FLAG == 0 {
+        return Ok(Decoded {
+            sequence,
+            epoch,
+            messages: vec![deserialize(&body).context("Failed to deserialise the message")?],
+        });
+    }
+
+    let mut messages = Vec::new();
+    let mut body = &body[..];
+    while !body.is_empty() {
+        if body.len() < LEN_SIZE {
+            bail!("Truncated batch: missing the size of the message")
+        }
+        let (len, rest) = body.split_at(LEN_SIZE);
+        let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        if rest.len() < len {
+            bail!(
+                "Truncated batch: expected a message of {} bytes, {} remaining",
+                len,
+                rest.len()
+            )
+        }
+        let (message, rest) = rest.split_at(len);
+        messages
+            .push(deserialize(message).context("Failed to deserialise a message of the batch")?);
+        body = rest;
+    }
+
+    if let Some(first) = sequence {
+        if first
+            .checked_add(messages.len().saturating_sub(1) as u64)
+            .is_none()
+        {
+            bail!(
+                "Invalid batch: the sequence numbers of its {} messages, starting at {}, overflow",
+                messages.len(),
+                first
+            )
+        }
+    }
+
+    Ok(Decoded {
+        sequence,
+        epoch,
+        messages,
+    })
+}
+
+// Decompresses a LZ4 body, whose decompressed size is prepended, checking this size before allocating it.
+fn decompress_lz4(body: &[u8]) -> Result<Vec<u8>> {
+    if body.len() < LEN_SIZE {
+        bail!("Truncated body: missing the size of the message compressed with LZ4")
+    }
+    let len = u32::from_le_bytes([body[0], body[1], body[2], body[3]]) as usize;
+    if len > MAX_BODY_SIZE {
+        bail!(
+            "The message compressed with LZ4 announces {} bytes, more than the maximum of {} bytes",
+            len,
+            MAX_BODY_SIZE
+        )
+    }
+
+    lz4_flex::decompress_size_prepended(body).context("Failed to decompress the message with LZ4")
+}
+
+// Decompresses a Zstandard body, stopping as soon as it exceeds the maximum size.
+fn decompress_zstd(body: &[u8]) -> Result<Vec<u8>> {
+    let decoder = zstd::stream::read::Decoder::new(body)
+        .context("Failed to decompress the message with Zstandard")?;
+
+    let mut decompressed = Vec::new();
+    decoder
+        .take(MAX_BODY_SIZE as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .context("Failed to decompress the message with Zstandard")?;
+    if decompressed.len() > MAX_BODY_SIZE {
+        bail!(
+            "The message compressed with Zstandard exceeds the maximum of {} bytes once decompressed",
+            MAX_BODY_SIZE
+        )
+    }
+
+    Ok(decompressed)
+}
+
+// Reads a little-endian `u64` at the start of `bytes`, advancing them.
+fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+    if bytes.len() < U64_LEN {
+        return None;
+    }
+    let (le_bytes, rest) = bytes.split_at(U64_LEN);
+    *bytes = rest;
+    Some(u64::from_le_bytes(
+        le_bytes
+            .try_into()
+            .expect("the slice has the size of a u64"),
+    ))
+}
+
+// Publications without envelope contain the bincode serialisation of the message, as it was before the sample
+// metadata and key suffix were added. Runtimes predating the trace context of the messages only serialised their
+// payload and timestamp.
+//
+// NOTE: As bincode ignores trailing bytes, the most recent layout has to be tried first.
+fn decode_legacy(bytes: &[u8]) -> Result<LinkMessage> {
+    deserialize_traced(bytes)
+        .or_else(|_| {
+            bincode::deserialize::<(Payload, Timestamp)>(bytes)
+                .map(|(payload, timestamp)| LinkMessage::new(payload, timestamp))
+        })
+        .context("Failed to deserialise a message without envelope, is it coming from a Zenoh-Flow runtime?")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use uhlc::HLC;
+    use zenoh_flow_commons::{BatchingConfiguration, Compression};
+    use zenoh_flow_nodes::{
+        prelude::{LinkMessage, Payload, SampleMetadata},
+        TraceContext,
+    };
+
+    use super::{
+        decode, encode_into, Batch, BATCH_INITIAL_CAPACITY, MAGIC, MAX_BODY_SIZE, SEQUENCE_FLAG,
+        VERSION,
+    };
+
+    #[test]
+    fn test_encode_decode() {
+        let hlc = HLC::default();
+        let message = LinkMessage::new(Payload::from(vec![42u8; 1024]), hlc.new_timestamp());
+        let (mut buffer, mut message_buffer, mut payload_buffer) =
+            (Vec::new(), Vec::new(), Vec::new());
+
+        for compression in [Compression::None, Compression::Lz4, Compression::Zstd] {
+            encode_into(
+                &message,
+                compression,
+                11,
+                7,
+                &mut buffer,
+                &mut message_buffer,
+                &mut payload_buffer,
+            )
+            .unwrap();
+            assert!(buffer.starts_with(MAGIC));
+            if !compression.is_none() {
+                assert!(buffer.len() < message_buffer.len());
+            }
+
+            let decoded = decode(&buffer).unwrap();
+            assert_eq!(decoded.sequence, Some(7));
+            assert_eq!(decoded.epoch, Some(11));
+            assert_eq!(decoded.messages.len(), 1);
+            let decoded = &decoded.messages[0];
+            assert_eq!(decoded.timestamp(), message.timestamp());
+            assert_eq!(*decoded.payload().try_as_bytes().unwrap(), vec![42u8; 1024]);
+        }
+
+        // Messages without envelope, in the current and in the legacy layouts.
+        let decoded = decode(&message_buffer).unwrap();
+        assert_eq!(decoded.sequence, None);
+        assert_eq!(decoded.messages[0].timestamp(), message.timestamp());
+        let legacy = bincode::serialize(&(message.payload(), message.timestamp())).unwrap();
+        let decoded = decode(&legacy).unwrap();
+        assert_eq!(decoded.messages[0].timestamp(), message.timestamp());
+
+        // Unknown version, flags and compression.
+        let unknown_version = [&MAGIC[..], &[VERSION + 1, 0]].concat();
+        assert!(format!("{:?}", decode(&unknown_version).unwrap_err()).contains("version"));
+        assert!(decode(&[&MAGIC[..], &[VERSION, 0b1000_0000]].concat()).is_err());
+        assert!(decode(&[&MAGIC[..], &[VERSION, 3]].concat()).is_err());
+        assert!(decode(&MAGIC[..]).is_err());
+        // The sequence number is announced but missing.
+        assert!(decode(&[&MAGIC[..], &[VERSION, 0b0000_1000, 0, 0]].concat()).is_err());
+        // The epoch is announced but missing.
+        assert!(decode(&[&MAGIC[..], &[VERSION, 0b0001_1000], &[0; 8]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_decompression_limit() {
+        // A LZ4 body announcing more than the maximum size is rejected before anything is allocated.
+        let oversized = ((MAX_BODY_SIZE + 1) as u32).to_le_bytes();
+        let envelope = [&MAGIC[..], &[VERSION, 1], &oversized, &[0; 16]].concat();
+        assert!(format!("{:?}", decode(&envelope).unwrap_err()).contains("maximum"));
+
+        // A Zstandard body is only decompressed up to the maximum size.
+        let zeros = std::io::repeat(0).take(MAX_BODY_SIZE as u64 + 1);
+        let body = zstd::stream::encode_all(zeros, 19).unwrap();
+        assert!(body.len() < 64 * 1024);
+        let envelope = [&MAGIC[..], &[VERSION, 2], &body].concat();
+        assert!(format!("{:?}", decode(&envelope).unwrap_err()).contains("maximum"));
+    }
+
+    #[test]
+    fn test_versions() {
+        let hlc = HLC::default();
+        let trace_context = TraceContext {
+            trace_id: [1; 16],
+            span_id: [2; 8],
+            trace_flags: 1,
+        };
+        let mut message = LinkMessage::new(Payload::from(vec![42u8; 16]), hlc.new_timestamp());
+        message.set_trace_context(Some(trace_context));
+        message.set_sample_metadata(Some(SampleMetadata {
+            key_expr: "zenoh-flow/sample".into(),
+            encoding: "application/json".into(),
+        }));
+        message.set_key_suffix(Some("suffix".into()));
+
+        // The current version carries the sample metadata and the key suffix.
+        let (mut buffer, mut message_buffer, mut payload_buffer) =
+            (Vec::new(), Vec::new(), Vec::new());
+        encode_into(
+            &message,
+            Compression::None,
+            0,
+            0,
+            &mut buffer,
+            &mut message_buffer,
+            &mut payload_buffer,
+        )
+        .unwrap();
+        let decoded = decode(&buffer).unwrap();
+        let decoded = &decoded.messages[0];
+        assert_eq!(decoded.trace_context(), Some(&trace_context));
+        assert_eq!(
+            decoded
+                .sample_metadata()
+                .map(|metadata| &*metadata.key_expr),
+            Some("zenoh-flow/sample")
+        );
+        assert_eq!(decoded.key_suffix(), Some("suffix"));
+
+        // The version 1 only carried the payload, the timestamp and the trace context.
+        let body =
+            bincode::serialize(&(message.payload(), message.timestamp(), Some(trace_context)))
+                .unwrap();
+        let envelope = [&MAGIC[..], &[1, SEQUENCE_FLAG], &3u64.to_le_bytes(), &body].concat();
+        let decoded = decode(&envelope).unwrap();
+        assert_eq!(decoded.sequence, Some(3));
+        assert_eq!(decoded.epoch, None);
+        let decoded = &decoded.messages[0];
+        assert_eq!(decoded.timestamp(), message.timestamp());
+        assert_eq!(decoded.trace_context(), Some(&trace_context));
+        assert!(decoded.sample_metadata().is_none() && decoded.key_suffix().is_none());
+
+        // The version 0 never existed.
+        assert!(decode(&[&MAGIC[..], &[0, 0], &body].concat()).is_err());
+    }
+
+    #[test]
+    fn test_batch_capacity() {
+        // A large maximum size is not allocated upfront.
+        let batch = Batch::new(BatchingConfiguration {
+            max_size: 1 << 40,
+            max_latency: 1_000,
+        });
+        assert!(batch.body.capacity() <= BATCH_INITIAL_CAPACITY);
+    }
+
+    #[test]
+    fn test_batch() {
+        let hlc = HLC::default();
+        let (mut buffer, mut message_buffer, mut payload_buffer) =
+            (Vec::new(), Vec::new(), Vec::new());
+        let mut batch = Batch::new(BatchingConfiguration {
+            max_size: 1_024,
+            max_latency: 60_000_000,
+        });
+        assert!(batch.is_empty() && !batch.is_ready());
+
+        let messages = (0..10u8)
+            .map(|i| LinkMessage::new(Payload::from(vec![i; 64]), hlc.new_timestamp()))
+            .collect::<Vec<_>>();
+        for message in messages.iter() {
+            batch
+                .push(message, &mut message_buffer, &mut payload_buffer)
+                .unwrap();
+        }
+        assert!(!batch.is_empty());
+        assert!(batch.deadline().is_some());
+        // 10 messages of more than 64 bytes each exceed the maximum size of the batch.
+        assert!(batch.is_ready());
+
+        for compression in [Compression::None, Compression::Lz4, Compression::Zstd] {
+            if batch.is_empty() {
+                for message in messages.iter() {
+                    batch
+                        .push(message, &mut message_buffer, &mut payload_buffer)
+                        .unwrap();
+                }
+            }
+
+            assert_eq!(batch.len(), messages.len() as u64);
+            batch.encode_into(compression, 1, 42, &mut buffer).unwrap();
+            assert!(batch.is_empty() && batch.deadline().is_none());
+
+            let decoded = decode(&buffer).unwrap();
+            assert_eq!(decoded.sequence, Some(42));
+            assert_eq!(decoded.messages.len(), messages.len());
+            for (i, (decoded, message)) in decoded.messages.iter().zip(messages.iter()).enumerate()
+            {
+                assert_eq!(decoded.timestamp(), message.timestamp());
+                assert_eq!(
+                    *decoded.payload().try_as_bytes().unwrap(),
+                    vec![i as u8; 64]
+                );
+            }
+
+            // A truncated batch is rejected.
+            if compression.is_none() {
+                assert!(decode(&buffer[..buffer.len() - 1]).is_err());
+            }
+        }
+
+        // The sequence numbers of a batch cannot overflow, the last one can be `u64::MAX`.
+        for message in messages.iter() {
+            batch
+                .push(message, &mut message_buffer, &mut payload_buffer)
+                .unwrap();
+        }
+        batch
+            .encode_into(Compression::None, 1, u64::MAX - 9, &mut buffer)
+            .unwrap();
+        assert_eq!(decode(&buffer).unwrap().messages.len(), messages.len());
+        for message in messages.iter() {
+            batch
+                .push(message, &mut message_buffer, &mut payload_buffer)
+                .unwrap();
+        }
+        batch
+            .encode_into(Compression::None, 1, u64::MAX - 8, &mut buffer)
+            .unwrap();
+        assert!(format!("{:?}", decode(&buffer).unwrap_err()).contains("overflow"));
+
+        // A batch is ready once its oldest message reached the maximum latency.
+        let mut batch = Batch::new(BatchingConfiguration {
+            max_size: 1_024,
+            max_latency: 0,
+        });
+        batch
+            .push(&messages[0], &mut message_buffer, &mut payload_buffer)
+            .unwrap();
+        assert!(batch.is_ready());
+    }
+}
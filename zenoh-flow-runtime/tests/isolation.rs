@@ -0,0 +1,308 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{path::Path, sync::Arc, sync::Mutex, time::Duration};
+
+use futures::{FutureExt, StreamExt};
+use uhlc::Timestamp;
+use zenoh_flow_descriptors::FlattenedDataFlowDescriptor;
+use zenoh_flow_nodes::prelude::{InputRaw, Node};
+use zenoh_flow_records::DataFlowRecord;
+use zenoh_flow_runtime::{InstanceEventKind, InstanceState, Runtime};
+
+const HELPER: &str = env!("CARGO_BIN_EXE_zenoh-flow-node-host");
+
+// The examples are built by `cargo test`, next to the helper in the `examples` directory.
+fn example_library(example: &str) -> String {
+    let library = Path::new(HELPER)
+        .parent()
+        .expect("The helper has a parent directory")
+        .join("examples")
+        .join(format!(
+            "{}{}{}",
+            std::env::consts::DLL_PREFIX,
+            example.replace('-', "_"),
+            std::env::consts::DLL_SUFFIX
+        ));
+    assert!(
+        library.exists(),
+        "Missing {}, build it with `cargo build -p zenoh-flow-runtime --example {}`",
+        library.display(),
+        example
+    );
+
+    format!("file://{}", library.display())
+}
+
+#[async_std::test]
+async fn test_isolated_node_crash() {
+    let flow = format!(
+        r#"
+name: crash
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    outputs:
+      - out
+sinks:
+  - id: crashing-sink
+    library: "{}"
+    isolation: process
+    inputs:
+      - in
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: crashing-sink
+      input: in
+"#,
+        example_library("crashing-sink")
+    );
+
+    let runtime = Runtime::builder("isolation-test")
+        .isolation_helper(HELPER)
+        .build()
+        .await
+        .expect("Failed to build the runtime");
+    let mut events = runtime.subscribe_events();
+
+    let descriptor = serde_yaml::from_str::<FlattenedDataFlowDescriptor>(&flow).unwrap();
+    let record = DataFlowRecord::try_new(&descriptor, runtime.id()).unwrap();
+    let instance_id = record.instance_id().clone();
+    runtime
+        .try_load_data_flow(record)
+        .await
+        .expect("Failed to load the data flow");
+    runtime.try_start_instance(&instance_id).await.unwrap();
+
+    // The first tick makes the helper process abort: it is reported as a failure of the node.
+    let error = async_std::future::timeout(Duration::from_secs(10), async {
+        while let Some(event) = events.next().await {
+            if let InstanceEventKind::NodeError { node, error, .. } = event.kind {
+                assert_eq!(node, "crashing-sink".into());
+                return error;
+            }
+        }
+        panic!("The stream of events ended");
+    })
+    .await
+    .expect("The crash of the isolated node was not reported");
+    assert!(error.contains("Isolated node failed"), "{error}");
+
+    // The runtime, and the other nodes of the data flow, keep running.
+    assert!(matches!(
+        runtime
+            .get_instance_status(&instance_id)
+            .await
+            .unwrap()
+            .state,
+        InstanceState::Running(_)
+    ));
+    runtime.try_abort_instance(&instance_id).await.unwrap();
+    runtime.try_delete_instance(&instance_id).await.unwrap();
+    assert!(runtime.get_instance_status(&instance_id).await.is_none());
+}
+
+#[async_std::test]
+async fn test_isolated_node_printing() {
+    let path = std::env::temp_dir().join(format!("zf-isolation-{}.log", uuid::Uuid::new_v4()));
+    let flow = format!(
+        r#"
+name: printing
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    outputs:
+      - out
+operators:
+  - id: printing-operator
+    library: "{}"
+    isolation: process
+    inputs:
+      - in
+    outputs:
+      - out
+sinks:
+  - id: file
+    file:
+      path: {}
+    inputs:
+      - in
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: printing-operator
+      input: in
+  - from:
+      node: printing-operator
+      output: out
+    to:
+      node: file
+      input: in
+"#,
+        example_library("printing-operator"),
+        path.display()
+    );
+
+    let runtime = Runtime::builder("isolation-printing-test")
+        .isolation_helper(HELPER)
+        .build()
+        .await
+        .expect("Failed to build the runtime");
+    let mut events = runtime.subscribe_events();
+
+    let descriptor = serde_yaml::from_str::<FlattenedDataFlowDescriptor>(&flow).unwrap();
+    let record = DataFlowRecord::try_new(&descriptor, runtime.id()).unwrap();
+    let instance_id = record.instance_id().clone();
+    runtime
+        .try_load_data_flow(record)
+        .await
+        .expect("Failed to load the data flow");
+    runtime.try_start_instance(&instance_id).await.unwrap();
+
+    // What the node prints does not corrupt the frames: the messages keep flowing through it.
+    async_std::future::timeout(Duration::from_secs(10), async {
+        loop {
+            let lines = async_std::fs::read_to_string(&path)
+                .await
+                .map(|content| content.lines().count())
+                .unwrap_or_default();
+            if lines >= 5 {
+                return;
+            }
+            async_std::task::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("The isolated node did not forward the messages");
+
+    runtime.try_abort_instance(&instance_id).await.unwrap();
+    while let Some(Some(event)) = events.next().now_or_never() {
+        assert!(
+            !matches!(event.kind, InstanceEventKind::NodeError { .. }),
+            "{event:?}"
+        );
+    }
+    runtime.try_delete_instance(&instance_id).await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+// The timestamps of the messages received by the `builtin://timestamps` Sink.
+static TIMESTAMPS: Mutex<Vec<Timestamp>> = Mutex::new(Vec::new());
+
+struct TimestampsSink {
+    input: InputRaw,
+}
+
+#[async_trait::async_trait]
+impl Node for TimestampsSink {
+    async fn iteration(&self) -> zenoh_flow_commons::Result<()> {
+        let message = self.input.recv().await?;
+        TIMESTAMPS.lock().unwrap().push(*message.timestamp());
+        Ok(())
+    }
+}
+
+#[async_std::test]
+async fn test_isolated_node_timestamps() {
+    let flow = format!(
+        r#"
+name: timestamps
+sources:
+  - id: ticker
+    ticker:
+      period: 10ms
+      output: out
+    outputs:
+      - out
+operators:
+  - id: printing-operator
+    library: "{}"
+    isolation: process
+    inputs:
+      - in
+    outputs:
+      - out
+sinks:
+  - id: timestamps
+    library: "builtin://timestamps"
+    inputs:
+      - in
+links:
+  - from:
+      node: ticker
+      output: out
+    to:
+      node: printing-operator
+      input: in
+  - from:
+      node: printing-operator
+      output: out
+    to:
+      node: timestamps
+      input: in
+"#,
+        example_library("printing-operator")
+    );
+
+    let runtime = Runtime::builder("isolation-timestamps-test")
+        .isolation_helper(HELPER)
+        .register_sink("timestamps", |_, _, mut inputs| {
+            Box::pin(async move {
+                let input = inputs.take("in").expect("No input `in`").raw();
+                Ok(Arc::new(TimestampsSink { input }) as Arc<dyn Node>)
+            })
+        })
+        .build()
+        .await
+        .expect("Failed to build the runtime");
+
+    let descriptor = serde_yaml::from_str::<FlattenedDataFlowDescriptor>(&flow).unwrap();
+    let record = DataFlowRecord::try_new(&descriptor, runtime.id()).unwrap();
+    let instance_id = record.instance_id().clone();
+    runtime
+        .try_load_data_flow(record)
+        .await
+        .expect("Failed to load the data flow");
+    runtime.try_start_instance(&instance_id).await.unwrap();
+
+    async_std::future::timeout(Duration::from_secs(10), async {
+        while TIMESTAMPS.lock().unwrap().len() < 5 {
+            async_std::task::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("The isolated node did not send the messages");
+    runtime.try_abort_instance(&instance_id).await.unwrap();
+
+    // The helper timestamps the messages with its own HLC: its identifier differs from the one of the runtime, whose
+    // HLC is updated with the timestamps it receives.
+    let timestamps = TIMESTAMPS.lock().unwrap().clone();
+    let hlc = runtime.hlc();
+    assert!(timestamps
+        .iter()
+        .all(|timestamp| timestamp.get_id() != hlc.get_id()));
+    assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(hlc.new_timestamp().get_time() > timestamps.last().unwrap().get_time());
+
+    runtime.try_delete_instance(&instance_id).await.unwrap();
+}
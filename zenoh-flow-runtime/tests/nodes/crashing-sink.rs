@@ -0,0 +1,47 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! A Sink that aborts the process in which it runs as soon as it receives a message, used to check that the crash of
+//! an isolated node does not affect its Zenoh-Flow runtime.
+
+use zenoh_flow_nodes::prelude::*;
+
+#[export_sink]
+pub struct CrashingSink {
+    input: InputRaw,
+}
+
+#[async_trait::async_trait]
+impl Node for CrashingSink {
+    async fn iteration(&self) -> Result<()> {
+        self.input.recv().await?;
+        std::process::abort();
+    }
+}
+
+#[async_trait::async_trait]
+impl Sink for CrashingSink {
+    async fn new(
+        _context: Context,
+        _configuration: Configuration,
+        mut inputs: Inputs,
+    ) -> Result<Self> {
+        Ok(CrashingSink {
+            input: inputs
+                .take("in")
+                .ok_or_else(|| anyhow!("No Input called 'in' found"))?
+                .raw(),
+        })
+    }
+}
@@ -0,0 +1,58 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! An Operator that writes on its standard output when it is created and for every message it receives, used to check
+//! that what an isolated node prints does not interfere with the exchanges with its Zenoh-Flow runtime.
+//!
+//! The payload of every message received is sent in a new message, timestamped by the helper process.
+
+use zenoh_flow_nodes::prelude::*;
+
+#[export_operator]
+pub struct PrintingOperator {
+    input: InputRaw,
+    output: OutputRaw,
+}
+
+#[async_trait::async_trait]
+impl Node for PrintingOperator {
+    async fn iteration(&self) -> Result<()> {
+        let message = self.input.recv().await?;
+        println!("Forwarding a message");
+        self.output.send(message.payload().clone(), None).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Operator for PrintingOperator {
+    async fn new(
+        _context: Context,
+        _configuration: Configuration,
+        mut inputs: Inputs,
+        mut outputs: Outputs,
+    ) -> Result<Self> {
+        println!("Creating the PrintingOperator");
+
+        Ok(PrintingOperator {
+            input: inputs
+                .take("in")
+                .ok_or_else(|| anyhow!("No Input called 'in' found"))?
+                .raw(),
+            output: outputs
+                .take("out")
+                .ok_or_else(|| anyhow!("No Output called 'out' found"))?
+                .raw(),
+        })
+    }
+}
@@ -17,8 +17,9 @@ use std::path::PathBuf;
 use anyhow::Context;
 use async_std::io::ReadExt;
 use clap::Parser;
+use url::Url;
 use zenoh_flow_commons::{parse_vars, Vars};
-use zenoh_flow_descriptors::{DataFlowDescriptor, FlattenedDataFlowDescriptor};
+use zenoh_flow_descriptors::{DescriptorResolver, FlattenedDataFlowDescriptor};
 use zenoh_flow_records::DataFlowRecord;
 use zenoh_flow_runtime::{zenoh::AsyncResolve, Extensions, Runtime};
 
@@ -67,25 +68,15 @@ async fn main() {
         None => Extensions::default(),
     };
 
-    let vars = match cli.vars {
-        Some(v) => Vars::from(v),
-        None => Vars::default(),
-    };
-
-    let (data_flow, vars) =
-        zenoh_flow_commons::try_parse_from_file::<DataFlowDescriptor>(cli.flow.as_os_str(), vars)
-            .context(format!(
-                "Failed to load data flow descriptor from < {} >",
-                &cli.flow.display()
-            ))
-            .unwrap();
-
-    let flattened_flow = FlattenedDataFlowDescriptor::try_flatten(data_flow, vars)
-        .context(format!(
-            "Failed to flattened data flow extracted from < {} >",
-            &cli.flow.display()
-        ))
-        .unwrap();
+    let flow_url = std::fs::canonicalize(&cli.flow)
+        .ok()
+        .and_then(|path| Url::from_file_path(path).ok())
+        .unwrap_or_else(|| {
+            panic!(
+                "Failed to canonicalize path (did you put an absolute path?):\n{}",
+                cli.flow.display()
+            )
+        });
 
     let mut runtime_builder = Runtime::builder("zenoh-flow-standalone-runtime")
         .add_extensions(extensions)
@@ -113,6 +104,35 @@ async fn main() {
         .await
         .expect("Failed to build the Zenoh-Flow runtime");
 
+    // NOTE: The descriptors can be fetched from Zenoh, through the session of the runtime, or an HTTP server. As the
+    // resolvers block while fetching them, the data flow is loaded and flattened in a blocking task.
+    let resolver = DescriptorResolver::default().with_zenoh(runtime.session());
+    let flow_path = cli.flow.clone();
+    let cli_vars = cli.vars;
+    let flattened_flow = async_std::task::spawn_blocking(move || {
+        // NOTE: The `Vars` are created in the blocking task as they cannot be sent across threads.
+        let vars = match cli_vars {
+            Some(v) => Vars::from(v),
+            None => Vars::default(),
+        };
+
+        let (data_flow, vars) = resolver
+            .try_load_data_flow(&flow_url, vars)
+            .context(format!(
+                "Failed to load data flow descriptor from < {} >",
+                flow_path.display()
+            ))?;
+
+        FlattenedDataFlowDescriptor::try_flatten_with_resolver(data_flow, vars, &resolver).context(
+            format!(
+                "Failed to flattened data flow extracted from < {} >",
+                flow_path.display()
+            ),
+        )
+    })
+    .await
+    .unwrap();
+
     let record = DataFlowRecord::try_new(&flattened_flow, runtime.id())
         .context("Failed to create a Record from the flattened data flow descriptor")
         .unwrap();
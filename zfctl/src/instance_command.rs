@@ -12,17 +12,18 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::{anyhow, bail};
 use clap::Subcommand;
 use comfy_table::Table;
 use itertools::Itertools;
+use url::Url;
 use uuid::Uuid;
 use zenoh::prelude::r#async::*;
 use zenoh_flow_commons::{parse_vars, Result, RuntimeId, Vars};
 use zenoh_flow_daemon::queries::*;
-use zenoh_flow_descriptors::{DataFlowDescriptor, FlattenedDataFlowDescriptor};
+use zenoh_flow_descriptors::{DescriptorResolver, FlattenedDataFlowDescriptor};
 
 use super::ZENOH_FLOW_INTERNAL_ERROR;
 use crate::row;
@@ -72,28 +73,57 @@ pub(crate) enum InstanceCommand {
 }
 
 impl InstanceCommand {
-    pub async fn run(self, session: Session, orchestrator_id: RuntimeId) -> Result<()> {
+    pub async fn run(self, session: Arc<Session>, orchestrator_id: RuntimeId) -> Result<()> {
         let mut selector = selector_instances(&orchestrator_id);
         let query = match self {
             InstanceCommand::Create { flow, vars } => {
-                let vars = match vars {
-                    Some(v) => Vars::from(v),
-                    None => Vars::default(),
-                };
-
                 tracing::trace!("Path to data flow descriptor is: {}", flow.display());
-                let (data_flow_desc, vars) =
-                    zenoh_flow_commons::try_parse_from_file::<DataFlowDescriptor>(&flow, vars)
-                        .map_err(|e| {
+
+                // The descriptors referenced by the data flow can be fetched from Zenoh or an HTTP server, in which case
+                // they are cached locally.
+                let mut resolver = DescriptorResolver::default().with_zenoh(session.clone());
+                if let Some(cache_dir) = dirs::cache_dir() {
+                    resolver =
+                        resolver.with_cache_dir(cache_dir.join("zenoh-flow").join("descriptors"));
+                }
+
+                let flow_url = std::fs::canonicalize(&flow)
+                    .ok()
+                    .and_then(|path| Url::from_file_path(path).ok())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Failed to canonicalize path (did you put an absolute path?):\n{}",
+                            flow.display()
+                        )
+                    })?;
+
+                // NOTE: Loading the data flow through the resolver allows the problems detected when flattening it to
+                // indicate where, in the descriptors, they are located. As the resolvers block while fetching the
+                // descriptors, the data flow is loaded and flattened in a blocking task.
+                let flat_flow = async_std::task::spawn_blocking(move || {
+                    // NOTE: The `Vars` are created in the blocking task as they cannot be sent across threads.
+                    let vars = match vars {
+                        Some(v) => Vars::from(v),
+                        None => Vars::default(),
+                    };
+
+                    let (data_flow_desc, vars) =
+                        resolver.try_load_data_flow(&flow_url, vars).map_err(|e| {
                             tracing::error!("{:?}", e);
                             anyhow!("Failed to parse data flow from < {} >", flow.display())
                         })?;
 
-                let flat_flow = FlattenedDataFlowDescriptor::try_flatten(data_flow_desc, vars)
+                    FlattenedDataFlowDescriptor::try_flatten_with_resolver(
+                        data_flow_desc,
+                        vars,
+                        &resolver,
+                    )
                     .map_err(|e| {
                         tracing::error!("{:?}", e);
                         anyhow!("Failed to flatten data flow < {} >", flow.display())
-                    })?;
+                    })
+                })
+                .await?;
 
                 InstancesQuery::Create(Box::new(flat_flow))
             }
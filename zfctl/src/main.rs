@@ -18,6 +18,9 @@ use instance_command::InstanceCommand;
 mod runtime_command;
 use runtime_command::RuntimeCommand;
 
+mod schema_command;
+use schema_command::SchemaCommand;
+
 mod utils;
 use std::path::PathBuf;
 
@@ -82,6 +85,16 @@ enum Command {
     /// To interact with a Zenoh-Flow runtime.
     #[command(subcommand)]
     Runtime(RuntimeCommand),
+
+    /// Print the JSON Schema of a descriptor.
+    ///
+    /// Editors can use it to validate and autocomplete the descriptors, for
+    /// instance by redirecting the output to a file:
+    ///   zfctl schema data-flow > zenoh-flow.schema.json
+    ///
+    /// No connection to the Zenoh network is made.
+    #[command(verbatim_doc_comment)]
+    Schema(SchemaCommand),
 }
 
 #[async_std::main]
@@ -94,6 +107,11 @@ async fn main() -> Result<()> {
 
     let zfctl = Zfctl::parse();
 
+    // Printing a JSON Schema does not require a Zenoh session.
+    if let Command::Schema(command) = zfctl.command {
+        return command.run().await;
+    }
+
     let zenoh_config = match zfctl.zenoh_configuration {
         Some(path) => zenoh::prelude::Config::from_file(path.clone()).map_err(|e| {
             anyhow!(
@@ -107,7 +125,8 @@ async fn main() -> Result<()> {
     let session = zenoh::open(zenoh_config)
         .res()
         .await
-        .map_err(|e| anyhow!("Failed to open Zenoh session:\n{:?}", e))?;
+        .map_err(|e| anyhow!("Failed to open Zenoh session:\n{:?}", e))?
+        .into_arc();
 
     match zfctl.command {
         Command::Instance {
@@ -124,5 +143,6 @@ async fn main() -> Result<()> {
             command.run(session, orchestrator_id).await
         }
         Command::Runtime(command) => command.run(&session).await,
+        Command::Schema(_) => unreachable!("The schema command does not require a Zenoh session"),
     }
 }
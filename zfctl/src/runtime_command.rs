@@ -57,6 +57,17 @@ pub(crate) enum RuntimeCommand {
         #[arg(short = 'n', long = "name", group = "runtime")]
         runtime_name: Option<String>,
     },
+    /// Print, as they happen, the events of the data flow instances managed
+    /// by the provided Zenoh-Flow runtime.
+    ///
+    /// If no identifier is provided, the events of all the Zenoh-Flow
+    /// runtimes are printed.
+    #[command(verbatim_doc_comment)]
+    Events {
+        /// The unique identifier of the Zenoh-Flow runtime to watch.
+        #[arg(short = 'i', long = "id")]
+        runtime_id: Option<RuntimeId>,
+    },
 }
 
 impl RuntimeCommand {
@@ -174,6 +185,28 @@ impl RuntimeCommand {
                     }
                 }
             }
+
+            RuntimeCommand::Events { runtime_id } => {
+                let selector = match runtime_id {
+                    Some(runtime_id) => selector_events(&runtime_id),
+                    None => selector_all_events(),
+                };
+
+                let subscriber = session
+                    .declare_subscriber(selector.clone())
+                    .res()
+                    .await
+                    .map_err(|e| anyhow!("Failed to subscribe to < {} >: {:?}", selector, e))?;
+
+                while let Ok(sample) = subscriber.recv_async().await {
+                    match serde_json::from_slice::<InstanceEvent>(&sample.payload.contiguous()) {
+                        Ok(event) => println!("{} {}", event.runtime_id, event),
+                        Err(e) => {
+                            tracing::error!("Failed to parse sample as an `InstanceEvent`: {:?}", e)
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -0,0 +1,55 @@
+//
+// Copyright © 2021 ZettaScale Technology <contact@zettascale.tech>
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use anyhow::anyhow;
+use clap::{Args, ValueEnum};
+use zenoh_flow_commons::Result;
+use zenoh_flow_descriptors::{data_flow_schema, operator_schema, sink_schema, source_schema};
+
+#[derive(Args)]
+pub(crate) struct SchemaCommand {
+    /// The descriptor whose JSON Schema is printed.
+    #[arg(value_enum, default_value_t = DescriptorKind::DataFlow)]
+    descriptor: DescriptorKind,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DescriptorKind {
+    /// The descriptor of a data flow.
+    DataFlow,
+    /// The descriptor of a Source, imported by a data flow.
+    Source,
+    /// The descriptor of an Operator, imported by a data flow or a composite
+    /// Operator.
+    Operator,
+    /// The descriptor of a Sink, imported by a data flow.
+    Sink,
+}
+
+impl SchemaCommand {
+    pub async fn run(self) -> Result<()> {
+        let schema = match self.descriptor {
+            DescriptorKind::DataFlow => data_flow_schema(),
+            DescriptorKind::Source => source_schema(),
+            DescriptorKind::Operator => operator_schema(),
+            DescriptorKind::Sink => sink_schema(),
+        };
+
+        let schema = serde_json::to_string_pretty(&schema)
+            .map_err(|e| anyhow!("Failed to serialize the JSON Schema:\n{e:?}"))?;
+        println!("{schema}");
+
+        Ok(())
+    }
+}